@@ -0,0 +1,41 @@
+// Times a draw-heavy program: run with cargo bench --bench draw
+use std::hint::black_box;
+use std::time::Instant;
+use chip8::{Chip8Config, Variant};
+
+const CYCLES: u32 = 1_000_000;
+
+// Draws sprites of 15 rows at moving positions forever, many of them running off an edge
+const PROGRAM: [u8; 16] = [
+    0xF0, 0x29, // FX29: I = sprite of the digit in V0
+    0xD1, 0x2F, // D12F: draws 15 rows at (V1, V2)
+    0xD2, 0x1F, // D21F: draws 15 rows at (V2, V1)
+    0xD1, 0x2F, // D12F: erases the first sprite
+    0xD2, 0x1F, // D21F: erases the second sprite
+    0x71, 0x03, // 7103: V1 += 3
+    0x72, 0x01, // 7201: V2 += 1
+    0x12, 0x00, // 1200: jumps back to 0x200
+];
+
+fn run(name: &str, variant: Variant, hires: bool) {
+    let mut chip8 = Chip8Config::new().variant(variant).display_wait(false).seed(0).build().unwrap();
+    let mut program = PROGRAM.to_vec();
+    if hires {
+        // 00FF: switches to high resolution first
+        program.splice(0..0, [0x00, 0xFF]);
+        let last = program.len() - 1;
+        program[last] = 0x02;
+    }
+    chip8.load_rom(&program).unwrap();
+    let start = Instant::now();
+    for _ in 0..CYCLES {
+        black_box(chip8.emulate_cycle().unwrap());
+    }
+    let elapsed = start.elapsed();
+    println!("{name}: {CYCLES} cycles in {elapsed:?} ({:.1} ns per cycle)", elapsed.as_nanos() as f64 / f64::from(CYCLES));
+}
+
+fn main() {
+    run("lores draw loop", Variant::Chip48, false);
+    run("hires draw loop", Variant::SuperChip, true);
+}
@@ -0,0 +1,36 @@
+// Runs a ROM for a number of frames without a display and tells how many frames drew something:
+// cargo run --example headless -- <rom file> [frames]
+use std::env;
+use std::error::Error;
+use std::process;
+use chip8::{Chip8Config, State};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = env::args().skip(1);
+    let Some(rom_path) = args.next() else {
+        eprintln!("Usage: headless <rom file> [frames]");
+        process::exit(2);
+    };
+    let frame_count: usize = match args.next() {
+        Some(frames) => frames.parse()?,
+        None => 600,
+    };
+
+    let mut chip8 = Chip8Config::new().seed(0).build()?;
+    chip8.load_game(&rom_path)?;
+
+    let mut frames_run = 0;
+    let mut frames_drawn = 0;
+    for frame in chip8.frames().take(frame_count) {
+        frames_run += 1;
+        if frame.info.drew {
+            frames_drawn += 1;
+        }
+    }
+
+    println!("{frames_drawn} of {frames_run} frames drew something");
+    if let State::Faulted(error) = chip8.state() {
+        return Err(error.clone().into());
+    }
+    Ok(())
+}
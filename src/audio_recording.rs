@@ -0,0 +1,99 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./audio_recording_tests.rs"]
+mod audio_recording_tests;
+
+// WAV recording of the beep, enabled with the record-audio feature. The samples are made in emulated time, 1/60 s of
+// them per tick of the timers whatever the speed of the host, so that the track keeps in step with the GIF recording
+// which also counts the frames run
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, Write};
+use std::mem;
+use std::path::Path;
+use std::rc::Rc;
+use hound::{SampleFormat, WavSpec, WavWriter};
+use crate::{BeepGate, BeepSource, Buzzer, FrameSound, TickHook, Waveform};
+
+// Mono 16-bit samples at 48 kHz, 800 per 60 Hz frame
+pub const AUDIO_SAMPLE_RATE: u32 = 48_000;
+pub const SAMPLES_PER_FRAME: usize = AUDIO_SAMPLE_RATE as usize / 60;
+
+pub struct WavRecorder<W: Write + Seek> {
+    // None once the recording is finished
+    writer: Option<WavWriter<W>>,
+    // The beep of the recording, at full volume whatever the volume and the mute of the frontend
+    gate: BeepGate,
+    source: BeepSource,
+    buffer: Vec<f32>,
+    // Sound of the frames run since the last write, noted by the tick hook. None once the recording is finished
+    frames: Rc<RefCell<Option<Vec<FrameSound>>>>,
+}
+
+impl WavRecorder<BufWriter<File>> {
+    pub fn create(path: &Path, waveform: Waveform, frequency: f32) -> io::Result<WavRecorder<BufWriter<File>>> {
+        WavRecorder::new(BufWriter::new(File::create(path)?), waveform, frequency)
+    }
+}
+
+impl<W: Write + Seek> WavRecorder<W> {
+    // The tone is played for the programs without an XO-CHIP audio pattern, like by the audio backends
+    pub fn new(writer: W, waveform: Waveform, frequency: f32) -> io::Result<WavRecorder<W>> {
+        let spec = WavSpec {
+            channels: 1,
+            sample_rate: AUDIO_SAMPLE_RATE,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let writer = WavWriter::new(writer, spec).map_err(io::Error::other)?;
+        let gate = BeepGate::default();
+        let source = BeepSource::new(gate.clone(), AUDIO_SAMPLE_RATE).tone(waveform, frequency);
+        Ok(WavRecorder {
+            writer: Some(writer),
+            gate,
+            source,
+            buffer: vec![0.0; SAMPLES_PER_FRAME],
+            frames: Rc::new(RefCell::new(Some(Vec::new()))),
+        })
+    }
+
+    // Hook for Chip8::set_tick_hook, noting the sound of each frame run until the next call of write_frames. The
+    // frames run after the end of the recording are not noted
+    pub fn tick_hook(&self) -> TickHook {
+        let frames = Rc::clone(&self.frames);
+        Box::new(move |sound| {
+            if let Some(frames) = frames.borrow_mut().as_mut() {
+                frames.push(sound);
+            }
+        })
+    }
+
+    // Adds 1/60 s of samples for each frame run since the last call, with the sound the frame ended with. A batch of
+    // frames run at once, while fast-forwarding or catching up, keeps the beeps of each
+    pub fn write_frames(&mut self) -> io::Result<()> {
+        let Some(writer) = self.writer.as_mut() else {
+            return Ok(());
+        };
+        let frames = self.frames.borrow_mut().as_mut().map(mem::take).unwrap_or_default();
+        for sound in frames {
+            self.gate.set_active(sound.active);
+            self.gate.set_pattern(sound.pattern.as_ref().map(|(pattern, rate)| (pattern, *rate)));
+            self.source.fill(&mut self.buffer);
+            for &sample in &self.buffer {
+                let sample = (sample * f32::from(i16::MAX)).round() as i16;
+                writer.write_sample(sample).map_err(io::Error::other)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Writes the sizes in the header. Nothing is recorded afterwards
+    pub fn finish(&mut self) -> io::Result<()> {
+        self.frames.borrow_mut().take();
+        if let Some(writer) = self.writer.take() {
+            writer.finalize().map_err(io::Error::other)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod audio_recording_tests {
+    use std::io::Cursor;
+    use crate::{Chip8, Chip8Config, Waveform, WavRecorder, AUDIO_SAMPLE_RATE, BEEP_VOLUME, SAMPLES_PER_FRAME};
+
+    // Samples of a WAV file
+    fn decode(file: Vec<u8>) -> Vec<i16> {
+        let mut reader = hound::WavReader::new(Cursor::new(file)).unwrap();
+        let spec = reader.spec();
+        assert_eq!((spec.channels, spec.sample_rate, spec.bits_per_sample), (1, AUDIO_SAMPLE_RATE, 16));
+        reader.samples::<i16>().map(Result::unwrap).collect()
+    }
+
+    fn record(chip8: &mut Chip8, frames: usize) -> Vec<i16> {
+        let mut file = Cursor::new(Vec::new());
+        let mut recorder = WavRecorder::new(&mut file, Waveform::Square, 440.0).unwrap();
+        chip8.set_tick_hook(recorder.tick_hook());
+        for _ in 0..frames {
+            chip8.run_frame().unwrap();
+            recorder.write_frames().unwrap();
+        }
+        recorder.finish().unwrap();
+        drop(recorder);
+        decode(file.into_inner())
+    }
+
+    #[test]
+    fn recording_without_sound_is_800_silent_samples_per_frame() {
+        assert_eq!(SAMPLES_PER_FRAME, 800);
+        // Silent without any sound
+        let samples = record(&mut Chip8::with_program(&[0x1200]), 30);
+        assert_eq!(samples.len(), 30 * 800);
+        assert!(samples.iter().all(|&sample| sample == 0));
+    }
+
+    #[test]
+    fn recording_beeps_while_the_sound_timer_runs() {
+        // Waits 2 frames with the delay timer, then sets the sound timer to 3 and loops
+        let mut chip8 = Chip8::with_program(&[0x6002, 0xF015, 0xF107, 0x3100, 0x1204, 0x6003, 0xF018, 0x120E]);
+        let samples = record(&mut chip8, 10);
+        assert_eq!(samples.len(), 10 * 800);
+        let full = (BEEP_VOLUME * f32::from(i16::MAX)).round() as i16;
+        // The sound timer set by the third frame is still running after it and the fourth, and runs out with the
+        // fifth, like the audio backends hear it after each frame. The beep fades in and out over 96 samples
+        let (start, end) = (2 * 800, 4 * 800);
+        assert!(samples[..start].iter().all(|&sample| sample == 0));
+        assert!(samples[start..start + 96].iter().any(|&sample| sample != 0 && sample.abs() < full));
+        assert!(samples[start + 96..end].iter().all(|&sample| sample.abs() == full));
+        assert!(samples[end..end + 96].iter().all(|&sample| sample.abs() < full));
+        assert!(samples[end + 96..].iter().all(|&sample| sample == 0));
+    }
+
+    #[test]
+    fn recording_adds_the_frames_run_since_the_last_write_until_the_end() {
+        let mut chip8 = Chip8::with_program(&[0x6078, 0xF018, 0x1204]);
+        let mut file = Cursor::new(Vec::new());
+        let mut recorder = WavRecorder::new(&mut file, Waveform::Sine, 220.0).unwrap();
+        chip8.set_tick_hook(recorder.tick_hook());
+        chip8.run_for(crate::FRAME_DURATION * 4).unwrap();
+        recorder.write_frames().unwrap();
+        // Nothing is added without a frame run, nor after the end
+        recorder.write_frames().unwrap();
+        recorder.finish().unwrap();
+        chip8.run_for(crate::FRAME_DURATION * 2).unwrap();
+        recorder.write_frames().unwrap();
+        drop(recorder);
+        let samples = decode(file.into_inner());
+        assert_eq!(samples.len(), 4 * 800);
+        assert!(samples[96..].iter().any(|&sample| sample != 0));
+    }
+
+    #[test]
+    fn a_batch_of_frames_keeps_the_beep_of_each_frame() {
+        let mut chip8 = Chip8Config::new().instructions_per_frame(4).build().unwrap();
+        chip8.load_rom(&[
+            0x60, 0x03, // V0 = 3
+            0xF0, 0x15, // delay timer = V0
+            0xF1, 0x07, // V1 = delay timer
+            0x31, 0x00, // skips the jump once the delay timer is 0
+            0x12, 0x04, // jumps back to the delay timer read
+            0x60, 0x02, // V0 = 2
+            0xF0, 0x18, // sound timer = V0
+            0x12, 0x0E, // loops forever
+        ]).unwrap();
+        let mut file = Cursor::new(Vec::new());
+        let mut recorder = WavRecorder::new(&mut file, Waveform::Square, 440.0).unwrap();
+        chip8.set_tick_hook(recorder.tick_hook());
+        // 10 frames in one call, like while fast-forwarding
+        let frame = chip8.run_for(crate::FRAME_DURATION * 10).unwrap();
+        assert!(!frame.sound_active);
+        recorder.write_frames().unwrap();
+        recorder.finish().unwrap();
+        drop(recorder);
+        let samples = decode(file.into_inner());
+        assert_eq!(samples.len(), 10 * 800);
+        // The delay timer runs out with the fourth frame, the fifth sets the sound timer which lasts after it and
+        // runs out with the sixth. Only the fifth frame beeps, faded out over the first 96 samples of the sixth
+        let (start, end) = (4 * 800, 5 * 800);
+        assert!(samples[..start].iter().all(|&sample| sample == 0));
+        assert!(samples[start..end].iter().any(|&sample| sample != 0));
+        assert!(samples[end + 96..].iter().all(|&sample| sample == 0));
+    }
+}
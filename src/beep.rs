@@ -0,0 +1,237 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./beep_tests.rs"]
+mod beep_tests;
+
+// Beep of the audio backends: a tone generated on the audio thread, or the audio pattern of an XO-CHIP program,
+// sounding while a gate shared with the emulation loop is open. The wave fades in and out over a few milliseconds so
+// that opening and closing it does not click.
+//
+// The only state handed from the emulation to the audio thread is whether the sound timer is above zero, so the gate is
+// a single atomic flag rather than a ring buffer of samples: the audio callback never waits for the emulation nor
+// allocates, works with any buffer size the device asks for, and a late emulation frame delays the switch instead of
+// starving the device. The flag is switched by the sound hook as soon as FX18 or the timer tick changes the sound, so
+// that the beep follows the emulated frames even when several run at once, or by update_buzzer once per frame. The
+// pattern of an XO-CHIP program is handed the same way, as atomic bits read by the audio thread for every sample
+
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use crate::{Buzzer, SoundHook, Tone, Waveform};
+
+pub const BEEP_FREQUENCY: f32 = 440.0;
+// Bits of an XO-CHIP audio pattern
+const PATTERN_BITS: f64 = 128.0;
+// Amplitude of the wave, full scale is 1
+pub const BEEP_VOLUME: f32 = 0.25;
+// Time to fade from silence to the full volume or back
+pub const BEEP_RAMP: Duration = Duration::from_millis(2);
+// Volume set by the user, in percent of BEEP_VOLUME
+pub const MAX_VOLUME: u8 = 100;
+
+// Linear gain of the samples for a volume in percent, above 100 is taken as 100. Muting only silences the output,
+// the sound timer and the gate go on
+pub fn gain(volume: u8, muted: bool) -> f32 {
+    if muted {
+        return 0.0;
+    }
+    f32::from(volume.min(MAX_VOLUME)) / f32::from(MAX_VOLUME)
+}
+
+#[derive(Debug)]
+struct GateState {
+    open: AtomicBool,
+    volume: AtomicU8,
+    muted: AtomicBool,
+    // Silent while the emulation is paused or fast-forwarded, the gate still follows the sound timer
+    held: AtomicBool,
+    // XO-CHIP audio pattern, its first byte in the high bits, and its rate as the bits of an f32. The tone is played
+    // instead while no pattern was set
+    pattern: [AtomicU64; 2],
+    pattern_rate: AtomicU32,
+    pattern_set: AtomicBool,
+}
+
+// Gate of a BeepSource, switched by its sound hook from the timer ticks or as a buzzer by update_buzzer, with the
+// volume and the mute of the output. Clones share the state
+#[derive(Debug, Clone)]
+pub struct BeepGate(Arc<GateState>);
+
+impl Default for BeepGate {
+    fn default() -> BeepGate {
+        BeepGate(Arc::new(GateState {
+            open: AtomicBool::new(false),
+            volume: AtomicU8::new(MAX_VOLUME),
+            muted: AtomicBool::new(false),
+            held: AtomicBool::new(false),
+            pattern: [AtomicU64::new(0), AtomicU64::new(0)],
+            pattern_rate: AtomicU32::new(0),
+            pattern_set: AtomicBool::new(false),
+        }))
+    }
+}
+
+impl BeepGate {
+    pub fn is_open(&self) -> bool {
+        self.0.open.load(Ordering::Relaxed)
+    }
+
+    // Hook for Chip8::set_sound_hook, switching the gate from tick_timers
+    pub fn sound_hook(&self) -> SoundHook {
+        let gate = self.0.clone();
+        Box::new(move |on| gate.open.store(on, Ordering::Relaxed))
+    }
+
+    pub fn volume(&self) -> u8 {
+        self.0.volume.load(Ordering::Relaxed)
+    }
+
+    // In percent, clamped to MAX_VOLUME
+    pub fn set_volume(&self, volume: u8) {
+        self.0.volume.store(volume.min(MAX_VOLUME), Ordering::Relaxed);
+    }
+
+    pub fn muted(&self) -> bool {
+        self.0.muted.load(Ordering::Relaxed)
+    }
+
+    pub fn set_muted(&self, muted: bool) {
+        self.0.muted.store(muted, Ordering::Relaxed);
+    }
+
+    pub fn held(&self) -> bool {
+        self.0.held.load(Ordering::Relaxed)
+    }
+
+    // Keeps the beep silent without closing the gate, so that it sounds again on release if the sound timer still runs
+    pub fn set_held(&self, held: bool) {
+        self.0.held.store(held, Ordering::Relaxed);
+    }
+
+    pub fn gain(&self) -> f32 {
+        gain(self.volume(), self.muted())
+    }
+
+    // Audio pattern of the program and its sample rate, as given by Chip8::loaded_audio_pattern once per frame. None
+    // plays the tone of the source
+    pub fn set_pattern(&self, pattern: Option<(&[u8; 16], f64)>) {
+        let Some((pattern, sample_rate)) = pattern else {
+            self.0.pattern_set.store(false, Ordering::Relaxed);
+            return;
+        };
+        let bits = u128::from_be_bytes(*pattern);
+        self.0.pattern[0].store((bits >> 64) as u64, Ordering::Relaxed);
+        self.0.pattern[1].store(bits as u64, Ordering::Relaxed);
+        self.0.pattern_rate.store((sample_rate as f32).to_bits(), Ordering::Relaxed);
+        self.0.pattern_set.store(true, Ordering::Relaxed);
+    }
+
+    // The pattern as 128 bits from the highest, and its sample rate
+    fn pattern(&self) -> Option<(u128, f64)> {
+        if !self.0.pattern_set.load(Ordering::Relaxed) {
+            return None;
+        }
+        let high = u128::from(self.0.pattern[0].load(Ordering::Relaxed));
+        let low = u128::from(self.0.pattern[1].load(Ordering::Relaxed));
+        let sample_rate = f32::from_bits(self.0.pattern_rate.load(Ordering::Relaxed));
+        Some((high << 64 | low, f64::from(sample_rate)))
+    }
+}
+
+impl Buzzer for BeepGate {
+    fn set_active(&mut self, on: bool) {
+        self.0.open.store(on, Ordering::Relaxed);
+    }
+}
+
+// Samples of the beep at any sample rate, endless. The gate is read for every sample, the level of the wave moves
+// towards its gain while it is open and towards silence otherwise, by a step per sample, so that muting and volume
+// changes fade too. The wave is the tone, a square wave at BEEP_FREQUENCY by default, or the pattern of the gate once
+// the program set one
+#[derive(Debug, Clone)]
+pub struct BeepSource {
+    gate: BeepGate,
+    tone: Tone,
+    // Position in the pattern, in bits from 0 to 128, when the pattern started at its sample rate and the samples
+    // played since. Counting the samples rather than adding up the steps keeps a bit as long as it should be
+    pattern_start: f64,
+    pattern_rate: f64,
+    pattern_samples: u64,
+    // Level of the fade, from 0 for silence to the gain
+    level: f32,
+    level_step: f32,
+}
+
+impl BeepSource {
+    pub fn new(gate: BeepGate, sample_rate: u32) -> BeepSource {
+        let sample_rate = sample_rate.max(1);
+        let ramp_samples = (BEEP_RAMP.as_secs_f32() * sample_rate as f32).max(1.0);
+        BeepSource {
+            gate,
+            tone: Tone::new(Waveform::Square, BEEP_FREQUENCY, sample_rate),
+            pattern_start: 0.0,
+            pattern_rate: 0.0,
+            pattern_samples: 0,
+            level: 0.0,
+            level_step: 1.0 / ramp_samples,
+        }
+    }
+
+    // Plays this tone at the sample rate of the source, for the programs without an audio pattern
+    pub fn tone(mut self, waveform: Waveform, frequency: f32) -> BeepSource {
+        self.set_tone(waveform, frequency);
+        self
+    }
+
+    pub fn set_tone(&mut self, waveform: Waveform, frequency: f32) {
+        self.tone = Tone::new(waveform, frequency, self.tone.sample_rate());
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.tone.sample_rate()
+    }
+
+    pub fn next_sample(&mut self) -> f32 {
+        let target = if self.gate.is_open() && !self.gate.held() { self.gate.gain() } else { 0.0 };
+        if self.level < target {
+            self.level = (self.level + self.level_step).min(target);
+        } else {
+            self.level = (self.level - self.level_step).max(target);
+        }
+        let wave = match self.gate.pattern() {
+            Some((pattern, sample_rate)) => {
+                // A new pitch goes on from the current position
+                if sample_rate != self.pattern_rate {
+                    self.pattern_start = self.pattern_position();
+                    self.pattern_rate = sample_rate;
+                    self.pattern_samples = 0;
+                }
+                let bit = pattern >> (127 - self.pattern_position() as u32) & 1;
+                self.pattern_samples += 1;
+                if bit == 1 { 1.0 } else { -1.0 }
+            }
+            None => self.tone.next_sample(),
+        };
+        wave * BEEP_VOLUME * self.level
+    }
+
+    fn pattern_position(&self) -> f64 {
+        let played = self.pattern_samples as f64 * self.pattern_rate / f64::from(self.sample_rate());
+        (self.pattern_start + played) % PATTERN_BITS
+    }
+
+    // Fills a buffer of the audio device, without allocating
+    pub fn fill(&mut self, samples: &mut [f32]) {
+        for sample in samples {
+            *sample = self.next_sample();
+        }
+    }
+}
+
+impl Iterator for BeepSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        Some(self.next_sample())
+    }
+}
@@ -0,0 +1,278 @@
+#[cfg(test)]
+mod beep_tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::{gain, BeepGate, BeepSource, Buzzer, Chip8, Tone, Waveform, BEEP_FREQUENCY, BEEP_VOLUME};
+
+    fn source(sample_rate: u32) -> (BeepGate, BeepSource) {
+        let gate = BeepGate::default();
+        (gate.clone(), BeepSource::new(gate, sample_rate))
+    }
+
+    #[test]
+    fn beep_is_silent_while_the_gate_is_closed() {
+        let (_, mut source) = source(44_100);
+        let mut samples = [1.0; 512];
+        source.fill(&mut samples);
+        assert!(samples.iter().all(|&sample| sample == 0.0));
+    }
+
+    #[test]
+    fn beep_fades_in_when_the_gate_opens() {
+        // 2 ms are 96 samples at 48 kHz
+        let (mut gate, mut source) = source(48_000);
+        gate.set_active(true);
+        let samples: Vec<f32> = source.by_ref().take(96).collect();
+        assert!((samples[0] - BEEP_VOLUME / 96.0).abs() < 1e-6);
+        assert!(samples.windows(2).take(50).all(|pair| pair[1] > pair[0]));
+        assert!((samples[95].abs() - BEEP_VOLUME).abs() < 1e-6);
+        // Then at the full volume
+        assert!(source.take(1000).all(|sample| sample.abs() == BEEP_VOLUME));
+    }
+
+    #[test]
+    fn beep_fades_out_when_the_gate_closes() {
+        let (mut gate, mut source) = source(48_000);
+        gate.set_active(true);
+        source.by_ref().take(1000).for_each(drop);
+        gate.set_active(false);
+        let samples: Vec<f32> = source.by_ref().take(96).collect();
+        assert!(samples.iter().all(|sample| sample.abs() < BEEP_VOLUME));
+        assert!(samples[95].abs() < 1e-6);
+        assert!(source.take(1000).all(|sample| sample == 0.0));
+    }
+
+    #[test]
+    fn beep_is_a_square_wave_at_440_hz() {
+        let (mut gate, mut source) = source(44_000);
+        gate.set_active(true);
+        source.by_ref().take(1000).for_each(drop);
+        // 100 samples per period, half of them high
+        let period: Vec<f32> = source.take(100).collect();
+        assert_eq!(period.iter().filter(|&&sample| sample > 0.0).count(), 50);
+        assert_eq!(period.iter().filter(|&&sample| sample < 0.0).count(), 50);
+    }
+
+    #[test]
+    fn gate_clones_share_the_state() {
+        let mut gate = BeepGate::default();
+        let other = gate.clone();
+        assert!(!other.is_open());
+        gate.set_active(true);
+        assert!(other.is_open());
+    }
+
+    // Samples where a period starts, over one second of beep
+    fn period_starts(sample_rate: u32) -> Vec<usize> {
+        let (mut gate, source) = source(sample_rate);
+        gate.set_active(true);
+        let samples: Vec<f32> = source.take(sample_rate as usize).collect();
+        (0..samples.len()).filter(|&i| samples[i] > 0.0 && (i == 0 || samples[i - 1] <= 0.0)).collect()
+    }
+
+    // Checks the periods are the whole numbers of samples around the exact period, with the exact one on average
+    fn assert_periods(sample_rate: u32) {
+        let starts = period_starts(sample_rate);
+        assert_eq!(starts.len(), BEEP_FREQUENCY as usize);
+        let exact = sample_rate as f32 / BEEP_FREQUENCY;
+        let periods: Vec<usize> = starts.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        assert!(periods.iter().all(|&period| period == exact.floor() as usize || period == exact.ceil() as usize));
+        let average = (starts[starts.len() - 1] - starts[0]) as f32 / periods.len() as f32;
+        assert!((average - exact).abs() < 0.01, "{average} {exact}");
+    }
+
+    #[test]
+    fn beep_keeps_its_period_at_44100_hz() {
+        // 100.2 samples per period: mostly 100, with a longer one now and then to keep the frequency
+        assert_periods(44_100);
+    }
+
+    #[test]
+    fn beep_keeps_its_period_at_48000_hz() {
+        // 109.1 samples per period
+        assert_periods(48_000);
+    }
+
+    #[test]
+    fn buffer_sizes_do_not_change_the_wave() {
+        let (mut gate, mut whole) = source(48_000);
+        gate.set_active(true);
+        let mut expected = [0.0; 600];
+        whole.fill(&mut expected);
+        let (mut gate, mut chunked) = source(48_000);
+        gate.set_active(true);
+        let mut samples = [0.0; 600];
+        for chunk in samples.chunks_mut(37) {
+            chunked.fill(chunk);
+        }
+        assert_eq!(samples, expected);
+    }
+
+    #[test]
+    fn gate_sound_hook_follows_the_sound_timer() {
+        // V0 = 2, then ST = V0
+        let mut chip8 = Chip8::with_program(&[0x6002, 0xF018]);
+        let gate = BeepGate::default();
+        chip8.set_sound_hook(gate.sound_hook());
+        chip8.emulate_cycle().unwrap();
+        chip8.emulate_cycle().unwrap();
+        assert!(gate.is_open());
+        chip8.tick_timers();
+        assert!(gate.is_open());
+        chip8.tick_timers();
+        assert!(!gate.is_open());
+    }
+
+    #[test]
+    fn gate_sound_hook_closes_the_gate_within_a_batch_of_frames() {
+        // ST = 3 with the first frame, then loops
+        let mut chip8 = Chip8::with_program(&[0x6003, 0xF018, 0x1204]);
+        let gate = BeepGate::default();
+        chip8.set_sound_hook(gate.sound_hook());
+        chip8.run_frame().unwrap();
+        assert!(gate.is_open());
+        // Closed by the tick of the frame the sound timer runs out with, not at the end of the batch
+        let ticks = Rc::new(RefCell::new(Vec::new()));
+        let (tick_gate, tick_states) = (gate.clone(), ticks.clone());
+        chip8.set_tick_hook(Box::new(move |_| tick_states.borrow_mut().push(tick_gate.is_open())));
+        chip8.run_for(crate::FRAME_DURATION * 3).unwrap();
+        assert_eq!(*ticks.borrow(), [true, false, false]);
+    }
+
+    #[test]
+    fn reset_closes_the_gate_of_the_sound_hook() {
+        let mut chip8 = Chip8::with_program(&[0x601E, 0xF018]);
+        let gate = BeepGate::default();
+        chip8.set_sound_hook(gate.sound_hook());
+        chip8.step_n(2).unwrap();
+        assert!(gate.is_open());
+        chip8.reset();
+        assert!(!gate.is_open());
+    }
+
+    #[test]
+    fn held_gate_is_silent_while_the_sound_hook_goes_on() {
+        // V0 = 30, then ST = V0
+        let mut chip8 = Chip8::with_program(&[0x601E, 0xF018]);
+        let (gate, mut source) = source(48_000);
+        chip8.set_sound_hook(gate.sound_hook());
+        gate.set_held(true);
+        chip8.step_n(2).unwrap();
+        assert!(gate.is_open());
+        assert!(source.by_ref().take(1000).all(|sample| sample == 0.0));
+        // Sounds on release while the sound timer runs
+        gate.set_held(false);
+        source.by_ref().take(96).for_each(drop);
+        assert!(source.take(1000).all(|sample| (sample.abs() - BEEP_VOLUME).abs() < 1e-6));
+    }
+
+    #[test]
+    fn gain_is_the_volume_clamped_to_100_or_zero_when_muted() {
+        assert_eq!(gain(100, false), 1.0);
+        assert_eq!(gain(50, false), 0.5);
+        assert_eq!(gain(0, false), 0.0);
+        // Clamped to 100
+        assert_eq!(gain(255, false), 1.0);
+        assert_eq!(gain(100, true), 0.0);
+        let gate = BeepGate::default();
+        gate.set_volume(150);
+        assert_eq!(gate.volume(), 100);
+    }
+
+    #[test]
+    fn volume_scales_the_wave() {
+        let (mut gate, mut source) = source(48_000);
+        gate.set_volume(40);
+        gate.set_active(true);
+        source.by_ref().take(1000).for_each(drop);
+        assert!(source.take(1000).all(|sample| (sample.abs() - BEEP_VOLUME * 0.4).abs() < 1e-6));
+    }
+
+    #[test]
+    fn beep_is_silent_at_volume_0_while_the_sound_timer_runs() {
+        // V0 = 30, then ST = V0
+        let mut chip8 = Chip8::with_program(&[0x601E, 0xF018]);
+        let (gate, mut source) = source(48_000);
+        gate.set_volume(0);
+        chip8.set_sound_hook(gate.sound_hook());
+        chip8.emulate_cycle().unwrap();
+        chip8.emulate_cycle().unwrap();
+        assert!(chip8.sound_active());
+        assert!(gate.is_open());
+        let mut samples = [1.0; 1024];
+        source.fill(&mut samples);
+        assert!(samples.iter().all(|&sample| sample == 0.0));
+    }
+
+    #[test]
+    fn mute_fades_the_beep_out_and_back_in() {
+        let (mut gate, mut source) = source(48_000);
+        gate.set_active(true);
+        source.by_ref().take(1000).for_each(drop);
+        gate.set_muted(true);
+        let fade: Vec<f32> = source.by_ref().take(96).collect();
+        assert!(fade.iter().all(|sample| sample.abs() < BEEP_VOLUME));
+        assert!(source.by_ref().take(1000).all(|sample| sample == 0.0));
+        // The gate stays open while muted
+        assert!(gate.is_open());
+        gate.set_muted(false);
+        source.by_ref().take(96).for_each(drop);
+        assert!(source.take(1000).all(|sample| (sample.abs() - BEEP_VOLUME).abs() < 1e-6));
+    }
+
+    #[test]
+    fn beep_plays_the_tone() {
+        let gate = BeepGate::default();
+        let mut source = BeepSource::new(gate.clone(), 48_000).tone(Waveform::Triangle, 220.0);
+        gate.clone().set_active(true);
+        source.by_ref().take(96).for_each(drop);
+        let tone = Tone::new(Waveform::Triangle, 220.0, 48_000).skip(96);
+        for (sample, expected) in source.by_ref().zip(tone).take(1000) {
+            assert!((sample - expected * BEEP_VOLUME).abs() < 1e-6);
+        }
+        source.set_tone(Waveform::Sine, 480.0);
+        assert_eq!(source.sample_rate(), 48_000);
+        // A new tone starts its period
+        let samples: Vec<f32> = source.take(100).collect();
+        assert_eq!(samples[0], 0.0);
+        assert!((samples[25] - BEEP_VOLUME).abs() < 1e-6);
+    }
+
+    #[test]
+    fn beep_plays_the_pattern_instead_of_the_tone() {
+        let (mut gate, mut source) = source(48_000);
+        // 8 bits set and 8 cleared at 4000 bits per second: 96 samples at each level
+        let pattern = [0xFF, 0x00].repeat(8).try_into().unwrap();
+        gate.set_pattern(Some((&pattern, 4000.0)));
+        gate.set_active(true);
+        source.by_ref().take(192).for_each(drop);
+        let samples: Vec<f32> = source.by_ref().take(192 * 4).collect();
+        for (index, &sample) in samples.iter().enumerate() {
+            let expected = if index % 192 < 96 { BEEP_VOLUME } else { -BEEP_VOLUME };
+            assert!((sample - expected).abs() < 1e-6, "sample {index}");
+        }
+        // Back to the tone, a square wave at BEEP_FREQUENCY
+        gate.set_pattern(None);
+        let tone = Tone::new(Waveform::Square, BEEP_FREQUENCY, 48_000);
+        for (sample, expected) in source.zip(tone).take(1000) {
+            assert!((sample - expected * BEEP_VOLUME).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn beep_plays_the_pattern_loaded_by_a_program() {
+        // F002 loads the 16 bytes at I, then V0 = 112 and FX3A: 8000 bits per second
+        let mut chip8 = Chip8::with_program(&[0xA20A, 0xF002, 0x6070, 0xF03A, 0x1208, 0x8000]);
+        for _ in 0..4 {
+            chip8.emulate_cycle().unwrap();
+        }
+        let (mut gate, mut source) = source(48_000);
+        gate.set_pattern(chip8.loaded_audio_pattern());
+        gate.set_active(true);
+        // The first bit set and the next ones cleared: 6 samples at 8000 bits per second, then 90 for 15 bits
+        source.by_ref().take(768).for_each(drop);
+        let samples: Vec<f32> = source.take(96).collect();
+        assert!(samples[..6].iter().all(|&sample| (sample - BEEP_VOLUME).abs() < 1e-6));
+        assert!(samples[6..].iter().all(|&sample| (sample + BEEP_VOLUME).abs() < 1e-6));
+    }
+}
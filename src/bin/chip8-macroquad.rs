@@ -0,0 +1,317 @@
+// Runs a ROM in a macroquad window with sound, until the window is closed or Esc is pressed:
+// cargo run --features macroquad --bin chip8-macroquad -- [--scale N] [--theme amber] [--phosphor 4] pong.rom
+
+use std::env;
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+#[cfg(feature = "screenshot")]
+use macroquad::input::{is_key_pressed, KeyCode};
+use macroquad::time::get_frame_time;
+use macroquad::window::{next_frame, Conf};
+use chip8::{
+    overlay_lines, parse_color, parse_keymap, parse_palette, settings_path, window_size, Buzzer, Chip8Config, Control,
+    Crt, DebugInfo, FramePacer, Keymap, MacroquadFrontend, NullInput, Rotation, RunControl, Settings, Theme, Waveform,
+    BEEP_FREQUENCY, DEFAULT_SCALE, DEFAULT_TURBO, MAX_VOLUME,
+};
+
+// Instructions per second, about the speed of the COSMAC VIP interpreter
+const CLOCK_HZ: u32 = 700;
+
+const USAGE: &str = "Usage: chip8-macroquad [--scale N] [--theme classic|green|amber|paperwhite|inverted] \
+    [--fg #RRGGBB] [--bg #RRGGBB] [--palette octo|lcd|hotdog|gray|cga0|cga1|COLORS] [--phosphor FRAMES] [--crt] \
+    [--vignette] [--rotate 0|90|180|270] [--screenshot-dir DIR] [--volume 0..100] \
+    [--beep-freq HZ] [--beep-wave square|sine|triangle] [--turbo FACTOR] [--keymap KEY=NAME,...] [--show-keymap] \
+    <rom file>";
+
+struct Options {
+    // Window pixels per CHIP-8 pixel
+    scale: u32,
+    theme: Theme,
+    // Frames the pixels turned off stay visible
+    phosphor: u32,
+    crt: Crt,
+    // Rotation of the shown screen and the screenshots
+    rotation: Rotation,
+    // Volume of the beep, from --volume or kept from the last runs
+    volume: Option<u8>,
+    // Wave of the beep
+    beep_wave: Waveform,
+    beep_frequency: f32,
+    // Speed factor of the emulation while Tab is held
+    turbo: u32,
+    // Where F12 saves the screenshots, named after the ROM
+    #[cfg_attr(not(feature = "screenshot"), allow(dead_code))]
+    screenshot_dir: PathBuf,
+    // Keyboard keys of the keypad, from the settings file and --keymap
+    keymap: Keymap,
+    rom_path: String,
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("{message}\n{USAGE}");
+    process::exit(2);
+}
+
+fn parse_args() -> Options {
+    let mut scale = DEFAULT_SCALE;
+    let mut theme = Theme::default();
+    let mut phosphor = 0;
+    let mut crt = Crt::OFF;
+    let mut rotation = Rotation::None;
+    let mut volume = None;
+    let mut beep_wave = Waveform::default();
+    let mut beep_frequency = BEEP_FREQUENCY;
+    let mut turbo = DEFAULT_TURBO;
+    // Applied over the theme whatever the order of the options
+    let (mut foreground, mut background) = (None, None);
+    // Replaces all the colors
+    let mut palette = None;
+    let mut screenshot_dir = PathBuf::from(".");
+    let mut keymap = Vec::new();
+    let mut show_keymap = false;
+    let mut rom_path = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--scale" {
+            scale = args.next()
+                .and_then(|scale| scale.parse().ok())
+                .filter(|&scale| scale > 0)
+                .unwrap_or_else(|| usage_error("The scale must be a whole number above 0"));
+        } else if arg == "--phosphor" {
+            phosphor = args.next()
+                .and_then(|frames| frames.parse().ok())
+                .unwrap_or_else(|| usage_error("The phosphor decay must be a whole number of frames, 0 for none"));
+        } else if arg == "--crt" {
+            crt.scanlines = true;
+            crt.grid = true;
+        } else if arg == "--vignette" {
+            crt.vignette = true;
+        } else if arg == "--rotate" {
+            rotation = args.next()
+                .and_then(|degrees| degrees.parse().ok())
+                .and_then(Rotation::from_degrees)
+                .unwrap_or_else(|| usage_error("The rotation must be 0, 90, 180 or 270 degrees"));
+        } else if arg == "--volume" {
+            volume = Some(args.next()
+                .and_then(|volume| volume.parse().ok())
+                .filter(|&volume| volume <= MAX_VOLUME)
+                .unwrap_or_else(|| usage_error("The volume must be a whole number from 0 to 100")));
+        } else if arg == "--beep-freq" {
+            beep_frequency = args.next()
+                .and_then(|frequency| frequency.parse().ok())
+                .filter(|&frequency: &f32| (20.0..=20_000.0).contains(&frequency))
+                .unwrap_or_else(|| usage_error("The beep frequency must be a number of Hz from 20 to 20000"));
+        } else if arg == "--beep-wave" {
+            beep_wave = args.next()
+                .and_then(|name| Waveform::from_name(&name))
+                .unwrap_or_else(|| usage_error("The beep wave must be square, sine or triangle"));
+        } else if arg == "--turbo" {
+            turbo = args.next()
+                .and_then(|factor| factor.parse().ok())
+                .filter(|&factor| factor > 0)
+                .unwrap_or_else(|| usage_error("The turbo speed must be a whole number above 0, 1 for none"));
+        } else if arg == "--screenshot-dir" {
+            screenshot_dir = args.next().map(PathBuf::from).unwrap_or_else(|| usage_error("Missing screenshot directory"));
+        } else if arg == "--keymap" {
+            let assignments = args.next().unwrap_or_else(|| usage_error("Missing keymap"));
+            keymap = parse_keymap(&assignments).unwrap_or_else(|error| usage_error(&error.to_string()));
+        } else if arg == "--show-keymap" {
+            show_keymap = true;
+        } else if arg == "--theme" {
+            let name = args.next().unwrap_or_else(|| usage_error("Missing theme name"));
+            theme = Theme::preset(&name).unwrap_or_else(|error| usage_error(&error.to_string()));
+        } else if arg == "--palette" {
+            let colors = args.next().unwrap_or_else(|| usage_error("Missing palette"));
+            palette = Some(parse_palette(&colors).unwrap_or_else(|error| usage_error(&error.to_string())));
+        } else if arg == "--fg" || arg == "--bg" {
+            let color = args.next().unwrap_or_else(|| usage_error(&format!("Missing color after {arg}")));
+            let color = Some(parse_color(&color).unwrap_or_else(|error| usage_error(&error.to_string())));
+            if arg == "--fg" {
+                foreground = color;
+            } else {
+                background = color;
+            }
+        } else {
+            rom_path = Some(arg);
+        }
+    }
+    // --keymap changes the keys given in the settings file, which change the default ones. The map is shown before
+    // the window opens
+    let keymap = match parse_keymap(&Settings::load().keymap) {
+        Ok(assignments) => Keymap::default().assign(&assignments).assign(&keymap),
+        Err(error) => {
+            let path = settings_path().unwrap_or_default();
+            eprintln!("Invalid [keymap] section in {} : {error}", path.display());
+            process::exit(2);
+        }
+    };
+    if show_keymap {
+        print!("{keymap}");
+        process::exit(0);
+    }
+    let Some(rom_path) = rom_path else {
+        usage_error("Missing rom file");
+    };
+    if let Some(foreground) = foreground {
+        theme = theme.foreground(foreground);
+    }
+    if let Some(background) = background {
+        theme = theme.background(background);
+    }
+    if let Some(palette) = palette {
+        if foreground.is_some() || background.is_some() {
+            usage_error("--palette gives all the colors, without --fg and --bg");
+        }
+        theme = palette;
+    }
+    Options {
+        scale,
+        theme,
+        phosphor,
+        crt,
+        rotation,
+        volume,
+        beep_wave,
+        beep_frequency,
+        turbo,
+        screenshot_dir,
+        keymap,
+        rom_path,
+    }
+}
+
+// macroquad opens the window before main, the arguments are read here for its size
+fn window_conf() -> Conf {
+    let options = parse_args();
+    let (width, height) = options.rotation.size(window_size(options.scale));
+    Conf {
+        window_title: String::from("Chip8"),
+        window_width: width as i32,
+        window_height: height as i32,
+        ..Conf::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    let options = parse_args();
+
+    // Initialize the chip 8 system and load the game into the memory
+    let mut chip8 = Chip8Config::new().clock_hz(CLOCK_HZ).build().expect("the default configuration is valid");
+    if let Err(error) = chip8.load_game(&options.rom_path) {
+        eprintln!("{error}");
+        process::exit(1);
+    }
+
+    let mut frontend = MacroquadFrontend::new().await;
+    frontend.set_theme(options.theme);
+    frontend.set_phosphor(options.phosphor);
+    frontend.set_crt(options.crt);
+    frontend.set_rotation(options.rotation);
+    frontend.set_keymap(options.keymap.clone());
+    frontend.set_tone(options.beep_wave, options.beep_frequency).await;
+    // The volume given is kept for the next runs
+    let mut settings = Settings::load();
+    if let Some(volume) = options.volume {
+        settings.volume = volume;
+        settings.save();
+    }
+    frontend.set_volume(settings.volume);
+    frontend.set_muted(settings.muted);
+
+    // Only measures the speed shown by the overlay, macroquad paces the frames
+    let mut pacer = FramePacer::new();
+    // macroquad does not tell the focus, only P pauses
+    let mut run = RunControl::new(false);
+    let mut turbo = false;
+    loop { // Emulation loop, once per displayed frame, until Esc is pressed
+        // P pauses and resumes, Backspace restarts the program, Esc quits
+        for control in frontend.take_controls() {
+            match control {
+                Control::Pause => {
+                    if let Some(paused) = run.toggle_pause() {
+                        // Silent while paused, the sound timer does not tick
+                        frontend.set_active(!paused && !turbo && chip8.sound_active());
+                    }
+                }
+                Control::Reset => {
+                    chip8.reset();
+                    chip8.render(&mut frontend);
+                }
+                Control::Quit => return,
+            }
+        }
+        // Tab fast-forwards while held, without the beep which would screech. The overlay shows the speed
+        if frontend.turbo_held() != turbo {
+            turbo = !turbo;
+            chip8.set_speed(if turbo { options.turbo } else { 1 });
+            frontend.set_active(!run.paused() && !turbo && chip8.sound_active());
+        }
+
+        // Runs the frames due since the last displayed one, the timers tick once per frame. Nothing runs while paused
+        // and the keys read as released
+        let frame = if run.paused() {
+            chip8.set_keys(&mut NullInput);
+            pacer.skip()
+        } else {
+            chip8.set_keys(&mut frontend);
+            let frame = match chip8.run_for(Duration::from_secs_f32(get_frame_time())) {
+                Ok(frame) => frame,
+                Err(error) => {
+                    eprintln!("{error}");
+                    process::exit(1);
+                }
+            };
+            pacer.count(1, frame.instructions);
+            frame
+        };
+        if frame.halted { // The program exited
+            break;
+        }
+
+        if frame.drew { // If the screen changed, update it
+            chip8.render(&mut frontend);
+        }
+
+        // The buzzer stays off while fast-forwarding, it follows the sound timer again afterwards
+        if !turbo {
+            chip8.update_buzzer(&mut frontend);
+        }
+        // M mutes the beep and unmutes it, for the next runs too. The sound timer goes on
+        if frontend.mute_requested() {
+            settings.muted = !settings.muted;
+            frontend.set_muted(settings.muted);
+            settings.save();
+        }
+
+        // Saves the screen at the size and rotation it has in a window of the scale, a failure only loses the
+        // screenshot
+        #[cfg(feature = "screenshot")]
+        if is_key_pressed(KeyCode::F12) {
+            let framebuffer = chip8::rotate(&chip8.framebuffer(), chip8.width(), options.rotation);
+            let (width, _) = options.rotation.size(chip8.resolution());
+            let scale = (options.rotation.size(window_size(options.scale)).0 as usize / width).max(1);
+            let rom_path = std::path::Path::new(&options.rom_path);
+            match chip8::save_screenshot(&options.screenshot_dir, rom_path, &framebuffer, width, scale, &options.theme) {
+                Ok(path) => println!("Screenshot saved to {}", path.display()),
+                Err(error) => eprintln!("Cannot save the screenshot : {error}"),
+            }
+        }
+
+        // The title of the window cannot change, the overlay tells the mute and the pause
+        if frontend.overlay_shown() {
+            let mut lines = overlay_lines(&DebugInfo::new(&chip8, pacer.throughput(), pacer.skipped_frames()));
+            if settings.muted {
+                lines.push(String::from("MUTED"));
+            }
+            if run.paused() {
+                lines.push(String::from("PAUSED"));
+            }
+            frontend.set_overlay(&lines);
+        }
+
+        frontend.present();
+        next_frame().await;
+    }
+}
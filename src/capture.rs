@@ -0,0 +1,36 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./capture_tests.rs"]
+mod capture_tests;
+
+// Names of the files captured from the screen, the screenshots and the recordings
+
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::rom_name;
+
+// <ROM name>-<YYYYMMDD>-<HHMMSS>.<extension>, the time in UTC. The name of the ROM is its file name without the
+// extension
+pub fn capture_file_name(rom_path: &Path, time: SystemTime, extension: &str) -> String {
+    let rom_name = rom_name(rom_path);
+    let seconds = time.duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+    let (year, month, day) = civil_date(seconds / 86_400);
+    let (hours, minutes, seconds) = (seconds / 3600 % 24, seconds / 60 % 60, seconds % 60);
+    format!("{rom_name}-{year:04}{month:02}{day:02}-{hours:02}{minutes:02}{seconds:02}.{extension}")
+}
+
+// Year, month and day of a number of days since 1970-01-01, in the Gregorian calendar
+fn civil_date(days: u64) -> (u64, u64, u64) {
+    // Days since 0000-03-01, so that the leap day ends the year, split in eras of 400 years
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    // Months starting in March
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+    (year, month, day)
+}
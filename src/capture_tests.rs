@@ -0,0 +1,20 @@
+#[cfg(test)]
+mod capture_tests {
+    use std::path::Path;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    use crate::capture::capture_file_name;
+
+    fn at(time: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(time)
+    }
+
+    #[test]
+    fn capture_file_name_is_the_rom_name_then_the_utc_date_and_time() {
+        let rom = Path::new("roms/Space Invaders.ch8");
+        assert_eq!(capture_file_name(rom, at(0), "png"), "Space Invaders-19700101-000000.png");
+        assert_eq!(capture_file_name(rom, at(1_709_251_199), "png"), "Space Invaders-20240229-235959.png");
+        assert_eq!(capture_file_name(Path::new("pong"), at(951_782_400), "png"), "pong-20000229-000000.png");
+        assert_eq!(capture_file_name(Path::new("pong.rom"), at(4_102_444_800), "png"), "pong-21000101-000000.png");
+        assert_eq!(capture_file_name(Path::new("pong.rom"), at(0), "gif"), "pong-19700101-000000.gif");
+    }
+}
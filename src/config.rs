@@ -0,0 +1,253 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./config_tests.rs"]
+mod config_tests;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use crate::{Chip8, Chip8Error, FontStyle, ProtectedWritePolicy, Random, SysOpcodePolicy, Variant};
+
+// Settings of a Chip8 built with Chip8Config::new().shift_uses_vy(true).clock_hz(700).seed(42).build().
+// The default settings are the ones of Chip8::default()
+#[derive(Debug, Clone)]
+pub struct Chip8Config {
+    // Variant whose quirks were picked, some of them can have been changed afterwards
+    pub(crate) variant: Option<Variant>,
+    pub(crate) vf_reset: bool,
+    pub(crate) load_store_quirk: bool,
+    pub(crate) display_wait: bool,
+    pub(crate) clip_sprites: bool,
+    pub(crate) shift_uses_vy: bool,
+    pub(crate) jump_uses_vx: bool,
+    pub(crate) lores_half_scroll: bool,
+    pub(crate) key_wait_on_release: bool,
+    pub(crate) index_overflow_quirk: bool,
+    pub(crate) index_mask_12bit: bool,
+    pub(crate) sys_opcode_policy: SysOpcodePolicy,
+    pub(crate) protected_write_policy: ProtectedWritePolicy,
+    pub(crate) auto_variant: bool,
+    pub(crate) hires_chip8: bool,
+    pub(crate) start_address: u16,
+    pub(crate) font: FontStyle,
+    pub(crate) clock_hz: u32,
+    pub(crate) seed: Option<u64>,
+}
+
+impl Default for Chip8Config {
+    fn default() -> Chip8Config {
+        Chip8Config {
+            variant: None,
+            vf_reset: false,
+            load_store_quirk: false,
+            display_wait: false,
+            clip_sprites: false,
+            shift_uses_vy: false,
+            jump_uses_vx: false,
+            lores_half_scroll: false,
+            key_wait_on_release: false,
+            index_overflow_quirk: false,
+            index_mask_12bit: false,
+            sys_opcode_policy: SysOpcodePolicy::Ignore,
+            protected_write_policy: ProtectedWritePolicy::Allow,
+            auto_variant: false,
+            hires_chip8: false,
+            start_address: 0x200,
+            font: FontStyle::Chip8,
+            clock_hz: 500,
+            seed: None,
+        }
+    }
+}
+
+impl Chip8Config {
+    pub fn new() -> Chip8Config {
+        Chip8Config::default()
+    }
+
+    // Sets all the quirks to the behavior of the given platform. Quirks can still be changed individually afterwards
+    pub fn variant(self, variant: Variant) -> Chip8Config {
+        match variant {
+            Variant::Chip8 => Chip8Config {
+                variant: Some(variant),
+                vf_reset: true,
+                load_store_quirk: false,
+                display_wait: true,
+                clip_sprites: true,
+                shift_uses_vy: true,
+                jump_uses_vx: false,
+                lores_half_scroll: false,
+                key_wait_on_release: true,
+                ..self
+            },
+            // CHIP-48 increments I by X only after FX55/FX65, the closest behavior is incrementing it by X + 1
+            Variant::Chip48 => Chip8Config {
+                variant: Some(variant),
+                vf_reset: false,
+                load_store_quirk: false,
+                display_wait: false,
+                clip_sprites: true,
+                shift_uses_vy: false,
+                jump_uses_vx: true,
+                lores_half_scroll: false,
+                key_wait_on_release: false,
+                ..self
+            },
+            Variant::SuperChip => Chip8Config {
+                variant: Some(variant),
+                vf_reset: false,
+                load_store_quirk: true,
+                display_wait: false,
+                clip_sprites: true,
+                shift_uses_vy: false,
+                jump_uses_vx: true,
+                lores_half_scroll: true,
+                key_wait_on_release: false,
+                ..self
+            },
+            Variant::XoChip => Chip8Config {
+                variant: Some(variant),
+                vf_reset: false,
+                load_store_quirk: false,
+                display_wait: false,
+                clip_sprites: false,
+                shift_uses_vy: true,
+                jump_uses_vx: false,
+                lores_half_scroll: false,
+                key_wait_on_release: true,
+                ..self
+            },
+        }
+    }
+
+    // Quirk: the COSMAC VIP 8XY1, 8XY2 and 8XY3 reset VF to 0
+    pub fn vf_reset(self, enabled: bool) -> Chip8Config {
+        Chip8Config { vf_reset: enabled, ..self }
+    }
+
+    // Quirk: SCHIP leaves I unchanged after FX55/FX65, the COSMAC VIP interpreter increments it by X + 1
+    pub fn load_store_quirk(self, enabled: bool) -> Chip8Config {
+        Chip8Config { load_store_quirk: enabled, ..self }
+    }
+
+    // Quirk: the COSMAC VIP waits for the vertical blank after drawing a sprite
+    pub fn display_wait(self, enabled: bool) -> Chip8Config {
+        Chip8Config { display_wait: enabled, ..self }
+    }
+
+    // Quirk: the COSMAC VIP clips sprites running off the right or bottom edge instead of wrapping them around
+    pub fn clip_sprites(self, enabled: bool) -> Chip8Config {
+        Chip8Config { clip_sprites: enabled, ..self }
+    }
+
+    // Quirk: the COSMAC VIP 8XY6 and 8XYE shift VY and store the result in VX
+    pub fn shift_uses_vy(self, enabled: bool) -> Chip8Config {
+        Chip8Config { shift_uses_vy: enabled, ..self }
+    }
+
+    // Quirk: CHIP-48 and SCHIP read BNNN as BXNN
+    pub fn jump_uses_vx(self, enabled: bool) -> Chip8Config {
+        Chip8Config { jump_uses_vx: enabled, ..self }
+    }
+
+    // Quirk: the legacy SCHIP interpreter scrolls by half the distance in low resolution mode
+    pub fn lores_half_scroll(self, enabled: bool) -> Chip8Config {
+        Chip8Config { lores_half_scroll: enabled, ..self }
+    }
+
+    // Quirk: the COSMAC VIP FX0A only completes once the pressed key is released, like Octo for XO-CHIP. Without it
+    // FX0A completes on the press
+    pub fn key_wait_on_release(self, enabled: bool) -> Chip8Config {
+        Chip8Config { key_wait_on_release: enabled, ..self }
+    }
+
+    // Quirk: the Amiga interpreter sets VF to 1 when FX1E makes I overflow past 0x0FFF
+    pub fn index_overflow_quirk(self, enabled: bool) -> Chip8Config {
+        Chip8Config { index_overflow_quirk: enabled, ..self }
+    }
+
+    // Wraps I to 12 bits like the original interpreters
+    pub fn index_mask_12bit(self, enabled: bool) -> Chip8Config {
+        Chip8Config { index_mask_12bit: enabled, ..self }
+    }
+
+    pub fn sys_opcode_policy(self, policy: SysOpcodePolicy) -> Chip8Config {
+        Chip8Config { sys_opcode_policy: policy, ..self }
+    }
+
+    // What to do when a program writes below 0x200, where the fonts are
+    pub fn protected_write_policy(self, policy: ProtectedWritePolicy) -> Chip8Config {
+        Chip8Config { protected_write_policy: policy, ..self }
+    }
+
+    // Picks the quirks of the variant detected when loading the program
+    pub fn auto_variant(self, enabled: bool) -> Chip8Config {
+        Chip8Config { auto_variant: enabled, ..self }
+    }
+
+    // 64x64 display of the hires CHIP-8 variant
+    pub fn hires_chip8(self, enabled: bool) -> Chip8Config {
+        Chip8Config { hires_chip8: enabled, ..self }
+    }
+
+    // Address where the program is loaded and starts, 0x600 for ETI-660 programs
+    pub fn start_address(self, address: u16) -> Chip8Config {
+        Chip8Config { start_address: address, ..self }
+    }
+
+    // Built-in font pointed at by FX29, kept by Chip8::reset
+    pub fn font(self, font: FontStyle) -> Chip8Config {
+        Chip8Config { font, ..self }
+    }
+
+    // Instructions executed per second by the frontend
+    pub fn clock_hz(self, hz: u32) -> Chip8Config {
+        Chip8Config { clock_hz: hz, ..self }
+    }
+
+    // Instructions executed per 60 Hz frame by run_frame, the same as a clock speed of 60 times that
+    pub fn instructions_per_frame(self, instructions: u32) -> Chip8Config {
+        Chip8Config { clock_hz: instructions * 60, ..self }
+    }
+
+    // Seeds the random number generator of CXNN so that runs can be reproduced
+    pub fn seed(self, seed: u64) -> Chip8Config {
+        Chip8Config { seed: Some(seed), ..self }
+    }
+
+    // Fails when the start address is odd or outside of the memory, or when it leaves no room for another font
+    pub fn build(self) -> Result<Chip8, Chip8Error> {
+        let mut chip8 = Chip8::default();
+        chip8.set_start_address(self.start_address)?;
+        // The COSMAC VIP font is already loaded
+        if self.font != FontStyle::Chip8 {
+            chip8.load_font(self.font.glyphs())?;
+        }
+        chip8.set_quirks(&self);
+        chip8.index_overflow_quirk = self.index_overflow_quirk;
+        chip8.index_mask_12bit = self.index_mask_12bit;
+        chip8.sys_opcode_policy = self.sys_opcode_policy;
+        chip8.memory.protected_write_policy = self.protected_write_policy;
+        chip8.auto_variant = self.auto_variant;
+        chip8.screen.hires_chip8 = self.hires_chip8;
+        chip8.clock_hz = self.clock_hz;
+        if let Some(seed) = self.seed {
+            chip8.rng = Random(StdRng::seed_from_u64(seed));
+        }
+        Ok(chip8)
+    }
+}
+
+impl Chip8 {
+    // Copies the quirks changed by a variant
+    pub(crate) fn set_quirks(&mut self, config: &Chip8Config) {
+        self.variant = config.variant;
+        self.vf_reset = config.vf_reset;
+        self.load_store_quirk = config.load_store_quirk;
+        self.display_wait = config.display_wait;
+        self.clip_sprites = config.clip_sprites;
+        self.shift_uses_vy = config.shift_uses_vy;
+        self.jump_uses_vx = config.jump_uses_vx;
+        self.lores_half_scroll = config.lores_half_scroll;
+        self.key_wait_on_release = config.key_wait_on_release;
+    }
+}
@@ -0,0 +1,197 @@
+#[cfg(test)]
+mod config_tests {
+    use crate::{Chip8, Chip8Config, Chip8Error, FontStyle, ProtectedWritePolicy, SysOpcodePolicy, Variant};
+
+    fn quirks(chip8: &Chip8) -> (bool, bool, bool, bool, bool, bool, bool, bool) {
+        (
+            chip8.vf_reset,
+            chip8.load_store_quirk,
+            chip8.display_wait,
+            chip8.clip_sprites,
+            chip8.shift_uses_vy,
+            chip8.jump_uses_vx,
+            chip8.lores_half_scroll,
+            chip8.key_wait_on_release,
+        )
+    }
+
+    #[test]
+    fn variant_sets_the_quirks_of_the_cosmac_vip() {
+        let chip8 = Chip8Config::new().variant(Variant::Chip8).build().unwrap();
+
+        assert_eq!(quirks(&chip8), (true, false, true, true, true, false, false, true));
+    }
+
+    #[test]
+    fn variant_sets_the_quirks_of_chip48() {
+        let chip8 = Chip8Config::new().variant(Variant::Chip48).build().unwrap();
+
+        assert_eq!(quirks(&chip8), (false, false, false, true, false, true, false, false));
+    }
+
+    #[test]
+    fn variant_sets_the_quirks_of_superchip() {
+        let chip8 = Chip8Config::new().variant(Variant::SuperChip).build().unwrap();
+
+        assert_eq!(quirks(&chip8), (false, true, false, true, false, true, true, false));
+    }
+
+    #[test]
+    fn variant_sets_the_quirks_of_xochip() {
+        let chip8 = Chip8Config::new().variant(Variant::XoChip).build().unwrap();
+
+        assert_eq!(quirks(&chip8), (false, false, false, false, true, false, false, true));
+    }
+
+    #[test]
+    fn variant_is_remembered() {
+        assert_eq!(Chip8Config::new().build().unwrap().variant(), None);
+        let chip8 = Chip8Config::new().variant(Variant::SuperChip).clip_sprites(false).build().unwrap();
+
+        assert_eq!(chip8.variant(), Some(Variant::SuperChip));
+    }
+
+    #[test]
+    fn variant_quirks_can_be_overridden() {
+        let chip8 = Chip8Config::new().variant(Variant::SuperChip).clip_sprites(false).build().unwrap();
+
+        assert_eq!(quirks(&chip8), (false, true, false, false, false, true, true, false));
+    }
+
+    #[test]
+    fn variant_changes_the_result_of_the_same_program() {
+        let program = [
+            0x6F, 0x01, // VF = 1
+            0x60, 0x05, // V0 = 5
+            0x61, 0x03, // V1 = 3
+            0x80, 0x11, // V0 |= V1
+        ];
+        let mut chip8 = Chip8Config::new().variant(Variant::Chip8).build().unwrap();
+        let mut super_chip = Chip8Config::new().variant(Variant::SuperChip).build().unwrap();
+        chip8.memory[0x200..0x208].copy_from_slice(&program);
+        super_chip.memory[0x200..0x208].copy_from_slice(&program);
+
+        for _ in 0..4 {
+            chip8.emulate_cycle().unwrap();
+            super_chip.emulate_cycle().unwrap();
+        }
+
+        assert_eq!(chip8.register(0), 0x07);
+        assert_eq!(super_chip.register(0), 0x07);
+        assert_eq!(chip8.register(0x0F), 0);
+        assert_eq!(super_chip.register(0x0F), 1);
+    }
+
+    #[test]
+    fn default_config_builds_the_default_machine() {
+        let chip8 = Chip8Config::default().build().unwrap();
+        let default = Chip8::default();
+
+        assert_eq!(quirks(&chip8), quirks(&default));
+        assert_eq!(chip8.index_overflow_quirk, default.index_overflow_quirk);
+        assert_eq!(chip8.index_mask_12bit, default.index_mask_12bit);
+        assert_eq!(chip8.sys_opcode_policy, default.sys_opcode_policy);
+        assert_eq!(chip8.auto_variant, default.auto_variant);
+        assert_eq!(chip8.screen.hires_chip8, default.screen.hires_chip8);
+        assert_eq!(chip8.start_address, default.start_address);
+        assert_eq!(chip8.pc(), default.pc());
+        assert_eq!(chip8.clock_hz(), default.clock_hz());
+    }
+
+    #[test]
+    fn each_quirk_method_sets_its_quirk() {
+        assert!(Chip8Config::new().vf_reset(true).build().unwrap().vf_reset);
+        assert!(Chip8Config::new().load_store_quirk(true).build().unwrap().load_store_quirk);
+        assert!(Chip8Config::new().display_wait(true).build().unwrap().display_wait);
+        assert!(Chip8Config::new().clip_sprites(true).build().unwrap().clip_sprites);
+        assert!(Chip8Config::new().shift_uses_vy(true).build().unwrap().shift_uses_vy);
+        assert!(Chip8Config::new().jump_uses_vx(true).build().unwrap().jump_uses_vx);
+        assert!(Chip8Config::new().lores_half_scroll(true).build().unwrap().lores_half_scroll);
+        assert!(Chip8Config::new().key_wait_on_release(true).build().unwrap().key_wait_on_release);
+        assert!(Chip8Config::new().index_overflow_quirk(true).build().unwrap().index_overflow_quirk);
+        assert!(Chip8Config::new().index_mask_12bit(true).build().unwrap().index_mask_12bit);
+        assert!(Chip8Config::new().auto_variant(true).build().unwrap().auto_variant);
+        assert!(Chip8Config::new().hires_chip8(true).build().unwrap().screen.hires_chip8);
+    }
+
+    #[test]
+    fn sys_opcode_policy_sets_the_policy() {
+        let chip8 = Chip8Config::new().sys_opcode_policy(SysOpcodePolicy::Error).build().unwrap();
+
+        assert_eq!(chip8.sys_opcode_policy, SysOpcodePolicy::Error);
+    }
+
+    #[test]
+    fn protected_write_policy_sets_the_policy() {
+        let chip8 = Chip8Config::new().protected_write_policy(ProtectedWritePolicy::Deny).build().unwrap();
+
+        assert_eq!(chip8.memory.protected_write_policy, ProtectedWritePolicy::Deny);
+    }
+
+    #[test]
+    fn start_address_sets_where_the_program_is_loaded_and_starts() {
+        let chip8 = Chip8Config::new().start_address(0x600).build().unwrap();
+
+        assert_eq!(chip8.start_address, 0x600);
+        assert_eq!(chip8.pc(), 0x600);
+    }
+
+    #[test]
+    fn build_rejects_an_invalid_start_address() {
+        let result = Chip8Config::new().start_address(0x601).build();
+
+        assert!(matches!(result, Err(Chip8Error::InvalidStartAddress { addr: 0x601 })));
+    }
+
+    #[test]
+    fn font_sets_the_glyphs_pointed_at_by_fx29_also_after_a_reset() {
+        for font in [FontStyle::Chip8, FontStyle::Dream6800, FontStyle::Eti660] {
+            let mut chip8 = Chip8Config::new().font(font).build().unwrap();
+            chip8.load_rom(&[0x61, 0x07, 0xF1, 0x29]).unwrap(); // I = font digit 7
+
+            for _ in 0..2 {
+                chip8.step_n(2).unwrap();
+
+                let glyph = usize::from(chip8.index());
+                assert_eq!(chip8.memory()[glyph..glyph + 5], font.glyphs()[35..40]);
+                chip8.reset();
+            }
+        }
+        assert_ne!(FontStyle::Dream6800.glyphs()[35..40], FontStyle::Chip8.glyphs()[35..40]);
+    }
+
+    #[test]
+    fn clock_hz_sets_the_clock_speed() {
+        let chip8 = Chip8Config::new().clock_hz(700).build().unwrap();
+
+        assert_eq!(chip8.clock_hz(), 700);
+    }
+
+    #[test]
+    fn instructions_per_frame_sets_the_clock_speed() {
+        let chip8 = Chip8Config::new().instructions_per_frame(11).build().unwrap();
+
+        assert_eq!(chip8.clock_hz(), 660);
+        assert_eq!(chip8.instructions_per_frame(), 11);
+    }
+
+    // CXNN with NN = 0xFF stores the random number as is
+    fn random_numbers(chip8: &mut Chip8) -> Vec<u8> {
+        (0..8).map(|_| {
+            chip8.op_0xcxnn(0, 0xFF);
+            chip8.v[0]
+        }).collect()
+    }
+
+    #[test]
+    fn seed_makes_the_random_numbers_reproducible() {
+        let mut chip8 = Chip8Config::new().seed(42).build().unwrap();
+        let mut same_seed = Chip8Config::new().seed(42).build().unwrap();
+        let mut other_seed = Chip8Config::new().seed(43).build().unwrap();
+
+        let numbers = random_numbers(&mut chip8);
+
+        assert_eq!(numbers, random_numbers(&mut same_seed));
+        assert_ne!(numbers, random_numbers(&mut other_seed));
+    }
+}
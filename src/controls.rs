@@ -0,0 +1,92 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./controls_tests.rs"]
+mod controls_tests;
+
+// Keys controlling the emulator instead of the program: P pauses and resumes, Backspace and R reset the program, Esc
+// and Q quit. The frontends read them before the keypad so that a program never sees them. P, R and Q are letters the
+// keymap may give to the keypad, R and Q are on it by default: a letter of the keypad stays a keypad key. M muting the
+// beep is read the same way
+
+use crate::{FocusPause, Keymap, PhysicalKey};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    Pause,
+    Reset,
+    Quit,
+}
+
+// Keys of the controls, read by the frontends in their own key codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlKey {
+    Escape,
+    Backspace,
+    Letter(PhysicalKey),
+}
+
+impl ControlKey {
+    pub const ALL: [ControlKey; 5] = [
+        ControlKey::Escape,
+        ControlKey::Backspace,
+        ControlKey::Letter(PhysicalKey::P),
+        ControlKey::Letter(PhysicalKey::R),
+        ControlKey::Letter(PhysicalKey::Q),
+    ];
+}
+
+// Control of a key pressed, None for a letter the keymap gives to the keypad
+pub fn control(key: ControlKey, keymap: &Keymap) -> Option<Control> {
+    match key {
+        ControlKey::Escape => Some(Control::Quit),
+        ControlKey::Backspace => Some(Control::Reset),
+        ControlKey::Letter(letter) if !letter_free(letter, keymap) => None,
+        ControlKey::Letter(PhysicalKey::P) => Some(Control::Pause),
+        ControlKey::Letter(PhysicalKey::R) => Some(Control::Reset),
+        ControlKey::Letter(PhysicalKey::Q) => Some(Control::Quit),
+        ControlKey::Letter(_) => None,
+    }
+}
+
+// Whether a letter of the emulator keys, P, R, Q or M, controls the emulator: the keymap does not give it to the keypad
+pub fn letter_free(letter: PhysicalKey, keymap: &Keymap) -> bool {
+    keymap.key(letter).is_none()
+}
+
+// Pause of the emulation, asked with P or by the focus loss. Getting the focus back does not resume a game paused with
+// P, and P does not resume it while the window does not have the focus
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunControl {
+    focus: FocusPause,
+    paused_by_key: bool,
+}
+
+impl RunControl {
+    pub fn new(pause_on_focus_loss: bool) -> RunControl {
+        RunControl { focus: FocusPause::new(pause_on_focus_loss), paused_by_key: false }
+    }
+
+    // Follows the focus of the window, read once per frame. Returns the new state when the emulation was paused or
+    // resumed by this call
+    pub fn update_focus(&mut self, focused: bool) -> Option<bool> {
+        let paused = self.paused();
+        self.focus.update(focused);
+        (self.paused() != paused).then_some(self.paused())
+    }
+
+    // P pressed, returns the new state when the emulation was paused or resumed
+    pub fn toggle_pause(&mut self) -> Option<bool> {
+        let paused = self.paused();
+        self.paused_by_key = !self.paused_by_key;
+        (self.paused() != paused).then_some(self.paused())
+    }
+
+    // While paused no instruction runs and the timers do not tick, the last screen stays shown
+    pub fn paused(&self) -> bool {
+        self.paused_by_key || self.focus.paused()
+    }
+
+    pub fn paused_by_key(&self) -> bool {
+        self.paused_by_key
+    }
+}
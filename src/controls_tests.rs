@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod controls_tests {
+    use crate::{control, letter_free, parse_keymap, Control, ControlKey, Keymap, PhysicalKey, RunControl};
+
+    #[test]
+    fn control_leaves_the_keypad_letters_of_the_default_keymap() {
+        let keymap = Keymap::default();
+        assert_eq!(control(ControlKey::Escape, &keymap), Some(Control::Quit));
+        assert_eq!(control(ControlKey::Backspace, &keymap), Some(Control::Reset));
+        assert_eq!(control(ControlKey::Letter(PhysicalKey::P), &keymap), Some(Control::Pause));
+        // 4 and D of the keypad
+        assert_eq!(control(ControlKey::Letter(PhysicalKey::Q), &keymap), None);
+        assert_eq!(control(ControlKey::Letter(PhysicalKey::R), &keymap), None);
+        assert_eq!(control(ControlKey::Letter(PhysicalKey::M), &keymap), None);
+    }
+
+    #[test]
+    fn control_reads_the_letters_left_by_the_keymap() {
+        let keymap = Keymap::default().assign(&parse_keymap("4=Up,D=Down,5=P").unwrap());
+        assert_eq!(control(ControlKey::Letter(PhysicalKey::Q), &keymap), Some(Control::Quit));
+        assert_eq!(control(ControlKey::Letter(PhysicalKey::R), &keymap), Some(Control::Reset));
+        assert_eq!(control(ControlKey::Letter(PhysicalKey::P), &keymap), None);
+        // Esc and Backspace are never keypad keys
+        assert_eq!(control(ControlKey::Escape, &keymap), Some(Control::Quit));
+    }
+
+    #[test]
+    fn m_mutes_only_while_the_keymap_leaves_it_free() {
+        assert!(letter_free(PhysicalKey::M, &Keymap::default()));
+        let keymap = Keymap::default().assign(&parse_keymap("5=M").unwrap());
+        assert!(!letter_free(PhysicalKey::M, &keymap));
+    }
+
+    #[test]
+    fn toggle_pause_pauses_and_resumes() {
+        let mut run = RunControl::new(true);
+        assert!(!run.paused());
+        assert_eq!(run.toggle_pause(), Some(true));
+        assert!(run.paused() && run.paused_by_key());
+        assert_eq!(run.toggle_pause(), Some(false));
+        assert!(!run.paused());
+    }
+
+    #[test]
+    fn focus_does_not_resume_a_pause_by_key() {
+        let mut run = RunControl::new(true);
+        assert_eq!(run.toggle_pause(), Some(true));
+        // Already paused, the focus changes show nothing
+        assert_eq!(run.update_focus(false), None);
+        assert_eq!(run.update_focus(true), None);
+        assert!(run.paused());
+        assert_eq!(run.toggle_pause(), Some(false));
+    }
+
+    #[test]
+    fn key_does_not_resume_a_pause_by_focus() {
+        let mut run = RunControl::new(true);
+        assert_eq!(run.update_focus(false), Some(true));
+        // P pressed twice without the focus, as terminals and some windows still report keys
+        assert_eq!(run.toggle_pause(), None);
+        assert_eq!(run.toggle_pause(), None);
+        assert!(run.paused());
+        assert_eq!(run.update_focus(true), Some(false));
+        // Pressed once: paused by the key when the focus comes back
+        run.update_focus(false);
+        run.toggle_pause();
+        assert_eq!(run.update_focus(true), None);
+        assert!(run.paused_by_key());
+    }
+
+    #[test]
+    fn key_pauses_without_the_focus_pause() {
+        let mut run = RunControl::new(false);
+        assert_eq!(run.update_focus(false), None);
+        assert_eq!(run.toggle_pause(), Some(true));
+        assert_eq!(run.update_focus(true), None);
+        assert!(run.paused());
+    }
+}
@@ -0,0 +1,670 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./cpu_tests.rs"]
+mod cpu_tests;
+
+use rand::Rng;
+use crate::cpu::ProgramCounterInstruction::{GOTO, NEXT, SKIP};
+use crate::display::{ALL_PLANES, PLANES};
+use crate::memory::{BIG_FONTSET_START, FONTSET_START};
+use crate::instruction::decode;
+use crate::Instruction::{self, *};
+use std::time::Duration;
+use crate::{Chip8, Chip8Error, CycleInfo, FrameInfo, RunSummary, State, SysOpcodePolicy, FRAME_DURATION, STACK_SIZE};
+
+impl Chip8 {
+    // Runs one cycle and reports what happened during it. The draw flag is kept until the screen is rendered
+    pub fn emulate_cycle(&mut self) -> Result<CycleInfo, Chip8Error> {
+        let pc_before = self.pc;
+        let sound_active = self.timers.sound_active();
+        let was_waiting_for_key = self.waiting_for_key();
+        let draw_pending = std::mem::take(&mut self.draw_flag);
+        let result = self.step();
+        let drew = self.draw_flag;
+        self.draw_flag |= draw_pending;
+        if let Err(error) = &result {
+            self.state = State::Faulted(error.clone());
+        }
+        result?;
+
+        if drew {
+            self.call_draw_hook();
+        }
+        self.call_sound_hook(sound_active);
+        if let (State::WaitingForKey { dest }, false) = (&self.state, was_waiting_for_key) {
+            self.call_key_wait_hook(usize::from(*dest));
+        }
+
+        Ok(CycleInfo {
+            opcode: self.opcode,
+            pc_before,
+            drew,
+            waiting_for_key: self.waiting_for_key(),
+            halted: self.halted(),
+            sound_changed: self.timers.sound_active() != sound_active,
+        })
+    }
+
+    fn step(&mut self) -> Result<(), Chip8Error> {
+        match &self.state {
+            State::Running => {}
+            State::WaitingForKey { dest } => {
+                if let Some(key) = self.keypad.take_awaited_key(self.key_wait_on_release) {
+                    self.v[usize::from(*dest)] = u8::from(key);
+                    self.state = State::Running;
+                    self.pc += 2;
+                }
+                return Ok(());
+            }
+            // Nothing is executed until the vblank, which happens with the next timer tick
+            State::WaitingForVblank => return Ok(()),
+            State::Halted => return Ok(()),
+            State::Faulted(error) => return Err(error.clone()),
+        }
+
+        self.opcode = self.read_word(usize::from(self.pc))?;
+        let instruction = decode(self.opcode).map_err(|_| Chip8Error::UnknownOpcode { opcode: self.opcode, pc: self.pc })?;
+        self.execute(instruction)
+    }
+
+    // Runs n cycles, stopping at the first error. The timers are not ticked
+    pub fn step_n(&mut self, n: usize) -> Result<(), Chip8Error> {
+        for _ in 0..n {
+            self.emulate_cycle()?;
+        }
+        Ok(())
+    }
+
+    // Runs the instructions of one 60 Hz frame, then ticks the timers once and clears the key edges. The frame ends
+    // early when the program exits or waits for a key, since nothing would be executed until the keys are polled
+    // again
+    pub fn run_frame(&mut self) -> Result<FrameInfo, Chip8Error> {
+        let (mut drew, mut instructions) = (false, 0);
+        for _ in 0..self.instructions_per_frame() {
+            instructions += usize::from(self.state == State::Running);
+            let cycle = self.emulate_cycle()?;
+            drew |= cycle.drew;
+            if cycle.halted || cycle.waiting_for_key {
+                break;
+            }
+        }
+        self.tick_timers();
+        self.keypad.clear_edges();
+        Ok(FrameInfo { drew, sound_active: self.sound_active(), halted: self.halted(), instructions, frames: 1 })
+    }
+
+    // Runs as many frames as fit in the time elapsed on the host times the speed, the remaining time is kept for the
+    // next call. The drawing, the instructions and the frames add up over the frames
+    pub fn run_for(&mut self, duration: Duration) -> Result<FrameInfo, Chip8Error> {
+        self.pending_time += duration * self.speed;
+        let mut frame = FrameInfo {
+            drew: false,
+            sound_active: self.sound_active(),
+            halted: self.halted(),
+            instructions: 0,
+            frames: 0,
+        };
+        while self.pending_time >= FRAME_DURATION {
+            self.pending_time -= FRAME_DURATION;
+            let (drew, instructions, frames) = (frame.drew, frame.instructions, frame.frames);
+            frame = self.run_frame()?;
+            frame.drew |= drew;
+            frame.instructions += instructions;
+            frame.frames += frames;
+        }
+        Ok(frame)
+    }
+
+    // Runs n cycles with no frontend, ticking the timers and clearing the key edges once every instructions_per_frame
+    // cycles like run_frame. The run ends early when the program exits. With a seeded RNG the digest of the summary is
+    // reproducible
+    pub fn run_cycles(&mut self, n: usize) -> Result<RunSummary, Chip8Error> {
+        let cycles_per_tick = self.instructions_per_frame();
+        let mut summary = RunSummary { instructions: 0, draws: 0, digest: 0 };
+        for cycle in 1..=n {
+            let executing = self.state == State::Running;
+            let info = self.emulate_cycle()?;
+            summary.instructions += usize::from(executing);
+            summary.draws += usize::from(info.drew);
+            if info.halted {
+                break;
+            }
+            if cycle % cycles_per_tick == 0 {
+                self.tick_timers();
+                self.keypad.clear_edges();
+            }
+        }
+        summary.digest = self.digest();
+        Ok(summary)
+    }
+
+    fn waiting_for_key(&self) -> bool {
+        matches!(self.state, State::WaitingForKey { .. })
+    }
+
+    fn halted(&self) -> bool {
+        self.state == State::Halted
+    }
+
+    // Executes the instruction as if it was read at the PC, then moves the PC. The timers are not ticked, which
+    // allows tools to run single instructions without writing them to the memory first
+    pub fn execute(&mut self, instruction: Instruction) -> Result<(), Chip8Error> {
+        let program_counter_action = match instruction {
+            Sys(nnn) => self.op_0x0nnn(nnn)?,
+            ClearScreen => self.op_0x00e0(),
+            Return => self.op_0x00ee()?,
+            ScrollDown(n) => self.op_0x00cn(n),
+            ScrollRight => self.op_0x00fb(),
+            ScrollLeft => self.op_0x00fc(),
+            Exit => self.op_0x00fd(),
+            LowRes => self.op_0x00fe(),
+            HighRes => self.op_0x00ff(),
+            Jump(nnn) => self.op_0x1nnn(nnn),
+            Call(nnn) => self.op_0x2nnn(nnn)?,
+            SkipEqImm { x, nn } => self.op_0x3xnn(usize::from(x), nn),
+            SkipNeImm { x, nn } => self.op_0x4xnn(usize::from(x), nn),
+            SkipEqReg { x, y } => self.op_0x5xy0(usize::from(x), usize::from(y)),
+            StoreRange { x, y } => self.op_0x5xy2(usize::from(x), usize::from(y))?,
+            LoadRange { x, y } => self.op_0x5xy3(usize::from(x), usize::from(y))?,
+            LoadImm { x, nn } => self.op_0x6xnn(usize::from(x), nn),
+            AddImm { x, nn } => self.op_0x7xnn(usize::from(x), nn),
+            Move { x, y } => self.op_0x8xy0(usize::from(x), usize::from(y)),
+            Or { x, y } => self.op_0x8xy1(usize::from(x), usize::from(y)),
+            And { x, y } => self.op_0x8xy2(usize::from(x), usize::from(y)),
+            Xor { x, y } => self.op_0x8xy3(usize::from(x), usize::from(y)),
+            Add { x, y } => self.op_0x8xy4(usize::from(x), usize::from(y)),
+            Sub { x, y } => self.op_0x8xy5(usize::from(x), usize::from(y)),
+            ShiftRight { x, y } => self.op_0x8xy6(usize::from(x), usize::from(y)),
+            SubReverse { x, y } => self.op_0x8xy7(usize::from(x), usize::from(y)),
+            ShiftLeft { x, y } => self.op_0x8xye(usize::from(x), usize::from(y)),
+            SkipNeReg { x, y } => self.op_0x9xy0(usize::from(x), usize::from(y)),
+            LoadIndex(nnn) => self.op_0xannn(nnn),
+            JumpOffset { x, nnn } => self.op_0xbnnn(usize::from(x), nnn),
+            Random { x, nn } => self.op_0xcxnn(usize::from(x), nn),
+            Draw { x, y, n } => self.op_0xdxyn(usize::from(x), usize::from(y), n)?,
+            SkipKeyPressed { x } => self.op_0xex9e(usize::from(x)),
+            SkipKeyNotPressed { x } => self.op_0xexa1(usize::from(x)),
+            LoadLongIndex => self.op_0xf000()?,
+            SelectPlanes(n) => self.op_0xfn01(n),
+            LoadAudioPattern => self.op_0xf002()?,
+            LoadDelay { x } => self.op_0xfx07(usize::from(x)),
+            WaitKey { x } => self.op_0xfx0a(usize::from(x)),
+            SetDelay { x } => self.op_0xfx15(usize::from(x)),
+            SetSound { x } => self.op_0xfx18(usize::from(x)),
+            AddIndex { x } => self.op_0xfx1e(usize::from(x)),
+            LoadFont { x } => self.op_0xfx29(usize::from(x)),
+            LoadBigFont { x } => self.op_0xfx30(usize::from(x)),
+            StoreBcd { x } => self.op_0xfx33(usize::from(x))?,
+            SetPitch { x } => self.op_0xfx3a(usize::from(x)),
+            StoreRegisters { x } => self.op_0xfx55(usize::from(x))?,
+            LoadRegisters { x } => self.op_0xfx65(usize::from(x))?,
+            StoreFlags { x } => self.op_0xfx75(usize::from(x)),
+            LoadFlags { x } => self.op_0xfx85(usize::from(x)),
+        };
+
+        match program_counter_action {
+            NEXT => self.pc += 2,
+            SKIP => self.pc += if self.is_long_instruction_next() { 6 } else { 4 },
+            GOTO(addr) => self.pc = addr
+        }
+        Ok(())
+    }
+
+    //0NNN: Calls machine code routine at address NNN. There is no machine code to run, so depending on the
+    // policy it is either skipped or reported as an error
+    pub(crate) fn op_0x0nnn(&self, nnn: u16) -> Result<ProgramCounterInstruction, Chip8Error> {
+        match self.sys_opcode_policy {
+            SysOpcodePolicy::Ignore => Ok(NEXT),
+            SysOpcodePolicy::Error => Err(Chip8Error::SysOpcode { addr: nnn, pc: self.pc }),
+        }
+    }
+
+    //00E0: Clears the screen
+    pub(crate) fn op_0x00e0(&mut self) -> ProgramCounterInstruction {
+        self.screen.clear(self.screen.planes);
+        self.draw_flag = true;
+        NEXT
+    }
+    
+    //00EE: Returns from subroutine
+    pub(crate) fn op_0x00ee(&mut self) -> Result<ProgramCounterInstruction, Chip8Error> {
+        match self.stack.pop() {
+            Some(previous_pc) => Ok(GOTO(previous_pc + 2)), // The stack holds the address of the call itself
+            None => Err(Chip8Error::StackUnderflow { pc: self.pc }),
+        }
+    }
+
+    //00CN: Scrolls the display down by N pixels (SCHIP)
+    pub(crate) fn op_0x00cn(&mut self, n: u8) -> ProgramCounterInstruction {
+        self.screen.scroll_down(self.scroll_distance(usize::from(n)));
+        self.draw_flag = true;
+        NEXT
+    }
+
+    //00FB: Scrolls the display right by 4 pixels (SCHIP)
+    pub(crate) fn op_0x00fb(&mut self) -> ProgramCounterInstruction {
+        self.screen.scroll_right(self.scroll_distance(4));
+        self.draw_flag = true;
+        NEXT
+    }
+
+    //00FC: Scrolls the display left by 4 pixels (SCHIP)
+    pub(crate) fn op_0x00fc(&mut self) -> ProgramCounterInstruction {
+        self.screen.scroll_left(self.scroll_distance(4));
+        self.draw_flag = true;
+        NEXT
+    }
+
+    //00FD: Exits the interpreter (SCHIP)
+    pub(crate) fn op_0x00fd(&mut self) -> ProgramCounterInstruction {
+        self.state = State::Halted;
+        GOTO(self.pc)
+    }
+
+    //00FE: Disables high resolution mode (SCHIP)
+    pub(crate) fn op_0x00fe(&mut self) -> ProgramCounterInstruction {
+        self.screen.hires = false;
+        self.screen.clear(ALL_PLANES);
+        self.draw_flag = true;
+        NEXT
+    }
+
+    //00FF: Enables high resolution mode (SCHIP)
+    pub(crate) fn op_0x00ff(&mut self) -> ProgramCounterInstruction {
+        self.screen.hires = true;
+        self.screen.clear(ALL_PLANES);
+        self.draw_flag = true;
+        NEXT
+    }
+
+    //1NNN: Jumps to address NNN
+    pub(crate) fn op_0x1nnn(&self, nnn: u16) -> ProgramCounterInstruction {
+        GOTO(nnn)
+    }
+
+    //2NNN: Calls subroutine at NNN
+    pub(crate) fn op_0x2nnn(&mut self, nnn: u16) -> Result<ProgramCounterInstruction, Chip8Error> {
+        if self.stack.len() == STACK_SIZE {
+            return Err(Chip8Error::StackOverflow { pc: self.pc });
+        }
+        self.stack.push(self.pc);
+        Ok(GOTO(nnn))
+    }
+
+    //3XNN: Skips the next instruction if VX equals NN (Usually the next instruction ia a jump to skip a code block)
+    pub(crate) fn op_0x3xnn(&self, x: usize, nn: u8) -> ProgramCounterInstruction {
+        if self.v[x] == nn {
+            SKIP
+        } else {
+            NEXT
+        }
+    }
+
+    //4XNN: Skips the next instruction if VX does not equals NN (Usually the next instruction ia a jump to skip a code block)
+    pub(crate) fn op_0x4xnn(&self, x: usize, nn: u8) -> ProgramCounterInstruction {
+        if self.v[x] != nn {
+            SKIP
+        } else {
+            NEXT
+        }
+    }
+
+    //5XY0: Skips the next instruction if VX equals VY (Usually the next instruction ia a jump to skip a code block)
+    pub(crate) fn op_0x5xy0(&self, x: usize, y: usize) -> ProgramCounterInstruction {
+        if self.v[x] == self.v[y] {
+            SKIP
+        } else {
+            NEXT
+        }
+    }
+
+    //5XY2: Stores VX to VY (including VY) in memory, starting at address I, without changing I (XO-CHIP).
+    // When X is greater than Y, the registers are stored in reverse order
+    pub(crate) fn op_0x5xy2(&mut self, x: usize, y: usize) -> Result<ProgramCounterInstruction, Chip8Error> {
+        for (offset, register) in register_range(x, y).enumerate() {
+            self.write_byte(usize::from(self.i) + offset, self.v[register])?;
+        }
+        Ok(NEXT)
+    }
+
+    //5XY3: Fills VX to VY (including VY) with values from memory, starting at address I, without changing I (XO-CHIP).
+    // When X is greater than Y, the registers are filled in reverse order
+    pub(crate) fn op_0x5xy3(&mut self, x: usize, y: usize) -> Result<ProgramCounterInstruction, Chip8Error> {
+        for (offset, register) in register_range(x, y).enumerate() {
+            self.v[register] = self.read_byte(usize::from(self.i) + offset)?;
+        }
+        Ok(NEXT)
+    }
+
+    //6XNN: Sets VX to NN
+    pub(crate) fn op_0x6xnn(&mut self, x: usize, nn: u8) -> ProgramCounterInstruction {
+        self.v[x] = nn;
+        NEXT
+    }
+
+    //7XNN: Adds NN to VX
+    pub(crate) fn op_0x7xnn(&mut self, x: usize, nn: u8) -> ProgramCounterInstruction {
+        let addend = self.v[x] as u16;
+        let augend = nn as u16;
+        self.v[x] = (augend + addend) as u8;
+        NEXT
+    }
+
+    //8XY0: Sets VX to the value of VY
+    pub(crate) fn op_0x8xy0(&mut self, x: usize, y: usize) -> ProgramCounterInstruction {
+        self.v[x] = self.v[y];
+        NEXT
+    }
+
+    //8XY1: Set VX to VX or VY (Bitwise OR operation)
+    pub(crate) fn op_0x8xy1(&mut self, x: usize, y: usize) -> ProgramCounterInstruction {
+        self.v[x] |= self.v[y];
+        self.reset_vf_quirk();
+        NEXT
+    }
+
+    //8XY2: Set VX to VX and VY (Bitwise AND operation)
+    pub(crate) fn op_0x8xy2(&mut self, x: usize, y: usize) -> ProgramCounterInstruction {
+        self.v[x] &= self.v[y];
+        self.reset_vf_quirk();
+        NEXT
+    }
+
+    //8XY3: Set VX to VX xor VY
+    pub(crate) fn op_0x8xy3(&mut self, x: usize, y: usize) -> ProgramCounterInstruction {
+        self.v[x] ^= self.v[y];
+        self.reset_vf_quirk();
+        NEXT
+    }
+
+    fn reset_vf_quirk(&mut self) {
+        if self.vf_reset {
+            self.v[0x0F] = 0;
+        }
+    }
+
+    //8XY4: Adds VY to VX. VF is set to 1 when there's a carry and to 0 when there is not
+    pub(crate) fn op_0x8xy4(&mut self, x: usize, y: usize) -> ProgramCounterInstruction {
+        let result = (self.v[x] as u16) + (self.v[y] as u16);
+        self.v[x] = result as u8;
+        self.v[0x0F] = if result > 0xFF { 1 } else { 0 };
+        NEXT
+    }
+
+    //8XY5: VY is subtracted from VX. VF is set to 0 when there's a borrow, and 1 when there is not.
+    pub(crate) fn op_0x8xy5(&mut self, x: usize, y: usize) -> ProgramCounterInstruction {
+        let result = self.v[x].wrapping_sub(self.v[y]);
+        self.v[0x0F] = if self.v[x] >= self.v[y] { 1 } else { 0 };
+        self.v[x] = result;
+        NEXT
+    }
+
+    //8XY6: Stores the least significant bit of VX in VF and then shifts VX to the right by 1.
+    // With the shift quirk, VY is shifted instead and the result is stored in VX
+    pub(crate) fn op_0x8xy6(&mut self, x: usize, y: usize) -> ProgramCounterInstruction {
+        let source = if self.shift_uses_vy { self.v[y] } else { self.v[x] };
+        self.v[0x0F] = source & 0x1;
+        self.v[x] = source >> 1;
+        NEXT
+    }
+
+    //8XY7: Sets VX to VY minus VX. VF is set to 0 when there's a borrow, and 1 when there is not.
+    pub(crate) fn op_0x8xy7(&mut self, x: usize, y: usize) -> ProgramCounterInstruction {
+        self.v[0x0F] = if self.v[y] >= self.v[x] { 1 } else { 0 };
+        let result = self.v[y].wrapping_sub(self.v[x]);
+        self.v[x] = result;
+        NEXT
+    }
+
+    //8XYE: Stores the most significant bit of VX in VF and then shifts VX to the left by 1
+    // With the shift quirk, VY is shifted instead and the result is stored in VX
+    pub(crate) fn op_0x8xye(&mut self, x: usize, y: usize) -> ProgramCounterInstruction {
+        let source = if self.shift_uses_vy { self.v[y] } else { self.v[x] };
+        self.v[0x0F] = (source & 0b1000_0000) >> 7;
+        self.v[x] = source << 1;
+        NEXT
+    }
+
+    //9XY0: Skips the next instruction if VX does not equal VY. (Usually the next instruction is a jump to skip a code block)
+    pub(crate) fn op_0x9xy0(&self, x: usize, y: usize) -> ProgramCounterInstruction {
+        if self.v[x] != self.v[y] {
+            SKIP
+        } else {
+            NEXT
+        }
+    }
+
+    //ANNN: Sets i to the address NNN
+    pub(crate) fn op_0xannn(&mut self, nnn: u16) -> ProgramCounterInstruction {
+        self.set_index(nnn);
+        NEXT
+    }
+
+    //BNNN: Jumps to the address NNN plus V0. With the jump quirk, jumps to the address XNN plus VX
+    pub(crate) fn op_0xbnnn(&mut self, x: usize, nnn: u16) -> ProgramCounterInstruction {
+        let offset = if self.jump_uses_vx { self.v[x] } else { self.v[0] };
+        GOTO(u16::from(offset) + nnn)
+    }
+
+    //CXNN: Sets VX to the result of a bitwise and operation on a random number (Typically: 0 to 255) and NN
+    pub(crate) fn op_0xcxnn(&mut self, x: usize, nn: u8) -> ProgramCounterInstruction {
+        let random_u8: u8 = self.rng.0.gen();
+        self.v[x] = random_u8 & nn;
+        NEXT
+    }
+
+    //DXYN: Draws a sprite at coordinate (VX, VY) that has a width of 8 pixels and a height of N pixels.
+    // Each row of 8 pixels is read as bit-coded starting from memory location I; I value does not change after
+    // the execution of this instruction. As described above, VF is set to 1 if any screen pixels are flipped
+    // from set to unset when the sprite is drawn, and to 0 if that does not happen
+    pub(crate) fn op_0xdxyn(&mut self, x: usize, y: usize, n: u8) -> Result<ProgramCounterInstruction, Chip8Error> {
+        self.draw(self.v[x], self.v[y], n)?;
+        if self.display_wait {
+            self.state = State::WaitingForVblank;
+        }
+        Ok(NEXT)
+    }
+
+    //EX9E: Skips the next instruction if the key stored in VX is pressed. (Usually the next instruction is a jump to skip a code block)
+    pub(crate) fn op_0xex9e(&self, x: usize) -> ProgramCounterInstruction {
+        if self.keypad.is_key_pressed(self.v[x]) {
+            SKIP
+        } else {
+            NEXT
+        }
+    }
+
+    //EXA1: Skips the next instruction if the key stored in VX is not pressed. (Usually the next instruction is a jump to skip a code block)
+    pub(crate) fn op_0xexa1(&self, x: usize) -> ProgramCounterInstruction {
+        if !self.keypad.is_key_pressed(self.v[x]) {
+            SKIP
+        } else {
+            NEXT
+        }
+    }
+
+    //F000 NNNN: Sets I to the address NNNN stored in the next two bytes (XO-CHIP). Goes past the address itself, which
+    // a skip would take for another F000 when NNNN is F000
+    pub(crate) fn op_0xf000(&mut self) -> Result<ProgramCounterInstruction, Chip8Error> {
+        self.i = self.read_word(usize::from(self.pc) + 2)?;
+        Ok(GOTO(self.pc + 4))
+    }
+
+    //FN01: Selects the bitplanes N (0 to 3) used by drawing, clearing and scrolling (XO-CHIP)
+    pub(crate) fn op_0xfn01(&mut self, n: u8) -> ProgramCounterInstruction {
+        self.screen.planes = n & ALL_PLANES;
+        NEXT
+    }
+
+    //F002: Loads the 16 bytes audio pattern buffer from memory, starting at address I (XO-CHIP)
+    pub(crate) fn op_0xf002(&mut self) -> Result<ProgramCounterInstruction, Chip8Error> {
+        for offset in 0..self.timers.pattern_buffer.len() {
+            self.timers.pattern_buffer[offset] = self.read_byte(usize::from(self.i) + offset)?;
+        }
+        self.timers.pattern_loaded = true;
+        Ok(NEXT)
+    }
+
+    //FX07: Sets VX to the value of the delay timer
+    pub(crate) fn op_0xfx07(&mut self, x: usize) -> ProgramCounterInstruction {
+        self.v[x] = self.timers.delay_timer;
+        NEXT
+    }
+
+    //FX0A: A key press is awaited, and then stored in VX (Blocking operation, all instruction halted until next key event)
+    //With the key_wait_on_release quirk the key is stored once released
+    pub(crate) fn op_0xfx0a(&mut self, x: usize) -> ProgramCounterInstruction {
+        self.state = State::WaitingForKey { dest: x as u8 };
+        self.keypad.awaited = None;
+        GOTO(self.pc)
+    }
+
+    //FX15: Sets the delay timer to VX
+    pub(crate) fn op_0xfx15(&mut self, x: usize) -> ProgramCounterInstruction {
+        self.timers.delay_timer = self.v[x];
+        NEXT
+    }
+
+    //FX18: Sets the sound timer to VX
+    pub(crate) fn op_0xfx18(&mut self, x: usize) -> ProgramCounterInstruction {
+        self.timers.sound_timer = self.v[x];
+        NEXT
+    }
+
+    //FX1E: Adds VX to I. VF is not affected, unless the index overflow quirk is enabled
+    pub(crate) fn op_0xfx1e(&mut self, x: usize) -> ProgramCounterInstruction {
+        let result = self.i.wrapping_add(u16::from(self.v[x]));
+        if self.index_overflow_quirk {
+            self.v[0x0F] = if result > 0x0FFF { 1 } else { 0 };
+        }
+        self.set_index(result);
+        NEXT
+    }
+
+    //FX29: Sets I to the location of the sprite for the character in VX. Characters 0-F are represented by a 4x5 font
+    pub(crate) fn op_0xfx29(&mut self, x: usize) -> ProgramCounterInstruction {
+        self.i = FONTSET_START + u16::from(self.v[x] & 0x0F) * 5;
+        NEXT
+    }
+
+    //FX30: Sets I to the location of the big sprite for the character in VX (SCHIP). Characters are represented
+    // by a 8x10 font, only the low nibble of VX is used like for FX29
+    pub(crate) fn op_0xfx30(&mut self, x: usize) -> ProgramCounterInstruction {
+        self.i = BIG_FONTSET_START + u16::from(self.v[x] & 0x0F) * 10;
+        NEXT
+    }
+
+    //FX33: Stores the binary-coded decimal representation of VX, with the hundreds digit at I, the tens digit at
+    // I + 1 and the ones digit at I + 2. I is not changed
+    pub(crate) fn op_0xfx33(&mut self, x: usize) -> Result<ProgramCounterInstruction, Chip8Error> {
+        let value = self.v[x];
+        let address = usize::from(self.i);
+        for (offset, digit) in [value / 100, value / 10 % 10, value % 10].into_iter().enumerate() {
+            self.write_byte(address + offset, digit)?;
+        }
+        Ok(NEXT)
+    }
+
+    //FX3A: Sets the audio pattern playback rate to VX (XO-CHIP)
+    pub(crate) fn op_0xfx3a(&mut self, x: usize) -> ProgramCounterInstruction {
+        self.timers.pitch = self.v[x];
+        NEXT
+    }
+
+    //FX55: Stores from V0 to VX (including VX) in memory, starting at address I.
+    pub(crate) fn op_0xfx55(&mut self, x: usize) -> Result<ProgramCounterInstruction, Chip8Error> {
+        for offset in 0..=x {
+            self.write_byte(usize::from(self.i) + offset, self.v[offset])?;
+        }
+        if !self.load_store_quirk {
+            self.set_index(self.i.wrapping_add(x as u16 + 1));
+        }
+        Ok(NEXT)
+    }
+
+    //FX65: Fills from V0 to VX (including VX) with values from memory, starting at address I.
+    pub(crate) fn op_0xfx65(&mut self, x: usize) -> Result<ProgramCounterInstruction, Chip8Error> {
+        for offset in 0..=x {
+            self.v[offset] = self.read_byte(usize::from(self.i) + offset)?;
+        }
+        if !self.load_store_quirk {
+            self.set_index(self.i.wrapping_add(x as u16 + 1));
+        }
+        Ok(NEXT)
+    }
+
+    //FX75: Stores V0 to VX in the RPL user flags (SCHIP). There are only 8 flags, X above 7 is clamped to 7
+    pub(crate) fn op_0xfx75(&mut self, x: usize) -> ProgramCounterInstruction {
+        let count = x.min(7) + 1;
+        self.rpl_flags[..count].copy_from_slice(&self.v[..count]);
+        NEXT
+    }
+
+    //FX85: Fills V0 to VX with the RPL user flags (SCHIP). There are only 8 flags, X above 7 is clamped to 7
+    pub(crate) fn op_0xfx85(&mut self, x: usize) -> ProgramCounterInstruction {
+        let count = x.min(7) + 1;
+        self.v[..count].copy_from_slice(&self.rpl_flags[..count]);
+        NEXT
+    }
+
+    // The whole sprite is read first so that nothing is drawn if it lies outside of the memory.
+    // With XO-CHIP bitplanes, the sprite of each selected plane follows the one of the previous plane
+    fn draw(&mut self, vx: u8, vy: u8, n: u8) -> Result<(), Chip8Error> {
+        let (sprite_width, sprite_height) = self.screen.sprite_size(n);
+        let bytes_per_row = sprite_width / 8;
+        let mut sprites = [[0u16; 16]; PLANES];
+        let mut address = usize::from(self.i);
+        for (plane, sprite) in sprites.iter_mut().enumerate() {
+            if self.screen.planes & (1 << plane) == 0 {
+                continue;
+            }
+            for sprite_row in sprite.iter_mut().take(sprite_height) {
+                for _ in 0..bytes_per_row {
+                    *sprite_row = *sprite_row << 8 | u16::from(self.read_byte(address)?);
+                    address += 1;
+                }
+            }
+        }
+
+        let collision = self.screen.draw(vx, vy, &sprites, (sprite_width, sprite_height), self.clip_sprites);
+        self.v[0x0F] = if collision { 1 } else { 0 };
+        self.draw_flag = true;
+        Ok(())
+    }
+
+    // Scrolling is done in high resolution pixels on the legacy SCHIP interpreter, so with the half scroll quirk
+    // a low resolution scroll only moves by half the distance (rounded down)
+    fn scroll_distance(&self, pixels: usize) -> usize {
+        if self.lores_half_scroll && !self.screen.hires {
+            pixels / 2
+        } else {
+            pixels
+        }
+    }
+
+    // F000 NNNN is the only four bytes long instruction, skipping it has to jump over the address too
+    fn is_long_instruction_next(&self) -> bool {
+        matches!(self.read_word(usize::from(self.pc) + 2), Ok(0xF000))
+    }
+
+    // Sets I, wrapped to 12 bits with index_mask_12bit. Public for debuggers editing a paused machine
+    pub fn set_index(&mut self, value: u16) {
+        self.i = if self.index_mask_12bit { value & 0x0FFF } else { value };
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+pub enum ProgramCounterInstruction {
+    NEXT,
+    SKIP,
+    GOTO(u16)
+}
+
+// Registers from X to Y (including Y), in reverse order when X is greater than Y
+fn register_range(x: usize, y: usize) -> Box<dyn Iterator<Item = usize>> {
+    if x <= y {
+        Box::new(x..=y)
+    } else {
+        Box::new((y..=x).rev())
+    }
+}
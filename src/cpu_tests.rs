@@ -0,0 +1,1305 @@
+#[cfg(test)]
+mod cpu_tests {
+    use std::time::Duration;
+    use crate::{Chip8, Chip8Config, Chip8Error, CycleInfo, FrameInfo, Instruction, Key, RunSummary, State, SysOpcodePolicy, FRAME_DURATION};
+    use crate::timers::Timers;
+    use crate::ProgramCounterInstruction::GOTO;
+    use crate::ProgramCounterInstruction::NEXT;
+
+    #[test]
+    fn op_0x00ee_returns_after_the_call_on_top_of_the_stack() {
+        let mut chip8 = Chip8::default();
+        chip8.stack.push(0x300);
+
+        let result = chip8.op_0x00ee();
+
+        assert!(matches!(result, Ok(GOTO(0x302))));
+        assert!(chip8.stack.is_empty());
+    }
+
+    #[test]
+    fn op_0x00ee_returns_an_error_when_the_stack_is_empty() {
+        let mut chip8 = Chip8::default();
+        chip8.memory[0x200] = 0x00;
+        chip8.memory[0x201] = 0xEE;
+
+        let result = chip8.emulate_cycle();
+
+        assert!(matches!(result, Err(Chip8Error::StackUnderflow { pc: 0x200 })));
+    }
+
+    #[test]
+    fn op_0x00fd_halts_the_program() {
+        let mut chip8 = Chip8::default();
+        chip8.memory[0x200] = 0x00;
+        chip8.memory[0x201] = 0xFD;
+
+        let result = chip8.emulate_cycle();
+
+        assert!(result.unwrap().halted);
+        assert_eq!(chip8.pc(), 0x200);
+        assert_eq!(chip8.state(), &State::Halted);
+    }
+
+    #[test]
+    fn emulate_cycle_does_nothing_once_halted() {
+        let mut chip8 = Chip8 { timers: Timers { delay_timer: 10, ..Timers::default() }, ..Chip8::default() };
+        chip8.memory[0x200] = 0x00;
+        chip8.memory[0x201] = 0xFD;
+        chip8.memory[0x202] = 0x60; // 6XNN setting V0 to 0xFF
+        chip8.memory[0x203] = 0xFF;
+        chip8.emulate_cycle().unwrap();
+        let registers = chip8.v;
+
+        for _ in 0..3 {
+            assert!(chip8.emulate_cycle().unwrap().halted);
+        }
+
+        assert_eq!(chip8.pc(), 0x200);
+        assert_eq!(chip8.v, registers);
+        assert_eq!(chip8.delay_timer(), 10);
+    }
+
+    #[test]
+    fn state_goes_from_running_to_waiting_for_a_key_and_back_until_halted() {
+        let mut chip8 = Chip8::default();
+        // 6012: V0 = 0x12, F10A: waits for a key in V1, 7001: V0 += 1, 00FD: exits
+        chip8.load_rom(&[0x60, 0x12, 0xF1, 0x0A, 0x70, 0x01, 0x00, 0xFD]).unwrap();
+
+        chip8.emulate_cycle().unwrap();
+        assert_eq!(chip8.state(), &State::Running);
+        assert_eq!((chip8.pc(), chip8.register(0)), (0x202, 0x12));
+
+        chip8.emulate_cycle().unwrap();
+        chip8.emulate_cycle().unwrap();
+        assert_eq!(chip8.state(), &State::WaitingForKey { dest: 1 });
+        assert_eq!((chip8.pc(), chip8.register(0)), (0x202, 0x12));
+
+        chip8.press(Key::K7);
+        chip8.emulate_cycle().unwrap();
+        assert_eq!(chip8.state(), &State::Running);
+        assert_eq!((chip8.pc(), chip8.register(1)), (0x204, 0x7));
+
+        chip8.emulate_cycle().unwrap();
+        chip8.emulate_cycle().unwrap();
+        assert_eq!(chip8.state(), &State::Halted);
+        assert_eq!((chip8.pc(), chip8.register(0)), (0x206, 0x13));
+    }
+
+    #[test]
+    fn emulate_cycle_returns_the_error_again_once_faulted() {
+        let mut chip8 = Chip8::default();
+        // 00EE: returns without a call
+        chip8.load_rom(&[0x00, 0xEE]).unwrap();
+
+        let error = chip8.emulate_cycle().unwrap_err();
+
+        assert_eq!(error, Chip8Error::StackUnderflow { pc: 0x200 });
+        assert_eq!(chip8.state(), &State::Faulted(error.clone()));
+        assert_eq!(chip8.emulate_cycle().unwrap_err(), error);
+        chip8.reset();
+        assert_eq!(chip8.state(), &State::Running);
+    }
+
+    #[test]
+    fn emulate_cycle_is_running_until_halted() {
+        let mut chip8 = Chip8::default();
+        chip8.memory[0x200] = 0x60;
+        chip8.memory[0x201] = 0xFF;
+
+        let result = chip8.emulate_cycle();
+
+        assert!(!result.unwrap().halted);
+    }
+
+    #[test]
+    fn emulate_cycle_reports_an_arithmetic_opcode() {
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(&[0x70, 0x05]).unwrap(); // V0 += 5
+
+        let info = chip8.emulate_cycle().unwrap();
+
+        assert_eq!(info, CycleInfo { opcode: 0x7005, pc_before: 0x200, drew: false, waiting_for_key: false, halted: false, sound_changed: false });
+    }
+
+    #[test]
+    fn emulate_cycle_reports_a_draw() {
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(&[0x60, 0x00, 0xD0, 0x05]).unwrap(); // V0 = 0, draw the 0 digit at (V0, V0)
+        chip8.emulate_cycle().unwrap();
+
+        let info = chip8.emulate_cycle().unwrap();
+
+        assert_eq!(info, CycleInfo { opcode: 0xD005, pc_before: 0x202, drew: true, waiting_for_key: false, halted: false, sound_changed: false });
+    }
+
+    #[test]
+    fn emulate_cycle_reports_the_wait_for_a_key() {
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(&[0xF1, 0x0A]).unwrap();
+
+        let first = chip8.emulate_cycle().unwrap();
+        let second = chip8.emulate_cycle().unwrap();
+        chip8.press(Key::K4);
+        let third = chip8.emulate_cycle().unwrap();
+
+        assert_eq!(first, CycleInfo { opcode: 0xF10A, pc_before: 0x200, drew: false, waiting_for_key: true, halted: false, sound_changed: false });
+        assert_eq!(second, first);
+        assert_eq!(third, CycleInfo { waiting_for_key: false, ..first });
+        assert_eq!(chip8.pc(), 0x202);
+    }
+
+    #[test]
+    fn emulate_cycle_reports_the_sound_switching_on() {
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(&[0x60, 0x02, 0xF0, 0x18, 0x12, 0x04]).unwrap(); // sound timer = 2, loop forever
+
+        let sound_changes: Vec<bool> = (0..4).map(|_| chip8.emulate_cycle().unwrap().sound_changed).collect();
+
+        assert_eq!(sound_changes, [false, true, false, false]);
+    }
+
+    #[test]
+    fn emulate_cycle_keeps_the_draw_flag_until_the_screen_is_rendered() {
+        let mut chip8 = Chip8 { draw_flag: true, ..Chip8::default() };
+        chip8.load_rom(&[0x70, 0x05]).unwrap();
+
+        let info = chip8.emulate_cycle().unwrap();
+
+        assert!(!info.drew);
+        assert!(chip8.draw_flag);
+    }
+
+    #[test]
+    fn execute_gives_the_same_state_as_running_the_program_from_memory() {
+        let program = [
+            Instruction::LoadImm { x: 0, nn: 0x07 },
+            Instruction::LoadImm { x: 1, nn: 0x03 },
+            Instruction::Sub { x: 0, y: 1 },
+            Instruction::LoadIndex(0x300),
+            Instruction::StoreRegisters { x: 1 },
+            Instruction::LoadFont { x: 0 },
+            Instruction::Draw { x: 1, y: 1, n: 5 },
+            Instruction::SkipEqImm { x: 0, nn: 0x04 },
+        ];
+        let rom: Vec<u8> = program.iter().flat_map(|instruction| instruction.encode().to_be_bytes()).collect();
+        let mut from_memory = Chip8::default();
+        from_memory.load_rom(&rom).unwrap();
+        let mut executed = Chip8::default();
+
+        for instruction in program {
+            from_memory.emulate_cycle().unwrap();
+            executed.execute(instruction).unwrap();
+        }
+
+        assert_eq!(executed.v, from_memory.v);
+        assert_eq!(executed.index(), from_memory.index());
+        assert_eq!(executed.pc(), 0x212);
+        assert_eq!(executed.pc(), from_memory.pc());
+        assert_eq!(executed.memory[0x300..0x302], from_memory.memory[0x300..0x302]);
+        assert_eq!(executed.framebuffer(), from_memory.framebuffer());
+    }
+
+    #[test]
+    fn execute_does_not_tick_the_timers() {
+        let mut chip8 = Chip8 { timers: Timers { delay_timer: 10, ..Timers::default() }, ..Chip8::default() };
+
+        chip8.execute(Instruction::LoadImm { x: 0, nn: 0x01 }).unwrap();
+
+        assert_eq!(chip8.delay_timer(), 10);
+        assert_eq!(chip8.pc(), 0x202);
+    }
+
+    #[test]
+    fn execute_returns_the_error_of_the_instruction() {
+        let mut chip8 = Chip8::default();
+
+        let result = chip8.execute(Instruction::Return);
+
+        assert!(matches!(result, Err(Chip8Error::StackUnderflow { pc: 0x200 })));
+    }
+
+    // Sets V0 over and over, one instruction every two bytes
+    const STRAIGHT_LINE: [u8; 32] = [0x60; 32];
+
+    #[test]
+    fn run_frame_runs_the_instructions_of_one_frame() {
+        let mut chip8 = Chip8Config::new().instructions_per_frame(4).build().unwrap();
+        chip8.load_rom(&STRAIGHT_LINE).unwrap();
+
+        chip8.run_frame().unwrap();
+        assert_eq!(chip8.pc(), 0x208);
+        chip8.run_frame().unwrap();
+        chip8.run_frame().unwrap();
+
+        assert_eq!(chip8.pc(), 0x218);
+    }
+
+    #[test]
+    fn run_frame_reports_the_drawing_and_the_sound() {
+        let mut chip8 = Chip8Config::new().instructions_per_frame(4).build().unwrap();
+        chip8.load_rom(&[0x60, 0x02, 0xF0, 0x18, 0xD0, 0x05, 0x12, 0x06]).unwrap(); // sound timer = 2, draw, loop
+
+        let first = chip8.run_frame().unwrap();
+        let second = chip8.run_frame().unwrap();
+
+        assert_eq!(first, FrameInfo { drew: true, sound_active: true, halted: false, instructions: 4, frames: 1 });
+        assert_eq!(second, FrameInfo { drew: false, sound_active: false, halted: false, instructions: 4, frames: 1 });
+    }
+
+    #[test]
+    fn run_frame_stops_early_when_the_program_exits() {
+        let mut chip8 = Chip8Config::new().instructions_per_frame(10).build().unwrap();
+        chip8.load_rom(&[0x60, 0x01, 0x00, 0xFD]).unwrap();
+
+        let frame = chip8.run_frame().unwrap();
+
+        assert!(frame.halted);
+        assert_eq!(chip8.pc(), 0x202);
+    }
+
+    #[test]
+    fn run_frame_stops_early_when_waiting_for_a_key() {
+        let mut chip8 = Chip8Config::new().instructions_per_frame(10).build().unwrap();
+        chip8.load_rom(&[0xF1, 0x0A, 0x60, 0x01, 0x12, 0x04]).unwrap(); // wait for a key, V0 = 1, loop forever
+        chip8.run_frame().unwrap();
+        assert_eq!(chip8.pc(), 0x200);
+        chip8.press(Key::K2);
+
+        chip8.run_frame().unwrap();
+
+        assert_eq!(chip8.register(1), 0x2);
+        assert_eq!(chip8.register(0), 0x1);
+        assert_eq!(chip8.pc(), 0x204);
+    }
+
+    #[test]
+    fn run_for_runs_the_frames_fitting_in_the_duration_and_keeps_the_rest() {
+        let mut chip8 = Chip8Config::new().instructions_per_frame(1).build().unwrap();
+        chip8.load_rom(&STRAIGHT_LINE).unwrap();
+
+        chip8.run_for(Duration::from_millis(10)).unwrap();
+        assert_eq!(chip8.pc(), 0x200);
+        chip8.run_for(Duration::from_millis(10)).unwrap();
+        assert_eq!(chip8.pc(), 0x202);
+        chip8.run_for(Duration::from_millis(50)).unwrap();
+
+        assert_eq!(chip8.pc(), 0x208);
+    }
+
+    #[test]
+    fn run_for_runs_the_frames_of_the_speed() {
+        let mut chip8 = Chip8Config::new().instructions_per_frame(1).build().unwrap();
+        chip8.load_rom(&[0x60, 0xFF, 0xF0, 0x15, 0x12, 0x04]).unwrap(); // Delay timer = 255, then loops forever
+        chip8.set_speed(8);
+
+        let frames = chip8.run_for(FRAME_DURATION * 3).unwrap();
+        assert_eq!(frames.frames, 24);
+        // Set during the second frame, then ticked once by each
+        assert_eq!(chip8.delay_timer(), 255 - 23);
+
+        // Back to normal speed, and a factor of 0 is taken as 1
+        chip8.set_speed(0);
+        assert_eq!(chip8.speed(), 1);
+        assert_eq!(chip8.run_for(FRAME_DURATION * 3).unwrap().frames, 3);
+    }
+
+    #[test]
+    fn run_for_reports_a_drawing_in_any_of_the_frames() {
+        let mut chip8 = Chip8Config::new().instructions_per_frame(1).build().unwrap();
+        chip8.load_rom(&[0xD0, 0x05, 0x60, 0x01, 0x12, 0x04]).unwrap();
+
+        let frames = chip8.run_for(FRAME_DURATION * 3).unwrap();
+
+        assert!(frames.drew);
+        assert_eq!(chip8.pc(), 0x204);
+    }
+
+    #[test]
+    fn run_for_counts_the_instructions_executed() {
+        let mut chip8 = Chip8Config::new().instructions_per_frame(3).build().unwrap();
+        chip8.load_rom(&[0x60, 0x01, 0xF1, 0x0A, 0x12, 0x00]).unwrap(); // V0 = 1, wait for a key, loop
+
+        // The first frame stops at the key wait, the next ones only wait
+        let frames = chip8.run_for(FRAME_DURATION * 3).unwrap();
+        assert_eq!(frames.instructions, 2);
+        assert_eq!(frames.frames, 3);
+
+        // Of the 3 cycles, taking the key is not an instruction, the jump and V0 = 1 are
+        chip8.press(Key::K1);
+        let frames = chip8.run_for(FRAME_DURATION).unwrap();
+        assert_eq!(frames.instructions, 2);
+    }
+
+    // Draws random digits at random positions, with a delay between two drawings
+    const RANDOM_DIGITS: [u8; 22] = [
+        0xC0, 0x0F, // V0 = random digit
+        0xF0, 0x29, // I = font digit V0
+        0xC1, 0x3F, // V1 = random x
+        0xC2, 0x1F, // V2 = random y
+        0xD1, 0x25, // draws the digit at (V1, V2)
+        0x63, 0x02, // V3 = 2
+        0xF3, 0x15, // delay timer = V3
+        0xF3, 0x07, // V3 = delay timer
+        0x33, 0x00, // skips the jump once the delay timer is 0
+        0x12, 0x0E, // jumps back to the delay timer read
+        0x12, 0x00, // starts again
+    ];
+
+    #[test]
+    fn run_cycles_counts_the_instructions_and_the_draws_until_the_program_exits() {
+        let mut chip8 = Chip8::default();
+        // Draws twice, waits for the vblank once, then exits
+        chip8.load_rom(&[0xD0, 0x05, 0xD0, 0x05, 0x00, 0xFD]).unwrap();
+        chip8.display_wait = true;
+
+        let summary = chip8.run_cycles(100).unwrap();
+
+        assert_eq!(summary.instructions, 3);
+        assert_eq!(summary.draws, 2);
+        assert_eq!(summary.digest, chip8.digest());
+        assert_eq!(chip8.state(), &State::Halted);
+    }
+
+    #[test]
+    fn run_cycles_ticks_the_timers_once_per_frame() {
+        let mut chip8 = Chip8Config::new().instructions_per_frame(10).build().unwrap();
+        chip8.load_rom(&[0x60, 0x05, 0xF0, 0x15, 0x12, 0x04]).unwrap(); // delay timer = 5, loop forever
+
+        chip8.run_cycles(30).unwrap();
+
+        assert_eq!(chip8.delay_timer(), 2);
+    }
+
+    #[test]
+    fn run_cycles_clears_the_key_edges_once_per_frame() {
+        let mut chip8 = Chip8Config::new().instructions_per_frame(1).build().unwrap();
+        chip8.load_rom(&[0x60, 0x00, 0xF1, 0x0A]).unwrap(); // one frame, then wait for a key in V1
+        chip8.key_event(Key::K5, true);
+        chip8.key_event(Key::K5, false);
+
+        chip8.run_cycles(3).unwrap();
+
+        // The tap is over with the first frame, the wait does not take it
+        assert_eq!(chip8.state(), &State::WaitingForKey { dest: 1 });
+        assert_eq!(chip8.register(1), 0);
+    }
+
+    #[test]
+    fn run_cycles_gives_the_same_summary_for_the_same_seed() {
+        let run = |seed| {
+            let mut chip8 = Chip8Config::new().seed(seed).build().unwrap();
+            chip8.load_rom(&RANDOM_DIGITS).unwrap();
+            chip8.run_cycles(10_000).unwrap()
+        };
+
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7).digest, run(8).digest);
+    }
+
+    // The random numbers come from the StdRng of the locked rand version, the digest changes if its algorithm does
+    #[test]
+    fn run_cycles_digest_is_stable() {
+        let mut chip8 = Chip8Config::new().seed(42).build().unwrap();
+        chip8.load_rom(&RANDOM_DIGITS).unwrap();
+
+        let summary = chip8.run_cycles(10_000).unwrap();
+
+        assert_eq!(summary, RunSummary { instructions: 10_000, draws: 417, digest: 0xEDFD_0C1A_42CB_85B9 });
+    }
+
+    #[test]
+    fn op_0x1nnn_jumps_to_address_nnn() {
+        let mut chip8 = Chip8::with_program(&[0x1FFE]);
+
+        chip8.step_n(1).unwrap();
+
+        assert_eq!(chip8.pc(), 0xFFE);
+    }
+
+    #[test]
+    fn op_0x2nnn_call_subroutine_at_nnn() {
+        let mut chip8 = Chip8::with_program(&[0x2FFE]);
+
+        chip8.step_n(1).unwrap();
+
+        assert_eq!(chip8.pc(), 0xFFE);
+        assert_eq!(chip8.stack, [0x200]);
+    }
+
+    #[test]
+    fn op_0x2nnn_returns_an_error_on_the_17th_nested_call() {
+        let mut chip8 = Chip8::default();
+        for call in 0..17 {
+            let address = 0x200 + call * 2;
+            chip8.memory[address] = 0x20 | ((address + 2) >> 8) as u8;
+            chip8.memory[address + 1] = (address + 2) as u8;
+        }
+
+        for _ in 0..16 {
+            chip8.emulate_cycle().unwrap();
+        }
+        let result = chip8.emulate_cycle();
+
+        assert_eq!(chip8.stack.len(), 16);
+        assert!(matches!(result, Err(Chip8Error::StackOverflow { pc: 0x220 })));
+    }
+
+    #[test]
+    fn op_0x00ee_returns_from_the_16th_nested_call() {
+        let mut chip8 = Chip8::default();
+        for call in 0..16 {
+            let address = 0x200 + call * 2;
+            chip8.memory[address] = 0x20 | ((address + 2) >> 8) as u8;
+            chip8.memory[address + 1] = (address + 2) as u8;
+        }
+        chip8.memory[0x220] = 0x00;
+        chip8.memory[0x221] = 0xEE;
+
+        for _ in 0..16 {
+            chip8.emulate_cycle().unwrap();
+        }
+        assert_eq!(chip8.stack.len(), 16);
+        chip8.emulate_cycle().unwrap();
+
+        assert_eq!(chip8.stack.len(), 15);
+        assert_eq!(chip8.pc(), 0x220);
+    }
+
+    #[test]
+    fn op_0x3xnn_skip_instruction_when_vx_equals_nn() {
+        let mut chip8 = Chip8::with_program(&[0x600F, 0x300F]);
+
+        chip8.step_n(2).unwrap();
+
+        assert_eq!(chip8.pc(), 0x206);
+    }
+
+    #[test]
+    fn op_0x3xnn_does_not_skip_instruction_when_vx_dont_equals_nn() {
+        let mut chip8 = Chip8::with_program(&[0x6000, 0x300F]);
+
+        chip8.step_n(2).unwrap();
+
+        assert_eq!(chip8.pc(), 0x204);
+    }
+
+    #[test]
+    fn op_0x4xnn_skip_instruction_when_vx_dont_equals_nn() {
+        let mut chip8 = Chip8::with_program(&[0x60CC, 0x400F]);
+
+        chip8.step_n(2).unwrap();
+
+        assert_eq!(chip8.pc(), 0x206);
+    }
+
+    #[test]
+    fn op_0x4xnn_does_not_skip_instruction_when_vx_equals_nn() {
+        let mut chip8 = Chip8::with_program(&[0x600F, 0x400F]);
+
+        chip8.step_n(2).unwrap();
+
+        assert_eq!(chip8.pc(), 0x204);
+    }
+
+    #[test]
+    fn op_0x5xy0_skip_instruction_when_vx_equals_vy() {
+        let mut chip8 = Chip8::with_program(&[0x600A, 0x610A, 0x5010]);
+
+        chip8.step_n(3).unwrap();
+
+        assert_eq!(chip8.pc(), 0x208);
+    }
+
+    #[test]
+    fn op_0x5xy0_does_not_skip_instruction_when_vx_dont_equals_vy() {
+        let mut chip8 = Chip8::with_program(&[0x600A, 0x610B, 0x5010]);
+
+        chip8.step_n(3).unwrap();
+
+        assert_eq!(chip8.pc(), 0x206);
+    }
+
+    #[test]
+    fn op_0x6xnn_sets_vx_to_nn() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let nn = 0xC;
+
+        let result = chip8.op_0x6xnn(x, nn);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), nn);
+    }
+
+    #[test]
+    fn op_0x7xnn_adds_nn_to_vx() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let nn = 0xC;
+        chip8.v[x] = 0x1;
+
+        let result = chip8.op_0x7xnn(x, nn);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0xD);
+    }
+
+    #[test]
+    fn op_0x7xnn_adds_nn_to_vx_does_not_change_carry_flag() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let nn = 0xFF;
+        chip8.v[x] = 0x1;
+
+        let result = chip8.op_0x7xnn(x, nn);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0x00);
+        assert_eq!(chip8.register(0xF), 0x0);
+    }
+
+    #[test]
+    fn op_0x8xy0_sets_vx_to_vy() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0x00;
+        chip8.v[y] = 0xFF;
+
+        let result = chip8.op_0x8xy0(x, y);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0xFF);
+    }
+
+    #[test]
+    fn op_0x8xy1_sets_vx_to_vx_or_vy() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0xA0;
+        chip8.v[y] = 0x0A;
+
+        let result = chip8.op_0x8xy1(x, y);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0xAA);
+    }
+
+    #[test]
+    fn op_0x8xy2_sets_vx_to_vx_and_vy() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0xA0;
+        chip8.v[y] = 0x0A;
+
+        let result = chip8.op_0x8xy2(x, y);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0x00);
+    }
+
+    #[test]
+    fn op_0x8xy3_sets_vx_to_vx_xor_vy() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0xA0;
+        chip8.v[y] = 0xAA;
+
+        let result = chip8.op_0x8xy3(x, y);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0x0A);
+    }
+
+    #[test]
+    fn op_0x8xy1_does_not_change_vf_without_vf_reset_quirk() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0xA0;
+        chip8.v[y] = 0x0A;
+        chip8.v[0x0F] = 1;
+
+        chip8.op_0x8xy1(x, y);
+
+        assert_eq!(chip8.register(x), 0xAA);
+        assert_eq!(chip8.register(0x0F), 1);
+    }
+
+    #[test]
+    fn op_0x8xy1_resets_vf_with_vf_reset_quirk() {
+        let mut chip8 = Chip8Config::new().vf_reset(true).build().unwrap();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0xA0;
+        chip8.v[y] = 0x0A;
+        chip8.v[0x0F] = 1;
+
+        chip8.op_0x8xy1(x, y);
+
+        assert_eq!(chip8.register(x), 0xAA);
+        assert_eq!(chip8.register(0x0F), 0);
+    }
+
+    #[test]
+    fn op_0x8xy2_does_not_change_vf_without_vf_reset_quirk() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0xA0;
+        chip8.v[y] = 0x0A;
+        chip8.v[0x0F] = 1;
+
+        chip8.op_0x8xy2(x, y);
+
+        assert_eq!(chip8.register(x), 0x00);
+        assert_eq!(chip8.register(0x0F), 1);
+    }
+
+    #[test]
+    fn op_0x8xy2_resets_vf_with_vf_reset_quirk() {
+        let mut chip8 = Chip8Config::new().vf_reset(true).build().unwrap();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0xA0;
+        chip8.v[y] = 0x0A;
+        chip8.v[0x0F] = 1;
+
+        chip8.op_0x8xy2(x, y);
+
+        assert_eq!(chip8.register(x), 0x00);
+        assert_eq!(chip8.register(0x0F), 0);
+    }
+
+    #[test]
+    fn op_0x8xy3_does_not_change_vf_without_vf_reset_quirk() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0xA0;
+        chip8.v[y] = 0x0A;
+        chip8.v[0x0F] = 1;
+
+        chip8.op_0x8xy3(x, y);
+
+        assert_eq!(chip8.register(x), 0xAA);
+        assert_eq!(chip8.register(0x0F), 1);
+    }
+
+    #[test]
+    fn op_0x8xy3_resets_vf_with_vf_reset_quirk() {
+        let mut chip8 = Chip8Config::new().vf_reset(true).build().unwrap();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0xA0;
+        chip8.v[y] = 0x0A;
+        chip8.v[0x0F] = 1;
+
+        chip8.op_0x8xy3(x, y);
+
+        assert_eq!(chip8.register(x), 0xAA);
+        assert_eq!(chip8.register(0x0F), 0);
+    }
+
+    #[test]
+    fn op_0x8xy4_adds_vx_to_vy_without_carry_flag() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0x01;
+        chip8.v[y] = 0x01;
+
+        let result = chip8.op_0x8xy4(x, y);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0x02);
+        assert_eq!(chip8.register(0x0F), 0);
+    }
+
+    #[test]
+    fn op_0x8xy4_adds_vx_to_vy_with_carry_flag() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0xFF;
+        chip8.v[y] = 0x01;
+
+        let result = chip8.op_0x8xy4(x, y);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0x00);
+        assert_eq!(chip8.register(0x0F), 1);
+    }
+
+    #[test]
+    fn op_0x8xy5_subtract_vy_to_vx_without_borrow_flag() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0xFF;
+        chip8.v[y] = 0x01;
+
+        let result = chip8.op_0x8xy5(x, y);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0xFE);
+        assert_eq!(chip8.register(0x0F), 1);
+    }
+
+    #[test]
+    fn op_0x8xy5_subtract_vy_to_vx_with_borrow_flag() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0x00;
+        chip8.v[y] = 0x01;
+
+        let result = chip8.op_0x8xy5(x, y);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0xFF);
+        assert_eq!(chip8.register(0x0F), 0);
+    }
+
+    #[test]
+    fn op_0x8xy5_subtract_equal_vy_to_vx_without_borrow_flag() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0x42;
+        chip8.v[y] = 0x42;
+
+        let result = chip8.op_0x8xy5(x, y);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0x00);
+        assert_eq!(chip8.register(0x0F), 1);
+    }
+
+    #[test]
+    fn op_0x8xy6_shift_right_vx_by_1_and_store_the_least_significant_bit_in_vf() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0x03;
+        chip8.v[y] = 0x10;
+
+        let result = chip8.op_0x8xy6(x, y);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0x01);
+        assert_eq!(chip8.register(y), 0x10);
+        assert_eq!(chip8.register(0x0F), 1);
+    }
+
+    #[test]
+    fn op_0x8xy6_shift_right_vy_by_1_into_vx_with_shift_quirk() {
+        let mut chip8 = Chip8Config::new().shift_uses_vy(true).build().unwrap();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0x03;
+        chip8.v[y] = 0x10;
+
+        let result = chip8.op_0x8xy6(x, y);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0x08);
+        assert_eq!(chip8.register(y), 0x10);
+        assert_eq!(chip8.register(0x0F), 0);
+    }
+
+    #[test]
+    fn op_0x8xy6_store_the_least_significant_bit_of_vy_in_vf_with_shift_quirk() {
+        let mut chip8 = Chip8Config::new().shift_uses_vy(true).build().unwrap();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0x02;
+        chip8.v[y] = 0x05;
+
+        chip8.op_0x8xy6(x, y);
+
+        assert_eq!(chip8.register(x), 0x02);
+        assert_eq!(chip8.register(0x0F), 1);
+    }
+
+    #[test]
+    fn op_0x8xy7_subtract_vx_to_vy_and_store_in_vx_without_borrow_flag() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0x01;
+        chip8.v[y] = 0x02;
+
+        let result = chip8.op_0x8xy7(x, y);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0x01);
+        assert_eq!(chip8.register(0x0F), 1);
+    }
+
+    #[test]
+    fn op_0x8xy7_subtract_vx_to_vy_and_store_in_vx_with_borrow_flag() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0x02;
+        chip8.v[y] = 0x01;
+
+        let result = chip8.op_0x8xy7(x, y);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0xFF);
+        assert_eq!(chip8.register(0x0F), 0);
+    }
+
+    #[test]
+    fn op_0x8xy7_subtract_equal_vx_to_vy_and_store_in_vx_without_borrow_flag() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0x42;
+        chip8.v[y] = 0x42;
+
+        let result = chip8.op_0x8xy7(x, y);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0x00);
+        assert_eq!(chip8.register(0x0F), 1);
+    }
+
+    #[test]
+    fn op_0x8xye_shift_left_vx_by_1_and_store_the_most_significant_bit_in_vf() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0xF0;
+        chip8.v[y] = 0x01;
+
+        let result = chip8.op_0x8xye(x, y);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0xE0);
+        assert_eq!(chip8.register(y), 0x01);
+        assert_eq!(chip8.register(0x0F), 1);
+    }
+
+    #[test]
+    fn op_0x8xye_shift_left_vy_by_1_into_vx_with_shift_quirk() {
+        let mut chip8 = Chip8Config::new().shift_uses_vy(true).build().unwrap();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0xF0;
+        chip8.v[y] = 0x01;
+
+        let result = chip8.op_0x8xye(x, y);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0x02);
+        assert_eq!(chip8.register(y), 0x01);
+        assert_eq!(chip8.register(0x0F), 0);
+    }
+
+    #[test]
+    fn op_0x8xye_store_the_most_significant_bit_of_vy_in_vf_with_shift_quirk() {
+        let mut chip8 = Chip8Config::new().shift_uses_vy(true).build().unwrap();
+        let x = 1;
+        let y = 2;
+        chip8.v[x] = 0x01;
+        chip8.v[y] = 0x81;
+
+        chip8.op_0x8xye(x, y);
+
+        assert_eq!(chip8.register(x), 0x02);
+        assert_eq!(chip8.register(0x0F), 1);
+    }
+
+    #[test]
+    fn op_0x9xy0_skip_when_vx_is_different_from_vy() {
+        let mut chip8 = Chip8::with_program(&[0x61F0, 0x6201, 0x9120]);
+
+        chip8.step_n(3).unwrap();
+
+        assert_eq!(chip8.pc(), 0x208);
+    }
+
+    #[test]
+    fn op_0x9xy0_next_when_vx_is_equal_from_vy() {
+        let mut chip8 = Chip8::with_program(&[0x6101, 0x6201, 0x9120]);
+
+        chip8.step_n(3).unwrap();
+
+        assert_eq!(chip8.pc(), 0x206);
+    }
+
+    #[test]
+    fn op_0xannn_sets_i_to_nnn() {
+        let mut chip8 = Chip8::default();
+        let nnn: u16 = 0x55;
+
+        let result = chip8.op_0xannn(nnn);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.index(), nnn)
+    }
+
+    #[test]
+    fn op_0xbnnn_jumps_to_nnn_plus_v0() {
+        let mut chip8 = Chip8::with_program(&[0x6001, 0x6204, 0xB255]);
+
+        chip8.step_n(3).unwrap();
+
+        assert_eq!(chip8.pc(), 0x256);
+    }
+
+    #[test]
+    fn op_0xbnnn_jumps_to_xnn_plus_vx_with_jump_quirk() {
+        let mut chip8 = Chip8Config::new().jump_uses_vx(true).build().unwrap();
+        chip8.v[0] = 1;
+        chip8.v[2] = 4;
+
+        let result = chip8.op_0xbnnn(2, 0x255);
+
+        assert!(matches!(result, GOTO(0x259)));
+    }
+
+    #[test]
+    fn op_0xcxnn_return_next_and_set_vx_to_random() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        let nn: u8 = 0xFF;
+
+        let result = chip8.op_0xcxnn(x, nn);
+
+        assert!(matches!(result, NEXT));
+    }
+
+    #[test]
+    fn op_0xf000_sets_i_to_the_next_word_and_skips_it() {
+        let mut chip8 = Chip8::default();
+        chip8.memory[0x200..0x204].copy_from_slice(&[0xF0, 0x00, 0xAB, 0xCD]);
+
+        chip8.emulate_cycle().unwrap();
+
+        assert_eq!(chip8.index(), 0xABCD);
+        assert_eq!(chip8.pc(), 0x204);
+    }
+
+    #[test]
+    fn op_0xf000_runs_the_instruction_after_an_address_of_0xf000() {
+        let mut chip8 = Chip8::default();
+        chip8.memory[0x200..0x206].copy_from_slice(&[0xF0, 0x00, 0xF0, 0x00, 0x60, 0x01]);
+
+        chip8.step_n(2).unwrap();
+
+        assert_eq!(chip8.index(), 0xF000);
+        assert_eq!(chip8.register(0), 0x01);
+        assert_eq!(chip8.pc(), 0x206);
+    }
+
+    #[test]
+    fn op_0x3xnn_skips_over_a_jump() {
+        let mut chip8 = Chip8::with_program(&[0x6005, 0x3005, 0x1300, 0x6101]);
+
+        chip8.step_n(3).unwrap();
+
+        assert_eq!(chip8.pc(), 0x208);
+        assert_eq!(chip8.register(1), 0x01);
+    }
+
+    #[test]
+    fn op_0x3xnn_lands_on_a_jump_when_not_skipping() {
+        let mut chip8 = Chip8::with_program(&[0x6005, 0x3006, 0x1300, 0x6101]);
+
+        chip8.step_n(3).unwrap();
+
+        assert_eq!(chip8.pc(), 0x300);
+        assert_eq!(chip8.register(1), 0x00);
+    }
+
+    #[test]
+    fn op_0x4xnn_skips_over_a_jump_back_to_leave_a_loop() {
+        // Counts V0 up to 3 in a loop, then leaves it
+        let mut chip8 = Chip8::with_program(&[0x7001, 0x4003, 0x1208, 0x1200, 0x6101]);
+
+        chip8.step_n(10).unwrap();
+
+        assert_eq!(chip8.register(0), 3);
+        assert_eq!(chip8.register(1), 1);
+        assert_eq!(chip8.pc(), 0x20A);
+    }
+
+    #[test]
+    fn op_0x9xy0_skips_over_a_call() {
+        let mut chip8 = Chip8::with_program(&[0x6001, 0x9010, 0x2300, 0x6202]);
+
+        chip8.step_n(4).unwrap();
+
+        assert!(chip8.stack.is_empty());
+        assert_eq!(chip8.register(2), 0x02);
+    }
+
+    #[test]
+    fn op_0x3xnn_skips_over_both_words_of_f000() {
+        let mut chip8 = Chip8::default();
+        chip8.memory[0x200..0x208].copy_from_slice(&[0x30, 0x00, 0xF0, 0x00, 0xAB, 0xCD, 0x60, 0x01]);
+
+        chip8.emulate_cycle().unwrap();
+
+        assert_eq!(chip8.pc(), 0x206);
+        assert_eq!(chip8.index(), 0);
+    }
+
+    #[test]
+    fn op_0x3xnn_skips_a_single_word_when_the_next_instruction_is_not_f000() {
+        let mut chip8 = Chip8::default();
+        chip8.memory[0x200..0x206].copy_from_slice(&[0x30, 0x00, 0xF0, 0x01, 0xAB, 0xCD]);
+
+        chip8.emulate_cycle().unwrap();
+
+        assert_eq!(chip8.pc(), 0x204);
+    }
+
+    #[test]
+    fn op_0xfx1e_adds_vx_to_i() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        chip8.v[x] = 0x10;
+        chip8.i = 0x200;
+
+        let result = chip8.op_0xfx1e(x);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.index(), 0x210);
+        assert_eq!(chip8.register(0x0F), 0);
+    }
+
+    #[test]
+    fn op_0xfx1e_sets_vf_on_overflow_with_index_overflow_quirk() {
+        let mut chip8 = Chip8Config::new().index_overflow_quirk(true).build().unwrap();
+        let x = 1;
+        chip8.v[x] = 0x02;
+        chip8.i = 0x0FFF;
+
+        let result = chip8.op_0xfx1e(x);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.index(), 0x1001);
+        assert_eq!(chip8.register(0x0F), 1);
+    }
+
+    #[test]
+    fn op_0xfx1e_clears_vf_without_overflow_with_index_overflow_quirk() {
+        let mut chip8 = Chip8Config::new().index_overflow_quirk(true).build().unwrap();
+        let x = 1;
+        chip8.v[x] = 0x01;
+        chip8.v[0x0F] = 1;
+        chip8.i = 0x0FFE;
+
+        chip8.op_0xfx1e(x);
+
+        assert_eq!(chip8.index(), 0x0FFF);
+        assert_eq!(chip8.register(0x0F), 0);
+    }
+
+    #[test]
+    fn op_0xfx1e_does_not_touch_vf_on_overflow_without_index_overflow_quirk() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        chip8.v[x] = 0x02;
+        chip8.v[0x0F] = 0xAB;
+        chip8.i = 0x0FFF;
+
+        chip8.op_0xfx1e(x);
+
+        assert_eq!(chip8.index(), 0x1001);
+        assert_eq!(chip8.register(0x0F), 0xAB);
+    }
+
+    #[test]
+    fn op_0xfx1e_wraps_i_to_12_bits_with_index_mask() {
+        let mut chip8 = Chip8 { i: 0x0FFF, ..Chip8Config::new().index_mask_12bit(true).build().unwrap() };
+        chip8.v[1] = 0x02;
+
+        chip8.op_0xfx1e(1);
+
+        assert_eq!(chip8.index(), 0x0001);
+        assert_eq!(chip8.register(0x0F), 0);
+    }
+
+    #[test]
+    fn op_0xfx1e_wraps_i_to_12_bits_and_sets_vf_with_index_mask_and_index_overflow_quirk() {
+        let mut chip8 = Chip8 { i: 0x0FFF, ..Chip8Config::new().index_mask_12bit(true).index_overflow_quirk(true).build().unwrap() };
+        chip8.v[1] = 0x02;
+
+        chip8.op_0xfx1e(1);
+
+        assert_eq!(chip8.index(), 0x0001);
+        assert_eq!(chip8.register(0x0F), 1);
+    }
+
+    #[test]
+    fn op_0xfx75_stores_v0_to_vx_in_rpl_flags() {
+        let mut chip8 = Chip8::default();
+        chip8.v[0..4].copy_from_slice(&[0x01, 0x02, 0x03, 0x04]);
+
+        let result = chip8.op_0xfx75(2);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.rpl_flags, [0x01, 0x02, 0x03, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn op_0xfx75_clamps_x_to_the_last_rpl_flag() {
+        let mut chip8 = Chip8::default();
+        for register in 0..16 {
+            chip8.v[register] = register as u8 + 1;
+        }
+
+        chip8.op_0xfx75(0xF);
+
+        assert_eq!(chip8.rpl_flags, [1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn op_0xfx85_fills_v0_to_vx_from_rpl_flags() {
+        let mut chip8 = Chip8 { rpl_flags: [1, 2, 3, 4, 5, 6, 7, 8], ..Chip8::default() };
+
+        let result = chip8.op_0xfx85(2);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.v[0..4], [1, 2, 3, 0]);
+    }
+
+    #[test]
+    fn op_0xfx85_clamps_x_to_the_last_rpl_flag() {
+        let mut chip8 = Chip8 { rpl_flags: [1, 2, 3, 4, 5, 6, 7, 8], ..Chip8::default() };
+        chip8.v[8] = 0xAA;
+
+        chip8.op_0xfx85(0xF);
+
+        assert_eq!(chip8.v[0..9], [1, 2, 3, 4, 5, 6, 7, 8, 0xAA]);
+    }
+
+    #[test]
+    fn op_0xfx75_and_op_0xfx85_round_trip_the_registers() {
+        let mut chip8 = Chip8::default();
+        chip8.v[0..8].copy_from_slice(&[0x10, 0x20, 0x30, 0x40, 0x50, 0x60, 0x70, 0x80]);
+
+        chip8.op_0xfx75(7);
+        chip8.v = [0xFF; 16];
+        chip8.op_0xfx85(7);
+
+        assert_eq!(chip8.v[0..8], [0x10, 0x20, 0x30, 0x40, 0x50, 0x60, 0x70, 0x80]);
+        assert_eq!(chip8.v[8..16], [0xFF; 8]);
+    }
+
+    #[test]
+    fn emulate_cycle_fetches_both_bytes_of_the_opcode() {
+        let mut chip8 = Chip8::default();
+        chip8.memory[0x200] = 0xA2;
+        chip8.memory[0x201] = 0xF0;
+
+        chip8.emulate_cycle().unwrap();
+
+        assert_eq!(chip8.opcode, 0xA2F0);
+        assert_eq!(chip8.index(), 0x2F0);
+        assert_eq!(chip8.pc(), 0x202);
+    }
+
+    #[test]
+    fn op_0x0nnn_is_skipped_with_ignore_policy() {
+        let mut chip8 = Chip8Config::new().sys_opcode_policy(SysOpcodePolicy::Ignore).build().unwrap();
+        chip8.memory[0x200] = 0x01;
+        chip8.memory[0x201] = 0x23;
+
+        chip8.emulate_cycle().unwrap();
+
+        assert_eq!(chip8.pc(), 0x202);
+    }
+
+    #[test]
+    fn op_0x0nnn_returns_an_error_with_error_policy() {
+        let mut chip8 = Chip8Config::new().sys_opcode_policy(SysOpcodePolicy::Error).build().unwrap();
+        chip8.memory[0x200] = 0x01;
+        chip8.memory[0x201] = 0x23;
+
+        let result = chip8.emulate_cycle();
+
+        assert!(matches!(result, Err(Chip8Error::SysOpcode { addr: 0x123, pc: 0x200 })));
+        assert_eq!(chip8.pc(), 0x200);
+    }
+
+    #[test]
+    fn op_0x00ee_takes_priority_over_0nnn_with_error_policy() {
+        let mut chip8 = Chip8Config::new().sys_opcode_policy(SysOpcodePolicy::Error).build().unwrap();
+        chip8.memory[0x200] = 0x00;
+        chip8.memory[0x201] = 0xEE;
+        chip8.stack.push(0x300);
+
+        chip8.emulate_cycle().unwrap();
+
+        assert_eq!(chip8.pc(), 0x302);
+    }
+
+    #[test]
+    fn op_0x0nnn_is_neither_a_clear_screen_nor_a_return() {
+        let mut chip8 = Chip8::default();
+        chip8.memory[0x200] = 0x02;
+        chip8.memory[0x201] = 0x30;
+
+        chip8.emulate_cycle().unwrap();
+
+        assert_eq!(chip8.opcode, 0x0230);
+        assert_eq!(chip8.pc(), 0x202);
+        assert!(chip8.stack.is_empty());
+    }
+
+    #[test]
+    fn op_0x0nnn_with_ee_low_byte_is_not_a_return() {
+        let mut chip8 = Chip8::default();
+        chip8.memory[0x200] = 0x02;
+        chip8.memory[0x201] = 0xEE;
+        chip8.stack.push(0x400);
+
+        chip8.emulate_cycle().unwrap();
+
+        assert_eq!(chip8.pc(), 0x202);
+        assert_eq!(chip8.stack, vec![0x400]);
+    }
+
+    #[test]
+    fn emulate_cycle_returns_an_error_on_unknown_opcode() {
+        let mut chip8 = Chip8::default();
+        chip8.memory[0x200] = 0xE0;
+        chip8.memory[0x201] = 0x55;
+
+        let result = chip8.emulate_cycle();
+
+        assert!(matches!(result, Err(Chip8Error::UnknownOpcode { opcode: 0xE055, pc: 0x200 })));
+        assert_eq!(chip8.pc(), 0x200);
+    }
+
+    #[test]
+    fn unknown_opcode_error_is_displayed_in_hex() {
+        let error = Chip8Error::UnknownOpcode { opcode: 0xE055, pc: 0x20A };
+
+        assert_eq!(error.to_string(), "Unknown opcode read : 0xE055 at 0x020A");
+    }
+
+    #[test]
+    fn emulate_cycle_returns_an_error_when_fetching_past_the_end_of_memory() {
+        let mut chip8 = Chip8::default();
+        chip8.memory[0x200] = 0x1F;
+        chip8.memory[0x201] = 0xFF;
+
+        chip8.emulate_cycle().unwrap();
+        let result = chip8.emulate_cycle();
+
+        assert_eq!(chip8.pc(), 0xFFF);
+        assert!(matches!(result, Err(Chip8Error::MemoryOutOfBounds { addr: 0x1000, pc: 0xFFF })));
+    }
+}
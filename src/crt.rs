@@ -0,0 +1,84 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./crt_tests.rs"]
+mod crt_tests;
+
+// CRT look of the windows, computed on the CPU while scaling the screen up: darker scanlines, gaps between the pixels
+// and a vignette. The factors only depend on the position, so every frontend shows the same image
+
+use crate::Rgb;
+
+// Brightness of the odd rows of the scaled screen
+const SCANLINE_LEVEL: f32 = 0.7;
+// Brightness of the last row and column of each pixel, when a pixel is at least GRID_MIN_SCALE wide
+const GRID_LEVEL: f32 = 0.8;
+const GRID_MIN_SCALE: usize = 4;
+// Darkening of the corners by the vignette, the center keeps its brightness
+const VIGNETTE_STRENGTH: f32 = 0.35;
+
+// Parts of the effect, all off by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Crt {
+    pub scanlines: bool,
+    pub grid: bool,
+    pub vignette: bool,
+}
+
+impl Crt {
+    pub const OFF: Crt = Crt { scanlines: false, grid: false, vignette: false };
+    // Scanlines and pixel grid, without the vignette
+    pub const SCANLINES: Crt = Crt { scanlines: true, grid: true, vignette: false };
+
+    pub fn is_off(&self) -> bool {
+        *self == Crt::OFF
+    }
+}
+
+// Brightness of a row of the scaled screen, every other row is darkened
+pub fn scanline_level(row: usize) -> f32 {
+    if row % 2 == 1 { SCANLINE_LEVEL } else { 1.0 }
+}
+
+// Brightness of a row or column at the offset within a pixel of scale by scale, the last one is a gap between the
+// pixels when they are large enough to show it
+pub fn grid_level(offset: usize, scale: usize) -> f32 {
+    if scale >= GRID_MIN_SCALE && offset == scale - 1 { GRID_LEVEL } else { 1.0 }
+}
+
+// Brightness at (x, y) of a width by height image, falling from the center to the corners with the square of the
+// distance
+pub fn vignette_level(x: usize, y: usize, width: usize, height: usize) -> f32 {
+    let from_center = |position: usize, size: usize| (position as f32 + 0.5) / size.max(1) as f32 * 2.0 - 1.0;
+    let (dx, dy) = (from_center(x, width), from_center(y, height));
+    1.0 - VIGNETTE_STRENGTH * (dx * dx + dy * dy) / 2.0
+}
+
+// Scales the colors of a screen up, each pixel a square of scale by scale, with the effect applied to the scaled
+// image. Without any effect this is the plain scaling
+pub fn crt_image(colors: &[Rgb], width: usize, scale: usize, crt: &Crt) -> Vec<Rgb> {
+    let (width, scale) = (width.max(1), scale.max(1));
+    let height = colors.len() / width;
+    let (image_width, image_height) = (width * scale, height * scale);
+    let mut image = Vec::with_capacity(image_width * image_height);
+    for y in 0..image_height {
+        let mut row_level = 1.0;
+        if crt.scanlines {
+            row_level *= scanline_level(y);
+        }
+        if crt.grid {
+            row_level *= grid_level(y % scale, scale);
+        }
+        for x in 0..image_width {
+            let mut level = row_level;
+            if crt.grid {
+                level *= grid_level(x % scale, scale);
+            }
+            if crt.vignette {
+                level *= vignette_level(x, y, image_width, image_height);
+            }
+            let color = colors[y / scale * width + x / scale];
+            image.push(if level < 1.0 { color.map(|channel| (f32::from(channel) * level).round() as u8) } else { color });
+        }
+    }
+    image
+}
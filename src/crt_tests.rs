@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod crt_tests {
+    use crate::{crt_image, grid_level, scanline_level, vignette_level, Crt};
+
+    #[test]
+    fn scanline_level_darkens_every_other_row() {
+        assert_eq!(scanline_level(0), 1.0);
+        assert_eq!(scanline_level(1), 0.7);
+        assert_eq!(scanline_level(2), 1.0);
+        assert_eq!(scanline_level(641), 0.7);
+    }
+
+    #[test]
+    fn grid_level_darkens_the_last_row_and_column_of_each_pixel() {
+        // The last row or column of each pixel
+        assert_eq!(grid_level(0, 10), 1.0);
+        assert_eq!(grid_level(8, 10), 1.0);
+        assert_eq!(grid_level(9, 10), 0.8);
+        assert_eq!(grid_level(3, 4), 0.8);
+        // No gaps in small pixels
+        assert_eq!(grid_level(2, 3), 1.0);
+        assert_eq!(grid_level(0, 1), 1.0);
+    }
+
+    #[test]
+    fn vignette_level_darkens_towards_the_corners() {
+        // Brightest in the middle, symmetric, darkest in the corners
+        let center = vignette_level(50, 25, 100, 50);
+        assert!(center > 0.999);
+        assert_eq!(vignette_level(0, 0, 100, 50), vignette_level(99, 49, 100, 50));
+        assert_eq!(vignette_level(10, 0, 100, 50), vignette_level(89, 0, 100, 50));
+        let corner = vignette_level(0, 0, 100, 50);
+        // The centers of the corner pixels are 0.99 and 0.98 of the half sizes away from the center
+        assert!((corner - (1.0 - 0.35 * (0.99 * 0.99 + 0.98 * 0.98) / 2.0)).abs() < 0.001, "{corner}");
+        assert!(vignette_level(0, 25, 100, 50) > corner);
+        assert!(vignette_level(0, 25, 100, 50) < center);
+    }
+
+    #[test]
+    fn crt_image_off_scales_the_screen() {
+        let colors = [[10, 20, 30], [40, 50, 60]];
+        let image = crt_image(&colors, 2, 2, &Crt::OFF);
+        assert_eq!(image, [
+            [10, 20, 30], [10, 20, 30], [40, 50, 60], [40, 50, 60],
+            [10, 20, 30], [10, 20, 30], [40, 50, 60], [40, 50, 60],
+        ]);
+    }
+
+    #[test]
+    fn crt_image_darkens_the_scanlines() {
+        let crt = Crt { scanlines: true, ..Crt::OFF };
+        let image = crt_image(&[[100, 200, 0]], 1, 3, &crt);
+        // Rows of 3 pixels: the middle one darkened
+        assert_eq!(image[0], [100, 200, 0]);
+        assert_eq!(image[3], [70, 140, 0]);
+        assert_eq!(image[6], [100, 200, 0]);
+    }
+
+    #[test]
+    fn crt_image_draws_the_gaps_of_the_grid() {
+        let crt = Crt { grid: true, ..Crt::OFF };
+        let image = crt_image(&[[100, 100, 100]; 2], 2, 4, &crt);
+        assert_eq!(image.len(), 8 * 4);
+        assert_eq!(image[0], [100, 100, 100]);
+        // Gap at the right of each pixel, and both gaps at the bottom right corner
+        assert_eq!(image[3], [80, 80, 80]);
+        assert_eq!(image[4], [100, 100, 100]);
+        assert_eq!(image[3 * 8], [80, 80, 80]);
+        assert_eq!(image[3 * 8 + 3], [64, 64, 64]);
+    }
+
+    #[test]
+    fn crt_image_keeps_black() {
+        let image = crt_image(&[[0, 0, 0]; 4], 2, 5, &Crt { vignette: true, ..Crt::SCANLINES });
+        assert!(image.iter().all(|&color| color == [0, 0, 0]));
+    }
+
+    #[test]
+    fn crt_is_off_without_any_effect() {
+        assert!(Crt::default().is_off());
+        assert!(!Crt::SCANLINES.is_off());
+        assert!(!Crt { vignette: true, ..Crt::OFF }.is_off());
+    }
+}
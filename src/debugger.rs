@@ -0,0 +1,283 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./debugger_tests.rs"]
+mod debugger_tests;
+
+// Debugger frontend, enabled with the egui feature: a window with the screen, the registers, the disassembly around
+// the PC and the memory, updated while the program runs, with pause, step and speed controls. The registers can be
+// edited while paused
+
+use std::time::Instant;
+use eframe::egui;
+use eframe::egui::{Color32, ColorImage, Key as EguiKey, RichText, TextureHandle, TextureOptions};
+use crate::memory::MEMORY_SIZE;
+use crate::{decode, Chip8, Chip8Error, Instruction, Key, Keymap, PhysicalKey, State, Theme};
+
+// Key of egui for each keyboard key of the keymap
+fn egui_key(physical_key: PhysicalKey) -> EguiKey {
+    match physical_key {
+        PhysicalKey::Key0 => EguiKey::Num0,
+        PhysicalKey::Key1 => EguiKey::Num1,
+        PhysicalKey::Key2 => EguiKey::Num2,
+        PhysicalKey::Key3 => EguiKey::Num3,
+        PhysicalKey::Key4 => EguiKey::Num4,
+        PhysicalKey::Key5 => EguiKey::Num5,
+        PhysicalKey::Key6 => EguiKey::Num6,
+        PhysicalKey::Key7 => EguiKey::Num7,
+        PhysicalKey::Key8 => EguiKey::Num8,
+        PhysicalKey::Key9 => EguiKey::Num9,
+        PhysicalKey::A => EguiKey::A,
+        PhysicalKey::B => EguiKey::B,
+        PhysicalKey::C => EguiKey::C,
+        PhysicalKey::D => EguiKey::D,
+        PhysicalKey::E => EguiKey::E,
+        PhysicalKey::F => EguiKey::F,
+        PhysicalKey::G => EguiKey::G,
+        PhysicalKey::H => EguiKey::H,
+        PhysicalKey::I => EguiKey::I,
+        PhysicalKey::J => EguiKey::J,
+        PhysicalKey::K => EguiKey::K,
+        PhysicalKey::L => EguiKey::L,
+        PhysicalKey::M => EguiKey::M,
+        PhysicalKey::N => EguiKey::N,
+        PhysicalKey::O => EguiKey::O,
+        PhysicalKey::P => EguiKey::P,
+        PhysicalKey::Q => EguiKey::Q,
+        PhysicalKey::R => EguiKey::R,
+        PhysicalKey::S => EguiKey::S,
+        PhysicalKey::T => EguiKey::T,
+        PhysicalKey::U => EguiKey::U,
+        PhysicalKey::V => EguiKey::V,
+        PhysicalKey::W => EguiKey::W,
+        PhysicalKey::X => EguiKey::X,
+        PhysicalKey::Y => EguiKey::Y,
+        PhysicalKey::Z => EguiKey::Z,
+        PhysicalKey::Up => EguiKey::ArrowUp,
+        PhysicalKey::Down => EguiKey::ArrowDown,
+        PhysicalKey::Left => EguiKey::ArrowLeft,
+        PhysicalKey::Right => EguiKey::ArrowRight,
+        PhysicalKey::Space => EguiKey::Space,
+    }
+}
+
+// Instructions shown before and after the PC in the disassembly
+const DISASSEMBLY_CONTEXT: u16 = 12;
+
+// Size of a CHIP-8 pixel of the low resolution screen, in points
+const SCALE: f32 = 8.0;
+
+// Speeds offered by the slider, in instructions per second
+const MIN_CLOCK_HZ: u32 = 60;
+const MAX_CLOCK_HZ: u32 = 5000;
+
+// Addresses and opcodes around the PC, each with its instruction when it decodes. The addresses keep the parity of
+// the PC and stop at the ends of the memory
+pub(crate) fn disassembly(chip8: &Chip8, context: u16) -> Vec<(u16, u16, Option<Instruction>)> {
+    let pc = chip8.pc();
+    let memory = chip8.memory();
+    let first = pc - pc.min(context * 2) / 2 * 2;
+    (first..=pc.saturating_add(context * 2))
+        .step_by(2)
+        .filter(|&address| usize::from(address) + 1 < MEMORY_SIZE)
+        .map(|address| {
+            let opcode = u16::from_be_bytes([memory[usize::from(address)], memory[usize::from(address) + 1]]);
+            (address, opcode, decode(opcode).ok())
+        })
+        .collect()
+}
+
+pub struct Debugger {
+    chip8: Chip8,
+    paused: bool,
+    // Error stopping the program, shown until the machine is reset
+    error: Option<Chip8Error>,
+    screen: Option<TextureHandle>,
+    theme: Theme,
+    last_update: Instant,
+    keymap: Keymap,
+}
+
+impl Debugger {
+    // Starts paused on the first instruction
+    pub fn new(chip8: Chip8) -> Debugger {
+        Debugger {
+            chip8,
+            paused: true,
+            error: None,
+            screen: None,
+            theme: Theme::default(),
+            last_update: Instant::now(),
+            keymap: Keymap::default(),
+        }
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+    }
+
+    // Opens the debugger window, returns once it is closed
+    pub fn run(self, title: &str) -> Result<(), eframe::Error> {
+        let options = eframe::NativeOptions {
+            viewport: egui::ViewportBuilder::default().with_inner_size([1200.0, 720.0]).with_title(title),
+            ..eframe::NativeOptions::default()
+        };
+        eframe::run_native(title, options, Box::new(|_| Ok(Box::new(self))))
+    }
+
+    fn record(&mut self, result: Result<(), Chip8Error>) {
+        if let Err(error) = result {
+            self.error = Some(error);
+            self.paused = true;
+        }
+    }
+
+    fn controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let label = if self.paused { "Run" } else { "Pause" };
+            if ui.button(label).clicked() {
+                self.paused = !self.paused;
+            }
+            if ui.add_enabled(self.paused, egui::Button::new("Step")).clicked() {
+                let result = self.chip8.emulate_cycle().map(|_| ());
+                self.record(result);
+            }
+            if ui.add_enabled(self.paused, egui::Button::new("Frame")).clicked() {
+                let result = self.chip8.run_frame().map(|_| ());
+                self.record(result);
+            }
+            if ui.button("Reset").clicked() {
+                self.chip8.reset();
+                self.error = None;
+            }
+            ui.separator();
+            let mut clock_hz = self.chip8.clock_hz();
+            let slider = egui::Slider::new(&mut clock_hz, MIN_CLOCK_HZ..=MAX_CLOCK_HZ).logarithmic(true).suffix(" Hz");
+            if ui.add(slider).changed() {
+                self.chip8.set_clock_hz(clock_hz);
+            }
+            ui.separator();
+            match (&self.error, self.chip8.state()) {
+                (Some(error), _) => ui.colored_label(Color32::RED, error.to_string()),
+                (None, state) => ui.label(format!("{state:?}")),
+            };
+        });
+    }
+
+    // Editable while paused, read only while running
+    fn registers(&mut self, ui: &mut egui::Ui) {
+        let enabled = self.paused;
+        egui::Grid::new("registers").num_columns(4).striped(true).show(ui, |ui| {
+            for x in 0..16 {
+                let mut value = self.chip8.register(x);
+                ui.label(format!("V{x:X}"));
+                if ui.add_enabled(enabled, egui::DragValue::new(&mut value).hexadecimal(2, false, true)).changed() {
+                    self.chip8.set_register(x, value);
+                }
+                if x % 2 == 1 {
+                    ui.end_row();
+                }
+            }
+            let mut index = self.chip8.index();
+            ui.label("I");
+            if ui.add_enabled(enabled, egui::DragValue::new(&mut index).hexadecimal(4, false, true)).changed() {
+                self.chip8.set_index(index);
+            }
+            let mut pc = self.chip8.pc();
+            ui.label("PC");
+            if ui.add_enabled(enabled, egui::DragValue::new(&mut pc).hexadecimal(4, false, true)).changed() {
+                self.chip8.set_pc(pc);
+            }
+            ui.end_row();
+            let mut delay = self.chip8.delay_timer();
+            ui.label("DT");
+            if ui.add_enabled(enabled, egui::DragValue::new(&mut delay)).changed() {
+                self.chip8.set_delay_timer(delay);
+            }
+            let mut sound = self.chip8.sound_timer();
+            ui.label("ST");
+            if ui.add_enabled(enabled, egui::DragValue::new(&mut sound)).changed() {
+                self.chip8.set_sound_timer(sound);
+            }
+            ui.end_row();
+        });
+        ui.separator();
+        let stack: Vec<String> = self.chip8.stack().iter().map(|address| format!("{address:04X}")).collect();
+        ui.label(format!("Stack: {}", stack.join(" ")));
+    }
+
+    fn disassembly(&self, ui: &mut egui::Ui) {
+        let pc = self.chip8.pc();
+        for (address, opcode, instruction) in disassembly(&self.chip8, DISASSEMBLY_CONTEXT) {
+            let text = match instruction {
+                Some(instruction) => format!("{address:04X}  {opcode:04X}  {instruction:?}"),
+                None => format!("{address:04X}  {opcode:04X}  ????"),
+            };
+            let text = RichText::new(text).monospace();
+            if address == pc {
+                ui.label(text.background_color(Color32::DARK_BLUE).color(Color32::WHITE));
+            } else {
+                ui.label(text);
+            }
+        }
+    }
+
+    fn memory(&self, ui: &mut egui::Ui) {
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        let memory = self.chip8.memory();
+        egui::ScrollArea::vertical().auto_shrink(false).show_rows(ui, row_height, memory.len() / 16, |ui, rows| {
+            for row in rows {
+                let bytes: Vec<String> = memory[row * 16..row * 16 + 16].iter().map(|byte| format!("{byte:02X}")).collect();
+                ui.monospace(format!("{:04X}  {}", row * 16, bytes.join(" ")));
+            }
+        });
+    }
+
+    fn screen(&mut self, ui: &mut egui::Ui) {
+        let (width, height) = self.chip8.resolution();
+        let pixels = self.chip8.pixels().map(|pixel| {
+            let [red, green, blue] = self.theme.color(pixel);
+            Color32::from_rgb(red, green, blue)
+        }).collect();
+        let image = ColorImage { size: [width, height], pixels };
+        let texture = match &mut self.screen {
+            Some(texture) => {
+                texture.set(image, TextureOptions::NEAREST);
+                texture
+            }
+            None => self.screen.insert(ui.ctx().load_texture("screen", image, TextureOptions::NEAREST)),
+        };
+        // The high resolution screen has the size of the low resolution one
+        let size = egui::vec2(64.0 * SCALE, 64.0 * SCALE * height as f32 / width as f32);
+        ui.image((texture.id(), size));
+    }
+}
+
+impl eframe::App for Debugger {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        ctx.input(|input| {
+            let keys = self.keymap.poll(|physical_key| input.key_down(egui_key(physical_key)));
+            for (key, pressed) in Key::ALL.into_iter().zip(keys) {
+                self.chip8.key_event(key, pressed);
+            }
+        });
+
+        let now = Instant::now();
+        let elapsed = now - self.last_update;
+        self.last_update = now;
+        if !self.paused && self.chip8.state() != &State::Halted {
+            let result = self.chip8.run_for(elapsed).map(|_| ());
+            self.record(result);
+            ctx.request_repaint();
+        }
+
+        egui::TopBottomPanel::top("controls").show(ctx, |ui| self.controls(ui));
+        egui::SidePanel::right("registers").show(ctx, |ui| self.registers(ui));
+        egui::SidePanel::left("disassembly").show(ctx, |ui| self.disassembly(ui));
+        egui::TopBottomPanel::bottom("memory").resizable(true).default_height(200.0).show(ctx, |ui| self.memory(ui));
+        egui::CentralPanel::default().show(ctx, |ui| self.screen(ui));
+    }
+}
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod debugger_tests {
+    use crate::debugger::disassembly;
+    use crate::{Chip8, Instruction};
+
+    #[test]
+    fn disassembly_is_centered_on_the_pc() {
+        let mut chip8 = Chip8::with_program(&[0x6005, 0x7001, 0x1202, 0x0000]);
+        chip8.step_n(2).unwrap();
+
+        let lines = disassembly(&chip8, 2);
+
+        let addresses: Vec<u16> = lines.iter().map(|&(address, _, _)| address).collect();
+        assert_eq!(addresses, [0x200, 0x202, 0x204, 0x206, 0x208]);
+        assert_eq!(lines[1], (0x202, 0x7001, Some(Instruction::AddImm { x: 0, nn: 1 })));
+        assert_eq!(lines[2], (0x204, 0x1202, Some(Instruction::Jump(0x202))));
+    }
+
+    #[test]
+    fn disassembly_stops_at_the_ends_of_the_memory() {
+        let mut chip8 = Chip8::default();
+
+        chip8.set_pc(0x0002);
+        let start: Vec<u16> = disassembly(&chip8, 4).iter().map(|&(address, _, _)| address).collect();
+        assert_eq!(start, [0x000, 0x002, 0x004, 0x006, 0x008, 0x00A]);
+
+        chip8.set_pc(0x0FFC);
+        let end: Vec<u16> = disassembly(&chip8, 4).iter().map(|&(address, _, _)| address).collect();
+        assert_eq!(end, [0xFF4, 0xFF6, 0xFF8, 0xFFA, 0xFFC, 0xFFE]);
+    }
+
+    #[test]
+    fn disassembly_keeps_the_parity_of_an_odd_pc() {
+        let mut chip8 = Chip8::default();
+        chip8.set_pc(0x0203);
+
+        let lines = disassembly(&chip8, 1);
+
+        let addresses: Vec<u16> = lines.iter().map(|&(address, _, _)| address).collect();
+        assert_eq!(addresses, [0x201, 0x203, 0x205]);
+    }
+
+    #[test]
+    fn disassembly_marks_unknown_opcodes() {
+        let chip8 = Chip8::with_program(&[0x5001]);
+
+        let lines = disassembly(&chip8, 0);
+
+        assert_eq!(lines, [(0x200, 0x5001, None)]);
+    }
+}
@@ -0,0 +1,256 @@
+// The graphics system: The chip 8 has one instruction that draws sprite to the screen.
+// Drawing is done in XOR mode and if a pixel is turned off as a result of drawing,
+// the VF register is set. This is used for collision detection.
+
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./display_tests.rs"]
+mod display_tests;
+
+pub(crate) const SCREEN_WIDTH: usize = 64;
+pub(crate) const SCREEN_HEIGHT: usize = 32;
+// SCHIP high resolution mode
+pub(crate) const HIRES_SCREEN_WIDTH: usize = 128;
+pub(crate) const HIRES_SCREEN_HEIGHT: usize = 64;
+// Hires CHIP-8 variant
+pub(crate) const HIRES_CHIP8_SCREEN_HEIGHT: usize = 64;
+// XO-CHIP has two bitplanes, giving 4 colors
+pub(crate) const PLANES: usize = 2;
+pub(crate) const ALL_PLANES: u8 = 0b11;
+
+// A row of pixels, the leftmost pixel in the highest bit. It is as wide as the SCHIP high resolution mode, narrower
+// modes only use the highest bits
+pub(crate) type Row = u128;
+
+#[derive(Clone, PartialEq)]
+pub(crate) struct Screen {
+    // The graphics of the Chip 8 are black and white and the screen has a total of 2048 pixels (64 x 32)
+    // The buffer is sized for the SCHIP high resolution mode (128 x 64), one row of bits per XO-CHIP bitplane so
+    // that sprites are drawn with a few shifts and XORs
+    pub(crate) rows: [[Row; HIRES_SCREEN_HEIGHT]; PLANES],
+    pub(crate) hires: bool,
+    // Two pages (64 x 64) display of the hires CHIP-8 variant, used by a few programs like Hires Invaders
+    pub(crate) hires_chip8: bool,
+    // Mask of the XO-CHIP bitplanes affected by drawing, clearing and scrolling
+    pub(crate) planes: u8,
+}
+
+impl Default for Screen {
+    fn default() -> Screen {
+        Screen {
+            rows: [[0; HIRES_SCREEN_HEIGHT]; PLANES],
+            hires: false,
+            hires_chip8: false,
+            planes: 0b01,
+        }
+    }
+}
+
+impl Screen {
+    pub(crate) fn clear(&mut self, planes: u8) {
+        for plane in self.selected_planes(planes) {
+            self.rows[plane].fill(0);
+        }
+    }
+
+    pub(crate) fn scroll_down(&mut self, rows: usize) {
+        let (_, height) = self.resolution();
+        let rows = rows.min(height);
+        for plane in self.selected_planes(self.planes) {
+            self.rows[plane].copy_within(0..height - rows, rows);
+            self.rows[plane][..rows].fill(0);
+        }
+    }
+
+    pub(crate) fn scroll_right(&mut self, columns: usize) {
+        let mask = self.row_mask();
+        for plane in self.selected_planes(self.planes) {
+            for row in self.rows[plane].iter_mut() {
+                *row = (*row >> columns) & mask;
+            }
+        }
+    }
+
+    pub(crate) fn scroll_left(&mut self, columns: usize) {
+        let mask = self.row_mask();
+        for plane in self.selected_planes(self.planes) {
+            for row in self.rows[plane].iter_mut() {
+                *row = (*row << columns) & mask;
+            }
+        }
+    }
+
+    // Indexes of the planes in the mask
+    fn selected_planes(&self, planes: u8) -> impl Iterator<Item = usize> {
+        (0..PLANES).filter(move |plane| planes & (1 << plane) != 0)
+    }
+
+    // Bits of a row that are on the screen in the current mode
+    fn row_mask(&self) -> Row {
+        let (width, _) = self.resolution();
+        Row::MAX << (HIRES_SCREEN_WIDTH - width)
+    }
+
+    // Visible pixels, row by row, one byte per pixel holding one bit per plane
+    pub(crate) fn pixels(&self) -> impl Iterator<Item = u8> + '_ {
+        let (width, height) = self.resolution();
+        (0..height).flat_map(move |y| (0..width).map(move |x| self.pixel(x, y)))
+    }
+
+    // Visible part of the buffer, expanded to one byte per pixel for the frontends
+    pub(crate) fn framebuffer(&self) -> Vec<u8> {
+        self.pixels().collect()
+    }
+
+    // Color of a pixel, made of one bit per plane (0 to 3)
+    pub(crate) fn pixel(&self, x: usize, y: usize) -> u8 {
+        let bit = HIRES_SCREEN_WIDTH - 1 - x;
+        (0..PLANES).fold(0, |color, plane| color | (((self.rows[plane][y] >> bit) & 1) as u8) << plane)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn set_pixel(&mut self, x: usize, y: usize, color: u8) {
+        let bit: Row = 1 << (HIRES_SCREEN_WIDTH - 1 - x);
+        for plane in 0..PLANES {
+            if color & (1 << plane) != 0 {
+                self.rows[plane][y] |= bit;
+            } else {
+                self.rows[plane][y] &= !bit;
+            }
+        }
+    }
+
+    // Width and height of the screen in the current mode
+    pub(crate) fn resolution(&self) -> (usize, usize) {
+        if self.hires {
+            (HIRES_SCREEN_WIDTH, HIRES_SCREEN_HEIGHT)
+        } else if self.hires_chip8 {
+            (SCREEN_WIDTH, HIRES_CHIP8_SCREEN_HEIGHT)
+        } else {
+            (SCREEN_WIDTH, SCREEN_HEIGHT)
+        }
+    }
+
+    // DXY0 draws a 16x16 sprite (two bytes per row) in high resolution mode, and a 8x16 sprite in low resolution
+    // mode like the SCHIP interpreter
+    pub(crate) fn sprite_size(&self, n: u8) -> (usize, usize) {
+        match n {
+            0 if self.hires => (16, 16),
+            0 => (8, 16),
+            _ => (8, usize::from(n)),
+        }
+    }
+
+    // Sprites are XORed onto the screen, one sprite per selected plane. The starting position always wraps around,
+    // pixels going off an edge wrap around to the opposite one or are clipped. Returns whether a pixel was erased
+    pub(crate) fn draw(&mut self, vx: u8, vy: u8, sprites: &[[u16; 16]; PLANES], (sprite_width, sprite_height): (usize, usize), clip: bool) -> bool {
+        let mut collision = false;
+        let (width, height) = self.resolution();
+        let mask = self.row_mask();
+        let start_x = usize::from(vx) % width;
+        let start_y = usize::from(vy) % height;
+        for plane in self.selected_planes(self.planes) {
+            for (row, &sprite_row) in sprites[plane].iter().enumerate().take(sprite_height) {
+                if clip && start_y + row >= height {
+                    break;
+                }
+                // The sprite row is moved to its column, the pixels past the right edge are either clipped or
+                // wrapped around to the left edge
+                let aligned = Row::from(sprite_row) << (HIRES_SCREEN_WIDTH - sprite_width);
+                let shifted = aligned >> start_x;
+                let mut bits = shifted & mask;
+                if !clip {
+                    // In a narrow mode the pixels past the edge are still in the row, in the widest one they were
+                    // shifted out
+                    bits |= (shifted & !mask).checked_shl(width as u32).unwrap_or(0);
+                    bits |= aligned.checked_shl((HIRES_SCREEN_WIDTH - start_x) as u32).unwrap_or(0);
+                }
+                let screen_row = &mut self.rows[plane][(start_y + row) % height];
+                collision |= *screen_row & bits != 0;
+                *screen_row ^= bits;
+            }
+        }
+        collision
+    }
+}
+
+// Rendering backend of a frontend. The framebuffer and the drawing logic stay in the core, the display only shows
+// the pixels it is handed, one byte per pixel (0 is off, otherwise the bits of the lit planes), row by row
+pub trait Display {
+    fn draw(&mut self, framebuffer: &[u8], width: usize, height: usize);
+    fn clear(&mut self);
+}
+
+// Display showing nothing, for headless runs
+pub struct NullDisplay;
+
+impl Display for NullDisplay {
+    fn draw(&mut self, _framebuffer: &[u8], _width: usize, _height: usize) {}
+
+    fn clear(&mut self) {}
+}
+
+// Reference display printing the screen to stdout, redrawn in place with ANSI escape codes
+pub struct AsciiDisplay;
+
+impl Display for AsciiDisplay {
+    fn draw(&mut self, framebuffer: &[u8], width: usize, _height: usize) {
+        print!("\x1B[H{}", to_ascii_string(framebuffer, width, '#', ' '));
+    }
+
+    fn clear(&mut self) {
+        print!("\x1B[2J");
+    }
+}
+
+// Pixels as lines of characters, on for the pixels lit in any plane and off for the others
+pub fn to_ascii_string(framebuffer: &[u8], width: usize, on: char, off: char) -> String {
+    let mut ascii = String::with_capacity(framebuffer.len() + framebuffer.len() / width.max(1));
+    for row in framebuffer.chunks(width.max(1)) {
+        ascii.extend(row.iter().map(|&pixel| if pixel != 0 { on } else { off }));
+        ascii.push('\n');
+    }
+    ascii
+}
+
+// Bit of each dot of a braille character, indexed by [y][x] in its 2x4 cell
+const BRAILLE_DOTS: [[u32; 2]; 4] = [
+    [0x01, 0x08],
+    [0x02, 0x10],
+    [0x04, 0x20],
+    [0x40, 0x80],
+];
+
+// Packs the pixels into braille characters of 2x4 dots (U+2800 to U+28FF), a line of characters per 4 rows of pixels.
+// The dots past the last row or column are off
+pub fn to_braille_string(framebuffer: &[u8], width: usize) -> String {
+    let mut braille = String::new();
+    for rows in framebuffer.chunks(width * 4) {
+        for x in (0..width).step_by(2) {
+            let mut dots = 0;
+            for (row, bits) in rows.chunks(width).zip(BRAILLE_DOTS) {
+                for (dx, bit) in bits.into_iter().enumerate() {
+                    if row.get(x + dx).is_some_and(|&pixel| pixel != 0) {
+                        dots |= bit;
+                    }
+                }
+            }
+            braille.push(char::from_u32(0x2800 + dots).unwrap_or(' '));
+        }
+        braille.push('\n');
+    }
+    braille
+}
+
+// Display printing the screen to stdout as braille characters, 32x8 of them for the low resolution screen
+pub struct BrailleDisplay;
+
+impl Display for BrailleDisplay {
+    fn draw(&mut self, framebuffer: &[u8], width: usize, _height: usize) {
+        print!("\x1B[H{}", to_braille_string(framebuffer, width));
+    }
+
+    fn clear(&mut self) {
+        print!("\x1B[2J");
+    }
+}
@@ -0,0 +1,760 @@
+#[cfg(test)]
+mod display_tests {
+    use crate::{Chip8, Chip8Config, Chip8Error, State};
+    use crate::display::{to_ascii_string, to_braille_string, Display, NullDisplay, Screen};
+    use crate::timers::Timers;
+    use crate::ProgramCounterInstruction::NEXT;
+
+    #[test]
+    fn op_0x00e0_clears_the_screen() {
+        let mut chip8 = Chip8::default();
+        chip8.screen.set_pixel(0, 0, 1);
+        chip8.screen.set_pixel(63, 31, 1);
+
+        let result = chip8.op_0x00e0();
+
+        assert!(matches!(result, NEXT));
+        assert!(chip8.framebuffer().iter().all(|&pixel| pixel == 0));
+        assert!(chip8.draw_flag);
+    }
+
+    #[test]
+    fn op_0x00e0_clears_the_screen_in_hires_mode() {
+        let mut chip8 = Chip8 { screen: Screen { hires: true, ..Screen::default() }, ..Chip8::default() };
+        chip8.screen.set_pixel(127, 63, 1);
+
+        chip8.op_0x00e0();
+
+        assert!(chip8.framebuffer().iter().all(|&pixel| pixel == 0));
+    }
+
+    #[test]
+    fn op_0x00e0_clears_only_the_selected_planes() {
+        let mut chip8 = Chip8 { screen: Screen { planes: 2, ..Screen::default() }, ..Chip8::default() };
+        chip8.screen.set_pixel(1, 0, 1);
+        chip8.screen.set_pixel(2, 0, 2);
+        chip8.screen.set_pixel(3, 0, 3);
+
+        chip8.op_0x00e0();
+
+        assert_eq!(chip8.framebuffer()[0..4], [0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn op_0x00ff_switches_to_hires_mode_and_clears_the_screen() {
+        let mut chip8 = Chip8::default();
+        chip8.screen.set_pixel(0, 0, 1);
+
+        let result = chip8.op_0x00ff();
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.resolution(), (128, 64));
+        assert!(chip8.framebuffer().iter().all(|&pixel| pixel == 0));
+    }
+
+    #[test]
+    fn op_0x00cn_scrolls_the_display_down_by_n_pixels() {
+        let mut chip8 = Chip8::default();
+        chip8.screen.set_pixel(5, 0, 1);
+        chip8.screen.set_pixel(7, 10, 1);
+        chip8.screen.set_pixel(0, 31, 1);
+
+        let result = chip8.op_0x00cn(3);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.framebuffer()[3 * 64 + 5], 1);
+        assert_eq!(chip8.framebuffer()[13 * 64 + 7], 1);
+        assert!(chip8.framebuffer()[0..3 * 64].iter().all(|&pixel| pixel == 0));
+        assert_eq!(chip8.framebuffer().iter().filter(|&&pixel| pixel == 1).count(), 2);
+        assert!(chip8.draw_flag);
+    }
+
+    #[test]
+    fn op_0x00cn_scrolls_the_display_down_in_hires_mode() {
+        let mut chip8 = Chip8 { screen: Screen { hires: true, ..Screen::default() }, ..Chip8::default() };
+        chip8.screen.set_pixel(100, 0, 1);
+        chip8.screen.set_pixel(100, 60, 1);
+
+        chip8.op_0x00cn(4);
+
+        assert_eq!(chip8.framebuffer()[4 * 128 + 100], 1);
+        assert_eq!(chip8.framebuffer().iter().filter(|&&pixel| pixel == 1).count(), 1);
+    }
+
+    #[test]
+    fn op_0x00cn_scrolls_half_the_distance_in_lores_mode_with_half_scroll_quirk() {
+        let mut chip8 = Chip8Config::new().lores_half_scroll(true).build().unwrap();
+        chip8.screen.set_pixel(5, 0, 1);
+
+        chip8.op_0x00cn(4);
+
+        assert_eq!(chip8.framebuffer()[2 * 64 + 5], 1);
+        assert_eq!(chip8.framebuffer().iter().filter(|&&pixel| pixel == 1).count(), 1);
+    }
+
+    #[test]
+    fn op_0x00cn_scrolls_only_the_selected_planes() {
+        let mut chip8 = Chip8 { screen: Screen { planes: 2, ..Screen::default() }, ..Chip8::default() };
+        chip8.screen.set_pixel(5, 0, 3);
+
+        chip8.op_0x00cn(1);
+
+        assert_eq!(chip8.framebuffer()[5], 1);
+        assert_eq!(chip8.framebuffer()[64 + 5], 2);
+    }
+
+    #[test]
+    fn op_0x00fb_scrolls_the_display_right_by_4_pixels() {
+        let mut chip8 = Chip8::default();
+        chip8.screen.set_pixel(0, 0, 1);
+        chip8.screen.set_pixel(10, 1, 1);
+        chip8.screen.set_pixel(62, 1, 1);
+
+        let result = chip8.op_0x00fb();
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.framebuffer()[4], 1);
+        assert_eq!(chip8.framebuffer()[64 + 14], 1);
+        assert_eq!(chip8.framebuffer()[64..64 + 4], [0, 0, 0, 0]);
+        assert_eq!(chip8.framebuffer().iter().filter(|&&pixel| pixel == 1).count(), 2);
+    }
+
+    #[test]
+    fn op_0x00fb_scrolls_half_the_distance_in_lores_mode_with_half_scroll_quirk() {
+        let mut chip8 = Chip8Config::new().lores_half_scroll(true).build().unwrap();
+        chip8.screen.set_pixel(0, 0, 1);
+
+        chip8.op_0x00fb();
+
+        assert_eq!(chip8.framebuffer()[0..3], [0, 0, 1]);
+    }
+
+    #[test]
+    fn op_0x00fc_scrolls_the_display_left_by_4_pixels() {
+        let mut chip8 = Chip8::default();
+        chip8.screen.set_pixel(1, 0, 1);
+        chip8.screen.set_pixel(10, 1, 1);
+        chip8.screen.set_pixel(63, 1, 1);
+
+        let result = chip8.op_0x00fc();
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.framebuffer()[64 + 6], 1);
+        assert_eq!(chip8.framebuffer()[64 + 59], 1);
+        assert_eq!(chip8.framebuffer()[64 + 60..64 + 64], [0, 0, 0, 0]);
+        assert_eq!(chip8.framebuffer().iter().filter(|&&pixel| pixel == 1).count(), 2);
+    }
+
+    #[test]
+    fn op_0x00fc_scrolls_the_display_left_in_hires_mode() {
+        let mut chip8 = Chip8 { screen: Screen { hires: true, ..Screen::default() }, ..Chip8::default() };
+        chip8.screen.set_pixel(127, 0, 1);
+        chip8.screen.set_pixel(2, 1, 1);
+
+        chip8.op_0x00fc();
+
+        assert_eq!(chip8.framebuffer()[123], 1);
+        assert_eq!(chip8.framebuffer()[124..128], [0, 0, 0, 0]);
+        assert_eq!(chip8.framebuffer().iter().filter(|&&pixel| pixel == 1).count(), 1);
+    }
+
+    #[test]
+    fn op_0x00fe_switches_to_lores_mode_and_clears_the_screen() {
+        let mut chip8 = Chip8 { screen: Screen { hires: true, ..Screen::default() }, ..Chip8::default() };
+        chip8.screen.set_pixel(0, 0, 1);
+
+        let result = chip8.op_0x00fe();
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.resolution(), (64, 32));
+        assert!(chip8.framebuffer().iter().all(|&pixel| pixel == 0));
+    }
+
+    #[test]
+    fn op_0xdxyn_draws_a_one_byte_sprite_at_vx_vy() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.memory[0x300] = 0b1010_0001;
+        chip8.v[1] = 2;
+        chip8.v[2] = 3;
+
+        let result = chip8.op_0xdxyn(1, 2, 1);
+
+        assert!(matches!(result, Ok(NEXT)));
+        assert_eq!(chip8.framebuffer()[3 * 64 + 2..3 * 64 + 10], [1, 0, 1, 0, 0, 0, 0, 1]);
+        assert_eq!(chip8.framebuffer().iter().filter(|&&pixel| pixel == 1).count(), 3);
+        assert_eq!(chip8.register(0x0F), 0);
+        assert!(chip8.draw_flag);
+    }
+
+    #[test]
+    fn op_0xdxyn_sets_vf_when_a_pixel_is_erased() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.memory[0x300] = 0b1100_0000;
+        chip8.screen.set_pixel(0, 0, 1);
+
+        chip8.op_0xdxyn(0, 1, 1).unwrap();
+
+        assert_eq!(chip8.framebuffer()[0..2], [0, 1]);
+        assert_eq!(chip8.register(0x0F), 1);
+    }
+
+    #[test]
+    fn op_0xdxyn_clears_vf_without_collision() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.memory[0x300] = 0b1100_0000;
+        chip8.screen.set_pixel(2, 0, 1);
+        chip8.v[0x0F] = 1;
+
+        chip8.op_0xdxyn(0, 1, 1).unwrap();
+
+        assert_eq!(chip8.framebuffer()[0..3], [1, 1, 1]);
+        assert_eq!(chip8.register(0x0F), 0);
+    }
+
+    #[test]
+    fn op_0xdxyn_wraps_a_sprite_crossing_the_right_edge() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.memory[0x300] = 0xFF;
+        chip8.v[1] = 60;
+        chip8.v[2] = 0;
+
+        chip8.op_0xdxyn(1, 2, 1).unwrap();
+
+        assert_eq!(chip8.framebuffer()[60..64], [1, 1, 1, 1]);
+        assert_eq!(chip8.framebuffer()[0..4], [1, 1, 1, 1]);
+        assert_eq!(chip8.framebuffer()[4], 0);
+    }
+
+    #[test]
+    fn op_0xdxyn_wraps_a_sprite_crossing_the_bottom_edge() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.memory[0x300..0x304].copy_from_slice(&[0x80, 0x80, 0x80, 0x80]);
+        chip8.v[1] = 0;
+        chip8.v[2] = 30;
+
+        chip8.op_0xdxyn(1, 2, 4).unwrap();
+
+        assert_eq!(chip8.framebuffer()[30 * 64], 1);
+        assert_eq!(chip8.framebuffer()[31 * 64], 1);
+        assert_eq!(chip8.framebuffer()[0], 1);
+        assert_eq!(chip8.framebuffer()[64], 1);
+        assert_eq!(chip8.framebuffer()[2 * 64], 0);
+    }
+
+    #[test]
+    fn op_0xdxyn_clips_a_sprite_crossing_the_right_edge_with_clip_sprites_quirk() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8Config::new().clip_sprites(true).build().unwrap() };
+        chip8.memory[0x300] = 0xFF;
+        chip8.v[1] = 60;
+        chip8.v[2] = 0;
+
+        chip8.op_0xdxyn(1, 2, 1).unwrap();
+
+        assert_eq!(chip8.framebuffer()[60..64], [1, 1, 1, 1]);
+        assert_eq!(chip8.framebuffer().iter().filter(|&&pixel| pixel == 1).count(), 4);
+    }
+
+    #[test]
+    fn op_0xdxyn_clips_a_sprite_crossing_the_bottom_edge_with_clip_sprites_quirk() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8Config::new().clip_sprites(true).build().unwrap() };
+        chip8.memory[0x300..0x304].copy_from_slice(&[0x80, 0x80, 0x80, 0x80]);
+        chip8.v[1] = 0;
+        chip8.v[2] = 30;
+
+        chip8.op_0xdxyn(1, 2, 4).unwrap();
+
+        assert_eq!(chip8.framebuffer()[30 * 64], 1);
+        assert_eq!(chip8.framebuffer()[31 * 64], 1);
+        assert_eq!(chip8.framebuffer().iter().filter(|&&pixel| pixel == 1).count(), 2);
+    }
+
+    #[test]
+    fn op_0xdxyn_wraps_the_starting_position_with_clip_sprites_quirk() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8Config::new().clip_sprites(true).build().unwrap() };
+        chip8.memory[0x300] = 0x80;
+        chip8.v[1] = 64 + 5;
+        chip8.v[2] = 32 + 2;
+
+        chip8.op_0xdxyn(1, 2, 1).unwrap();
+
+        assert_eq!(chip8.framebuffer()[2 * 64 + 5], 1);
+    }
+
+    #[test]
+    fn op_0xdxyn_executes_back_to_back_in_consecutive_cycles_without_display_wait_quirk() {
+        let mut chip8 = Chip8 { timers: Timers { delay_timer: 10, ..Timers::default() }, ..Chip8::default() };
+        chip8.memory[0x200..0x204].copy_from_slice(&[0xD0, 0x11, 0xD0, 0x11]);
+
+        chip8.emulate_cycle().unwrap();
+        chip8.emulate_cycle().unwrap();
+
+        assert_eq!(chip8.pc(), 0x204);
+        assert_eq!(chip8.delay_timer(), 10);
+    }
+
+    #[test]
+    fn op_0xdxyn_waits_for_vblank_with_display_wait_quirk() {
+        let mut chip8 = Chip8 { timers: Timers { delay_timer: 10, ..Timers::default() }, ..Chip8Config::new().display_wait(true).build().unwrap() };
+        chip8.memory[0x200..0x204].copy_from_slice(&[0xD0, 0x11, 0xD0, 0x11]);
+
+        chip8.emulate_cycle().unwrap();
+        assert_eq!(chip8.pc(), 0x202);
+        assert_eq!(chip8.state(), &State::WaitingForVblank);
+        chip8.emulate_cycle().unwrap();
+        chip8.emulate_cycle().unwrap();
+        assert_eq!(chip8.pc(), 0x202);
+        chip8.tick_timers();
+        assert_eq!(chip8.state(), &State::Running);
+        chip8.emulate_cycle().unwrap();
+
+        assert_eq!(chip8.pc(), 0x204);
+        assert_eq!(chip8.delay_timer(), 9);
+    }
+
+    #[test]
+    fn op_0xdxyn_returns_an_error_when_the_sprite_crosses_the_end_of_memory() {
+        let mut chip8 = Chip8 { i: 0xFFE, ..Chip8::default() };
+        chip8.memory[0xFFE] = 0xFF;
+        chip8.memory[0xFFF] = 0xFF;
+
+        let result = chip8.op_0xdxyn(0, 1, 4);
+
+        assert!(matches!(result, Err(Chip8Error::MemoryOutOfBounds { addr: 0x1000, pc: 0x200 })));
+        assert!(chip8.framebuffer().iter().all(|&pixel| pixel == 0));
+        assert!(!chip8.draw_flag);
+    }
+
+    #[test]
+    fn op_0xdxyn_draws_in_hires_mode() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.memory[0x300] = 0x80;
+        chip8.v[1] = 100;
+        chip8.v[2] = 50;
+        chip8.op_0x00ff();
+
+        chip8.op_0xdxyn(1, 2, 1).unwrap();
+
+        assert_eq!(chip8.framebuffer()[50 * 128 + 100], 1);
+        assert_eq!(chip8.framebuffer().iter().filter(|&&pixel| pixel == 1).count(), 1);
+    }
+
+    #[test]
+    fn op_0xdxyn_wraps_the_starting_position_in_lores_mode() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.memory[0x300] = 0x80;
+        chip8.v[1] = 100;
+        chip8.v[2] = 50;
+        chip8.op_0x00ff();
+        chip8.op_0x00fe();
+
+        chip8.op_0xdxyn(1, 2, 1).unwrap();
+
+        assert_eq!(chip8.framebuffer()[18 * 64 + 36], 1);
+        assert_eq!(chip8.framebuffer().iter().filter(|&&pixel| pixel == 1).count(), 1);
+    }
+
+    #[test]
+    fn op_0xdxyn_wraps_a_sprite_crossing_the_right_edge_in_hires_mode() {
+        let mut chip8 = Chip8 { i: 0x300, screen: Screen { hires: true, ..Screen::default() }, ..Chip8::default() };
+        chip8.memory[0x300] = 0xFF;
+        chip8.v[1] = 124;
+        chip8.v[2] = 63;
+
+        chip8.op_0xdxyn(1, 2, 1).unwrap();
+
+        assert_eq!(chip8.framebuffer()[63 * 128 + 124..63 * 128 + 128], [1, 1, 1, 1]);
+        assert_eq!(chip8.framebuffer()[63 * 128..63 * 128 + 4], [1, 1, 1, 1]);
+        assert_eq!(chip8.register(0x0F), 0);
+    }
+
+    #[test]
+    fn op_0xdxyn_draws_below_row_32_in_hires_chip8_mode() {
+        let mut chip8 = Chip8 { i: 0x300, screen: Screen { hires_chip8: true, ..Screen::default() }, ..Chip8::default() };
+        chip8.memory[0x300] = 0x80;
+        chip8.v[1] = 10;
+        chip8.v[2] = 40;
+
+        chip8.op_0xdxyn(1, 2, 1).unwrap();
+
+        assert_eq!(chip8.resolution(), (64, 64));
+        assert_eq!(chip8.framebuffer()[40 * 64 + 10], 1);
+        assert_eq!(chip8.framebuffer()[8 * 64 + 10], 0);
+    }
+
+    #[test]
+    fn op_0xdxyn_wraps_at_row_64_in_hires_chip8_mode() {
+        let mut chip8 = Chip8 { i: 0x300, screen: Screen { hires_chip8: true, ..Screen::default() }, ..Chip8::default() };
+        chip8.memory[0x300..0x302].copy_from_slice(&[0x80, 0x80]);
+        chip8.v[1] = 0;
+        chip8.v[2] = 63;
+
+        chip8.op_0xdxyn(1, 2, 2).unwrap();
+
+        assert_eq!(chip8.framebuffer()[63 * 64], 1);
+        assert_eq!(chip8.framebuffer()[0], 1);
+    }
+
+    #[test]
+    fn op_0xdxy0_draws_a_16x16_sprite_in_hires_mode() {
+        let mut chip8 = Chip8 { i: 0x300, screen: Screen { hires: true, ..Screen::default() }, ..Chip8::default() };
+        for row in 0..16 {
+            let pattern = if row % 2 == 0 { 0xAA } else { 0x55 };
+            chip8.memory[0x300 + row * 2] = pattern;
+            chip8.memory[0x300 + row * 2 + 1] = pattern;
+        }
+        chip8.v[1] = 10;
+        chip8.v[2] = 20;
+
+        let result = chip8.op_0xdxyn(1, 2, 0);
+
+        assert!(matches!(result, Ok(NEXT)));
+        for row in 0..16 {
+            for col in 0..16 {
+                let expected = if (row + col) % 2 == 0 { 1 } else { 0 };
+                assert_eq!(chip8.framebuffer()[(20 + row) * 128 + 10 + col], expected);
+            }
+        }
+        assert_eq!(chip8.framebuffer().iter().filter(|&&pixel| pixel == 1).count(), 128);
+        assert_eq!(chip8.register(0x0F), 0);
+    }
+
+    #[test]
+    fn op_0xdxy0_wraps_a_16x16_sprite_at_the_corner_in_hires_mode() {
+        let mut chip8 = Chip8 { i: 0x300, screen: Screen { hires: true, ..Screen::default() }, ..Chip8::default() };
+        for row in 0..16 {
+            let pattern = if row % 2 == 0 { 0xAA } else { 0x55 };
+            chip8.memory[0x300 + row * 2] = pattern;
+            chip8.memory[0x300 + row * 2 + 1] = pattern;
+        }
+        chip8.v[1] = 120;
+        chip8.v[2] = 56;
+        chip8.screen.set_pixel(124, 60, 1);
+
+        chip8.op_0xdxyn(1, 2, 0).unwrap();
+
+        assert_eq!(chip8.framebuffer()[56 * 128 + 120], 1);
+        assert_eq!(chip8.framebuffer()[56 * 128 + 121], 0);
+        assert_eq!(chip8.framebuffer()[56 * 128], 1);
+        assert_eq!(chip8.framebuffer()[7], 0);
+        assert_eq!(chip8.framebuffer()[7 * 128 + 7], 1);
+        assert_eq!(chip8.framebuffer()[60 * 128 + 124], 0);
+        assert_eq!(chip8.register(0x0F), 1);
+    }
+
+    #[test]
+    fn op_0xdxy0_clips_a_16x16_sprite_at_the_corner_with_clip_sprites_quirk() {
+        let mut chip8 = Chip8 { i: 0x300, screen: Screen { hires: true, ..Screen::default() }, ..Chip8Config::new().clip_sprites(true).build().unwrap() };
+        chip8.memory[0x300..0x320].copy_from_slice(&[0xFF; 32]);
+        chip8.v[1] = 120;
+        chip8.v[2] = 56;
+
+        chip8.op_0xdxyn(1, 2, 0).unwrap();
+
+        assert_eq!(chip8.framebuffer().iter().filter(|&&pixel| pixel == 1).count(), 64);
+        assert_eq!(chip8.framebuffer()[63 * 128 + 127], 1);
+    }
+
+    #[test]
+    fn op_0xdxy0_draws_a_8x16_sprite_in_lores_mode() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.memory[0x300..0x310].copy_from_slice(&[0x80; 16]);
+
+        chip8.op_0xdxyn(1, 2, 0).unwrap();
+
+        for row in 0..16 {
+            assert_eq!(chip8.framebuffer()[row * 64], 1);
+        }
+        assert_eq!(chip8.framebuffer().iter().filter(|&&pixel| pixel == 1).count(), 16);
+    }
+
+    #[test]
+    fn op_0xdxyn_draws_on_each_selected_plane() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.memory[0x300] = 0b1100_0000;
+        chip8.memory[0x301] = 0b1010_0000;
+
+        chip8.op_0xfn01(1);
+        chip8.op_0xdxyn(0, 1, 1).unwrap();
+        chip8.op_0xfn01(2);
+        chip8.i = 0x301;
+        chip8.op_0xdxyn(0, 1, 1).unwrap();
+
+        assert_eq!(chip8.pixel(0, 0), 3);
+        assert_eq!(chip8.pixel(1, 0), 1);
+        assert_eq!(chip8.pixel(2, 0), 2);
+        assert_eq!(chip8.pixel(3, 0), 0);
+        assert_eq!(chip8.register(0x0F), 0);
+    }
+
+    #[test]
+    fn op_0xdxyn_reads_one_sprite_per_plane_when_both_planes_are_selected() {
+        let mut chip8 = Chip8 { i: 0x300, screen: Screen { planes: 3, ..Screen::default() }, ..Chip8::default() };
+        chip8.memory[0x300] = 0b1100_0000;
+        chip8.memory[0x301] = 0b1010_0000;
+
+        chip8.op_0xdxyn(0, 1, 1).unwrap();
+
+        assert_eq!(chip8.framebuffer()[0..4], [3, 1, 2, 0]);
+    }
+
+    #[test]
+    fn op_0xdxyn_sets_vf_on_collision_in_any_selected_plane() {
+        let mut chip8 = Chip8 { i: 0x300, screen: Screen { planes: 2, ..Screen::default() }, ..Chip8::default() };
+        chip8.memory[0x300] = 0b1000_0000;
+        chip8.screen.set_pixel(0, 0, 1);
+
+        chip8.op_0xdxyn(0, 1, 1).unwrap();
+        assert_eq!(chip8.register(0x0F), 0);
+        chip8.op_0xdxyn(0, 1, 1).unwrap();
+
+        assert_eq!(chip8.framebuffer()[0], 1);
+        assert_eq!(chip8.register(0x0F), 1);
+    }
+
+    #[test]
+    fn op_0xdxyn_draws_nothing_without_selected_planes() {
+        let mut chip8 = Chip8 { i: 0x300, screen: Screen { planes: 0, ..Screen::default() }, ..Chip8::default() };
+        chip8.memory[0x300] = 0xFF;
+
+        chip8.op_0xdxyn(0, 1, 1).unwrap();
+
+        assert!(chip8.framebuffer().iter().all(|&pixel| pixel == 0));
+    }
+
+    #[test]
+    fn op_0xfn01_selects_the_planes() {
+        let mut chip8 = Chip8::default();
+        chip8.memory[0x200] = 0xF3;
+        chip8.memory[0x201] = 0x01;
+
+        chip8.emulate_cycle().unwrap();
+
+        assert_eq!(chip8.screen.planes, 3);
+        assert_eq!(chip8.pc(), 0x202);
+    }
+
+    #[test]
+    fn screen_draw_reports_a_collision_only_when_a_pixel_is_erased() {
+        let mut screen = Screen::default();
+        let mut sprites = [[0u16; 16]; 2];
+        sprites[0][0] = 0b1000_0000;
+
+        assert!(!screen.draw(3, 4, &sprites, (8, 1), false));
+        assert_eq!(screen.pixel(3, 4), 1);
+        assert!(screen.draw(3, 4, &sprites, (8, 1), false));
+        assert_eq!(screen.pixel(3, 4), 0);
+    }
+
+    #[test]
+    fn screen_draw_xors_the_sprite_into_the_packed_row() {
+        let mut screen = Screen::default();
+        let mut sprites = [[0u16; 16]; 2];
+        sprites[0][0] = 0b1010_0001;
+
+        screen.draw(2, 0, &sprites, (8, 1), false);
+
+        assert_eq!(screen.rows[0][0], 0b0010_1000_0100u128 << 116);
+        assert_eq!(screen.rows[1][0], 0);
+    }
+
+    #[test]
+    fn screen_draw_wraps_the_packed_row_at_the_right_edge_of_each_mode() {
+        let mut sprites = [[0u16; 16]; 2];
+        sprites[0][0] = 0xFF;
+        let mut lores = Screen::default();
+        let mut hires = Screen { hires: true, ..Screen::default() };
+
+        lores.draw(60, 0, &sprites, (8, 1), false);
+        hires.draw(124, 0, &sprites, (8, 1), false);
+
+        assert_eq!(lores.rows[0][0], 0xF000_0000_0000_000Fu128 << 64);
+        assert_eq!(hires.rows[0][0], 0xF000_0000_0000_0000_0000_0000_0000_000F);
+    }
+
+    #[test]
+    fn screen_scroll_left_fills_the_right_edge_with_blank_pixels() {
+        let mut screen = Screen::default();
+        screen.set_pixel(63, 0, 1);
+        screen.set_pixel(4, 0, 1);
+
+        screen.scroll_left(4);
+
+        assert_eq!(screen.pixel(0, 0), 1);
+        assert_eq!(screen.pixel(59, 0), 1);
+        assert_eq!(screen.pixel(63, 0), 0);
+    }
+
+    #[test]
+    fn screen_sprite_size_depends_on_n_and_resolution() {
+        let lores = Screen::default();
+        let hires = Screen { hires: true, ..Screen::default() };
+
+        assert_eq!(lores.sprite_size(5), (8, 5));
+        assert_eq!(lores.sprite_size(0), (8, 16));
+        assert_eq!(hires.sprite_size(0), (16, 16));
+    }
+
+    #[derive(Default)]
+    struct MockDisplay {
+        frames: Vec<(Vec<u8>, usize, usize)>,
+        clears: usize,
+    }
+
+    impl Display for MockDisplay {
+        fn draw(&mut self, framebuffer: &[u8], width: usize, height: usize) {
+            self.frames.push((framebuffer.to_vec(), width, height));
+        }
+
+        fn clear(&mut self) {
+            self.clears += 1;
+        }
+    }
+
+    #[test]
+    fn render_hands_the_framebuffer_to_the_display_and_clears_the_draw_flag() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.memory[0x300] = 0b1000_0000;
+        chip8.v[0] = 2;
+        chip8.v[1] = 1;
+        chip8.op_0xdxyn(0, 1, 1).unwrap();
+        let mut display = MockDisplay::default();
+
+        chip8.render(&mut display);
+
+        assert!(!chip8.draw_flag);
+        assert_eq!(display.clears, 0);
+        assert_eq!(display.frames.len(), 1);
+        let (framebuffer, width, height) = &display.frames[0];
+        assert_eq!((*width, *height), (64, 32));
+        assert_eq!(framebuffer.len(), 64 * 32);
+        assert_eq!(framebuffer[64 + 2], 1);
+        assert_eq!(framebuffer.iter().filter(|&&pixel| pixel != 0).count(), 1);
+    }
+
+    #[test]
+    fn render_hands_the_whole_hires_framebuffer_to_the_display() {
+        let mut chip8 = Chip8::default();
+        chip8.op_0x00ff();
+        let mut display = MockDisplay::default();
+
+        chip8.render(&mut display);
+
+        let (framebuffer, width, height) = &display.frames[0];
+        assert_eq!((*width, *height), (128, 64));
+        assert_eq!(framebuffer.len(), 128 * 64);
+    }
+
+    #[test]
+    fn render_works_headless_with_the_null_display() {
+        let mut chip8 = Chip8 { draw_flag: true, ..Chip8::default() };
+
+        chip8.render(&mut NullDisplay);
+
+        assert!(!chip8.draw_flag);
+    }
+
+    #[test]
+    fn to_braille_string_maps_each_dot_position() {
+        // (x, y) in the 2x4 cell and the braille character with only that dot
+        let dots = [
+            ((0, 0), '⠁'), ((0, 1), '⠂'), ((0, 2), '⠄'), ((0, 3), '⡀'),
+            ((1, 0), '⠈'), ((1, 1), '⠐'), ((1, 2), '⠠'), ((1, 3), '⢀'),
+        ];
+        for ((x, y), expected) in dots {
+            let mut framebuffer = [0; 2 * 4];
+            framebuffer[y * 2 + x] = 1;
+            assert_eq!(to_braille_string(&framebuffer, 2), format!("{expected}\n"), "dot ({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn to_braille_string_patterns() {
+        assert_eq!(to_braille_string(&[0; 2 * 4], 2), "⠀\n");
+        assert_eq!(to_braille_string(&[1; 2 * 4], 2), "⣿\n");
+        // Any lit plane lights the dot
+        assert_eq!(to_braille_string(&[3, 0, 0, 0, 0, 0, 0, 2], 2), "⢁\n");
+        let diagonal = [
+            1, 0, 0, 0,
+            0, 1, 0, 0,
+            0, 0, 1, 0,
+            0, 0, 0, 1,
+        ];
+        assert_eq!(to_braille_string(&diagonal, 4), "⠑⢄\n");
+    }
+
+    #[test]
+    fn to_braille_string_partial_cells() {
+        // 3x5 pixels: the second line of characters only has its top row, the second column only its left dots
+        let framebuffer = [
+            0, 0, 1,
+            0, 0, 0,
+            0, 0, 0,
+            0, 0, 1,
+            1, 0, 1,
+        ];
+        assert_eq!(to_braille_string(&framebuffer, 3), "⠀⡁\n⠁⠁\n");
+    }
+
+    #[test]
+    fn to_braille_string_screen_size() {
+        let mut chip8 = Chip8::default();
+        chip8.screen.set_pixel(63, 31, 1);
+
+        let braille = chip8.to_braille_string();
+
+        let lines: Vec<&str> = braille.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert!(lines.iter().all(|line| line.chars().count() == 32));
+        assert_eq!(lines[7].chars().last(), Some('⢀'));
+    }
+
+    #[test]
+    fn to_ascii_string_writes_a_line_per_row() {
+        // Any lit plane lights the pixel
+        let framebuffer = [
+            1, 0, 3,
+            0, 2, 0,
+        ];
+        assert_eq!(to_ascii_string(&framebuffer, 3, '#', '.'), "#.#\n.#.\n");
+        assert_eq!(to_ascii_string(&framebuffer, 3, '█', ' '), "█ █\n █ \n");
+    }
+
+    #[test]
+    fn framebuffer_ascii_shows_a_drawn_digit() {
+        // V0 = 0, I = font digit V0, V1 = 1, draw the 5 rows of the digit at (V1, V1)
+        let mut chip8 = Chip8::with_program(&[0x6000, 0xF029, 0x6101, 0xD115]);
+        for _ in 0..4 {
+            chip8.emulate_cycle().unwrap();
+        }
+
+        let ascii = chip8.framebuffer_ascii();
+
+        let lines: Vec<&str> = ascii.lines().collect();
+        assert_eq!(lines.len(), 32);
+        assert!(lines.iter().all(|line| line.chars().count() == 64));
+        let corner: Vec<String> = lines[..7].iter().map(|line| line.chars().take(6).collect()).collect();
+        assert_eq!(corner, [
+            "      ",
+            " ████ ",
+            " █  █ ",
+            " █  █ ",
+            " █  █ ",
+            " ████ ",
+            "      ",
+        ]);
+        assert!(lines[7..].iter().all(|line| line.trim().is_empty()));
+    }
+
+    #[test]
+    fn framebuffer_ascii_follows_the_resolution() {
+        let mut chip8 = Chip8::with_program(&[0x00FF]);
+        chip8.emulate_cycle().unwrap();
+        chip8.screen.set_pixel(127, 63, 1);
+
+        let ascii = chip8.framebuffer_ascii_with('#', '.');
+
+        let lines: Vec<&str> = ascii.lines().collect();
+        assert_eq!(lines.len(), 64);
+        assert_eq!(lines[63], format!("{}#", ".".repeat(127)));
+    }
+}
@@ -0,0 +1,36 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./focus_tests.rs"]
+mod focus_tests;
+
+// Pause of the emulation while the window does not have the focus, so that a game does not go on behind another window.
+// Long unattended runs opt out of it
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FocusPause {
+    enabled: bool,
+    paused: bool,
+}
+
+impl FocusPause {
+    // Running until the focus is lost, or always when not enabled
+    pub fn new(enabled: bool) -> FocusPause {
+        FocusPause { enabled, paused: false }
+    }
+
+    // Follows the focus of the window, read once per frame. Returns the new state when the emulation was paused or
+    // resumed by this call, to release the keys held and update what shows the pause
+    pub fn update(&mut self, focused: bool) -> Option<bool> {
+        let paused = self.enabled && !focused;
+        if paused == self.paused {
+            return None;
+        }
+        self.paused = paused;
+        Some(paused)
+    }
+
+    // While paused no instruction runs and the timers do not tick, the last screen stays shown
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+}
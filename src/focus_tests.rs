@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod focus_tests {
+    use crate::FocusPause;
+
+    #[test]
+    fn focus_pause_pauses_while_the_focus_is_lost() {
+        let mut pause = FocusPause::new(true);
+        assert_eq!(pause.update(true), None);
+        assert!(!pause.paused());
+        assert_eq!(pause.update(false), Some(true));
+        assert!(pause.paused());
+        // Reported once, then paused until the focus comes back
+        assert_eq!(pause.update(false), None);
+        assert!(pause.paused());
+        assert_eq!(pause.update(true), Some(false));
+        assert!(!pause.paused());
+        assert_eq!(pause.update(true), None);
+    }
+
+    #[test]
+    fn focus_pause_starts_paused_in_a_window_without_the_focus() {
+        let mut pause = FocusPause::new(true);
+        assert_eq!(pause.update(false), Some(true));
+        assert_eq!(pause.update(true), Some(false));
+    }
+
+    #[test]
+    fn focus_pause_reports_each_change_of_the_focus_once() {
+        let mut pause = FocusPause::new(true);
+        let changes: Vec<Option<bool>> = [false, true, false, false, true, true, false]
+            .into_iter()
+            .map(|focused| pause.update(focused))
+            .collect();
+        assert_eq!(changes, [Some(true), Some(false), Some(true), None, Some(false), None, Some(true)]);
+    }
+
+    #[test]
+    fn focus_pause_never_pauses_when_disabled() {
+        let mut pause = FocusPause::new(false);
+        for focused in [false, true, false] {
+            assert_eq!(pause.update(focused), None);
+            assert!(!pause.paused());
+        }
+    }
+}
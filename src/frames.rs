@@ -0,0 +1,51 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./frames_tests.rs"]
+mod frames_tests;
+
+use crate::{Chip8, FrameInfo, State};
+
+// State of the machine after a 60 Hz frame, handed out by the frames() iterator
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    // Number of the frame, starting at 0
+    pub index: u64,
+    pub info: FrameInfo,
+    // Copy of the visible screen, one byte per pixel holding one bit per plane, row by row
+    pub framebuffer: Vec<u8>,
+    pub width: usize,
+    pub height: usize,
+}
+
+// Runs the emulator one frame per item with run_frame. It ends after the frame where the program exits, or at the
+// frame where an instruction fails, the error is then given by Chip8::state()
+pub struct Frames<'a> {
+    chip8: &'a mut Chip8,
+    index: u64,
+    done: bool,
+}
+
+impl Iterator for Frames<'_> {
+    type Item = Frame;
+
+    fn next(&mut self) -> Option<Frame> {
+        if self.done {
+            return None;
+        }
+        let info = self.chip8.run_frame().ok()?;
+        self.done = info.halted;
+        let (width, height) = self.chip8.resolution();
+        let frame = Frame { index: self.index, info, framebuffer: self.chip8.framebuffer(), width, height };
+        self.index += 1;
+        Some(frame)
+    }
+}
+
+impl Chip8 {
+    // for frame in chip8.frames().take(600) runs ten seconds of emulation. Nothing is run once the program exited
+    // or failed
+    pub fn frames(&mut self) -> Frames<'_> {
+        let done = matches!(self.state, State::Halted | State::Faulted(_));
+        Frames { chip8: self, index: 0, done }
+    }
+}
@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod frames_tests {
+    use crate::{Chip8, Chip8Config, Chip8Error, State};
+
+    // Draws the 0 digit once, then increments V1 for 40 cycles and exits
+    const PROGRAM: [u8; 14] = [
+        0xF0, 0x29, // I = font digit V0
+        0xD0, 0x05, // draws it at (V0, V0)
+        0x71, 0x01, // V1 += 1
+        0x31, 0x28, // skips the jump once V1 is 40
+        0x12, 0x04, // jumps back to the increment
+        0x00, 0xFD, // exits
+        0x00, 0x00,
+    ];
+
+    fn chip8() -> Chip8 {
+        let mut chip8 = Chip8Config::new().instructions_per_frame(10).build().unwrap();
+        chip8.load_rom(&PROGRAM).unwrap();
+        chip8
+    }
+
+    #[test]
+    fn frames_yields_one_frame_per_run_frame_until_the_program_exits() {
+        let mut chip8 = chip8();
+
+        let frames: Vec<_> = chip8.frames().collect();
+
+        assert_eq!(frames.len(), 13);
+        assert_eq!(frames.iter().map(|frame| frame.index).collect::<Vec<_>>(), (0..13).collect::<Vec<_>>());
+        assert!(frames.last().unwrap().info.halted);
+        assert!(frames[..12].iter().all(|frame| !frame.info.halted));
+        assert_eq!(chip8.register(1), 40);
+    }
+
+    #[test]
+    fn frames_carries_the_framebuffer_and_whether_it_changed() {
+        let mut chip8 = chip8();
+
+        let frames: Vec<_> = chip8.frames().take(2).collect();
+
+        assert!(frames[0].info.drew);
+        assert!(!frames[1].info.drew);
+        assert_eq!((frames[0].width, frames[0].height), (64, 32));
+        assert_eq!(frames[0].framebuffer[..4], [1, 1, 1, 1]);
+        assert_eq!(frames[1].framebuffer, frames[0].framebuffer);
+    }
+
+    #[test]
+    fn frames_stops_at_the_frame_where_an_instruction_fails() {
+        let mut chip8 = Chip8::default();
+        // 7001: V0 += 1 for a whole frame, then 00EE returns without a call
+        let mut program = [0x70, 0x01].repeat(8);
+        program.extend([0x00, 0xEE]);
+        chip8.load_rom(&program).unwrap();
+
+        let frames: Vec<_> = chip8.frames().collect();
+
+        assert_eq!(frames.len(), 1);
+        assert!(matches!(chip8.state(), State::Faulted(Chip8Error::StackUnderflow { pc: 0x210 })));
+    }
+
+    #[test]
+    fn frames_runs_nothing_once_halted() {
+        let mut chip8 = chip8();
+        chip8.frames().count();
+
+        assert_eq!(chip8.frames().count(), 0);
+    }
+}
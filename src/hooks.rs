@@ -0,0 +1,93 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./hooks_tests.rs"]
+mod hooks_tests;
+
+use crate::Chip8;
+
+pub type DrawHook = Box<dyn FnMut(&[u8])>;
+pub type SoundHook = Box<dyn FnMut(bool)>;
+pub type KeyWaitHook = Box<dyn FnMut(u8)>;
+pub type TickHook = Box<dyn FnMut(FrameSound)>;
+
+// Sound a 60 Hz frame ends with, given to the tick hook after each tick of the timers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameSound {
+    // The sound timer is above zero
+    pub active: bool,
+    // Like Chip8::loaded_audio_pattern
+    pub pattern: Option<([u8; 16], f64)>,
+}
+
+// Callbacks registered by a frontend instead of polling the state after each cycle. They are only handed copies or
+// borrows of the state, so they cannot run the emulator from inside a cycle
+#[derive(Default)]
+pub(crate) struct Hooks {
+    // Called with the visible framebuffer after a cycle changing the screen
+    pub(crate) draw: Option<DrawHook>,
+    // Called with the new state of the sound when it is switched on by FX18 or off by the timer tick
+    pub(crate) sound: Option<SoundHook>,
+    // Called with the register X when FX0A starts waiting for a key
+    pub(crate) key_wait: Option<KeyWaitHook>,
+    // Called after each tick of the timers, once per emulated frame whatever the speed of the host
+    pub(crate) tick: Option<TickHook>,
+}
+
+// The callbacks cannot be cloned, a cloned machine starts without hooks
+impl Clone for Hooks {
+    fn clone(&self) -> Hooks {
+        Hooks::default()
+    }
+}
+
+// The callbacks are not part of the machine state
+impl PartialEq for Hooks {
+    fn eq(&self, _other: &Hooks) -> bool {
+        true
+    }
+}
+
+impl Chip8 {
+    pub fn set_draw_hook(&mut self, hook: DrawHook) {
+        self.hooks.draw = Some(hook);
+    }
+
+    pub fn set_sound_hook(&mut self, hook: SoundHook) {
+        self.hooks.sound = Some(hook);
+    }
+
+    pub fn set_key_wait_hook(&mut self, hook: KeyWaitHook) {
+        self.hooks.key_wait = Some(hook);
+    }
+
+    pub fn set_tick_hook(&mut self, hook: TickHook) {
+        self.hooks.tick = Some(hook);
+    }
+
+    pub(crate) fn call_draw_hook(&mut self) {
+        if let Some(hook) = self.hooks.draw.as_mut() {
+            hook(&self.screen.framebuffer());
+        }
+    }
+
+    // Only calls the hook when the sound state differs from the one before the change
+    pub(crate) fn call_sound_hook(&mut self, was_active: bool) {
+        let active = self.timers.sound_active();
+        if let Some(hook) = self.hooks.sound.as_mut().filter(|_| active != was_active) {
+            hook(active);
+        }
+    }
+
+    pub(crate) fn call_tick_hook(&mut self) {
+        if let Some(hook) = self.hooks.tick.as_mut() {
+            let pattern = self.timers.pattern_loaded.then(|| (self.timers.pattern_buffer, self.timers.pattern_rate()));
+            hook(FrameSound { active: self.timers.sound_active(), pattern });
+        }
+    }
+
+    pub(crate) fn call_key_wait_hook(&mut self, x: usize) {
+        if let Some(hook) = self.hooks.key_wait.as_mut() {
+            hook(x as u8);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod hooks_tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::{Chip8, FrameSound, Key, FRAME_DURATION};
+
+    // Closure pushing each call argument to a shared list
+    fn recorder<T: 'static>() -> (Rc<RefCell<Vec<T>>>, impl FnMut(T)) {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let recorded = Rc::clone(&calls);
+        (calls, move |argument| recorded.borrow_mut().push(argument))
+    }
+
+    #[test]
+    fn draw_hook_is_called_with_the_framebuffer_after_each_drawing() {
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(&[
+            0x60, 0x00, // V0 = 0
+            0xF0, 0x29, // I = font digit V0
+            0xD0, 0x01, // draw the top row of the 0 digit at (V0, V0)
+            0x70, 0x01, // V0 += 1
+            0x00, 0xE0, // clear the screen
+        ]).unwrap();
+        let (calls, mut record) = recorder();
+        chip8.set_draw_hook(Box::new(move |framebuffer: &[u8]| record((framebuffer.len(), framebuffer[..5].to_vec()))));
+
+        for _ in 0..5 {
+            chip8.emulate_cycle().unwrap();
+        }
+
+        assert_eq!(*calls.borrow(), [(64 * 32, vec![1, 1, 1, 1, 0]), (64 * 32, vec![0, 0, 0, 0, 0])]);
+    }
+
+    #[test]
+    fn draw_hook_is_not_called_for_a_pending_draw_flag() {
+        let mut chip8 = Chip8 { draw_flag: true, ..Chip8::default() };
+        chip8.load_rom(&[0x60, 0x00]).unwrap();
+        let (calls, mut record) = recorder();
+        chip8.set_draw_hook(Box::new(move |_: &[u8]| record(())));
+
+        chip8.emulate_cycle().unwrap();
+
+        assert!(calls.borrow().is_empty());
+    }
+
+    #[test]
+    fn sound_hook_is_called_when_the_sound_starts_and_stops() {
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(&[
+            0x60, 0x02, // V0 = 2
+            0xF0, 0x18, // sound timer = V0
+            0xF0, 0x18, // sound timer = V0 again, the sound is already on
+            0x12, 0x06, // loop forever
+        ]).unwrap();
+        let (calls, record) = recorder();
+        chip8.set_sound_hook(Box::new(record));
+
+        let mut history = Vec::new();
+        for _ in 0..4 {
+            chip8.emulate_cycle().unwrap();
+            history.push(calls.borrow().clone());
+        }
+        chip8.tick_timers();
+        history.push(calls.borrow().clone());
+        chip8.tick_timers();
+        chip8.tick_timers();
+        history.push(calls.borrow().clone());
+
+        assert_eq!(history, [vec![], vec![true], vec![true], vec![true], vec![true], vec![true, false]]);
+    }
+
+    #[test]
+    fn tick_hook_is_called_with_the_sound_of_each_frame_run() {
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(&[
+            0x60, 0x02, // V0 = 2
+            0xF0, 0x18, // sound timer = V0
+            0x12, 0x04, // loop forever
+        ]).unwrap();
+        let (calls, record) = recorder();
+        chip8.set_tick_hook(Box::new(record));
+
+        chip8.run_for(FRAME_DURATION * 3).unwrap();
+
+        let active: Vec<bool> = calls.borrow().iter().map(|sound: &FrameSound| sound.active).collect();
+        assert_eq!(active, [true, false, false]);
+        assert!(calls.borrow().iter().all(|sound| sound.pattern.is_none()));
+    }
+
+    #[test]
+    fn key_wait_hook_is_called_once_when_fx0a_starts_waiting() {
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(&[0xF5, 0x0A, 0x12, 0x00]).unwrap(); // wait for a key in V5, start again
+        let (calls, record) = recorder();
+        chip8.set_key_wait_hook(Box::new(record));
+
+        for _ in 0..3 {
+            chip8.emulate_cycle().unwrap();
+        }
+        assert_eq!(*calls.borrow(), [0x5]);
+        chip8.press(Key::KA);
+        chip8.emulate_cycle().unwrap();
+        chip8.release(Key::KA);
+        chip8.emulate_cycle().unwrap();
+        chip8.emulate_cycle().unwrap();
+
+        assert_eq!(*calls.borrow(), [0x5, 0x5]);
+    }
+
+    #[test]
+    fn hooks_are_not_called_by_instructions_without_effect() {
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(&[0x60, 0x05, 0x70, 0x01, 0xA3, 0x00, 0x12, 0x06]).unwrap();
+        let calls = Rc::new(RefCell::new(0));
+        let (draws, sounds, key_waits) = (Rc::clone(&calls), Rc::clone(&calls), Rc::clone(&calls));
+        chip8.set_draw_hook(Box::new(move |_| *draws.borrow_mut() += 1));
+        chip8.set_sound_hook(Box::new(move |_| *sounds.borrow_mut() += 1));
+        chip8.set_key_wait_hook(Box::new(move |_| *key_waits.borrow_mut() += 1));
+
+        for _ in 0..10 {
+            chip8.run_frame().unwrap();
+        }
+
+        assert_eq!(*calls.borrow(), 0);
+    }
+}
@@ -0,0 +1,252 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./instruction_tests.rs"]
+mod instruction_tests;
+
+use crate::Chip8Error;
+use crate::Instruction::*;
+
+// Decoded opcodes of CHIP-8 and of the SCHIP and XO-CHIP extensions. X and Y are register indexes (0x0-0xF)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    // 0NNN: Calls machine code routine at address NNN
+    Sys(u16),
+    // 00E0: Clears the screen
+    ClearScreen,
+    // 00EE: Returns from subroutine
+    Return,
+    // 00CN: Scrolls the display down by N pixels (SCHIP)
+    ScrollDown(u8),
+    // 00FB: Scrolls the display right by 4 pixels (SCHIP)
+    ScrollRight,
+    // 00FC: Scrolls the display left by 4 pixels (SCHIP)
+    ScrollLeft,
+    // 00FD: Exits the interpreter (SCHIP)
+    Exit,
+    // 00FE: Disables high resolution mode (SCHIP)
+    LowRes,
+    // 00FF: Enables high resolution mode (SCHIP)
+    HighRes,
+    // 1NNN: Jumps to address NNN
+    Jump(u16),
+    // 2NNN: Calls subroutine at NNN
+    Call(u16),
+    // 3XNN: Skips the next instruction if VX equals NN
+    SkipEqImm { x: u8, nn: u8 },
+    // 4XNN: Skips the next instruction if VX does not equal NN
+    SkipNeImm { x: u8, nn: u8 },
+    // 5XY0: Skips the next instruction if VX equals VY
+    SkipEqReg { x: u8, y: u8 },
+    // 5XY2: Stores VX to VY in memory at I (XO-CHIP)
+    StoreRange { x: u8, y: u8 },
+    // 5XY3: Fills VX to VY from memory at I (XO-CHIP)
+    LoadRange { x: u8, y: u8 },
+    // 6XNN: Sets VX to NN
+    LoadImm { x: u8, nn: u8 },
+    // 7XNN: Adds NN to VX
+    AddImm { x: u8, nn: u8 },
+    // 8XY0: Sets VX to VY
+    Move { x: u8, y: u8 },
+    // 8XY1: Sets VX to VX or VY
+    Or { x: u8, y: u8 },
+    // 8XY2: Sets VX to VX and VY
+    And { x: u8, y: u8 },
+    // 8XY3: Sets VX to VX xor VY
+    Xor { x: u8, y: u8 },
+    // 8XY4: Adds VY to VX with carry in VF
+    Add { x: u8, y: u8 },
+    // 8XY5: Subtracts VY from VX with no borrow in VF
+    Sub { x: u8, y: u8 },
+    // 8XY6: Shifts VX (or VY with the shift quirk) right by 1
+    ShiftRight { x: u8, y: u8 },
+    // 8XY7: Sets VX to VY minus VX with no borrow in VF
+    SubReverse { x: u8, y: u8 },
+    // 8XYE: Shifts VX (or VY with the shift quirk) left by 1
+    ShiftLeft { x: u8, y: u8 },
+    // 9XY0: Skips the next instruction if VX does not equal VY. The low nibble is ignored like the original interpreter
+    SkipNeReg { x: u8, y: u8 },
+    // ANNN: Sets I to the address NNN
+    LoadIndex(u16),
+    // BNNN: Jumps to the address NNN plus V0, or XNN plus VX with the jump quirk
+    JumpOffset { x: u8, nnn: u16 },
+    // CXNN: Sets VX to a random number and NN
+    Random { x: u8, nn: u8 },
+    // DXYN: Draws a sprite of N rows at (VX, VY)
+    Draw { x: u8, y: u8, n: u8 },
+    // EX9E: Skips the next instruction if the key stored in VX is pressed
+    SkipKeyPressed { x: u8 },
+    // EXA1: Skips the next instruction if the key stored in VX is not pressed
+    SkipKeyNotPressed { x: u8 },
+    // F000 NNNN: Sets I to the address stored in the next two bytes (XO-CHIP)
+    LoadLongIndex,
+    // FN01: Selects the bitplanes N (XO-CHIP)
+    SelectPlanes(u8),
+    // F002: Loads the audio pattern buffer from memory at I (XO-CHIP)
+    LoadAudioPattern,
+    // FX07: Sets VX to the delay timer
+    LoadDelay { x: u8 },
+    // FX0A: Waits for a key press, stored in VX
+    WaitKey { x: u8 },
+    // FX15: Sets the delay timer to VX
+    SetDelay { x: u8 },
+    // FX18: Sets the sound timer to VX
+    SetSound { x: u8 },
+    // FX1E: Adds VX to I
+    AddIndex { x: u8 },
+    // FX29: Sets I to the small font sprite of the digit in VX
+    LoadFont { x: u8 },
+    // FX30: Sets I to the big font sprite of the digit in VX (SCHIP)
+    LoadBigFont { x: u8 },
+    // FX33: Stores the decimal digits of VX in memory at I, I + 1 and I + 2
+    StoreBcd { x: u8 },
+    // FX3A: Sets the audio pattern playback rate to VX (XO-CHIP)
+    SetPitch { x: u8 },
+    // FX55: Stores V0 to VX in memory at I
+    StoreRegisters { x: u8 },
+    // FX65: Fills V0 to VX from memory at I
+    LoadRegisters { x: u8 },
+    // FX75: Stores V0 to VX in the RPL user flags (SCHIP)
+    StoreFlags { x: u8 },
+    // FX85: Fills V0 to VX from the RPL user flags (SCHIP)
+    LoadFlags { x: u8 },
+}
+
+// Decodes an opcode without executing it. The opcode address is not known here, so an unknown opcode is reported
+// at PC 0x0000
+pub fn decode(opcode: u16) -> Result<Instruction, Chip8Error> {
+    let x = ((opcode & 0x0F00) >> 8) as u8;
+    let y = ((opcode & 0x00F0) >> 4) as u8;
+    let n = (opcode & 0x000F) as u8;
+    let nn = (opcode & 0x00FF) as u8;
+    let nnn = opcode & 0x0FFF;
+
+    let instruction = match opcode & 0xF000 {
+        0x0000 => match nnn {
+            0x00E0 => ClearScreen,
+            0x00C0..=0x00CF => ScrollDown(n),
+            0x00EE => Return,
+            0x00FB => ScrollRight,
+            0x00FC => ScrollLeft,
+            0x00FD => Exit,
+            0x00FE => LowRes,
+            0x00FF => HighRes,
+            _ => Sys(nnn),
+        },
+        0x1000 => Jump(nnn),
+        0x2000 => Call(nnn),
+        0x3000 => SkipEqImm { x, nn },
+        0x4000 => SkipNeImm { x, nn },
+        0x5000 => match n {
+            0x0 => SkipEqReg { x, y },
+            0x2 => StoreRange { x, y },
+            0x3 => LoadRange { x, y },
+            _ => return Err(Chip8Error::UnknownOpcode { opcode, pc: 0 }),
+        },
+        0x6000 => LoadImm { x, nn },
+        0x7000 => AddImm { x, nn },
+        0x8000 => match n {
+            0x0 => Move { x, y },
+            0x1 => Or { x, y },
+            0x2 => And { x, y },
+            0x3 => Xor { x, y },
+            0x4 => Add { x, y },
+            0x5 => Sub { x, y },
+            0x6 => ShiftRight { x, y },
+            0x7 => SubReverse { x, y },
+            0xE => ShiftLeft { x, y },
+            _ => return Err(Chip8Error::UnknownOpcode { opcode, pc: 0 }),
+        },
+        0x9000 => SkipNeReg { x, y },
+        0xA000 => LoadIndex(nnn),
+        0xB000 => JumpOffset { x, nnn },
+        0xC000 => Random { x, nn },
+        0xD000 => Draw { x, y, n },
+        0xE000 => match nn {
+            0x9E => SkipKeyPressed { x },
+            0xA1 => SkipKeyNotPressed { x },
+            _ => return Err(Chip8Error::UnknownOpcode { opcode, pc: 0 }),
+        },
+        0xF000 => match nn {
+            0x00 if x == 0 => LoadLongIndex,
+            0x01 => SelectPlanes(x),
+            0x02 if x == 0 => LoadAudioPattern,
+            0x07 => LoadDelay { x },
+            0x0A => WaitKey { x },
+            0x15 => SetDelay { x },
+            0x18 => SetSound { x },
+            0x1E => AddIndex { x },
+            0x29 => LoadFont { x },
+            0x30 => LoadBigFont { x },
+            0x33 => StoreBcd { x },
+            0x3A => SetPitch { x },
+            0x55 => StoreRegisters { x },
+            0x65 => LoadRegisters { x },
+            0x75 => StoreFlags { x },
+            0x85 => LoadFlags { x },
+            _ => return Err(Chip8Error::UnknownOpcode { opcode, pc: 0 }),
+        },
+        _ => return Err(Chip8Error::UnknownOpcode { opcode, pc: 0 }),
+    };
+    Ok(instruction)
+}
+
+impl Instruction {
+    // Opcode of the instruction, decode(instruction.encode()) gives the instruction back
+    pub fn encode(self) -> u16 {
+        let xy = |base: u16, x: u8, y: u8| base | u16::from(x) << 8 | u16::from(y) << 4;
+        let xnn = |base: u16, x: u8, nn: u8| base | u16::from(x) << 8 | u16::from(nn);
+        let fx = |x: u8, nn: u16| 0xF000 | u16::from(x) << 8 | nn;
+        match self {
+            Sys(nnn) => nnn,
+            ClearScreen => 0x00E0,
+            Return => 0x00EE,
+            ScrollDown(n) => 0x00C0 | u16::from(n),
+            ScrollRight => 0x00FB,
+            ScrollLeft => 0x00FC,
+            Exit => 0x00FD,
+            LowRes => 0x00FE,
+            HighRes => 0x00FF,
+            Jump(nnn) => 0x1000 | nnn,
+            Call(nnn) => 0x2000 | nnn,
+            SkipEqImm { x, nn } => xnn(0x3000, x, nn),
+            SkipNeImm { x, nn } => xnn(0x4000, x, nn),
+            SkipEqReg { x, y } => xy(0x5000, x, y),
+            StoreRange { x, y } => xy(0x5002, x, y),
+            LoadRange { x, y } => xy(0x5003, x, y),
+            LoadImm { x, nn } => xnn(0x6000, x, nn),
+            AddImm { x, nn } => xnn(0x7000, x, nn),
+            Move { x, y } => xy(0x8000, x, y),
+            Or { x, y } => xy(0x8001, x, y),
+            And { x, y } => xy(0x8002, x, y),
+            Xor { x, y } => xy(0x8003, x, y),
+            Add { x, y } => xy(0x8004, x, y),
+            Sub { x, y } => xy(0x8005, x, y),
+            ShiftRight { x, y } => xy(0x8006, x, y),
+            SubReverse { x, y } => xy(0x8007, x, y),
+            ShiftLeft { x, y } => xy(0x800E, x, y),
+            SkipNeReg { x, y } => xy(0x9000, x, y),
+            LoadIndex(nnn) => 0xA000 | nnn,
+            JumpOffset { nnn, .. } => 0xB000 | nnn,
+            Random { x, nn } => xnn(0xC000, x, nn),
+            Draw { x, y, n } => xy(0xD000, x, y) | u16::from(n),
+            SkipKeyPressed { x } => xnn(0xE000, x, 0x9E),
+            SkipKeyNotPressed { x } => xnn(0xE000, x, 0xA1),
+            LoadLongIndex => 0xF000,
+            SelectPlanes(n) => fx(n, 0x01),
+            LoadAudioPattern => 0xF002,
+            LoadDelay { x } => fx(x, 0x07),
+            WaitKey { x } => fx(x, 0x0A),
+            SetDelay { x } => fx(x, 0x15),
+            SetSound { x } => fx(x, 0x18),
+            AddIndex { x } => fx(x, 0x1E),
+            LoadFont { x } => fx(x, 0x29),
+            LoadBigFont { x } => fx(x, 0x30),
+            StoreBcd { x } => fx(x, 0x33),
+            SetPitch { x } => fx(x, 0x3A),
+            StoreRegisters { x } => fx(x, 0x55),
+            LoadRegisters { x } => fx(x, 0x65),
+            StoreFlags { x } => fx(x, 0x75),
+            LoadFlags { x } => fx(x, 0x85),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod instruction_tests {
+    use crate::{decode, Chip8Error};
+    use crate::Instruction::*;
+
+    #[test]
+    fn decode_reads_each_opcode_family() {
+        let cases = [
+            (0x0123, Sys(0x123)),
+            (0x00E0, ClearScreen),
+            (0x00EE, Return),
+            (0x00C7, ScrollDown(7)),
+            (0x00FB, ScrollRight),
+            (0x00FC, ScrollLeft),
+            (0x00FD, Exit),
+            (0x00FE, LowRes),
+            (0x00FF, HighRes),
+            (0x1ABC, Jump(0xABC)),
+            (0x2ABC, Call(0xABC)),
+            (0x3A12, SkipEqImm { x: 0xA, nn: 0x12 }),
+            (0x4A12, SkipNeImm { x: 0xA, nn: 0x12 }),
+            (0x5AB0, SkipEqReg { x: 0xA, y: 0xB }),
+            (0x5AB2, StoreRange { x: 0xA, y: 0xB }),
+            (0x5AB3, LoadRange { x: 0xA, y: 0xB }),
+            (0x6A12, LoadImm { x: 0xA, nn: 0x12 }),
+            (0x7A12, AddImm { x: 0xA, nn: 0x12 }),
+            (0x8AB0, Move { x: 0xA, y: 0xB }),
+            (0x8AB1, Or { x: 0xA, y: 0xB }),
+            (0x8AB2, And { x: 0xA, y: 0xB }),
+            (0x8AB3, Xor { x: 0xA, y: 0xB }),
+            (0x8AB4, Add { x: 0xA, y: 0xB }),
+            (0x8AB5, Sub { x: 0xA, y: 0xB }),
+            (0x8AB6, ShiftRight { x: 0xA, y: 0xB }),
+            (0x8AB7, SubReverse { x: 0xA, y: 0xB }),
+            (0x8ABE, ShiftLeft { x: 0xA, y: 0xB }),
+            (0x9AB0, SkipNeReg { x: 0xA, y: 0xB }),
+            (0xAABC, LoadIndex(0xABC)),
+            (0xBABC, JumpOffset { x: 0xA, nnn: 0xABC }),
+            (0xCA12, Random { x: 0xA, nn: 0x12 }),
+            (0xDAB5, Draw { x: 0xA, y: 0xB, n: 5 }),
+            (0xEA9E, SkipKeyPressed { x: 0xA }),
+            (0xEAA1, SkipKeyNotPressed { x: 0xA }),
+            (0xF000, LoadLongIndex),
+            (0xF301, SelectPlanes(3)),
+            (0xF002, LoadAudioPattern),
+            (0xFA07, LoadDelay { x: 0xA }),
+            (0xFA0A, WaitKey { x: 0xA }),
+            (0xFA15, SetDelay { x: 0xA }),
+            (0xFA18, SetSound { x: 0xA }),
+            (0xFA1E, AddIndex { x: 0xA }),
+            (0xFA29, LoadFont { x: 0xA }),
+            (0xFA30, LoadBigFont { x: 0xA }),
+            (0xFA33, StoreBcd { x: 0xA }),
+            (0xFA3A, SetPitch { x: 0xA }),
+            (0xFA55, StoreRegisters { x: 0xA }),
+            (0xFA65, LoadRegisters { x: 0xA }),
+            (0xFA75, StoreFlags { x: 0xA }),
+            (0xFA85, LoadFlags { x: 0xA }),
+        ];
+
+        for (opcode, instruction) in cases {
+            assert_eq!(decode(opcode).unwrap(), instruction, "0x{opcode:04X}");
+            assert_eq!(instruction.encode(), opcode, "{instruction:?}");
+        }
+    }
+
+    #[test]
+    fn decode_rejects_invalid_encodings() {
+        for opcode in [0x5AB1, 0x5ABF, 0x8AB8, 0x8ABF, 0xEA9F, 0xEA00, 0xF100, 0xF102, 0xFAFF] {
+            assert!(matches!(decode(opcode), Err(Chip8Error::UnknownOpcode { opcode: unknown, pc: 0 }) if unknown == opcode));
+        }
+    }
+
+    #[test]
+    fn decode_ignores_the_low_nibble_of_9xy0() {
+        assert_eq!(decode(0x9AB7).unwrap(), SkipNeReg { x: 0xA, y: 0xB });
+    }
+
+    #[test]
+    fn every_decoded_opcode_encodes_back_to_the_same_instruction() {
+        for opcode in 0..=0xFFFF {
+            if let Ok(instruction) = decode(opcode) {
+                let encoded = instruction.encode();
+                assert_eq!(decode(encoded).unwrap(), instruction, "0x{opcode:04X}");
+                if opcode & 0xF000 != 0x9000 {
+                    assert_eq!(encoded, opcode);
+                }
+            }
+        }
+    }
+}
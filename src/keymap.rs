@@ -0,0 +1,203 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./keymap_tests.rs"]
+mod keymap_tests;
+
+// Keys of the keyboard pressing the keys of the keypad. The keyboard keys are named by their place on a QWERTY
+// keyboard, each frontend turns them into its own key codes. The map is changed by assignments like "5=W,8=S",
+// written after --keymap or in the [keymap] section of the settings file
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use crate::Key;
+
+// Keys of the keyboard usable on the keypad, the digits of the top row, the letters, the arrows and the space bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PhysicalKey {
+    Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9,
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    Up, Down, Left, Right, Space,
+}
+
+impl PhysicalKey {
+    pub const ALL: [PhysicalKey; 41] = [
+        PhysicalKey::Key0, PhysicalKey::Key1, PhysicalKey::Key2, PhysicalKey::Key3, PhysicalKey::Key4,
+        PhysicalKey::Key5, PhysicalKey::Key6, PhysicalKey::Key7, PhysicalKey::Key8, PhysicalKey::Key9,
+        PhysicalKey::A, PhysicalKey::B, PhysicalKey::C, PhysicalKey::D, PhysicalKey::E, PhysicalKey::F,
+        PhysicalKey::G, PhysicalKey::H, PhysicalKey::I, PhysicalKey::J, PhysicalKey::K, PhysicalKey::L,
+        PhysicalKey::M, PhysicalKey::N, PhysicalKey::O, PhysicalKey::P, PhysicalKey::Q, PhysicalKey::R,
+        PhysicalKey::S, PhysicalKey::T, PhysicalKey::U, PhysicalKey::V, PhysicalKey::W, PhysicalKey::X,
+        PhysicalKey::Y, PhysicalKey::Z,
+        PhysicalKey::Up, PhysicalKey::Down, PhysicalKey::Left, PhysicalKey::Right, PhysicalKey::Space,
+    ];
+
+    // The name of the variant, like Key1, Q or Space, whatever its case
+    pub fn from_name(name: &str) -> Option<PhysicalKey> {
+        PhysicalKey::ALL.into_iter().find(|key| format!("{key:?}").eq_ignore_ascii_case(name))
+    }
+
+    // The key of a character typed without modifier, the letters whatever their case
+    pub fn from_char(character: char) -> Option<PhysicalKey> {
+        match character.to_ascii_uppercase() {
+            digit @ '0'..='9' => Some(PhysicalKey::ALL[digit as usize - '0' as usize]),
+            letter @ 'A'..='Z' => Some(PhysicalKey::ALL[10 + letter as usize - 'A' as usize]),
+            ' ' => Some(PhysicalKey::Space),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PhysicalKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+// Keys of the left side of a QWERTY keyboard, in the layout of the COSMAC VIP keypad:
+// 1 2 3 4    1 2 3 C
+// Q W E R    4 5 6 D
+// A S D F    7 8 9 E
+// Z X C V    A 0 B F
+const DEFAULT_KEYMAP: [(PhysicalKey, Key); 16] = [
+    (PhysicalKey::Key1, Key::K1), (PhysicalKey::Key2, Key::K2), (PhysicalKey::Key3, Key::K3),
+    (PhysicalKey::Key4, Key::KC),
+    (PhysicalKey::Q, Key::K4), (PhysicalKey::W, Key::K5), (PhysicalKey::E, Key::K6), (PhysicalKey::R, Key::KD),
+    (PhysicalKey::A, Key::K7), (PhysicalKey::S, Key::K8), (PhysicalKey::D, Key::K9), (PhysicalKey::F, Key::KE),
+    (PhysicalKey::Z, Key::KA), (PhysicalKey::X, Key::K0), (PhysicalKey::C, Key::KB), (PhysicalKey::V, Key::KF),
+];
+
+// Keypad keys in the order of the COSMAC VIP keypad, row by row
+const KEYPAD_LAYOUT: [[Key; 4]; 4] = [
+    [Key::K1, Key::K2, Key::K3, Key::KC],
+    [Key::K4, Key::K5, Key::K6, Key::KD],
+    [Key::K7, Key::K8, Key::K9, Key::KE],
+    [Key::KA, Key::K0, Key::KB, Key::KF],
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeymapError {
+    // Not written KEY=NAME
+    InvalidAssignment(String),
+    UnknownKey(String),
+    UnknownPhysicalKey(String),
+    // The same keyboard key given twice
+    DuplicateAssignment { physical_key: PhysicalKey, first: Key, second: Key },
+}
+
+impl fmt::Display for KeymapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeymapError::InvalidAssignment(assignment) => {
+                write!(f, "Invalid key assignment : \"{assignment}\", assignments are written KEY=NAME like 5=W")
+            }
+            KeymapError::UnknownKey(key) => {
+                write!(f, "Unknown keypad key : \"{key}\", the keys are 0 to 9 and A to F")
+            }
+            KeymapError::UnknownPhysicalKey(name) => {
+                let names: Vec<String> = PhysicalKey::ALL.iter().map(PhysicalKey::to_string).collect();
+                write!(f, "Unknown keyboard key : \"{name}\", the keys are {}", names.join(", "))
+            }
+            KeymapError::DuplicateAssignment { physical_key, first, second } => {
+                let (first, second) = (u8::from(*first), u8::from(*second));
+                write!(f, "Duplicate key assignment : {physical_key} is given to the keys {first:X} and {second:X}")
+            }
+        }
+    }
+}
+
+impl Error for KeymapError {}
+
+// Parses assignments of keyboard keys to keypad keys, like "5=W, 8=S, 5=Up", separated by commas or lines. A keypad
+// key may be given several keyboard keys, a keyboard key only one keypad key
+pub fn parse_keymap(assignments: &str) -> Result<Vec<(Key, PhysicalKey)>, KeymapError> {
+    let mut parsed: Vec<(Key, PhysicalKey)> = Vec::new();
+    for assignment in assignments.split([',', '\n']).map(str::trim).filter(|assignment| !assignment.is_empty()) {
+        let Some((key, name)) = assignment.split_once('=') else {
+            return Err(KeymapError::InvalidAssignment(assignment.to_string()));
+        };
+        let (key, name) = (key.trim(), name.trim());
+        let key = u8::from_str_radix(key, 16)
+            .ok()
+            .filter(|_| key.len() == 1)
+            .and_then(|key| Key::try_from(key).ok())
+            .ok_or_else(|| KeymapError::UnknownKey(key.to_string()))?;
+        let physical_key = PhysicalKey::from_name(name)
+            .ok_or_else(|| KeymapError::UnknownPhysicalKey(name.to_string()))?;
+        if let Some(&(first, _)) = parsed.iter().find(|&&(_, other)| other == physical_key) {
+            return Err(KeymapError::DuplicateAssignment { physical_key, first, second: key });
+        }
+        parsed.push((key, physical_key));
+    }
+    Ok(parsed)
+}
+
+// Keypad key of each keyboard key, the 1234, QWER, ASDF and ZXCV keys by default
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    keys: HashMap<PhysicalKey, Key>,
+}
+
+impl Default for Keymap {
+    fn default() -> Keymap {
+        Keymap { keys: DEFAULT_KEYMAP.into_iter().collect() }
+    }
+}
+
+impl Keymap {
+    // Gives the keypad keys of the assignments their keyboard keys instead of the ones they had, which no longer
+    // press them. A keyboard key given to another keypad key leaves the one it pressed
+    pub fn assign(mut self, assignments: &[(Key, PhysicalKey)]) -> Keymap {
+        self.keys.retain(|_, key| !assignments.iter().any(|(assigned, _)| assigned == key));
+        for &(key, physical_key) in assignments {
+            self.keys.insert(physical_key, key);
+        }
+        self
+    }
+
+    pub fn key(&self, physical_key: PhysicalKey) -> Option<Key> {
+        self.keys.get(&physical_key).copied()
+    }
+
+    // Keyboard keys pressing a keypad key, in the order of PhysicalKey::ALL
+    pub fn physical_keys(&self, key: Key) -> Vec<PhysicalKey> {
+        let mut physical_keys: Vec<PhysicalKey> = self.keys.iter()
+            .filter(|&(_, &other)| other == key)
+            .map(|(&physical_key, _)| physical_key)
+            .collect();
+        physical_keys.sort();
+        physical_keys
+    }
+
+    pub fn keys(&self) -> &HashMap<PhysicalKey, Key> {
+        &self.keys
+    }
+
+    // States of the keypad keys from whether each keyboard key is held, for the Input of the frontends
+    pub fn poll(&self, is_down: impl Fn(PhysicalKey) -> bool) -> [bool; 16] {
+        let mut keys = [false; 16];
+        for (&physical_key, &key) in &self.keys {
+            if is_down(physical_key) {
+                keys[usize::from(u8::from(key))] = true;
+            }
+        }
+        keys
+    }
+}
+
+// The keypad in the layout of the COSMAC VIP with the keyboard keys of each key, for --show-keymap
+impl fmt::Display for Keymap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in KEYPAD_LAYOUT {
+            let cells: Vec<String> = row.iter()
+                .map(|&key| {
+                    let names: Vec<String> = self.physical_keys(key).iter().map(PhysicalKey::to_string).collect();
+                    let names = if names.is_empty() { String::from("-") } else { names.join("/") };
+                    format!("{:X} {names:<10}", u8::from(key))
+                })
+                .collect();
+            writeln!(f, "{}", cells.join(" ").trim_end())?;
+        }
+        Ok(())
+    }
+}
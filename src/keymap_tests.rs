@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod keymap_tests {
+    use crate::{parse_keymap, Key, Keymap, KeymapError, PhysicalKey};
+
+    #[test]
+    fn physical_key_from_name_reads_the_names_in_any_case() {
+        assert_eq!(PhysicalKey::from_name("Key1"), Some(PhysicalKey::Key1));
+        assert_eq!(PhysicalKey::from_name("q"), Some(PhysicalKey::Q));
+        assert_eq!(PhysicalKey::from_name("SPACE"), Some(PhysicalKey::Space));
+        assert_eq!(PhysicalKey::from_name("1"), None);
+        assert_eq!(PhysicalKey::from_name("Enter"), None);
+        for physical_key in PhysicalKey::ALL {
+            assert_eq!(PhysicalKey::from_name(&physical_key.to_string()), Some(physical_key));
+        }
+    }
+
+    #[test]
+    fn physical_key_from_char_reads_the_digits_letters_and_space() {
+        assert_eq!(PhysicalKey::from_char('0'), Some(PhysicalKey::Key0));
+        assert_eq!(PhysicalKey::from_char('9'), Some(PhysicalKey::Key9));
+        assert_eq!(PhysicalKey::from_char('a'), Some(PhysicalKey::A));
+        assert_eq!(PhysicalKey::from_char('Z'), Some(PhysicalKey::Z));
+        assert_eq!(PhysicalKey::from_char(' '), Some(PhysicalKey::Space));
+        assert_eq!(PhysicalKey::from_char('é'), None);
+        assert_eq!(PhysicalKey::from_char(';'), None);
+    }
+
+    #[test]
+    fn default_keymap_is_the_left_of_a_qwerty_keyboard() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.keys().len(), 16);
+        assert_eq!(keymap.key(PhysicalKey::Key1), Some(Key::K1));
+        assert_eq!(keymap.key(PhysicalKey::Key4), Some(Key::KC));
+        assert_eq!(keymap.key(PhysicalKey::X), Some(Key::K0));
+        assert_eq!(keymap.key(PhysicalKey::V), Some(Key::KF));
+        assert_eq!(keymap.key(PhysicalKey::P), None);
+        // Every keypad key has one keyboard key
+        for key in Key::ALL {
+            assert_eq!(keymap.physical_keys(key).len(), 1, "{key:?}");
+        }
+    }
+
+    #[test]
+    fn parse_keymap_reads_the_assignments_in_order() {
+        assert_eq!(parse_keymap("1=Key1,a=Q"), Ok(vec![(Key::K1, PhysicalKey::Key1), (Key::KA, PhysicalKey::Q)]));
+        // Spaces, lines and empty assignments
+        assert_eq!(
+            parse_keymap(" 5 = W ,\n8=s\n\n,5=Up"),
+            Ok(vec![(Key::K5, PhysicalKey::W), (Key::K8, PhysicalKey::S), (Key::K5, PhysicalKey::Up)]),
+        );
+        assert_eq!(parse_keymap(""), Ok(vec![]));
+    }
+
+    #[test]
+    fn parse_keymap_rejects_unknown_keys_and_bad_assignments() {
+        assert_eq!(parse_keymap("5W"), Err(KeymapError::InvalidAssignment(String::from("5W"))));
+        assert_eq!(parse_keymap("G=W"), Err(KeymapError::UnknownKey(String::from("G"))));
+        assert_eq!(parse_keymap("10=W"), Err(KeymapError::UnknownKey(String::from("10"))));
+        assert_eq!(parse_keymap("=W"), Err(KeymapError::UnknownKey(String::new())));
+        assert_eq!(parse_keymap("5=Enter"), Err(KeymapError::UnknownPhysicalKey(String::from("Enter"))));
+    }
+
+    #[test]
+    fn parse_keymap_rejects_a_keyboard_key_given_twice() {
+        let error = KeymapError::DuplicateAssignment { physical_key: PhysicalKey::W, first: Key::K5, second: Key::KA };
+        assert_eq!(parse_keymap("5=W,8=S,A=w"), Err(error));
+        let error = KeymapError::DuplicateAssignment { physical_key: PhysicalKey::W, first: Key::K5, second: Key::K5 };
+        assert_eq!(parse_keymap("5=W,5=W"), Err(error));
+    }
+
+    #[test]
+    fn keymap_errors_name_the_keys_allowed() {
+        let message = KeymapError::UnknownPhysicalKey(String::from("Enter")).to_string();
+        assert!(message.starts_with("Unknown keyboard key : \"Enter\", the keys are Key0, Key1,"));
+        assert!(message.ends_with("Y, Z, Up, Down, Left, Right, Space"));
+        let error = KeymapError::DuplicateAssignment { physical_key: PhysicalKey::W, first: Key::K5, second: Key::KA };
+        assert_eq!(error.to_string(), "Duplicate key assignment : W is given to the keys 5 and A");
+        assert_eq!(
+            KeymapError::UnknownKey(String::from("G")).to_string(),
+            "Unknown keypad key : \"G\", the keys are 0 to 9 and A to F",
+        );
+    }
+
+    #[test]
+    fn assign_replaces_the_keyboard_keys_of_the_keys_given() {
+        let keymap = Keymap::default().assign(&parse_keymap("5=Up,5=I,C=Q").unwrap());
+        // 5 loses W, C loses Key4, and Q leaves 4
+        assert_eq!(keymap.physical_keys(Key::K5), [PhysicalKey::I, PhysicalKey::Up]);
+        assert_eq!(keymap.key(PhysicalKey::W), None);
+        assert_eq!(keymap.physical_keys(Key::KC), [PhysicalKey::Q]);
+        assert_eq!(keymap.key(PhysicalKey::Key4), None);
+        assert_eq!(keymap.physical_keys(Key::K4), []);
+        // The others keep theirs
+        assert_eq!(keymap.key(PhysicalKey::X), Some(Key::K0));
+        assert_eq!(Keymap::default().assign(&[]), Keymap::default());
+    }
+
+    #[test]
+    fn poll_presses_a_key_held_by_any_of_its_keyboard_keys() {
+        let keymap = Keymap::default().assign(&parse_keymap("5=Up").unwrap()).assign(&parse_keymap("5=W").unwrap());
+        let keys = keymap.poll(|physical_key| physical_key == PhysicalKey::W || physical_key == PhysicalKey::V);
+        let pressed: Vec<usize> = (0..16).filter(|&key| keys[key]).collect();
+        assert_eq!(pressed, [0x5, 0xF]);
+        assert_eq!(keymap.poll(|physical_key| physical_key == PhysicalKey::Up), [false; 16]);
+    }
+
+    #[test]
+    fn keymap_is_shown_as_the_keypad_layout() {
+        assert_eq!(Keymap::default().to_string(), concat!(
+            "1 Key1       2 Key2       3 Key3       C Key4\n",
+            "4 Q          5 W          6 E          D R\n",
+            "7 A          8 S          9 D          E F\n",
+            "A Z          0 X          B C          F V\n",
+        ));
+        let keymap = Keymap::default().assign(&parse_keymap("5=W,5=Up,C=Q").unwrap());
+        let lines: Vec<String> = keymap.to_string().lines().map(String::from).collect();
+        assert_eq!(lines[0], "1 Key1       2 Key2       3 Key3       C Q");
+        assert_eq!(lines[1], "4 -          5 W/Up       6 E          D R");
+    }
+}
@@ -0,0 +1,181 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./keypad_tests.rs"]
+mod keypad_tests;
+
+use std::collections::VecDeque;
+use crate::{Chip8, Chip8Error};
+
+// Keys of the HEX based keypad, Key::try_from(0xA) gives Key::KA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    K0, K1, K2, K3, K4, K5, K6, K7, K8, K9, KA, KB, KC, KD, KE, KF,
+}
+
+impl Key {
+    pub const ALL: [Key; 16] = [
+        Key::K0, Key::K1, Key::K2, Key::K3, Key::K4, Key::K5, Key::K6, Key::K7,
+        Key::K8, Key::K9, Key::KA, Key::KB, Key::KC, Key::KD, Key::KE, Key::KF,
+    ];
+}
+
+// Values above 0xF are not keys
+impl TryFrom<u8> for Key {
+    type Error = Chip8Error;
+
+    fn try_from(value: u8) -> Result<Key, Chip8Error> {
+        Key::ALL.get(usize::from(value)).copied().ok_or(Chip8Error::InvalidKey { key: value })
+    }
+}
+
+impl From<Key> for u8 {
+    fn from(key: Key) -> u8 {
+        key as u8
+    }
+}
+
+#[derive(Default, Clone, PartialEq)]
+pub(crate) struct Keypad {
+    // the Chip 8 has a HEX based keypad (0x0-0xF), an array store whether each key is pressed.
+    pub(crate) key: [bool; 16],
+    // Keys pressed and released since the end of the last frame. A key held down over several frames is pressed
+    // once, and a key pressed and released between two frames has both
+    pub(crate) pressed: [bool; 16],
+    pub(crate) released: [bool; 16],
+    // Key pressed during the wait of FX0A with the key_wait_on_release quirk, until it is released
+    pub(crate) awaited: Option<Key>,
+}
+
+impl Keypad {
+    // Records the level of the key, and an edge when it changes
+    pub(crate) fn key_event(&mut self, key: Key, pressed: bool) {
+        let index = key as usize;
+        if self.key[index] != pressed {
+            if pressed {
+                self.pressed[index] = true;
+            } else {
+                self.released[index] = true;
+            }
+        }
+        self.key[index] = pressed;
+    }
+
+    pub(crate) fn press(&mut self, key: Key) {
+        self.key_event(key, true);
+    }
+
+    pub(crate) fn release(&mut self, key: Key) {
+        self.key_event(key, false);
+    }
+
+    pub(crate) fn is_pressed(&self, key: Key) -> bool {
+        self.key[key as usize]
+    }
+
+    // Keys outside of the hex keypad (above 0xF) are never pressed
+    pub(crate) fn is_key_pressed(&self, key: u8) -> bool {
+        Key::try_from(key).is_ok_and(|key| self.is_pressed(key))
+    }
+
+    pub(crate) fn was_pressed(&self, key: Key) -> bool {
+        self.pressed[key as usize]
+    }
+
+    pub(crate) fn was_released(&self, key: Key) -> bool {
+        self.released[key as usize]
+    }
+
+    // Bit K is set when key K is pressed
+    pub(crate) fn bitmask(&self) -> u16 {
+        self.key.iter().rev().fold(0, |mask, &pressed| mask << 1 | u16::from(pressed))
+    }
+
+    // Lowest key pressed since the last frame, awaited by FX0A. Its press is taken so that a key held down completes
+    // one wait only
+    pub(crate) fn take_pressed_key(&mut self) -> Option<Key> {
+        let key = Key::ALL.into_iter().find(|&key| self.was_pressed(key))?;
+        self.pressed[key as usize] = false;
+        Some(key)
+    }
+
+    // Key completing the wait of FX0A. On the press, the lowest key pressed since the last frame. On the release, the
+    // first key pressed during the wait once it is up again, the keys pressed after it are ignored
+    pub(crate) fn take_awaited_key(&mut self, on_release: bool) -> Option<Key> {
+        if !on_release {
+            return self.take_pressed_key();
+        }
+        if self.awaited.is_none() {
+            self.awaited = self.take_pressed_key();
+        }
+        let key = self.awaited.filter(|&key| !self.is_pressed(key))?;
+        self.awaited = None;
+        Some(key)
+    }
+
+    // Called at the end of each frame, the levels are kept
+    pub(crate) fn clear_edges(&mut self) {
+        self.pressed = [false; 16];
+        self.released = [false; 16];
+    }
+}
+
+impl Chip8 {
+    // Press or release of a key, given by the frontends as it happens. The level is read by EX9E and EXA1, the
+    // edges last until the end of the frame
+    pub fn key_event(&mut self, key: Key, pressed: bool) {
+        self.keypad.key_event(key, pressed);
+    }
+
+    pub fn press(&mut self, key: Key) {
+        self.keypad.press(key);
+    }
+
+    pub fn release(&mut self, key: Key) {
+        self.keypad.release(key);
+    }
+
+    pub fn is_pressed(&self, key: Key) -> bool {
+        self.keypad.is_pressed(key)
+    }
+
+    // Whether the key was pressed since the end of the last frame, once however long it is held
+    pub fn was_pressed(&self, key: Key) -> bool {
+        self.keypad.was_pressed(key)
+    }
+
+    // Whether the key was released since the end of the last frame
+    pub fn was_released(&self, key: Key) -> bool {
+        self.keypad.was_released(key)
+    }
+}
+
+// Input backend of a frontend, polled for the state of the 16 keys (0x0-0xF) before each cycle
+pub trait Input {
+    fn poll(&mut self) -> [bool; 16];
+}
+
+// Input with no key ever pressed, for headless runs
+pub struct NullInput;
+
+impl Input for NullInput {
+    fn poll(&mut self) -> [bool; 16] {
+        [false; 16]
+    }
+}
+
+// Input returning scripted key states, one per poll. Once the script runs out, no key is pressed
+pub struct FixedKeypad {
+    states: VecDeque<[bool; 16]>,
+}
+
+impl FixedKeypad {
+    pub fn new(states: Vec<[bool; 16]>) -> FixedKeypad {
+        FixedKeypad { states: states.into() }
+    }
+}
+
+impl Input for FixedKeypad {
+    fn poll(&mut self) -> [bool; 16] {
+        self.states.pop_front().unwrap_or([false; 16])
+    }
+}
@@ -0,0 +1,443 @@
+#[cfg(test)]
+mod keypad_tests {
+    use crate::Chip8;
+    use crate::keypad::{FixedKeypad, Input, Key, Keypad};
+    use crate::{Chip8Config, Chip8Error, State};
+    use crate::ProgramCounterInstruction::GOTO;
+    use crate::ProgramCounterInstruction::NEXT;
+    use crate::ProgramCounterInstruction::SKIP;
+
+    #[test]
+    fn op_0xex9e_skip_when_key_in_vx_is_pressed() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        chip8.v[x] = 0xA;
+        chip8.press(Key::KA);
+
+        let result = chip8.op_0xex9e(x);
+
+        assert!(matches!(result, SKIP));
+    }
+
+    #[test]
+    fn op_0xex9e_next_when_key_in_vx_is_not_pressed() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        chip8.v[x] = 0xA;
+        chip8.press(Key::KB);
+
+        let result = chip8.op_0xex9e(x);
+
+        assert!(matches!(result, NEXT));
+    }
+
+    #[test]
+    fn op_0xex9e_skip_when_key_in_vx_is_pressed_with_other_keys() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        chip8.v[x] = 0xC;
+        chip8.press(Key::K1);
+        chip8.press(Key::KC);
+        chip8.press(Key::KD);
+
+        let result = chip8.op_0xex9e(x);
+
+        assert!(matches!(result, SKIP));
+    }
+
+    #[test]
+    fn op_0xex9e_next_when_vx_is_not_a_key() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        chip8.v[x] = 0x1F;
+        Key::ALL.into_iter().for_each(|key| chip8.press(key));
+
+        let result = chip8.op_0xex9e(x);
+
+        assert!(matches!(result, NEXT));
+    }
+
+    #[test]
+    fn op_0xexa1_skip_when_key_in_vx_is_not_pressed() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        chip8.v[x] = 0xA;
+        chip8.press(Key::KB);
+
+        let result = chip8.op_0xexa1(x);
+
+        assert!(matches!(result, SKIP));
+    }
+
+    #[test]
+    fn op_0xexa1_next_when_key_in_vx_is_pressed() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        chip8.v[x] = 0xA;
+        chip8.press(Key::KA);
+
+        let result = chip8.op_0xexa1(x);
+
+        assert!(matches!(result, NEXT));
+    }
+
+    #[test]
+    fn op_0xexa1_next_when_key_in_vx_is_pressed_with_other_keys() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        chip8.v[x] = 0xC;
+        chip8.press(Key::K1);
+        chip8.press(Key::KC);
+        chip8.press(Key::KD);
+
+        let result = chip8.op_0xexa1(x);
+
+        assert!(matches!(result, NEXT));
+    }
+
+    #[test]
+    fn op_0xexa1_skip_when_vx_is_not_a_key() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        chip8.v[x] = 0x1F;
+        Key::ALL.into_iter().for_each(|key| chip8.press(key));
+
+        let result = chip8.op_0xexa1(x);
+
+        assert!(matches!(result, SKIP));
+    }
+
+    #[test]
+    fn op_0xfx0a_waits_for_a_key_press_without_advancing() {
+        let mut chip8 = Chip8::default();
+        let x = 3;
+
+        let result = chip8.op_0xfx0a(x);
+
+        assert!(matches!(result, GOTO(0x200)));
+        assert_eq!(chip8.state(), &State::WaitingForKey { dest: 3 });
+    }
+
+    #[test]
+    fn op_0xfx0a_halts_until_a_key_is_pressed_then_stores_it_in_vx() {
+        let mut chip8 = Chip8::default();
+        chip8.memory[0x200] = 0xF3;
+        chip8.memory[0x201] = 0x0A;
+
+        for _ in 0..5 {
+            chip8.emulate_cycle().unwrap();
+            assert_eq!(chip8.pc(), 0x200);
+        }
+        chip8.press(Key::K5);
+        chip8.emulate_cycle().unwrap();
+
+        assert_eq!(chip8.pc(), 0x202);
+        assert_eq!(chip8.register(3), 0x5);
+        assert_eq!(chip8.state(), &State::Running);
+    }
+
+    #[test]
+    fn op_0xfx0a_keeps_timers_counting_down_while_waiting() {
+        let mut chip8 = Chip8::default();
+        chip8.memory[0x200] = 0xF3;
+        chip8.memory[0x201] = 0x0A;
+        chip8.timers.delay_timer = 10;
+        chip8.timers.sound_timer = 10;
+
+        for _ in 0..4 {
+            chip8.emulate_cycle().unwrap();
+            chip8.tick_timers();
+        }
+
+        assert_eq!(chip8.pc(), 0x200);
+        assert_eq!(chip8.delay_timer(), 6);
+        assert_eq!(chip8.sound_timer(), 6);
+    }
+
+    // F00A, F10A: waits for a key in V0 then one in V1, then loops forever
+    const TWO_KEY_WAITS: [u8; 6] = [0xF0, 0x0A, 0xF1, 0x0A, 0x12, 0x04];
+
+    // Runs a frame after giving the key events to the machine
+    fn run_frame_with(chip8: &mut Chip8, events: &[(Key, bool)]) {
+        for &(key, pressed) in events {
+            chip8.key_event(key, pressed);
+        }
+        chip8.run_frame().unwrap();
+    }
+
+    fn waiting_machine(on_release: bool) -> Chip8 {
+        let mut chip8 = Chip8Config::new().key_wait_on_release(on_release).build().unwrap();
+        chip8.load_rom(&TWO_KEY_WAITS).unwrap();
+        chip8.run_frame().unwrap();
+        assert_eq!(chip8.state(), &State::WaitingForKey { dest: 0 });
+        chip8
+    }
+
+    #[test]
+    fn op_0xfx0a_completes_on_the_press_without_the_quirk() {
+        let mut chip8 = waiting_machine(false);
+
+        run_frame_with(&mut chip8, &[(Key::K5, true)]);
+        assert_eq!(chip8.register(0), 0x5);
+        assert_eq!(chip8.state(), &State::WaitingForKey { dest: 1 });
+
+        // Still held, then released: the second wait goes on
+        run_frame_with(&mut chip8, &[]);
+        run_frame_with(&mut chip8, &[(Key::K5, false)]);
+        assert_eq!(chip8.state(), &State::WaitingForKey { dest: 1 });
+    }
+
+    #[test]
+    fn op_0xfx0a_takes_a_second_key_pressed_while_the_first_is_held_without_the_quirk() {
+        let mut chip8 = waiting_machine(false);
+
+        run_frame_with(&mut chip8, &[(Key::K5, true)]);
+        run_frame_with(&mut chip8, &[(Key::K7, true)]);
+
+        assert_eq!((chip8.register(0), chip8.register(1)), (0x5, 0x7));
+        assert_eq!(chip8.pc(), 0x204);
+    }
+
+    #[test]
+    fn op_0xfx0a_waits_for_the_release_with_the_quirk() {
+        let mut chip8 = waiting_machine(true);
+
+        run_frame_with(&mut chip8, &[(Key::K5, true)]);
+        for _ in 0..3 {
+            run_frame_with(&mut chip8, &[]);
+            assert_eq!(chip8.state(), &State::WaitingForKey { dest: 0 });
+            assert_eq!(chip8.pc(), 0x200);
+            assert_eq!(chip8.register(0), 0);
+        }
+
+        run_frame_with(&mut chip8, &[(Key::K5, false)]);
+        assert_eq!(chip8.register(0), 0x5);
+        assert_eq!(chip8.state(), &State::WaitingForKey { dest: 1 });
+    }
+
+    #[test]
+    fn op_0xfx0a_completes_on_a_tap_between_two_frames_with_the_quirk() {
+        let mut chip8 = waiting_machine(true);
+
+        run_frame_with(&mut chip8, &[(Key::K9, true), (Key::K9, false)]);
+
+        assert_eq!(chip8.register(0), 0x9);
+        assert_eq!(chip8.state(), &State::WaitingForKey { dest: 1 });
+    }
+
+    #[test]
+    fn op_0xfx0a_keeps_the_first_key_pressed_during_the_wait_with_the_quirk() {
+        let mut chip8 = waiting_machine(true);
+
+        run_frame_with(&mut chip8, &[(Key::K5, true)]);
+        // A second key pressed and released while the first is held is ignored
+        run_frame_with(&mut chip8, &[(Key::K2, true)]);
+        run_frame_with(&mut chip8, &[(Key::K2, false)]);
+        assert_eq!(chip8.state(), &State::WaitingForKey { dest: 0 });
+
+        run_frame_with(&mut chip8, &[(Key::K5, false)]);
+        assert_eq!(chip8.register(0), 0x5);
+        // Its press is over, the next wait needs a new one
+        run_frame_with(&mut chip8, &[]);
+        assert_eq!(chip8.state(), &State::WaitingForKey { dest: 1 });
+        run_frame_with(&mut chip8, &[(Key::K2, true)]);
+        run_frame_with(&mut chip8, &[(Key::K2, false)]);
+        assert_eq!(chip8.register(1), 0x2);
+        assert_eq!(chip8.pc(), 0x204);
+    }
+
+    #[test]
+    fn reset_forgets_the_key_noted_by_the_wait() {
+        let mut chip8 = waiting_machine(true);
+        run_frame_with(&mut chip8, &[(Key::K5, true)]);
+
+        chip8.reset();
+        chip8.run_frame().unwrap();
+        run_frame_with(&mut chip8, &[(Key::K5, false)]);
+
+        assert_eq!(chip8.state(), &State::WaitingForKey { dest: 0 });
+    }
+
+    #[test]
+    fn take_pressed_key_returns_none_when_no_key_is_pressed() {
+        let mut keypad = Keypad::default();
+
+        assert_eq!(keypad.take_pressed_key(), None);
+    }
+
+    #[test]
+    fn take_pressed_key_returns_the_lowest_pressed_key_once() {
+        let mut keypad = Keypad::default();
+        keypad.press(Key::KC);
+        keypad.press(Key::K5);
+
+        assert_eq!(keypad.take_pressed_key(), Some(Key::K5));
+        assert_eq!(keypad.take_pressed_key(), Some(Key::KC));
+        assert_eq!(keypad.take_pressed_key(), None);
+        assert!(keypad.is_pressed(Key::K5));
+    }
+
+    #[test]
+    fn key_event_records_the_edges_once_while_a_key_is_held() {
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(&[0x12, 0x00]).unwrap(); // loop forever
+
+        chip8.key_event(Key::K6, true);
+        assert!(chip8.is_pressed(Key::K6));
+        assert!(chip8.was_pressed(Key::K6));
+        chip8.run_frame().unwrap();
+
+        // Held over the next frames, the press is not repeated
+        for _ in 0..3 {
+            chip8.key_event(Key::K6, true);
+            assert!(chip8.is_pressed(Key::K6));
+            assert!(!chip8.was_pressed(Key::K6));
+            assert!(!chip8.was_released(Key::K6));
+            chip8.run_frame().unwrap();
+        }
+
+        chip8.key_event(Key::K6, false);
+        assert!(!chip8.is_pressed(Key::K6));
+        assert!(chip8.was_released(Key::K6));
+        chip8.run_frame().unwrap();
+        assert!(!chip8.was_released(Key::K6));
+    }
+
+    #[test]
+    fn key_event_keeps_both_edges_of_a_tap_between_two_frames() {
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(&[0x12, 0x00]).unwrap();
+
+        chip8.key_event(Key::K2, true);
+        chip8.key_event(Key::K2, false);
+
+        assert!(!chip8.is_pressed(Key::K2));
+        assert!(chip8.was_pressed(Key::K2));
+        assert!(chip8.was_released(Key::K2));
+        chip8.run_frame().unwrap();
+        assert!(!chip8.was_pressed(Key::K2));
+        assert!(!chip8.was_released(Key::K2));
+    }
+
+    #[test]
+    fn set_keys_gives_an_edge_only_to_the_keys_that_changed() {
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(&[0x12, 0x00]).unwrap();
+        let mut held = [false; 16];
+        held[0x3] = true;
+        let mut both = held;
+        both[0x9] = true;
+        let mut input = FixedKeypad::new(vec![held, both, held]);
+
+        chip8.set_keys(&mut input);
+        assert!(chip8.was_pressed(Key::K3));
+        chip8.run_frame().unwrap();
+        chip8.set_keys(&mut input);
+        assert!(!chip8.was_pressed(Key::K3));
+        assert!(chip8.was_pressed(Key::K9));
+        chip8.run_frame().unwrap();
+        chip8.set_keys(&mut input);
+        assert!(chip8.was_released(Key::K9));
+        assert!(!chip8.was_released(Key::K3));
+    }
+
+    #[test]
+    fn op_0xfx0a_is_completed_once_by_a_key_held_down() {
+        let mut chip8 = Chip8::default();
+        // F00A, F10A: waits for two keys, then loops forever
+        chip8.load_rom(&[0xF0, 0x0A, 0xF1, 0x0A, 0x12, 0x04]).unwrap();
+        let mut held = [false; 16];
+        held[0x8] = true;
+        // Pressed once the first wait started, then held
+        let mut input = FixedKeypad::new(vec![[false; 16], held, held, held, held]);
+
+        for _ in 0..5 {
+            chip8.set_keys(&mut input);
+            chip8.run_frame().unwrap();
+        }
+
+        assert_eq!(chip8.register(0), 0x8);
+        assert_eq!(chip8.state(), &State::WaitingForKey { dest: 1 });
+        assert_eq!(chip8.pc(), 0x202);
+    }
+
+    #[test]
+    fn op_0xfx0a_ignores_a_key_held_before_the_wait() {
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(&[0x60, 0x00, 0xF1, 0x0A]).unwrap();
+        chip8.press(Key::K4);
+        chip8.run_frame().unwrap();
+        assert_eq!(chip8.state(), &State::WaitingForKey { dest: 1 });
+
+        chip8.press(Key::K4);
+        chip8.run_frame().unwrap();
+        assert_eq!(chip8.state(), &State::WaitingForKey { dest: 1 });
+
+        chip8.press(Key::K7);
+        chip8.run_frame().unwrap();
+        assert_eq!(chip8.register(1), 0x7);
+    }
+
+    #[test]
+    fn fixed_keypad_returns_the_scripted_states_then_no_key() {
+        let mut pressed = [false; 16];
+        pressed[0x3] = true;
+        let mut input = FixedKeypad::new(vec![pressed, [false; 16], pressed]);
+
+        assert_eq!(input.poll(), pressed);
+        assert_eq!(input.poll(), [false; 16]);
+        assert_eq!(input.poll(), pressed);
+        assert_eq!(input.poll(), [false; 16]);
+    }
+
+    #[test]
+    fn set_keys_copies_the_polled_key_states() {
+        let mut chip8 = Chip8::default();
+        let mut pressed = [false; 16];
+        pressed[0xE] = true;
+        let mut input = FixedKeypad::new(vec![pressed]);
+
+        chip8.set_keys(&mut input);
+
+        assert!(chip8.is_pressed(Key::KE));
+        assert!(!chip8.is_pressed(Key::K0));
+    }
+
+    #[test]
+    fn bitmask_sets_one_bit_per_pressed_key() {
+        let mut keypad = Keypad::default();
+        keypad.press(Key::K1);
+        keypad.press(Key::KF);
+
+        assert_eq!(keypad.bitmask(), 0b1000_0000_0000_0010);
+    }
+
+    #[test]
+    fn several_keys_can_be_pressed_at_once() {
+        let mut chip8 = Chip8::default();
+
+        chip8.press(Key::K2);
+        chip8.press(Key::K8);
+        chip8.press(Key::KF);
+        chip8.release(Key::K8);
+
+        assert!(chip8.is_pressed(Key::K2));
+        assert!(!chip8.is_pressed(Key::K8));
+        assert!(chip8.is_pressed(Key::KF));
+        assert!(!chip8.is_pressed(Key::K0));
+    }
+
+    #[test]
+    fn key_try_from_accepts_the_hex_digits() {
+        assert_eq!(Key::try_from(0x0).unwrap(), Key::K0);
+        assert_eq!(Key::try_from(0xA).unwrap(), Key::KA);
+        assert_eq!(Key::try_from(0xF).unwrap(), Key::KF);
+        assert_eq!(u8::from(Key::KC), 0xC);
+    }
+
+    #[test]
+    fn key_try_from_rejects_values_above_0xf() {
+        assert!(matches!(Key::try_from(0x10), Err(Chip8Error::InvalidKey { key: 0x10 })));
+        assert!(matches!(Key::try_from(0xFF), Err(Chip8Error::InvalidKey { key: 0xFF })));
+    }
+}
@@ -0,0 +1,785 @@
+#[cfg(feature = "record-audio")]
+mod audio_recording;
+mod beep;
+mod capture;
+mod config;
+mod controls;
+mod cpu;
+mod crt;
+#[cfg(feature = "egui")]
+mod debugger;
+mod display;
+mod focus;
+mod frames;
+mod hooks;
+mod instruction;
+mod keymap;
+mod keypad;
+#[cfg(feature = "macroquad")]
+mod macroquad_frontend;
+mod memory;
+mod overlay;
+mod pacing;
+mod phosphor;
+#[cfg(feature = "minifb")]
+mod minifb_frontend;
+#[cfg(feature = "pixels")]
+mod pixels_frontend;
+#[cfg(feature = "record")]
+mod recording;
+mod rotation;
+mod scaling;
+#[cfg(feature = "screenshot")]
+mod screenshot;
+#[cfg(feature = "sdl2")]
+mod sdl;
+mod settings;
+mod sixel;
+#[cfg(feature = "terminal")]
+mod terminal;
+mod theme;
+mod timers;
+mod title;
+mod tone;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod window_frontend;
+
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./lib_tests.rs"]
+mod lib_tests;
+
+#[cfg(all(test, feature = "schip-check"))]
+#[allow(clippy::module_inception)]
+#[path = "./schip_check.rs"]
+mod schip_check;
+
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use crate::display::Screen;
+use crate::hooks::Hooks;
+use crate::keypad::Keypad;
+use crate::memory::{Memory, MEMORY_SIZE};
+use crate::timers::Timers;
+
+#[cfg(feature = "record-audio")]
+pub use crate::audio_recording::{WavRecorder, AUDIO_SAMPLE_RATE, SAMPLES_PER_FRAME};
+pub use crate::beep::{gain, BeepGate, BeepSource, BEEP_FREQUENCY, BEEP_RAMP, BEEP_VOLUME, MAX_VOLUME};
+pub use crate::capture::capture_file_name;
+pub use crate::config::Chip8Config;
+pub use crate::controls::{control, letter_free, Control, ControlKey, RunControl};
+pub use crate::cpu::ProgramCounterInstruction;
+pub use crate::crt::{crt_image, grid_level, scanline_level, vignette_level, Crt};
+#[cfg(feature = "egui")]
+pub use crate::debugger::Debugger;
+pub use crate::display::{to_ascii_string, to_braille_string, AsciiDisplay, BrailleDisplay, Display, NullDisplay};
+pub use crate::focus::FocusPause;
+pub use crate::frames::{Frame, Frames};
+pub use crate::hooks::{DrawHook, FrameSound, KeyWaitHook, SoundHook, TickHook};
+pub use crate::instruction::{decode, Instruction};
+pub use crate::keymap::{parse_keymap, Keymap, KeymapError, PhysicalKey};
+pub use crate::keypad::{FixedKeypad, Input, Key, NullInput};
+#[cfg(feature = "macroquad")]
+pub use crate::macroquad_frontend::MacroquadFrontend;
+pub use crate::memory::{FontStyle, ProtectedWritePolicy};
+pub use crate::overlay::{mnemonic, overlay_lines, overlay_scale, shade, DebugInfo, TextBitmap};
+pub use crate::pacing::{
+    frame_skip, Clock, FramePacer, FrameSkip, SystemClock, Throughput, DEFAULT_MAX_SKIP, DEFAULT_TURBO,
+};
+pub use crate::phosphor::Phosphor;
+#[cfg(feature = "minifb")]
+pub use crate::minifb_frontend::MinifbFrontend;
+#[cfg(feature = "pixels")]
+pub use crate::pixels_frontend::PixelsFrontend;
+#[cfg(feature = "record")]
+pub use crate::recording::GifRecorder;
+pub use crate::rotation::{rotate, rotated_index, Rotation};
+pub use crate::scaling::{letterbox, window_size, Viewport, DEFAULT_SCALE};
+#[cfg(feature = "screenshot")]
+pub use crate::screenshot::{encode_png, save_screenshot, to_scaled_rgba};
+#[cfg(feature = "sdl2")]
+pub use crate::sdl::SdlFrontend;
+pub use crate::settings::{settings_path, Settings};
+pub use crate::sixel::{advertises_sixel, to_sixel};
+#[cfg(feature = "terminal")]
+pub use crate::terminal::TerminalFrontend;
+pub use crate::theme::{parse_color, parse_palette, Rgb, Theme, ThemeError, PALETTES, PRESETS};
+pub use crate::timers::{Buzzer, NullBuzzer, StdoutBuzzer, FRAME_DURATION};
+pub use crate::title::{rom_name, window_title, TitleInfo};
+pub use crate::tone::{Tone, Waveform};
+#[cfg(feature = "wasm")]
+pub use crate::wasm::WasmChip8;
+pub use crate::window_frontend::WindowFrontend;
+
+const STACK_SIZE: usize = 16;
+
+// Random number generator of CXNN. StdRng cannot be compared, two generators are equal when they draw the same next
+// number
+#[derive(Clone)]
+pub(crate) struct Random(pub(crate) StdRng);
+
+impl Random {
+    // Seeded from the OS entropy. The wasm32 target has none, web pages seed the generator with Chip8Config::seed
+    #[cfg(not(target_arch = "wasm32"))]
+    fn new() -> Random {
+        Random(StdRng::from_entropy())
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn new() -> Random {
+        Random(StdRng::seed_from_u64(0))
+    }
+}
+
+impl PartialEq for Random {
+    fn eq(&self, other: &Random) -> bool {
+        self.0.clone().next_u64() == other.0.clone().next_u64()
+    }
+}
+
+// Clones and comparisons cover the whole machine state. The hooks are not part of it: a clone has none and they
+// are ignored by comparisons
+#[derive(Clone, PartialEq)]
+pub struct Chip8 {
+    // The chip 8 has 35 opcodes, all are 2 bytes long
+    opcode: u16,
+    // The chip 8 has 4K memory
+    memory: Memory,
+    // The chip 8 has 15 8-bit general purpose registers named V0, V1 -> VE
+    v: [u8; 16],
+    // Index register and program counter (which have values from 0x000 to 0xFFF)
+    i: u16,
+    pc: u16,
+    // Address where the program is loaded and starts
+    start_address: u16,
+    // Copy of the loaded ROM, programs modify their work RAM so it is copied again on reset
+    rom: Vec<u8>,
+    // Display memory and resolution
+    screen: Screen,
+    // SCHIP RPL user flags, saved by FX75 and restored by FX85
+    rpl_flags: [u8; 8],
+    // Delay and sound timers, with the XO-CHIP audio pattern
+    timers: Timers,
+    // The stack is used to remember the current location before a jump is performed.
+    // So anytime you perform a jump or call a subroutine, store the program counter in the stack before proceeding.
+    // The system has 16 levels of stack
+    stack: Vec<u16>,
+    // State of the HEX based keypad
+    keypad: Keypad,
+    pub draw_flag: bool,
+    // Whether instructions are executed, or what the CPU is waiting for
+    state: State,
+    // Quirk: Amiga interpreter sets VF to 1 when FX1E makes the index register overflow past 0x0FFF
+    index_overflow_quirk: bool,
+    // Wrap the index register to 12 bits (0x0FFF) after ANNN, FX1E, FX55 and FX65 like the original interpreters
+    index_mask_12bit: bool,
+    // Quirk: SCHIP leaves I unchanged after FX55/FX65, the COSMAC VIP interpreter increments it by X + 1
+    load_store_quirk: bool,
+    // Quirk: the COSMAC VIP clips sprites running off the right or bottom edge instead of wrapping them around
+    clip_sprites: bool,
+    // Quirk: the COSMAC VIP 8XY6 and 8XYE shift VY and store the result in VX, instead of shifting VX in place
+    shift_uses_vy: bool,
+    // Quirk: the COSMAC VIP 8XY1, 8XY2 and 8XY3 reset VF to 0
+    vf_reset: bool,
+    // Quirk: the COSMAC VIP waits for the vertical blank after drawing a sprite, so at most one sprite is drawn per frame
+    display_wait: bool,
+    // What to do with 0NNN opcodes calling machine code routines
+    sys_opcode_policy: SysOpcodePolicy,
+    // Quirk: the legacy SCHIP interpreter scrolls by half the distance in low resolution mode
+    lores_half_scroll: bool,
+    // Quirk: CHIP-48 and SCHIP read BNNN as BXNN, jumping to XNN plus VX instead of NNN plus V0
+    jump_uses_vx: bool,
+    // Quirk: the COSMAC VIP FX0A notes the key pressed, then waits for its release before storing it in VX
+    key_wait_on_release: bool,
+    // Pick the quirks of the variant detected when loading the program
+    auto_variant: bool,
+    // Variant whose quirks were configured or detected, None with the default quirks
+    variant: Option<Variant>,
+    // Instructions executed per second by the frontend
+    clock_hz: u32,
+    // Random number generator of CXNN, seeded from the OS unless a seed is configured
+    rng: Random,
+    // Time given to run_for that was too short to run a whole frame, kept for the next call
+    pending_time: Duration,
+    // Emulated time run by run_for per unit of host time, above 1 while fast-forwarding
+    speed: u32,
+    // Callbacks of the frontend
+    hooks: Hooks,
+}
+
+impl Default for Chip8 {
+    fn default() -> Chip8 {
+        Chip8 {
+            pc: 0x200,
+            start_address: 0x200,
+            rom: Vec::new(),
+            memory: Memory::default(),
+            v: [0; 16],
+            screen: Screen::default(),
+            rpl_flags: [0; 8],
+            stack: Vec::with_capacity(STACK_SIZE),
+            keypad: Keypad::default(),
+            opcode: 0,
+            i: 0,
+            timers: Timers::default(),
+            draw_flag: false,
+            state: State::Running,
+            index_overflow_quirk: false,
+            index_mask_12bit: false,
+            load_store_quirk: false,
+            clip_sprites: false,
+            shift_uses_vy: false,
+            vf_reset: false,
+            display_wait: false,
+            sys_opcode_policy: SysOpcodePolicy::Ignore,
+            lores_half_scroll: false,
+            jump_uses_vx: false,
+            key_wait_on_release: false,
+            auto_variant: false,
+            variant: None,
+            clock_hz: 500,
+            rng: Random::new(),
+            pending_time: Duration::ZERO,
+            speed: 1,
+            hooks: Hooks::default(),
+        }
+    }
+}
+
+impl Chip8 {
+    fn set_variant(&mut self, variant: Variant) {
+        self.set_quirks(&Chip8Config::new().variant(variant));
+    }
+
+    // Loads the ROM file into the memory, returns the number of bytes loaded
+    pub fn load_game<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, Chip8Error> {
+        let path = path.as_ref();
+        // The path is added to the message so that the error tells which file could not be read
+        let io_error = |error: io::Error| Chip8Error::Io(io::Error::new(error.kind(), format!("{} : {error}", path.display())));
+        let mut file = File::open(path).map_err(io_error)?;
+        let mut buffer = Vec::new();
+        file.read_to_end(&mut buffer).map_err(io_error)?;
+        self.load_rom(&buffer)?;
+        Ok(buffer.len())
+    }
+
+    // Programs are loaded and start at 0x200, except for ETI-660 programs (0x600) and a few others.
+    // The address has to be even and within the memory.
+    pub fn set_start_address(&mut self, address: u16) -> Result<(), Chip8Error> {
+        if !address.is_multiple_of(2) || usize::from(address) >= MEMORY_SIZE {
+            return Err(Chip8Error::InvalidStartAddress { addr: address });
+        }
+        self.start_address = address;
+        self.pc = address;
+        Ok(())
+    }
+
+    // Copies the ROM at the start address and starts executing it from there. The ROM has to fit in the memory
+    // left after the start address
+    pub fn load_rom(&mut self, rom: &[u8]) -> Result<(), Chip8Error> {
+        let start = usize::from(self.start_address);
+        let max = MEMORY_SIZE - start;
+        if rom.len() > max {
+            return Err(Chip8Error::RomTooLarge { size: rom.len(), max });
+        }
+        self.memory[start..start + rom.len()].copy_from_slice(rom);
+        self.rom = rom.to_vec();
+        self.pc = self.start_address;
+        if self.auto_variant {
+            if let Some(variant) = detect_variant(rom, self.start_address) {
+                self.set_variant(variant);
+            }
+        }
+        Ok(())
+    }
+
+    // Default machine with the words of the program stored big-endian at 0x200, ready to run. Meant for tests
+    // going through the fetch and decode of each opcode
+    pub fn with_program(program: &[u16]) -> Chip8 {
+        let rom: Vec<u8> = program.iter().flat_map(|word| word.to_be_bytes()).collect();
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(&rom).expect("the program does not fit in the memory");
+        chip8
+    }
+
+    // Restarts the loaded ROM as if it was just loaded. The quirks, the fonts and the RPL user flags are kept, the
+    // buzzer state too so that the next update switches it off
+    pub fn reset(&mut self) {
+        let sound_active = self.timers.sound_active();
+        let start = usize::from(self.start_address);
+        self.memory[start..].fill(0);
+        self.memory[start..start + self.rom.len()].copy_from_slice(&self.rom);
+        self.opcode = 0;
+        self.v = [0; 16];
+        self.i = 0;
+        self.pc = self.start_address;
+        self.screen = Screen { hires_chip8: self.screen.hires_chip8, ..Screen::default() };
+        self.timers = Timers { buzzing: self.timers.buzzing, ..Timers::default() };
+        self.stack.clear();
+        self.keypad = Keypad::default();
+        self.draw_flag = true;
+        self.state = State::Running;
+        // The sound stops with the timer
+        self.call_sound_hook(sound_active);
+    }
+
+    // Gives the state of the keys polled from the input as key events, the keys changed since the last poll get an
+    // edge
+    pub fn set_keys(&mut self, input: &mut dyn Input) {
+        for (key, pressed) in Key::ALL.into_iter().zip(input.poll()) {
+            self.key_event(key, pressed);
+        }
+    }
+
+    // Hands the visible part of the framebuffer to the display and clears the draw flag
+    pub fn render(&mut self, display: &mut dyn Display) {
+        let (width, height) = self.resolution();
+        display.draw(&self.framebuffer(), width, height);
+        self.draw_flag = false;
+    }
+
+    // Switches the buzzer on or off when the sound timer started or stopped since the last update
+    pub fn update_buzzer(&mut self, buzzer: &mut dyn Buzzer) {
+        self.timers.update_buzzer(buzzer);
+    }
+
+    // Audio pattern to play and its sample rate in Hz, or None when the sound timer is zero (silence)
+    pub fn audio_pattern(&self) -> Option<(&[u8; 16], f64)> {
+        self.timers.audio_pattern()
+    }
+
+    // Audio pattern of the program and its sample rate in Hz, whatever the sound timer, once F002 loaded one. None
+    // for the programs that only sound the beep, which the frontends then play with their own tone
+    pub fn loaded_audio_pattern(&self) -> Option<(&[u8; 16], f64)> {
+        self.timers.pattern_loaded.then(|| (&self.timers.pattern_buffer, self.timers.pattern_rate()))
+    }
+
+    // Visible part of the screen, one byte per pixel holding one bit per plane, row by row
+    pub fn framebuffer(&self) -> Vec<u8> {
+        self.screen.framebuffer()
+    }
+
+    // Same pixels as the framebuffer, without copying them
+    pub fn pixels(&self) -> impl Iterator<Item = u8> + '_ {
+        self.screen.pixels()
+    }
+
+    // Visible part of the screen as lines of █ for the lit pixels and spaces for the others, to look at the screen of
+    // a headless run
+    pub fn framebuffer_ascii(&self) -> String {
+        self.framebuffer_ascii_with('█', ' ')
+    }
+
+    // Same with other characters for the lit and unlit pixels, like # and . for text files
+    pub fn framebuffer_ascii_with(&self, on: char, off: char) -> String {
+        let (width, _) = self.resolution();
+        display::to_ascii_string(&self.framebuffer(), width, on, off)
+    }
+
+    // Visible part of the screen as lines of braille characters, each one showing 2x4 pixels
+    pub fn to_braille_string(&self) -> String {
+        let (width, _) = self.resolution();
+        display::to_braille_string(&self.framebuffer(), width)
+    }
+
+    // Whether the CPU is running, waiting or stopped, for frontends to show a pause or stop their loop
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    // 64-bit FNV-1a hash of the registers, I, the PC and the visible framebuffer. The algorithm is fixed so that the
+    // digest of a run can be compared across builds and releases
+    pub fn digest(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+        let registers = self.v.iter().copied().chain(self.i.to_be_bytes()).chain(self.pc.to_be_bytes());
+        registers.chain(self.pixels()).fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME))
+    }
+
+    // Register VX, for X from 0x0 to 0xF
+    pub fn register(&self, idx: usize) -> u8 {
+        self.v[idx]
+    }
+
+    pub fn pc(&self) -> u16 {
+        self.pc
+    }
+
+    // Index register I
+    pub fn index(&self) -> u16 {
+        self.i
+    }
+
+    pub fn delay_timer(&self) -> u8 {
+        self.timers.delay_timer
+    }
+
+    pub fn sound_timer(&self) -> u8 {
+        self.timers.sound_timer
+    }
+
+    // The buzzer sounds for as long as the sound timer is above zero. Frontends start their tone when it becomes
+    // true and stop it when it becomes false, the core never plays or prints anything
+    pub fn sound_active(&self) -> bool {
+        self.timers.sound_active()
+    }
+
+    // Return addresses of the subroutines being run, the innermost last
+    pub fn stack(&self) -> &[u16] {
+        &self.stack
+    }
+
+    // Whole memory, fonts included, for debuggers and memory views
+    pub fn memory(&self) -> &[u8] {
+        &self.memory
+    }
+
+    // The setters change the state between cycles, for debuggers editing a paused machine
+    pub fn set_register(&mut self, idx: usize, value: u8) {
+        self.v[idx] = value;
+    }
+
+    pub fn set_pc(&mut self, pc: u16) {
+        self.pc = pc;
+    }
+
+    pub fn set_delay_timer(&mut self, value: u8) {
+        self.timers.delay_timer = value;
+    }
+
+    // Also switches the sound on or off, the buzzer follows with the next update_buzzer
+    pub fn set_sound_timer(&mut self, value: u8) {
+        self.timers.sound_timer = value;
+    }
+
+    // Color of a pixel, made of one bit per plane (0 to 3)
+    pub fn pixel(&self, x: usize, y: usize) -> u8 {
+        self.screen.pixel(x, y)
+    }
+
+    // Width and height of the screen in the current mode
+    pub fn resolution(&self) -> (usize, usize) {
+        self.screen.resolution()
+    }
+
+    pub fn width(&self) -> usize {
+        self.resolution().0
+    }
+
+    pub fn height(&self) -> usize {
+        self.resolution().1
+    }
+
+    // Variant whose quirks were configured or detected when loading the program, for titles and status lines
+    pub fn variant(&self) -> Option<Variant> {
+        self.variant
+    }
+
+    // Instructions executed per second by the frontend
+    pub fn clock_hz(&self) -> u32 {
+        self.clock_hz
+    }
+
+    // Changes the speed of a running machine, for debuggers and speed controls
+    pub fn set_clock_hz(&mut self, hz: u32) {
+        self.clock_hz = hz;
+    }
+
+    // Emulated frames run by run_for per frame of host time, 1 at normal speed
+    pub fn speed(&self) -> u32 {
+        self.speed
+    }
+
+    // Fast-forwards run_for by a whole factor, at least 1. The instructions and the timers of each emulated frame are
+    // unchanged, the frames come that much more often
+    pub fn set_speed(&mut self, factor: u32) {
+        self.speed = factor.max(1);
+    }
+
+    // Instructions executed by run_frame, at least one even with a clock speed below 60 Hz
+    pub fn instructions_per_frame(&self) -> usize {
+        (self.clock_hz / 60).max(1) as usize
+    }
+}
+
+// State of the CPU without the memory and the framebuffer, which are too large to be read in a dump.
+// Use dump_memory for the memory
+impl fmt::Debug for Chip8 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Chip8")
+            .field("pc", &format_args!("0x{:04X}", self.pc))
+            .field("i", &format_args!("0x{:04X}", self.i))
+            .field("opcode", &format_args!("0x{:04X}", self.opcode))
+            .field("v", &format_args!("{:02X?}", self.v))
+            .field("delay_timer", &self.timers.delay_timer)
+            .field("sound_timer", &self.timers.sound_timer)
+            .field("stack", &format_args!("{:04X?}", self.stack))
+            .field("keys", &format_args!("0b{:016b}", self.keypad.bitmask()))
+            .field("draw_flag", &self.draw_flag)
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+impl fmt::Display for Chip8 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PC=0x{:04X} I=0x{:04X} opcode=0x{:04X}", self.pc, self.i, self.opcode)?;
+        if self.draw_flag {
+            write!(f, " (screen not rendered yet)")?;
+        }
+        writeln!(f)?;
+        for (x, vx) in self.v.iter().enumerate() {
+            let separator = if x % 8 == 7 { '\n' } else { ' ' };
+            write!(f, "V{x:02X}={vx:02X}{separator}")?;
+        }
+        let stack: Vec<String> = self.stack.iter().map(|address| format!("0x{address:04X}")).collect();
+        writeln!(f, "DT={:02X} ST={:02X} stack=[{}]", self.timers.delay_timer, self.timers.sound_timer, stack.join(", "))?;
+        write!(f, "keys=0b{:016b}", self.keypad.bitmask())
+    }
+}
+
+// Platforms running CHIP-8 programs, each with its own set of quirks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Variant {
+    // COSMAC VIP interpreter
+    Chip8,
+    // HP48 calculators interpreter
+    Chip48,
+    // SCHIP 1.1
+    SuperChip,
+    // Octo's XO-CHIP
+    XoChip,
+}
+
+// Names of the platforms as they are usually written
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Variant::Chip8 => "CHIP-8",
+            Variant::Chip48 => "CHIP-48",
+            Variant::SuperChip => "SCHIP",
+            Variant::XoChip => "XO-CHIP",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SysOpcodePolicy {
+    // Skip the opcode like modern interpreters do
+    Ignore,
+    // Trap with a Chip8Error::SysOpcode
+    Error,
+}
+
+// Lifecycle of the CPU. The timers keep counting down whatever the state is
+#[derive(Debug, Clone, PartialEq)]
+pub enum State {
+    // Instructions are executed
+    Running,
+    // FX0A waits for a key press, stored in the register dest. Execution resumes once a key is pressed
+    WaitingForKey { dest: u8 },
+    // DXYN with the display wait quirk stalls the CPU until the next timer tick (vblank)
+    WaitingForVblank,
+    // The program exited with 00FD, nothing is executed anymore
+    Halted,
+    // An instruction failed, each cycle returns the error again until the machine is reset
+    Faulted(Chip8Error),
+}
+
+// What happened during a frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameInfo {
+    // The screen changed and has to be rendered
+    pub drew: bool,
+    // The sound timer is above zero at the end of the frame
+    pub sound_active: bool,
+    // The program exited with 00FD
+    pub halted: bool,
+    // Instructions executed, the cycles spent waiting are not counted
+    pub instructions: usize,
+    // 60 Hz frames run, the timers ticked once for each
+    pub frames: u32,
+}
+
+// What happened during run_cycles
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunSummary {
+    // Cycles that executed an instruction, the ones spent waiting or halted are not counted
+    pub instructions: usize,
+    // Cycles that changed the screen
+    pub draws: usize,
+    // Chip8::digest() at the end of the run
+    pub digest: u64,
+}
+
+// What happened during a cycle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CycleInfo {
+    // Last opcode fetched, the one awaiting a key or the vblank while the CPU is waiting
+    pub opcode: u16,
+    pub pc_before: u16,
+    // The screen changed and has to be rendered
+    pub drew: bool,
+    // FX0A is waiting for a key press
+    pub waiting_for_key: bool,
+    // The program exited with 00FD
+    pub halted: bool,
+    // FX18 switched the sound on or off, the timer ticks switch it off too
+    pub sound_changed: bool,
+}
+
+#[derive(Debug)]
+pub enum Chip8Error {
+    Io(io::Error),
+    RomTooLarge { size: usize, max: usize },
+    UnknownOpcode { opcode: u16, pc: u16 },
+    StackOverflow { pc: u16 },
+    StackUnderflow { pc: u16 },
+    MemoryOutOfBounds { addr: u16, pc: u16 },
+    SysOpcode { addr: u16, pc: u16 },
+    InvalidStartAddress { addr: u16 },
+    FontOverlapsProgram { start_address: u16 },
+    InvalidKey { key: u8 },
+    ProtectedWrite { addr: u16, pc: u16 },
+}
+
+impl fmt::Display for Chip8Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Chip8Error::Io(error) => write!(f, "Cannot read the ROM : {error}"),
+            Chip8Error::RomTooLarge { size, max } => write!(f, "ROM too large : {size} bytes, at most {max} bytes fit in the memory"),
+            Chip8Error::UnknownOpcode { opcode, pc } => write!(f, "Unknown opcode read : 0x{opcode:04X} at 0x{pc:04X}"),
+            Chip8Error::StackOverflow { pc } => write!(f, "Stack overflow : more than {STACK_SIZE} nested calls at 0x{pc:04X}"),
+            Chip8Error::StackUnderflow { pc } => write!(f, "Stack underflow : return without a call at 0x{pc:04X}"),
+            Chip8Error::MemoryOutOfBounds { addr, pc } => write!(f, "Memory access out of bounds : 0x{addr:04X} at 0x{pc:04X}"),
+            Chip8Error::SysOpcode { addr, pc } => write!(f, "Machine code routine call to 0x{addr:03X} at 0x{pc:04X}"),
+            Chip8Error::InvalidStartAddress { addr } => write!(f, "Invalid start address : 0x{addr:04X}"),
+            Chip8Error::FontOverlapsProgram { start_address } => write!(f, "Font overlaps the program starting at 0x{start_address:04X}"),
+            Chip8Error::InvalidKey { key } => write!(f, "Invalid key : 0x{key:02X}, keys go from 0x0 to 0xF"),
+            Chip8Error::ProtectedWrite { addr, pc } => write!(f, "Write to the interpreter area : 0x{addr:04X} at 0x{pc:04X}"),
+        }
+    }
+}
+
+// The I/O errors cannot be cloned, the copy has the same kind and message
+impl Clone for Chip8Error {
+    fn clone(&self) -> Chip8Error {
+        match self {
+            Chip8Error::Io(error) => Chip8Error::Io(io::Error::new(error.kind(), error.to_string())),
+            Chip8Error::RomTooLarge { size, max } => Chip8Error::RomTooLarge { size: *size, max: *max },
+            Chip8Error::UnknownOpcode { opcode, pc } => Chip8Error::UnknownOpcode { opcode: *opcode, pc: *pc },
+            Chip8Error::StackOverflow { pc } => Chip8Error::StackOverflow { pc: *pc },
+            Chip8Error::StackUnderflow { pc } => Chip8Error::StackUnderflow { pc: *pc },
+            Chip8Error::MemoryOutOfBounds { addr, pc } => Chip8Error::MemoryOutOfBounds { addr: *addr, pc: *pc },
+            Chip8Error::SysOpcode { addr, pc } => Chip8Error::SysOpcode { addr: *addr, pc: *pc },
+            Chip8Error::InvalidStartAddress { addr } => Chip8Error::InvalidStartAddress { addr: *addr },
+            Chip8Error::FontOverlapsProgram { start_address } => Chip8Error::FontOverlapsProgram { start_address: *start_address },
+            Chip8Error::InvalidKey { key } => Chip8Error::InvalidKey { key: *key },
+            Chip8Error::ProtectedWrite { addr, pc } => Chip8Error::ProtectedWrite { addr: *addr, pc: *pc },
+        }
+    }
+}
+
+// I/O errors are equal when they have the same kind and message
+impl PartialEq for Chip8Error {
+    fn eq(&self, other: &Chip8Error) -> bool {
+        match (self, other) {
+            (Chip8Error::Io(a), Chip8Error::Io(b)) => a.kind() == b.kind() && a.to_string() == b.to_string(),
+            (Chip8Error::RomTooLarge { size: a, max: b }, Chip8Error::RomTooLarge { size: c, max: d }) => (a, b) == (c, d),
+            (Chip8Error::UnknownOpcode { opcode: a, pc: b }, Chip8Error::UnknownOpcode { opcode: c, pc: d }) => (a, b) == (c, d),
+            (Chip8Error::StackOverflow { pc: a }, Chip8Error::StackOverflow { pc: b }) => a == b,
+            (Chip8Error::StackUnderflow { pc: a }, Chip8Error::StackUnderflow { pc: b }) => a == b,
+            (Chip8Error::MemoryOutOfBounds { addr: a, pc: b }, Chip8Error::MemoryOutOfBounds { addr: c, pc: d }) => (a, b) == (c, d),
+            (Chip8Error::SysOpcode { addr: a, pc: b }, Chip8Error::SysOpcode { addr: c, pc: d }) => (a, b) == (c, d),
+            (Chip8Error::InvalidStartAddress { addr: a }, Chip8Error::InvalidStartAddress { addr: b }) => a == b,
+            (Chip8Error::FontOverlapsProgram { start_address: a }, Chip8Error::FontOverlapsProgram { start_address: b }) => a == b,
+            (Chip8Error::InvalidKey { key: a }, Chip8Error::InvalidKey { key: b }) => a == b,
+            (Chip8Error::ProtectedWrite { addr: a, pc: b }, Chip8Error::ProtectedWrite { addr: c, pc: d }) => (a, b) == (c, d),
+            _ => false,
+        }
+    }
+}
+
+impl Error for Chip8Error {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Chip8Error::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Chip8Error {
+    fn from(error: io::Error) -> Chip8Error {
+        Chip8Error::Io(error)
+    }
+}
+
+// Guesses the variant a program loaded at start was written for from the opcodes only supported by SCHIP or XO-CHIP.
+// Only the instructions reachable from the start are read, following the jumps, the calls and both ways of the skips,
+// since sprites like F0 00 or D0 00 read as such opcodes. The guess is conservative: a program using only CHIP-8
+// opcodes runs on every variant, and a reachable word that is not an instruction, or a machine code routine, means
+// that the walk went into data or that the program is for the COSMAC VIP. No variant is returned for either
+pub fn detect_variant(program: &[u8], start: u16) -> Option<Variant> {
+    let word = |offset: usize| program.get(offset..offset + 2).map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]));
+    // F000 NNNN takes 4 bytes, the skips skip all of it
+    let length = |offset: usize| if word(offset) == Some(0xF000) { 4 } else { 2 };
+    let offset_of = |address: u16| usize::from(address).checked_sub(usize::from(start));
+    let mut visited = vec![false; program.len()];
+    let mut pending = vec![0];
+    let (mut superchip, mut xochip) = (false, false);
+    while let Some(offset) = pending.pop() {
+        // Past the end of the program
+        let Some(opcode) = word(offset) else {
+            continue;
+        };
+        if visited[offset] {
+            continue;
+        }
+        visited[offset] = true;
+        match opcode {
+            0xF000 | 0xF002 => xochip = true,
+            _ if opcode & 0xFCFF == 0xF001 => xochip = true, // FN01
+            _ if opcode & 0xF00E == 0x5002 => xochip = true, // 5XY2 and 5XY3
+            _ if opcode & 0xF0FF == 0xF03A => xochip = true, // FX3A
+            0x00FB..=0x00FF => superchip = true,
+            _ if opcode & 0xFFF0 == 0x00C0 => superchip = true, // 00CN
+            _ if opcode & 0xF00F == 0xD000 => superchip = true, // DXY0
+            _ if matches!(opcode & 0xF0FF, 0xF030 | 0xF075 | 0xF085) => superchip = true,
+            _ => {}
+        }
+        let next = offset + length(offset);
+        match decode(opcode) {
+            Err(_) | Ok(Instruction::Sys(_)) => return None,
+            Ok(Instruction::Jump(address)) => pending.extend(offset_of(address)),
+            Ok(Instruction::Call(address)) => pending.extend(offset_of(address).into_iter().chain([next])),
+            // The target of BNNN depends on V0
+            Ok(Instruction::Return | Instruction::Exit | Instruction::JumpOffset { .. }) => {}
+            Ok(
+                Instruction::SkipEqImm { .. }
+                | Instruction::SkipNeImm { .. }
+                | Instruction::SkipEqReg { .. }
+                | Instruction::SkipNeReg { .. }
+                | Instruction::SkipKeyPressed { .. }
+                | Instruction::SkipKeyNotPressed { .. },
+            ) => pending.extend([next, next + length(next)]),
+            Ok(_) => pending.push(next),
+        }
+    }
+    if xochip {
+        Some(Variant::XoChip)
+    } else if superchip {
+        Some(Variant::SuperChip)
+    } else {
+        None
+    }
+}
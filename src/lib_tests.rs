@@ -0,0 +1,421 @@
+#[cfg(test)]
+mod lib_tests {
+    use std::env;
+    use std::fs;
+    use std::io::ErrorKind;
+    use std::path::PathBuf;
+    use crate::{detect_variant, Chip8, Chip8Config, Chip8Error, Key, State, Variant};
+
+    #[test]
+    fn detect_variant_returns_none_for_a_chip8_program() {
+        let program = [0x00, 0xE0, 0x60, 0x05, 0xA2, 0x0A, 0xD0, 0x15, 0x12, 0x00];
+
+        assert_eq!(detect_variant(&program, 0x200), None);
+    }
+
+    #[test]
+    fn detect_variant_detects_superchip_opcodes() {
+        assert_eq!(detect_variant(&[0x00, 0xFF, 0x12, 0x00], 0x200), Some(Variant::SuperChip));
+        assert_eq!(detect_variant(&[0x60, 0x05, 0x00, 0xC4], 0x200), Some(Variant::SuperChip));
+        assert_eq!(detect_variant(&[0xA3, 0x00, 0xD1, 0x20], 0x200), Some(Variant::SuperChip));
+        assert_eq!(detect_variant(&[0xF1, 0x30, 0x12, 0x00], 0x200), Some(Variant::SuperChip));
+    }
+
+    #[test]
+    fn detect_variant_detects_xochip_opcodes() {
+        assert_eq!(detect_variant(&[0xF0, 0x00, 0x12, 0x34], 0x200), Some(Variant::XoChip));
+        assert_eq!(detect_variant(&[0x00, 0xFF, 0xF2, 0x01], 0x200), Some(Variant::XoChip));
+        assert_eq!(detect_variant(&[0x51, 0x42, 0x12, 0x00], 0x200), Some(Variant::XoChip));
+    }
+
+    #[test]
+    fn detect_variant_returns_none_when_the_walk_reaches_a_word_that_is_not_an_instruction() {
+        assert_eq!(detect_variant(&[0x60, 0x00, 0xFF, 0x12], 0x200), None);
+    }
+
+    #[test]
+    fn detect_variant_ignores_the_sprites_of_a_chip8_program() {
+        let program = [
+            0xA2, 0x08, // I = sprite
+            0xD0, 0x15, // draws it
+            0x00, 0xE0, // clears the screen
+            0x12, 0x02, // draws it again
+            0xF0, 0x00, 0xF0, 0x01, 0xD0, 0x00, 0x00, 0xFF, // sprite bytes reading as XO-CHIP and SCHIP opcodes
+        ];
+
+        assert_eq!(detect_variant(&program, 0x200), None);
+    }
+
+    #[test]
+    fn detect_variant_follows_the_calls_and_both_ways_of_the_skips() {
+        // Calls 0x206, which skips to 00FF or returns
+        let called = [0x22, 0x06, 0x12, 0x02, 0x00, 0x00, 0x30, 0x01, 0x00, 0xEE, 0x00, 0xFF, 0x00, 0xEE];
+        assert_eq!(detect_variant(&called, 0x200), Some(Variant::SuperChip));
+        // Behind a jump, 5XY2 is never reached
+        assert_eq!(detect_variant(&[0x12, 0x00, 0x51, 0x42], 0x200), None);
+    }
+
+    #[test]
+    fn detect_variant_reads_the_jumps_from_the_start_address() {
+        assert_eq!(detect_variant(&[0x16, 0x04, 0x00, 0x00, 0x00, 0xFF], 0x600), Some(Variant::SuperChip));
+    }
+
+    #[test]
+    fn detect_variant_returns_none_for_a_machine_code_routine() {
+        assert_eq!(detect_variant(&[0x00, 0xFF, 0x03, 0x00], 0x200), None);
+    }
+
+    #[test]
+    fn load_rom_picks_the_detected_variant_with_auto_variant() {
+        let mut chip8 = Chip8Config::new().auto_variant(true).build().unwrap();
+
+        chip8.load_rom(&[0x00, 0xFF, 0x12, 0x00]).unwrap();
+
+        assert_eq!(chip8.memory[0x200..0x204], [0x00, 0xFF, 0x12, 0x00]);
+        assert!(chip8.load_store_quirk);
+        assert!(chip8.jump_uses_vx);
+        assert_eq!(chip8.variant(), Some(Variant::SuperChip));
+    }
+
+    #[test]
+    fn load_rom_keeps_the_quirks_without_auto_variant() {
+        let mut chip8 = Chip8::default();
+
+        chip8.load_rom(&[0x00, 0xFF, 0x12, 0x00]).unwrap();
+
+        assert!(!chip8.load_store_quirk);
+        assert!(!chip8.jump_uses_vx);
+    }
+
+    #[test]
+    fn load_rom_accepts_an_empty_rom() {
+        let mut chip8 = Chip8::default();
+
+        assert!(chip8.load_rom(&[]).is_ok());
+        assert_eq!(chip8.memory[0x200..], [0; 3584]);
+    }
+
+    #[test]
+    fn load_rom_accepts_a_rom_filling_the_memory() {
+        let mut chip8 = Chip8::default();
+        let rom = [0xAB; 3584];
+
+        assert!(chip8.load_rom(&rom).is_ok());
+        assert_eq!(chip8.memory[0x200..], rom);
+    }
+
+    #[test]
+    fn load_rom_rejects_a_rom_one_byte_too_large() {
+        let mut chip8 = Chip8::default();
+
+        let result = chip8.load_rom(&[0xAB; 3585]);
+
+        assert!(matches!(result, Err(Chip8Error::RomTooLarge { size: 3585, max: 3584 })));
+        assert_eq!(chip8.memory[0x200..], [0; 3584]);
+    }
+
+    #[test]
+    fn load_rom_resets_the_program_counter() {
+        let mut chip8 = Chip8 { pc: 0x2A0, ..Chip8::default() };
+
+        chip8.load_rom(&[0x60, 0x01]).unwrap();
+
+        assert_eq!(chip8.pc(), 0x200);
+    }
+
+    #[test]
+    fn set_start_address_loads_and_starts_the_program_at_the_address() {
+        let mut chip8 = Chip8::default();
+
+        chip8.set_start_address(0x600).unwrap();
+        chip8.load_rom(&[0xA2, 0xF0]).unwrap();
+        chip8.emulate_cycle().unwrap();
+
+        assert_eq!(chip8.memory[0x200..0x202], [0x00, 0x00]);
+        assert_eq!(chip8.opcode, 0xA2F0);
+        assert_eq!(chip8.index(), 0x2F0);
+        assert_eq!(chip8.pc(), 0x602);
+    }
+
+    #[test]
+    fn set_start_address_rejects_an_address_outside_of_the_memory() {
+        let mut chip8 = Chip8::default();
+
+        let result = chip8.set_start_address(0xFFFE);
+
+        assert!(matches!(result, Err(Chip8Error::InvalidStartAddress { addr: 0xFFFE })));
+        assert_eq!(chip8.start_address, 0x200);
+        assert_eq!(chip8.pc(), 0x200);
+    }
+
+    #[test]
+    fn set_start_address_rejects_an_odd_address() {
+        let mut chip8 = Chip8::default();
+
+        let result = chip8.set_start_address(0x601);
+
+        assert!(matches!(result, Err(Chip8Error::InvalidStartAddress { addr: 0x601 })));
+    }
+
+    // Writes the ROM in the temp directory, the name has to be unique as tests run in parallel
+    fn temp_rom(name: &str, rom: &[u8]) -> PathBuf {
+        let path = env::temp_dir().join(format!("chip8-{}-{name}.ch8", std::process::id()));
+        fs::write(&path, rom).unwrap();
+        path
+    }
+
+    #[test]
+    fn load_game_loads_the_file_at_the_start_address() {
+        let path = temp_rom("load_game", &[0x60, 0x01, 0x00, 0xFD, 0xAB]);
+        let mut chip8 = Chip8::default();
+
+        let result = chip8.load_game(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Ok(5)));
+        assert_eq!(chip8.memory[0x200..0x206], [0x60, 0x01, 0x00, 0xFD, 0xAB, 0x00]);
+    }
+
+    #[test]
+    fn load_game_loads_every_byte_of_a_rom_larger_than_pong() {
+        let rom: Vec<u8> = (0..1024).map(|offset| (offset * 7 % 251) as u8).collect();
+        let path = temp_rom("load_game_1k", &rom);
+        let mut chip8 = Chip8::default();
+
+        let result = chip8.load_game(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Ok(1024)));
+        assert_eq!(chip8.memory[0x200..0x600], rom[..]);
+        assert_eq!(chip8.memory[0x600], 0);
+    }
+
+    #[test]
+    fn load_game_rejects_a_rom_larger_than_the_memory() {
+        let path = temp_rom("load_game_4k", &[0xAB; 4096]);
+        let mut chip8 = Chip8::default();
+
+        let result = chip8.load_game(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(Chip8Error::RomTooLarge { size: 4096, max: 3584 })));
+        assert_eq!(result.unwrap_err().to_string(), "ROM too large : 4096 bytes, at most 3584 bytes fit in the memory");
+    }
+
+    #[test]
+    fn load_game_returns_an_error_with_the_path_when_the_file_is_missing() {
+        let path = env::temp_dir().join("chip8-missing.ch8");
+        let mut chip8 = Chip8::default();
+
+        let result = chip8.load_game(&path);
+
+        assert!(matches!(result, Err(Chip8Error::Io(ref error)) if error.kind() == ErrorKind::NotFound));
+        assert!(result.unwrap_err().to_string().contains(&path.display().to_string()));
+    }
+
+    #[test]
+    fn reset_restarts_the_program_from_a_freshly_loaded_state() {
+        let program = [
+            0x60, 0x2A, // V0 = 0x2A
+            0xA2, 0x10, // I = 0x210
+            0xF0, 0x55, // store V0 at 0x210, overwriting the program data
+            0xF0, 0x18, // sound timer = V0
+            0x22, 0x0E, // call 0x20E
+            0x00, 0x00,
+            0x00, 0x00,
+            0xD0, 0x05, // draw the sprite at 0x210
+            0x0F, 0xF0, // sprite data
+            0xF0,
+        ];
+        let mut fresh = Chip8::default();
+        fresh.load_rom(&program).unwrap();
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(&program).unwrap();
+        chip8.press(Key::K3);
+        for _ in 0..6 {
+            assert!(!chip8.emulate_cycle().unwrap().halted);
+        }
+        assert_eq!(chip8.memory[0x210], 0x2A);
+        assert_eq!(chip8.stack, [0x208]);
+
+        chip8.reset();
+
+        assert_eq!(chip8.memory[..], fresh.memory[..]);
+        assert_eq!(chip8.v, fresh.v);
+        assert_eq!(chip8.index(), fresh.index());
+        assert_eq!(chip8.pc(), 0x200);
+        assert!(chip8.stack.is_empty());
+        assert_eq!((chip8.delay_timer(), chip8.sound_timer()), (0, 0));
+        assert_eq!(chip8.framebuffer(), fresh.framebuffer());
+        assert!(Key::ALL.iter().all(|&key| !chip8.is_pressed(key)));
+        assert_eq!(chip8.state(), &State::Running);
+    }
+
+    #[test]
+    fn reset_leaves_the_wait_for_a_key() {
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(&[0xF1, 0x0A, 0x00, 0xFD]).unwrap();
+        chip8.emulate_cycle().unwrap();
+        assert_eq!(chip8.state(), &State::WaitingForKey { dest: 1 });
+
+        chip8.reset();
+
+        assert_eq!(chip8.state(), &State::Running);
+    }
+
+    // Runs the program until it fails
+    fn run_until_error(program: &[u8]) -> Chip8Error {
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(program).unwrap();
+        loop {
+            if let Err(error) = chip8.emulate_cycle() {
+                return error;
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_opcode_error_shows_the_opcode_and_the_pc() {
+        let error = run_until_error(&[0x60, 0x00, 0x80, 0x0F]);
+
+        assert!(matches!(error, Chip8Error::UnknownOpcode { opcode: 0x800F, pc: 0x202 }));
+        assert_eq!(error.to_string(), "Unknown opcode read : 0x800F at 0x0202");
+    }
+
+    #[test]
+    fn stack_overflow_error_shows_the_pc() {
+        let error = run_until_error(&[0x60, 0x00, 0x22, 0x02]); // calls itself forever
+
+        assert!(matches!(error, Chip8Error::StackOverflow { pc: 0x202 }));
+        assert_eq!(error.to_string(), "Stack overflow : more than 16 nested calls at 0x0202");
+    }
+
+    #[test]
+    fn stack_underflow_error_shows_the_pc() {
+        let error = run_until_error(&[0x60, 0x00, 0x00, 0xEE]);
+
+        assert!(matches!(error, Chip8Error::StackUnderflow { pc: 0x202 }));
+        assert_eq!(error.to_string(), "Stack underflow : return without a call at 0x0202");
+    }
+
+    #[test]
+    fn memory_out_of_bounds_error_shows_the_address_and_the_pc() {
+        let error = run_until_error(&[0xAF, 0xFE, 0xF3, 0x65]); // I = 0xFFE, load V0 to V3
+
+        assert!(matches!(error, Chip8Error::MemoryOutOfBounds { addr: 0x1000, pc: 0x202 }));
+        assert_eq!(error.to_string(), "Memory access out of bounds : 0x1000 at 0x0202");
+    }
+
+    #[test]
+    fn io_error_is_the_source_of_the_error() {
+        let error = Chip8Error::from(std::io::Error::new(ErrorKind::NotFound, "pong.rom : not found"));
+
+        assert_eq!(error.to_string(), "Cannot read the ROM : pong.rom : not found");
+        assert!(std::error::Error::source(&error).is_some());
+    }
+
+    fn known_state() -> Chip8 {
+        let mut chip8 = Chip8 { i: 0x0ABC, opcode: 0x2300, draw_flag: true, ..Chip8::default() };
+        chip8.v[0x0] = 0x12;
+        chip8.v[0xF] = 0x01;
+        chip8.stack.push(0x0204);
+        chip8.timers.delay_timer = 0x3C;
+        chip8.press(Key::K0);
+        chip8.press(Key::KA);
+        chip8
+    }
+
+    #[test]
+    fn display_dumps_the_cpu_state() {
+        let dump = known_state().to_string();
+
+        assert_eq!(dump, "PC=0x0200 I=0x0ABC opcode=0x2300 (screen not rendered yet)\n\
+            V00=12 V01=00 V02=00 V03=00 V04=00 V05=00 V06=00 V07=00\n\
+            V08=00 V09=00 V0A=00 V0B=00 V0C=00 V0D=00 V0E=00 V0F=01\n\
+            DT=3C ST=00 stack=[0x0204]\n\
+            keys=0b0000010000000001");
+    }
+
+    #[test]
+    fn debug_shows_the_registers_in_hex_without_the_memory() {
+        let dump = format!("{:?}", known_state());
+
+        assert!(dump.contains("pc: 0x0200"));
+        assert!(dump.contains("i: 0x0ABC"));
+        assert!(dump.contains("v: [12, 00, 00, 00, 00, 00, 00, 00, 00, 00, 00, 00, 00, 00, 00, 01]"));
+        assert!(dump.contains("stack: [0204]"));
+        assert!(dump.contains("keys: 0b0000010000000001"));
+        assert!(dump.contains("draw_flag: true"));
+        assert!(!dump.contains("memory"));
+    }
+
+    // Draws random sprites at random positions forever
+    fn random_program() -> Vec<u8> {
+        vec![0xC0, 0x3F, 0xC1, 0x1F, 0xC2, 0x0F, 0xF2, 0x29, 0xD0, 0x15, 0x12, 0x00]
+    }
+
+    #[test]
+    fn clone_runs_like_the_original() {
+        let mut chip8 = Chip8Config::new().seed(42).build().unwrap();
+        chip8.load_rom(&random_program()).unwrap();
+        for _ in 0..30 {
+            chip8.emulate_cycle().unwrap();
+        }
+
+        let mut copy = chip8.clone();
+        assert!(copy == chip8);
+        for _ in 0..300 {
+            chip8.emulate_cycle().unwrap();
+            copy.emulate_cycle().unwrap();
+        }
+
+        assert!(copy == chip8);
+    }
+
+    #[test]
+    fn machines_with_a_different_pixel_are_not_equal() {
+        let chip8 = Chip8Config::new().seed(42).build().unwrap();
+        let mut copy = chip8.clone();
+        copy.screen.set_pixel(0, 0, 1);
+
+        assert!(copy != chip8);
+    }
+
+    #[test]
+    fn machines_with_differently_seeded_generators_are_not_equal() {
+        let chip8 = Chip8Config::new().seed(42).build().unwrap();
+        let other = Chip8Config::new().seed(43).build().unwrap();
+
+        assert!(chip8 != other);
+    }
+
+    #[test]
+    fn setters_change_the_state_read_by_the_accessors() {
+        let mut chip8 = Chip8::default();
+
+        chip8.set_register(0xA, 0x42);
+        chip8.set_pc(0x0300);
+        chip8.set_index(0x0456);
+        chip8.set_delay_timer(10);
+        chip8.set_sound_timer(5);
+
+        assert_eq!(chip8.register(0xA), 0x42);
+        assert_eq!(chip8.pc(), 0x0300);
+        assert_eq!(chip8.index(), 0x0456);
+        assert_eq!(chip8.delay_timer(), 10);
+        assert_eq!(chip8.sound_timer(), 5);
+        assert!(chip8.sound_active());
+    }
+
+    #[test]
+    fn stack_and_memory_views() {
+        // Calls the subroutine at 0x206, which loops
+        let mut chip8 = Chip8::with_program(&[0x2206, 0x0000, 0x0000, 0x1206]);
+
+        chip8.step_n(2).unwrap();
+
+        assert_eq!(chip8.stack(), &[0x0200]);
+        assert_eq!(chip8.memory().len(), 4096);
+        assert_eq!(chip8.memory()[0x200..0x202], [0x22, 0x06]);
+    }
+}
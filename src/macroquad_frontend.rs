@@ -0,0 +1,404 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./macroquad_frontend_tests.rs"]
+mod macroquad_frontend_tests;
+
+// macroquad frontend, enabled with the macroquad feature: a window showing the screen, the keyboard as the keypad and
+// a tone for the buzzer. macroquad owns the main loop, see the chip8-macroquad binary
+
+use std::mem;
+use macroquad::audio::{load_sound_from_bytes, play_sound, set_sound_volume, stop_sound, PlaySoundParams, Sound};
+use macroquad::color::{Color, WHITE};
+use macroquad::input::{is_key_down, is_key_pressed, KeyCode};
+use macroquad::math::vec2;
+use macroquad::shapes::draw_rectangle;
+use macroquad::time::get_time;
+use macroquad::texture::{draw_texture_ex, DrawTextureParams, FilterMode, Texture2D};
+use macroquad::miniquad::window::set_window_position;
+use macroquad::window::{clear_background, request_new_screen_size, screen_height, screen_width, set_fullscreen};
+use crate::{
+    control, crt_image, gain, letter_free, letterbox, overlay_scale, rotate, Buzzer, Control, ControlKey, Crt, Display,
+    Input, Keymap, Phosphor, PhysicalKey, Rotation, TextBitmap, Theme, Viewport, Waveform, BEEP_FREQUENCY,
+    FRAME_DURATION, MAX_VOLUME,
+};
+
+// Key code of macroquad for each key of the emulator controls
+fn control_key_code(key: ControlKey) -> KeyCode {
+    match key {
+        ControlKey::Escape => KeyCode::Escape,
+        ControlKey::Backspace => KeyCode::Backspace,
+        ControlKey::Letter(letter) => key_code(letter),
+    }
+}
+
+// Key code of macroquad for each keyboard key of the keymap
+fn key_code(physical_key: PhysicalKey) -> KeyCode {
+    match physical_key {
+        PhysicalKey::Key0 => KeyCode::Key0,
+        PhysicalKey::Key1 => KeyCode::Key1,
+        PhysicalKey::Key2 => KeyCode::Key2,
+        PhysicalKey::Key3 => KeyCode::Key3,
+        PhysicalKey::Key4 => KeyCode::Key4,
+        PhysicalKey::Key5 => KeyCode::Key5,
+        PhysicalKey::Key6 => KeyCode::Key6,
+        PhysicalKey::Key7 => KeyCode::Key7,
+        PhysicalKey::Key8 => KeyCode::Key8,
+        PhysicalKey::Key9 => KeyCode::Key9,
+        PhysicalKey::A => KeyCode::A,
+        PhysicalKey::B => KeyCode::B,
+        PhysicalKey::C => KeyCode::C,
+        PhysicalKey::D => KeyCode::D,
+        PhysicalKey::E => KeyCode::E,
+        PhysicalKey::F => KeyCode::F,
+        PhysicalKey::G => KeyCode::G,
+        PhysicalKey::H => KeyCode::H,
+        PhysicalKey::I => KeyCode::I,
+        PhysicalKey::J => KeyCode::J,
+        PhysicalKey::K => KeyCode::K,
+        PhysicalKey::L => KeyCode::L,
+        PhysicalKey::M => KeyCode::M,
+        PhysicalKey::N => KeyCode::N,
+        PhysicalKey::O => KeyCode::O,
+        PhysicalKey::P => KeyCode::P,
+        PhysicalKey::Q => KeyCode::Q,
+        PhysicalKey::R => KeyCode::R,
+        PhysicalKey::S => KeyCode::S,
+        PhysicalKey::T => KeyCode::T,
+        PhysicalKey::U => KeyCode::U,
+        PhysicalKey::V => KeyCode::V,
+        PhysicalKey::W => KeyCode::W,
+        PhysicalKey::X => KeyCode::X,
+        PhysicalKey::Y => KeyCode::Y,
+        PhysicalKey::Z => KeyCode::Z,
+        PhysicalKey::Up => KeyCode::Up,
+        PhysicalKey::Down => KeyCode::Down,
+        PhysicalKey::Left => KeyCode::Left,
+        PhysicalKey::Right => KeyCode::Right,
+        PhysicalKey::Space => KeyCode::Space,
+    }
+}
+
+const SAMPLE_RATE: u32 = 44_100;
+// Periods of the looped beep sound, its length has a whole number of them so that the loop has no click
+const BEEP_PERIODS: usize = 100;
+const VOLUME: f32 = 0.25;
+
+// 16-bit mono WAV file of a full scale wave, macroquad only plays sounds loaded from files. The period is rounded to
+// whole samples
+pub(crate) fn tone_wav(waveform: Waveform, frequency: f32, sample_rate: u32, periods: usize) -> Vec<u8> {
+    let period = (sample_rate as f32 / frequency.max(1.0)).round().max(1.0) as usize;
+    let samples: Vec<i16> = (0..period * periods)
+        .map(|sample| waveform.sample((sample % period) as f64 / period as f64))
+        .map(|level| (level * f32::from(i16::MAX)).round() as i16)
+        .collect();
+    let data_size = (samples.len() * 2) as u32;
+    let mut wav = Vec::with_capacity(44 + samples.len() * 2);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    // Size of the format chunk, PCM format, 1 channel
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    // Bytes per second, bytes per sample and bits per sample
+    wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
+
+// miniquad only tells the position of the window on Windows and Linux
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+fn window_position() -> Option<(u32, u32)> {
+    Some(macroquad::miniquad::window::get_window_position())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn window_position() -> Option<(u32, u32)> {
+    None
+}
+
+pub struct MacroquadFrontend {
+    // Last drawn screen, rotated, and its size
+    rotation: Rotation,
+    framebuffer: Vec<u8>,
+    framebuffer_width: usize,
+    framebuffer_height: usize,
+    // Screen shown by each present, following the drawn one with the fading of the pixels turned off. With the
+    // persistence on it moves once per 60 Hz frame, at the time in seconds of last_step
+    phosphor: Phosphor,
+    last_step: f64,
+    // Shown screen and its size
+    texture: Texture2D,
+    width: usize,
+    height: usize,
+    theme: Theme,
+    // None when the audio device could not load it, the program then runs silently
+    beep: Option<Sound>,
+    beeping: bool,
+    // Volume of the beep in percent and its mute, the sound timer runs on while muted
+    volume: u8,
+    muted: bool,
+    // Size of the window before it went fullscreen, None while in the window, and its position when known
+    windowed_size: Option<(f32, f32)>,
+    windowed_position: Option<(u32, u32)>,
+    // Debug overlay over the top left corner of the screen, toggled by F3
+    overlay_shown: bool,
+    overlay: Option<TextBitmap>,
+    // CRT effect of the scaled screen, and the one F6 switches to. The shown screen with the effect is scaled on the
+    // CPU into its own texture, made again when the screen changes or the window is resized
+    crt: Crt,
+    crt_toggled: Crt,
+    crt_texture: Option<Texture2D>,
+    keymap: Keymap,
+}
+
+impl MacroquadFrontend {
+    // Must be called from the macroquad main, which sets up the window and the audio
+    pub async fn new() -> MacroquadFrontend {
+        let (width, height) = (64, 32);
+        let theme = Theme::default();
+        let texture = Texture2D::from_rgba8(width as u16, height as u16, &theme.to_rgba(&[0; 64 * 32]));
+        texture.set_filter(FilterMode::Nearest);
+        let wav = tone_wav(Waveform::Square, BEEP_FREQUENCY, SAMPLE_RATE, BEEP_PERIODS);
+        let beep = load_sound_from_bytes(&wav).await.ok();
+        MacroquadFrontend {
+            rotation: Rotation::None,
+            framebuffer: vec![0; width * height],
+            framebuffer_width: width,
+            framebuffer_height: height,
+            phosphor: Phosphor::new(0),
+            last_step: get_time(),
+            texture,
+            width,
+            height,
+            theme,
+            beep,
+            beeping: false,
+            volume: MAX_VOLUME,
+            muted: false,
+            windowed_size: None,
+            windowed_position: None,
+            overlay_shown: false,
+            overlay: None,
+            crt: Crt::OFF,
+            crt_toggled: Crt::SCANLINES,
+            crt_texture: None,
+            keymap: Keymap::default(),
+        }
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.refresh();
+    }
+
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+    }
+
+    // Frames a pixel stays visible after turning off, 0 shows the screen as it is
+    pub fn set_phosphor(&mut self, decay_frames: u32) {
+        self.phosphor = Phosphor::new(decay_frames);
+        self.refresh();
+    }
+
+    // F6 switches between this effect and none, or the scanlines when it is none
+    pub fn set_crt(&mut self, crt: Crt) {
+        self.crt = crt;
+        self.crt_toggled = if crt.is_off() { Crt::SCANLINES } else { Crt::OFF };
+        self.crt_texture = None;
+    }
+
+    // Applies to the next drawn screen, the window is opened at the rotated size by the binary
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.rotation = rotation;
+    }
+
+    // Whether the debug overlay is shown, its lines are then given once per frame to set_overlay. F3 shows and hides
+    // it
+    pub fn overlay_shown(&mut self) -> bool {
+        if is_key_pressed(KeyCode::F3) {
+            self.overlay_shown = !self.overlay_shown;
+            self.overlay = None;
+        }
+        self.overlay_shown
+    }
+
+    // Drawn by the next present
+    // Wave of the beep, loaded as a new sound. A sound the audio device cannot load leaves the previous one
+    pub async fn set_tone(&mut self, waveform: Waveform, frequency: f32) {
+        let Ok(beep) = load_sound_from_bytes(&tone_wav(waveform, frequency, SAMPLE_RATE, BEEP_PERIODS)).await else {
+            return;
+        };
+        if let Some(previous) = self.beep.replace(beep) {
+            stop_sound(&previous);
+        }
+        self.beeping = false;
+    }
+
+    // In percent, clamped to MAX_VOLUME
+    pub fn set_volume(&mut self, volume: u8) {
+        self.volume = volume.min(MAX_VOLUME);
+        self.apply_volume();
+    }
+
+    pub fn muted(&self) -> bool {
+        self.muted
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+        self.apply_volume();
+    }
+
+    // Whether M was pressed this frame, to mute or unmute the beep, unless the keymap gives M to the keypad
+    pub fn mute_requested(&self) -> bool {
+        is_key_pressed(KeyCode::M) && letter_free(PhysicalKey::M, &self.keymap)
+    }
+
+    // Whether Tab is held, to fast-forward the emulation
+    pub fn turbo_held(&self) -> bool {
+        is_key_down(KeyCode::Tab)
+    }
+
+    // P, Backspace, R, Esc and Q pressed this frame, the letters when the keymap leaves them
+    pub fn take_controls(&mut self) -> Vec<Control> {
+        ControlKey::ALL.into_iter()
+            .filter(|&key| is_key_pressed(control_key_code(key)))
+            .filter_map(|key| control(key, &self.keymap))
+            .collect()
+    }
+
+    fn apply_volume(&self) {
+        if let Some(beep) = self.beep.as_ref().filter(|_| self.beeping) {
+            set_sound_volume(beep, VOLUME * gain(self.volume, self.muted));
+        }
+    }
+
+    pub fn set_overlay(&mut self, lines: &[String]) {
+        self.overlay = Some(TextBitmap::new(lines));
+    }
+
+    // Draws the shown screen at the largest whole scale fitting in the window, with borders of the background color,
+    // and the overlay over it. macroquad does not keep the window content, so this is called every frame before
+    // next_frame. F11 and Alt+Enter switch between the window and fullscreen
+    pub fn present(&mut self) {
+        let alt = is_key_down(KeyCode::LeftAlt) || is_key_down(KeyCode::RightAlt);
+        if is_key_pressed(KeyCode::F11) || (alt && is_key_pressed(KeyCode::Enter)) {
+            self.toggle_fullscreen();
+        }
+        if is_key_pressed(KeyCode::F6) {
+            mem::swap(&mut self.crt, &mut self.crt_toggled);
+            self.crt_texture = None;
+        }
+        if self.phosphor.enabled() && get_time() - self.last_step >= FRAME_DURATION.as_secs_f64() {
+            self.last_step = get_time();
+            self.refresh();
+        }
+        let [red, green, blue, alpha] = self.theme.rgba(0);
+        clear_background(Color::from_rgba(red, green, blue, alpha));
+        let viewport = letterbox(self.width, self.height, screen_width() as u32, screen_height() as u32);
+        let size = vec2(viewport.width as f32, viewport.height as f32);
+        let params = DrawTextureParams { dest_size: Some(size), ..DrawTextureParams::default() };
+        let texture = if self.crt.is_off() { &self.texture } else { self.crt_texture(&viewport) };
+        draw_texture_ex(texture, viewport.x as f32, viewport.y as f32, WHITE, params);
+        if let Some(overlay) = self.overlay.as_ref().filter(|_| self.overlay_shown) {
+            // The box darkens the screen to a quarter below the white text
+            let scale = overlay_scale(&viewport) as f32;
+            let (x, y) = (viewport.x as f32, viewport.y as f32);
+            let (width, height) = (overlay.width as f32 * scale, overlay.height as f32 * scale);
+            draw_rectangle(x, y, width, height, Color::new(0.0, 0.0, 0.0, 0.75));
+            for (column, row) in overlay.lit() {
+                draw_rectangle(x + column as f32 * scale, y + row as f32 * scale, scale, scale, WHITE);
+            }
+        }
+    }
+
+    // Texture of the shown screen scaled to the viewport with the CRT effect
+    fn crt_texture(&mut self, viewport: &Viewport) -> &Texture2D {
+        let size = (viewport.width as f32, viewport.height as f32);
+        let current = self.crt_texture.take().filter(|texture| (texture.width(), texture.height()) == size);
+        self.crt_texture.insert(current.unwrap_or_else(|| {
+            let image = crt_image(&self.phosphor.colors(&self.theme), self.width, viewport.scale as usize, &self.crt);
+            let rgba: Vec<u8> = image.into_iter().flat_map(|[red, green, blue]| [red, green, blue, 0xFF]).collect();
+            let texture = Texture2D::from_rgba8(viewport.width as u16, viewport.height as u16, &rgba);
+            texture.set_filter(FilterMode::Nearest);
+            texture
+        }))
+    }
+
+    // The window gets back its size, and its position where miniquad tells it, when leaving the fullscreen
+    fn toggle_fullscreen(&mut self) {
+        if let Some((width, height)) = self.windowed_size.take() {
+            set_fullscreen(false);
+            request_new_screen_size(width, height);
+            if let Some((x, y)) = self.windowed_position {
+                set_window_position(x, y);
+            }
+        } else {
+            self.windowed_size = Some((screen_width(), screen_height()));
+            self.windowed_position = window_position();
+            set_fullscreen(true);
+        }
+    }
+
+    // Moves the shown screen one frame towards the drawn one and uploads it. The texture follows the resolution of
+    // the screen
+    fn refresh(&mut self) {
+        self.phosphor.step(&self.framebuffer);
+        self.crt_texture = None;
+        let rgba = self.phosphor.to_rgba(&self.theme);
+        let (width, height) = (self.framebuffer_width, self.framebuffer_height);
+        if (width, height) == (self.width, self.height) {
+            self.texture.update_from_bytes(width as u32, height as u32, &rgba);
+        } else {
+            self.texture = Texture2D::from_rgba8(width as u16, height as u16, &rgba);
+            self.texture.set_filter(FilterMode::Nearest);
+            self.width = width;
+            self.height = height;
+        }
+    }
+}
+
+impl Display for MacroquadFrontend {
+    fn draw(&mut self, framebuffer: &[u8], width: usize, height: usize) {
+        self.framebuffer = rotate(framebuffer, width, self.rotation);
+        (self.framebuffer_width, self.framebuffer_height) = self.rotation.size((width, height));
+        // With the phosphor the screen is shown by the next frame
+        if !self.phosphor.enabled() {
+            self.refresh();
+        }
+    }
+
+    fn clear(&mut self) {
+        self.framebuffer.fill(0);
+        if !self.phosphor.enabled() {
+            self.refresh();
+        }
+    }
+}
+
+impl Input for MacroquadFrontend {
+    fn poll(&mut self) -> [bool; 16] {
+        self.keymap.poll(|physical_key| is_key_down(key_code(physical_key)))
+    }
+}
+
+impl Buzzer for MacroquadFrontend {
+    fn set_active(&mut self, on: bool) {
+        let Some(beep) = &self.beep else { return };
+        if on && !self.beeping {
+            play_sound(beep, PlaySoundParams { looped: true, volume: VOLUME * gain(self.volume, self.muted) });
+        } else if !on && self.beeping {
+            stop_sound(beep);
+        }
+        self.beeping = on;
+    }
+}
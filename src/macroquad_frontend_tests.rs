@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod macroquad_frontend_tests {
+    use crate::macroquad_frontend::tone_wav;
+    use crate::Waveform;
+
+    #[test]
+    fn tone_wav_has_the_header_of_a_16_bit_mono_wav() {
+        let wav = tone_wav(Waveform::Square, 441.0, 44_100, 2);
+
+        // 2 periods of 100 samples of 2 bytes
+        assert_eq!(wav.len(), 44 + 400);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(wav[4..8], (36u32 + 400).to_le_bytes());
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(wav[22..24], 1u16.to_le_bytes());
+        assert_eq!(wav[24..28], 44_100u32.to_le_bytes());
+        assert_eq!(wav[34..36], 16u16.to_le_bytes());
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(wav[40..44], 400u32.to_le_bytes());
+    }
+
+    #[test]
+    fn tone_wav_holds_whole_periods_of_the_square_wave() {
+        let wav = tone_wav(Waveform::Square, 441.0, 44_100, 2);
+
+        let samples: Vec<i16> = wav[44..].chunks(2).map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]])).collect();
+        // Half a period high, half a period low, twice
+        for (index, &sample) in samples.iter().enumerate() {
+            let expected = if index % 100 < 50 { i16::MAX } else { -i16::MAX };
+            assert_eq!(sample, expected, "sample {index}");
+        }
+    }
+
+    #[test]
+    fn tone_wav_plays_the_other_waves_rounded_to_whole_periods() {
+        let samples = |waveform| -> Vec<i16> {
+            let wav = tone_wav(waveform, 441.0, 44_100, 1);
+            wav[44..].chunks(2).map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]])).collect()
+        };
+        let sine = samples(Waveform::Sine);
+        assert_eq!((sine[0], sine[25], sine[75]), (0, i16::MAX, -i16::MAX));
+        let triangle = samples(Waveform::Triangle);
+        assert_eq!((triangle[0], triangle[25], triangle[50], triangle[75]), (0, i16::MAX, 0, -i16::MAX));
+        // Periods of 109.09 samples at 48 kHz are rounded to 109
+        assert_eq!(tone_wav(Waveform::Sine, 440.0, 48_000, 1).len(), 44 + 218);
+    }
+}
@@ -1,378 +1,798 @@
-// Memory map
-// 0x000-0x1FF - Chip 8 interpreter (contains font set in emu)
-// 0x050-0x0A0 - Used for the built in 4x5 pixel font set (0-F)
-// 0x200-0xFFF - Program ROM and work RAM
-
-// The graphics system: The chip 8 has one instruction that draws sprite to the screen.
-// Drawing is done in XOR mode and if a pixel is turned off as a result of drawing,
-// the VF register is set. This is used for collision detection.
-
-#[cfg(test)]
-#[path = "./main_tests.rs"]
-mod main_tests;
-
-use std::fs::File;
-use std::io;
-use std::io::{Read};
-use rand;
-use crate::ProgramCounterInstruction::{GOTO, NEXT, SKIP};
-
-fn main() -> io::Result<()> {
-    // Set up render system and register input callbacks
-    setup_graphics();
-    setup_input();
-
-    // Initialize the chip 8 system and load the game into the memory
-    let mut chip8 = Chip8::default();
-    chip8.load_game()?;
-
-    loop { // Emulation loop
-        chip8.emulate_cycle();
+use std::env;
+use std::error::Error;
+use std::path::PathBuf;
+use std::process;
+use chip8::{
+    parse_color, parse_keymap, parse_palette, settings_path, AsciiDisplay, BrailleDisplay, Chip8, Chip8Config, Crt,
+    Display, FontStyle, FramePacer, Keymap, Rotation, Settings, Theme, Waveform, BEEP_FREQUENCY, DEFAULT_MAX_SKIP,
+    DEFAULT_SCALE, DEFAULT_TURBO, MAX_VOLUME,
+};
+#[cfg(feature = "egui")]
+use chip8::Debugger;
+#[cfg(feature = "terminal")]
+use chip8::TerminalFrontend;
+
+// Instructions per second, about the speed of the COSMAC VIP interpreter: 11 per 60 Hz frame
+const CLOCK_HZ: u32 = 700;
+
+// Size of a CHIP-8 pixel in the SIXEL image, in terminal pixels
+#[cfg(feature = "terminal")]
+const SIXEL_SCALE: usize = 8;
+
+const USAGE: &str = "Usage: chip8 [--frontend ascii|braille|headless|terminal|sixel|sdl2|minifb|pixels|debugger]
+    [--scale N] [--theme classic|green|amber|paperwhite|inverted] [--fg #RRGGBB] [--bg #RRGGBB]
+    [--palette octo|lcd|hotdog|gray|cga0|cga1|COLORS] [--phosphor FRAMES] [--crt] [--vignette] [--screenshot-dir DIR]
+    [--rotate 0|90|180|270] [--record FILE.gif] [--record-audio FILE.wav] [--ipf INSTRUCTIONS]
+    [--font chip8|dream6800|eti660] [--dump-frame-every FRAMES] [--max-skip FRAMES] [--turbo FACTOR]
+    [--volume 0..100] [--beep-freq HZ] [--beep-wave square|sine|triangle] [--keymap KEY=NAME,...] [--show-keymap]
+    [--no-pause-on-focus-loss] <rom file>";
+
+// Frontends built in, the window ones need their feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frontend {
+    Ascii,
+    Braille,
+    Headless,
+    #[cfg(feature = "terminal")]
+    Terminal,
+    #[cfg(feature = "terminal")]
+    Sixel,
+    #[cfg(feature = "sdl2")]
+    Sdl2,
+    #[cfg(feature = "minifb")]
+    Minifb,
+    #[cfg(feature = "pixels")]
+    Pixels,
+    #[cfg(feature = "egui")]
+    Debugger,
+}
 
-        if chip8.draw_flag { // If the draw flag is set, update the screen
-            draw_graphics();
+impl Frontend {
+    fn parse(name: &str) -> Option<Frontend> {
+        match name {
+            "ascii" => Some(Frontend::Ascii),
+            "braille" => Some(Frontend::Braille),
+            "headless" => Some(Frontend::Headless),
+            #[cfg(feature = "terminal")]
+            "terminal" => Some(Frontend::Terminal),
+            #[cfg(feature = "terminal")]
+            "sixel" => Some(Frontend::Sixel),
+            #[cfg(feature = "sdl2")]
+            "sdl2" => Some(Frontend::Sdl2),
+            #[cfg(feature = "minifb")]
+            "minifb" => Some(Frontend::Minifb),
+            #[cfg(feature = "pixels")]
+            "pixels" => Some(Frontend::Pixels),
+            #[cfg(feature = "egui")]
+            "debugger" => Some(Frontend::Debugger),
+            _ => None,
         }
+    }
 
-        chip8.set_keys();
+    // The lightest window frontend built in, the terminal one without any
+    #[allow(unreachable_code)]
+    fn default() -> Frontend {
+        #[cfg(feature = "minifb")]
+        return Frontend::Minifb;
+        #[cfg(feature = "pixels")]
+        return Frontend::Pixels;
+        #[cfg(feature = "sdl2")]
+        return Frontend::Sdl2;
+        #[cfg(feature = "terminal")]
+        return Frontend::Terminal;
+        Frontend::Ascii
+    }
+
+    // Whether the frontend runs in run_window, which turns the screen with --rotate and records it with --record and
+    // --record-audio. The printed ones, the headless one and the debugger do neither
+    fn runs_in_window(self) -> bool {
+        match self {
+            Frontend::Ascii | Frontend::Braille | Frontend::Headless => false,
+            #[cfg(feature = "terminal")]
+            Frontend::Terminal | Frontend::Sixel => true,
+            #[cfg(feature = "sdl2")]
+            Frontend::Sdl2 => true,
+            #[cfg(feature = "minifb")]
+            Frontend::Minifb => true,
+            #[cfg(feature = "pixels")]
+            Frontend::Pixels => true,
+            #[cfg(feature = "egui")]
+            Frontend::Debugger => false,
+        }
     }
 }
 
-struct Chip8 {
-    // The chip 8 has 35 opcodes, all are 2 bytes long
-    opcode: u16,
-    // The chip 8 has 4K memory
-    memory: [u8; 4096], // TODO Use vector instead : https://doc.rust-lang.org/std/vec/struct.Vec.html
-    // The chip 8 has 15 8-bit general purpose registers named V0, V1 -> VE
-    v: [u8; 16],
-    // Index register and program counter (which have values from 0x000 to 0xFFF)
-    i: u16,
-    pc: u16,
-    // The graphics of the Chip 8 are black and white and the screen has a total of 2048 pixels (64 x 32)
-    gfx: [u8; 64 * 32],
-    // Interrupts and hardware registers.
-    // The Chip 8 has none, but there are two timer registers that count at 60 Hz. When set above zero they will count down to zero.
-    delay_timer: u8,
-    // The system’s buzzer sounds whenever the sound timer reaches zero.
-    sound_timer: u8,
-    // The stack is used to remember the current location before a jump is performed.
-    // So anytime you perform a jump or call a subroutine, store the program counter in the stack before proceeding.
-    // The system has 16 levels of stack
-    stack: Vec<u16>,
-    // the Chip 8 has a HEX based keypad (0x0-0xF), an array store the current state of the key.
-    key: [u8; 16],
-    draw_flag: bool,
+// Settings of the frontends. Most are only read by the window ones, the theme and the rotation also by the terminal
+#[cfg_attr(not(any(feature = "sdl2", feature = "minifb", feature = "pixels")), allow(dead_code))]
+struct Options {
+    // Window pixels per CHIP-8 pixel
+    scale: u32,
+    theme: Theme,
+    // Frames the pixels turned off stay visible
+    phosphor: u32,
+    crt: Crt,
+    // Rotation of the shown screen, the screenshots and the recordings
+    rotation: Rotation,
+    // The emulation pauses while the window does not have the focus
+    pause_on_focus_loss: bool,
+    // Frames run without rendering them when the host is late, before the rest is dropped
+    max_skip: u32,
+    // Speed factor of the emulation while Tab is held
+    turbo: u32,
+    // Volume and mute of the beep, kept from the last runs
+    settings: Settings,
+    // Wave of the beep, the programs playing an XO-CHIP audio pattern play it instead
+    #[cfg_attr(not(any(feature = "sdl2", feature = "record-audio")), allow(dead_code))]
+    beep_wave: Waveform,
+    #[cfg_attr(not(any(feature = "sdl2", feature = "record-audio")), allow(dead_code))]
+    beep_frequency: f32,
+    // Where F12 saves the screenshots and F9 the recordings, named after the ROM
+    #[cfg_attr(not(any(feature = "screenshot", feature = "record")), allow(dead_code))]
+    screenshot_dir: PathBuf,
+    // GIF file recorded from the start
+    #[cfg_attr(not(feature = "record"), allow(dead_code))]
+    record_path: Option<PathBuf>,
+    // WAV file of the beep recorded from the start
+    #[cfg_attr(not(feature = "record-audio"), allow(dead_code))]
+    record_audio_path: Option<PathBuf>,
+    // Keyboard keys of the keypad, from the settings file and --keymap
+    keymap: Keymap,
+    rom_path: PathBuf,
 }
 
-impl Default for Chip8 {
-    fn default() -> Chip8 {
-        Chip8 {
-            pc: 0x200,
-            memory: [0; 4096],
-            v: [0; 16],
-            gfx: [0; 64 * 32],
-            stack: Vec::with_capacity(16),
-            key: [0; 16],
-            opcode: 0,
-            i: 0,
-            delay_timer: 0,
-            sound_timer: 0,
-            draw_flag: false,
+fn usage_error(message: &str) -> ! {
+    eprintln!("{}\n{}", message, USAGE);
+    process::exit(2);
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut frontend = Frontend::default();
+    let mut scale = DEFAULT_SCALE;
+    let mut phosphor = 0;
+    let mut crt = Crt::OFF;
+    let mut rotation = Rotation::None;
+    let mut pause_on_focus_loss = true;
+    let mut max_skip = DEFAULT_MAX_SKIP;
+    let mut turbo = DEFAULT_TURBO;
+    let mut volume = None;
+    let mut beep_wave = Waveform::default();
+    let mut beep_frequency = BEEP_FREQUENCY;
+    let mut theme = Theme::default();
+    // Applied over the theme whatever the order of the options
+    let (mut foreground, mut background) = (None, None);
+    // Replaces all the colors
+    let mut palette = None;
+    let mut screenshot_dir = PathBuf::from(".");
+    let mut record_path = None;
+    let mut record_audio_path = None;
+    let mut instructions_per_frame = None;
+    let mut font = FontStyle::Chip8;
+    let mut dump_frame_every = None;
+    let mut keymap = Vec::new();
+    let mut show_keymap = false;
+    let mut rom_path = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--frontend" {
+            let name = args.next().unwrap_or_else(|| usage_error("Missing frontend name"));
+            frontend = Frontend::parse(&name)
+                .unwrap_or_else(|| usage_error(&format!("Unknown or not built frontend: {}", name)));
+        } else if arg == "--scale" {
+            scale = args.next()
+                .and_then(|scale| scale.parse().ok())
+                .filter(|&scale| scale > 0)
+                .unwrap_or_else(|| usage_error("The scale must be a whole number above 0"));
+        } else if arg == "--ipf" {
+            instructions_per_frame = Some(args.next()
+                .and_then(|instructions| instructions.parse().ok())
+                .filter(|&instructions| instructions > 0)
+                .unwrap_or_else(|| usage_error("The instructions per frame must be a whole number above 0")));
+        } else if arg == "--font" {
+            font = args.next()
+                .and_then(|name| FontStyle::from_name(&name))
+                .unwrap_or_else(|| usage_error("The font must be chip8, dream6800 or eti660"));
+        } else if arg == "--max-skip" {
+            max_skip = args.next()
+                .and_then(|frames| frames.parse().ok())
+                .unwrap_or_else(|| usage_error("The frames skipped must be a whole number, 0 for none"));
+        } else if arg == "--turbo" {
+            turbo = args.next()
+                .and_then(|factor| factor.parse().ok())
+                .filter(|&factor| factor > 0)
+                .unwrap_or_else(|| usage_error("The turbo speed must be a whole number above 0, 1 for none"));
+        } else if arg == "--volume" {
+            volume = Some(args.next()
+                .and_then(|volume| volume.parse().ok())
+                .filter(|&volume| volume <= MAX_VOLUME)
+                .unwrap_or_else(|| usage_error("The volume must be a whole number from 0 to 100")));
+        } else if arg == "--beep-freq" {
+            beep_frequency = args.next()
+                .and_then(|frequency| frequency.parse().ok())
+                .filter(|&frequency: &f32| (20.0..=20_000.0).contains(&frequency))
+                .unwrap_or_else(|| usage_error("The beep frequency must be a number of Hz from 20 to 20000"));
+        } else if arg == "--beep-wave" {
+            beep_wave = args.next()
+                .and_then(|name| Waveform::from_name(&name))
+                .unwrap_or_else(|| usage_error("The beep wave must be square, sine or triangle"));
+        } else if arg == "--dump-frame-every" {
+            dump_frame_every = Some(args.next()
+                .and_then(|frames| frames.parse().ok())
+                .filter(|&frames| frames > 0)
+                .unwrap_or_else(|| usage_error("The frames between the dumps must be a whole number above 0")));
+        } else if arg == "--phosphor" {
+            phosphor = args.next()
+                .and_then(|frames| frames.parse().ok())
+                .unwrap_or_else(|| usage_error("The phosphor decay must be a whole number of frames, 0 for none"));
+        } else if arg == "--crt" {
+            crt.scanlines = true;
+            crt.grid = true;
+        } else if arg == "--vignette" {
+            crt.vignette = true;
+        } else if arg == "--rotate" {
+            rotation = args.next()
+                .and_then(|degrees| degrees.parse().ok())
+                .and_then(Rotation::from_degrees)
+                .unwrap_or_else(|| usage_error("The rotation must be 0, 90, 180 or 270 degrees"));
+        } else if arg == "--no-pause-on-focus-loss" {
+            pause_on_focus_loss = false;
+        } else if arg == "--screenshot-dir" {
+            screenshot_dir = args.next().map(PathBuf::from).unwrap_or_else(|| usage_error("Missing screenshot directory"));
+        } else if arg == "--record" {
+            record_path = Some(args.next().map(PathBuf::from).unwrap_or_else(|| usage_error("Missing recording file")));
+        } else if arg == "--record-audio" {
+            let path = args.next().map(PathBuf::from).unwrap_or_else(|| usage_error("Missing audio recording file"));
+            record_audio_path = Some(path);
+        } else if arg == "--keymap" {
+            let assignments = args.next().unwrap_or_else(|| usage_error("Missing keymap"));
+            keymap = parse_keymap(&assignments).unwrap_or_else(|error| usage_error(&error.to_string()));
+        } else if arg == "--show-keymap" {
+            show_keymap = true;
+        } else if arg == "--theme" {
+            let name = args.next().unwrap_or_else(|| usage_error("Missing theme name"));
+            theme = Theme::preset(&name).unwrap_or_else(|error| usage_error(&error.to_string()));
+        } else if arg == "--palette" {
+            let colors = args.next().unwrap_or_else(|| usage_error("Missing palette"));
+            palette = Some(parse_palette(&colors).unwrap_or_else(|error| usage_error(&error.to_string())));
+        } else if arg == "--fg" || arg == "--bg" {
+            let color = args.next().unwrap_or_else(|| usage_error(&format!("Missing color after {}", arg)));
+            let color = Some(parse_color(&color).unwrap_or_else(|error| usage_error(&error.to_string())));
+            if arg == "--fg" {
+                foreground = color;
+            } else {
+                background = color;
+            }
+        } else if arg.starts_with("--") {
+            usage_error(&format!("Unknown option: {}", arg));
+        } else if rom_path.is_some() {
+            usage_error(&format!("Only one rom file can be given, not also {}", arg));
+        } else {
+            rom_path = Some(arg);
+        }
+    }
+    // --keymap changes the keys given in the settings file, which change the default ones
+    let mut settings = Settings::load();
+    let keymap = match parse_keymap(&settings.keymap) {
+        Ok(assignments) => Keymap::default().assign(&assignments).assign(&keymap),
+        Err(error) => {
+            let path = settings_path().unwrap_or_default();
+            eprintln!("Invalid [keymap] section in {} : {error}", path.display());
+            process::exit(2);
+        }
+    };
+    if show_keymap {
+        print!("{keymap}");
+        return Ok(());
+    }
+    let Some(rom_path) = rom_path else {
+        usage_error("Missing rom file");
+    };
+    if dump_frame_every.is_some() && frontend != Frontend::Headless {
+        usage_error("--dump-frame-every needs the headless frontend");
+    }
+    if !frontend.runs_in_window() {
+        if record_path.is_some() || record_audio_path.is_some() {
+            usage_error("--record and --record-audio need a window or terminal frontend");
+        }
+        if rotation != Rotation::None {
+            usage_error("--rotate needs a window or terminal frontend");
+        }
+    }
+    if let Some(foreground) = foreground {
+        theme = theme.foreground(foreground);
+    }
+    if let Some(background) = background {
+        theme = theme.background(background);
+    }
+    if let Some(palette) = palette {
+        if foreground.is_some() || background.is_some() {
+            usage_error("--palette gives all the colors, without --fg and --bg");
+        }
+        theme = palette;
+    }
+    // The volume given is kept for the next runs
+    if let Some(volume) = volume {
+        settings.volume = volume;
+        settings.save();
+    }
+    let options = Options {
+        scale,
+        theme,
+        phosphor,
+        crt,
+        rotation,
+        pause_on_focus_loss,
+        max_skip,
+        turbo,
+        settings,
+        beep_wave,
+        beep_frequency,
+        screenshot_dir,
+        record_path,
+        record_audio_path,
+        keymap,
+        rom_path: PathBuf::from(rom_path),
+    };
+
+    // Initialize the chip 8 system and load the game into the memory
+    let mut config = Chip8Config::new().clock_hz(CLOCK_HZ).font(font);
+    if let Some(instructions) = instructions_per_frame {
+        config = config.instructions_per_frame(instructions);
+    }
+    let mut chip8 = config.build()?;
+    chip8.load_game(&options.rom_path)?;
+
+    match frontend {
+        Frontend::Ascii => run_printed(&mut chip8, Box::new(AsciiDisplay), options.max_skip),
+        Frontend::Braille => run_printed(&mut chip8, Box::new(BrailleDisplay), options.max_skip),
+        Frontend::Headless => run_headless(&mut chip8, dump_frame_every, options.max_skip),
+        #[cfg(feature = "terminal")]
+        Frontend::Terminal => run_terminal(&mut chip8, TerminalFrontend::new()?, &options),
+        #[cfg(feature = "terminal")]
+        Frontend::Sixel => run_terminal(&mut chip8, TerminalFrontend::with_sixel(SIXEL_SCALE)?, &options),
+        #[cfg(feature = "sdl2")]
+        Frontend::Sdl2 => run_sdl(&mut chip8, &options),
+        #[cfg(feature = "minifb")]
+        Frontend::Minifb => run_minifb(&mut chip8, &options),
+        #[cfg(feature = "pixels")]
+        Frontend::Pixels => run_pixels(&mut chip8, &options),
+        // Starts paused, the program runs with the Run button
+        #[cfg(feature = "egui")]
+        Frontend::Debugger => {
+            let mut debugger = Debugger::new(chip8);
+            debugger.set_theme(options.theme);
+            debugger.set_keymap(options.keymap);
+            Ok(debugger.run("Chip8 debugger")?)
         }
     }
 }
 
-impl Chip8 {
-    fn load_game(&mut self) -> io::Result<()> {
-        let mut file = File::open("pong.rom")?;
-        let mut buffer: [u8; 246] = [0; 246];
-        file.read(&mut buffer)?;
-        for i in 0..buffer.len() {
-            self.memory[i + 512] = buffer[i];
+// Prints the screen to the terminal with the given display, without input
+fn run_printed(chip8: &mut Chip8, mut display: Box<dyn Display>, max_skip: u32) -> Result<(), Box<dyn Error>> {
+    use chip8::{Buzzer, Input, NullInput, StdoutBuzzer};
+
+    // Set up render, input and sound systems
+    display.clear();
+    let mut input: Box<dyn Input> = Box::new(NullInput);
+    let mut buzzer: Box<dyn Buzzer> = Box::new(StdoutBuzzer);
+
+    let mut pacer = FramePacer::new().max_skip(max_skip);
+    loop { // Emulation loop
+        chip8.set_keys(input.as_mut());
+
+        // Runs the frames due since the last run, the timers tick once per frame
+        let frame = pacer.run(chip8)?;
+        if frame.halted { // The program exited
+            return Ok(());
         }
-        Ok(())
-    }
-
-    fn emulate_cycle(&mut self) {
-        let opcode_first_byte = u16::from(self.memory[usize::from(self.pc)] << 8);
-        let opcode_second_byte = u16::from(self.memory[usize::from(self.pc + 1)]);
-        self.opcode = opcode_first_byte | opcode_second_byte;
-        let nibbles = (
-            (self.opcode & 0xF000) >> 12 as u8,
-            (self.opcode & 0x0F00) >> 8 as u8,
-            (self.opcode & 0x00F0) >> 4 as u8,
-            (self.opcode & 0x000F) as u8
-        );
-        let nnn = (self.opcode & 0x0FFF) as u16;
-        let nn = (self.opcode & 0x00FF) as u8;
-        let n = (self.opcode & 0x000F) as u8;
-        let x = nibbles.1 as usize;
-        let y = nibbles.2 as usize;
-
-        let program_counter_action = match self.opcode & 0xF000 {
-            0x0000 => match self.opcode & 0x000F { // TODO 0NNN Might be missing (it calls machine code routine at address NNN)
-                0x0000 => self.op_0x00e0(),
-                0x000E => self.op_0x00ee(),
-                _ => panic!("Unknown opcode read : 0x{}", self.opcode)
-            },
-            0x1000 => self.op_0x1nnn(nnn),
-            0x2000 => self.op_0x2nnn(nnn),
-            0x3000 => self.op_0x3xnn(x, nn),
-            0x4000 => self.op_0x4xnn(x, nn),
-            0x5000 => self.op_0x5xy0(x, y),
-            0x6000 => self.op_0x6xnn(x, nn),
-            0x7000 => self.op_0x7xnn(x, nn),
-            0x8000 => match n {
-                0x0000 => self.op_0x8xy0(x, y),
-                0x0001 => self.op_0x8xy1(x, y),
-                0x0002 => self.op_0x8xy2(x, y),
-                0x0003 => self.op_0x8xy3(x, y),
-                0x0004 => self.op_0x8xy4(x, y),
-                0x0005 => self.op_0x8xy5(x, y),
-                0x0006 => self.op_0x8xy6(x),
-                0x0007 => self.op_0x8xy7(x, y),
-                0x000E => self.op_0x8xye(x),
-                _ => panic!("Unknown opcode read : 0x{}", self.opcode)
-            },
-            0x9000 => self.op_0x9xy0(x, y),
-            0xA000 => self.op_0xannn(nnn),
-            0xB000 => self.op_0xbnnn(nnn),
-            0xC000 => self.op_0xcxnn(x, nn),
-            0xD000 => self.op_0xdxyn(x, y, nn),
-            0xE000 => match n {
-                0x000E => self.op_0xex9e(x),
-                0x0001 => self.op_0xexa1(x),
-                _ => panic!("Unknown opcode read : 0x{}", self.opcode)
-            },
-            _ => panic!("Unknown opcode read : 0x{}", self.opcode)
-        };
 
-        match program_counter_action {
-            NEXT => self.pc += 2,
-            SKIP => self.pc += 4,
-            GOTO(addr) => self.pc = addr
+        if frame.drew { // If the screen changed, update it
+            chip8.render(display.as_mut());
         }
 
-        if self.delay_timer > 0 {
-            self.delay_timer -= 1;
+        chip8.update_buzzer(buzzer.as_mut());
+
+        // Sleeps until the next frame
+        pacer.wait();
+    }
+}
+
+// Runs without showing the screen nor reading keys, for tests and servers. The screen is printed as text every
+// dump_frame_every frames and when the program exits
+fn run_headless(chip8: &mut Chip8, dump_frame_every: Option<u32>, max_skip: u32) -> Result<(), Box<dyn Error>> {
+    use chip8::NullInput;
+
+    let mut pacer = FramePacer::new().max_skip(max_skip);
+    let mut frames: u64 = 0;
+    // Frame count of the next dump. A run may go past it by several frames, or run none, the dump comes once it is
+    // reached
+    let mut next_dump = dump_frame_every.map(u64::from);
+    loop { // Emulation loop
+        chip8.set_keys(&mut NullInput);
+
+        // Runs the frames due since the last run, the timers tick once per frame
+        let frame = pacer.run(chip8)?;
+        frames += u64::from(frame.frames);
+        if frame.halted { // The program exited
+            print!("Frame {frames}, exited:\n{}", chip8.framebuffer_ascii());
+            return Ok(());
         }
-        if self.sound_timer > 0 {
-            if self.sound_timer == 1 {
-                println!("BEEP");
+
+        if let (Some(every), Some(dump)) = (dump_frame_every, next_dump.as_mut()) {
+            if frames >= *dump {
+                print!("Frame {frames}:\n{}", chip8.framebuffer_ascii());
+                *dump = (frames / u64::from(every) + 1) * u64::from(every);
             }
-            self.sound_timer -= 1;
         }
-    }
 
-    //00E0: Clears the screen
-    fn op_0x00e0(&self) -> ProgramCounterInstruction {
-        self.clear_screen();
-        NEXT
-    }
-    
-    //00EE: Returns from subroutine
-    fn op_0x00ee(&mut self) -> ProgramCounterInstruction {
-        match self.stack.pop() {
-            Some(previous_pc) => GOTO(previous_pc),
-            None => panic!("Error: trying to pop the stack but it is empty"),
-        }
+        // Sleeps until the next frame
+        pacer.wait();
     }
+}
 
-    //1NNN: Jumps to address NNN
-    fn op_0x1nnn(&self, nnn: u16) -> ProgramCounterInstruction {
-        GOTO(nnn)
-    }
+// Shows the screen in the terminal with the keyboard as the keypad, until Esc or Ctrl+C is pressed. The frontend
+// restores the terminal when dropped, on return or on a panic
+#[cfg(feature = "terminal")]
+fn run_terminal(chip8: &mut Chip8, mut frontend: TerminalFrontend, options: &Options) -> Result<(), Box<dyn Error>> {
+    use chip8::WindowFrontend;
+
+    let mut title = TitleBar::new(chip8, options);
+    frontend.set_title(&title.text());
+    frontend.set_theme(options.theme);
+    frontend.set_rotation(options.rotation);
+    frontend.set_keymap(options.keymap.clone());
+    run_window(chip8, &mut frontend, &mut title, options)
+}
 
-    //2NNN: Calls subroutine at NNN
-    fn op_0x2nnn(&mut self, nnn: u16) -> ProgramCounterInstruction {
-        self.stack.push(self.pc);
-        GOTO(nnn)
-    }
+// Runs in a window until it is closed
+#[cfg(feature = "sdl2")]
+fn run_sdl(chip8: &mut Chip8, options: &Options) -> Result<(), Box<dyn Error>> {
+    use chip8::{SdlFrontend, WindowFrontend};
+
+    let mut title = TitleBar::new(chip8, options);
+    let mut frontend = SdlFrontend::new(&title.text(), options.scale, options.rotation)?;
+    frontend.set_theme(options.theme);
+    frontend.set_keymap(options.keymap.clone());
+    frontend.set_phosphor(options.phosphor);
+    frontend.set_crt(options.crt);
+    frontend.clear();
+    frontend.set_volume(options.settings.volume);
+    frontend.set_muted(options.settings.muted);
+    frontend.set_title(&title.set_muted(options.settings.muted));
+    frontend.set_tone(options.beep_wave, options.beep_frequency);
+    run_window(chip8, &mut frontend, &mut title, options)
+}
 
-    //3XNN: Skips the next instruction if VX equals NN (Usually the next instruction ia a jump to skip a code block)
-    fn op_0x3xnn(&self, x: usize, nn: u8) -> ProgramCounterInstruction {
-        if self.v[x] == nn {
-            SKIP
-        } else {
-            NEXT
+// Runs in a window until it is closed, without sound
+#[cfg(feature = "minifb")]
+fn run_minifb(chip8: &mut Chip8, options: &Options) -> Result<(), Box<dyn Error>> {
+    use chip8::MinifbFrontend;
+
+    let mut title = TitleBar::new(chip8, options);
+    let mut frontend = MinifbFrontend::new(&title.text(), options.scale, options.rotation)?;
+    frontend.set_theme(options.theme);
+    frontend.set_keymap(options.keymap.clone());
+    frontend.set_phosphor(options.phosphor);
+    frontend.set_crt(options.crt);
+    run_window(chip8, &mut frontend, &mut title, options)
+}
+
+// Runs in a GPU scaled window until it is closed, without sound
+#[cfg(feature = "pixels")]
+fn run_pixels(chip8: &mut Chip8, options: &Options) -> Result<(), Box<dyn Error>> {
+    use chip8::PixelsFrontend;
+
+    let mut title = TitleBar::new(chip8, options);
+    let mut frontend = PixelsFrontend::new(&title.text(), options.scale, options.rotation)?;
+    frontend.set_theme(options.theme);
+    frontend.set_keymap(options.keymap.clone());
+    frontend.set_phosphor(options.phosphor);
+    frontend.set_crt(options.crt);
+    frontend.clear();
+    run_window(chip8, &mut frontend, &mut title, options)
+}
+
+// Emulation loop of the terminal and the windows, until the window is closed, Esc is pressed or the program exits.
+// The frontends without sound or without some of the keys leave them out
+#[cfg(any(feature = "terminal", feature = "sdl2", feature = "minifb", feature = "pixels"))]
+fn run_window<W: chip8::WindowFrontend>(
+    chip8: &mut Chip8,
+    frontend: &mut W,
+    title: &mut TitleBar,
+    options: &Options,
+) -> Result<(), Box<dyn Error>> {
+    use chip8::{Control, NullInput, RunControl};
+
+    #[cfg(feature = "record")]
+    let mut recording = Recording::new(options);
+    #[cfg(feature = "record-audio")]
+    let mut audio_recording = AudioRecording::new(chip8, options);
+
+    // The frontends with sound switch the beep from the timer ticks, at the emulated frame it starts or stops
+    if let Some(hook) = frontend.sound_hook() {
+        chip8.set_sound_hook(hook);
+    }
+
+    let mut settings = options.settings.clone();
+    let mut run = RunControl::new(options.pause_on_focus_loss);
+    let mut turbo = false;
+    let mut pacer = FramePacer::new().max_skip(options.max_skip);
+    while frontend.handle_events() { // Emulation loop
+        let mut paused = run.update_focus(frontend.has_focus());
+        // P pauses and resumes, Backspace restarts the program, Esc quits. The recordings are finished when dropped
+        for control in frontend.take_controls() {
+            match control {
+                Control::Pause => paused = run.toggle_pause().or(paused),
+                Control::Reset => {
+                    chip8.reset();
+                    chip8.render(frontend);
+                }
+                Control::Quit => return Ok(()),
+            }
+        }
+        // Tab fast-forwards while held, without the beep which would screech
+        if frontend.turbo_held() != turbo {
+            turbo = !turbo;
+            chip8.set_speed(if turbo { options.turbo } else { 1 });
+            frontend.set_title(&title.set_turbo(turbo.then_some(options.turbo)));
+            frontend.set_sound_held(run.paused() || turbo);
+        }
+        if let Some(paused) = paused {
+            frontend.set_title(&title.set_paused(paused));
+            // Silent while paused, the sound timer does not tick
+            frontend.set_sound_held(paused || turbo);
+        }
+        // M mutes the beep and unmutes it, for the next runs too. The sound timer goes on
+        if frontend.mute_requested() {
+            settings.muted = !settings.muted;
+            frontend.set_muted(settings.muted);
+            frontend.set_title(&title.set_muted(settings.muted));
+            settings.save();
         }
-    }
 
-    //4XNN: Skips the next instruction if VX does not equals NN (Usually the next instruction ia a jump to skip a code block)
-    fn op_0x4xnn(&self, x: usize, nn: u8) -> ProgramCounterInstruction {
-        if self.v[x] != nn {
-            SKIP
+        // Runs the frames due since the last run, the timers tick once per frame. Nothing runs while paused and the
+        // keys read as released, so that a key held when the emulation paused does not stay pressed
+        let frame = if run.paused() {
+            chip8.set_keys(&mut NullInput);
+            pacer.skip()
         } else {
-            NEXT
+            chip8.set_keys(frontend);
+            pacer.run(chip8)?
+        };
+        if let Some(text) = title.update(pacer.throughput()) {
+            frontend.set_title(&text);
+        }
+        if frame.halted { // The program exited
+            return Ok(());
         }
-    }
 
-    //5XY0: Skips the next instruction if VX equals VY (Usually the next instruction ia a jump to skip a code block)
-    fn op_0x5xy0(&self, x: usize, y: usize) -> ProgramCounterInstruction {
-        return if self.v[x] == self.v[y] {
-            SKIP
-        } else {
-            NEXT
+        // Only redraws when the screen changed, which also avoids flickering in the terminal over slow links
+        if frame.drew {
+            chip8.render(frontend);
         }
-    }
 
-    //6XNN: Sets VX to NN
-    fn op_0x6xnn(&mut self, x: usize, nn: u8) -> ProgramCounterInstruction {
-        self.v[x] = nn;
-        NEXT
-    }
+        // The debug overlay follows the machine once per frame while F3 shows it
+        if frontend.overlay_shown() {
+            let info = chip8::DebugInfo::new(chip8, pacer.throughput(), pacer.skipped_frames());
+            frontend.set_overlay(&chip8::overlay_lines(&info));
+        }
 
-    //7XNN: Adds NN to VX
-    fn op_0x7xnn(&mut self, x: usize, nn: u8) -> ProgramCounterInstruction {
-        let addend = self.v[x] as u16;
-        let augend = nn as u16;
-        self.v[x] = (augend + addend) as u8;
-        NEXT
-    }
+        #[cfg(feature = "screenshot")]
+        if frontend.screenshot_requested() {
+            take_screenshot(chip8, options);
+        }
 
-    //8XY0: Sets VX to the value of VY
-    fn op_0x8xy0(&mut self, x: usize, y: usize) -> ProgramCounterInstruction {
-        self.v[x] = self.v[y];
-        NEXT
-    }
+        #[cfg(feature = "record")]
+        {
+            if frontend.record_requested() {
+                recording.toggle(options);
+            }
+            recording.push(chip8, frame.frames);
+        }
+        #[cfg(feature = "record-audio")]
+        audio_recording.write();
 
-    //8XY1: Set VX to VX or VY (Bitwise OR operation)
-    fn op_0x8xy1(&mut self, x: usize, y: usize) -> ProgramCounterInstruction {
-        self.v[x] |= self.v[y];
-        NEXT
-    }
+        frontend.set_audio_pattern(chip8.loaded_audio_pattern());
 
-    //8XY2: Set VX to VX and VY (Bitwise AND operation)
-    fn op_0x8xy2(&mut self, x: usize, y: usize) -> ProgramCounterInstruction {
-        self.v[x] &= self.v[y];
-        NEXT
+        // Sleeps until the next frame
+        pacer.wait();
     }
+    Ok(())
+}
 
-    //8XY3: Set VX to VX xor VY
-    fn op_0x8xy3(&mut self, x: usize, y: usize) -> ProgramCounterInstruction {
-        self.v[x] ^= self.v[y];
-        NEXT
-    }
+// Title of the windows: the ROM, its variant and the rates measured by the pacer, which change about once a second
+#[cfg(any(feature = "terminal", feature = "sdl2", feature = "minifb", feature = "pixels"))]
+struct TitleBar {
+    info: chip8::TitleInfo,
+}
 
-    //8XY4: Adds VY to VX. VF is set to 1 when there's a carry and to 0 when there is not
-    fn op_0x8xy4(&mut self, x: usize, y: usize) -> ProgramCounterInstruction {
-        let result = (self.v[x] as u16) + (self.v[y] as u16);
-        self.v[x] = result as u8;
-        self.v[0x0F] = if result > 0xFF { 1 } else { 0 };
-        NEXT
+#[cfg(any(feature = "terminal", feature = "sdl2", feature = "minifb", feature = "pixels"))]
+impl TitleBar {
+    fn new(chip8: &Chip8, options: &Options) -> TitleBar {
+        let rom_name = chip8::rom_name(&options.rom_path);
+        TitleBar { info: chip8::TitleInfo { rom_name, variant: chip8.variant(), ..chip8::TitleInfo::default() } }
     }
 
-    //8XY5: VY is subtracted from VX. VF is set to 0 when there's a borrow, and 1 when there is not.
-    fn op_0x8xy5(&mut self, x: usize, y: usize) -> ProgramCounterInstruction {
-        let result = self.v[x].wrapping_sub(self.v[y]);
-        self.v[0x0F] = if self.v[x] > self.v[y] { 1 } else { 0 };
-        self.v[x] = result as u8;
-        NEXT
+    fn text(&self) -> String {
+        chip8::window_title(&self.info)
     }
 
-    //8XY6: Stores the least significant bit of VX in VF and then shifts VX to the right by 1.
-    fn op_0x8xy6(&mut self, x: usize) -> ProgramCounterInstruction {
-        self.v[0x0F] = self.v[x] & 0x1;
-        self.v[x] >>= 1;
-        NEXT
+    fn set_paused(&mut self, paused: bool) -> String {
+        self.info.paused = paused;
+        self.text()
     }
 
-    //8XY7: Sets VX to VY minus VX. VF is set to 0 when there's a borrow, and 1 when there is not.
-    fn op_0x8xy7(&mut self, x: usize, y: usize) -> ProgramCounterInstruction {
-        self.v[0x0F] = if self.v[y] > self.v[x] { 1 } else { 0 };
-        let result = self.v[y].wrapping_sub(self.v[x]);
-        self.v[x] = result as u8;
-        NEXT
+    fn set_muted(&mut self, muted: bool) -> String {
+        self.info.muted = muted;
+        self.text()
     }
 
-    //8XYE: Stores the most significant bit of VX in VF and then shifts VX to the left by 1
-    fn op_0x8xye(&mut self, x: usize) -> ProgramCounterInstruction {
-        self.v[0x0F] = (self.v[x] & 0b1000_0000) >> 7;
-        self.v[x] <<= 1;
-        NEXT
+    fn set_turbo(&mut self, turbo: Option<u32>) -> String {
+        self.info.turbo = turbo;
+        self.text()
     }
 
-    //9XY0: Skips the next instruction if VX does not equal VY. (Usually the next instruction is a jump to skip a code block)
-    fn op_0x9xy0(&self, x: usize, y: usize) -> ProgramCounterInstruction {
-        if self.v[x] != self.v[y] {
-            SKIP
-        } else {
-            NEXT
+    // The new title when the rates changed
+    fn update(&mut self, throughput: chip8::Throughput) -> Option<String> {
+        if throughput == self.info.throughput {
+            return None;
         }
+        self.info.throughput = throughput;
+        Some(self.text())
     }
+}
 
-    //ANNN: Sets i to the address NNN
-    fn op_0xannn(&mut self, nnn: u16) -> ProgramCounterInstruction {
-        self.i = nnn;
-        NEXT
-    }
-
-    //BNNN: Jumps to the address NNN plus V0
-    fn op_0xbnnn(&mut self, nnn: u16) -> ProgramCounterInstruction {
-        GOTO(u16::from(self.v[0]) + nnn)
+// Saves the screen as a PNG file at the size and rotation it has in a window of the scale, a failure only loses the
+// screenshot
+#[cfg(all(
+    feature = "screenshot",
+    any(feature = "terminal", feature = "sdl2", feature = "minifb", feature = "pixels")
+))]
+fn take_screenshot(chip8: &Chip8, options: &Options) {
+    let framebuffer = chip8::rotate(&chip8.framebuffer(), chip8.width(), options.rotation);
+    let (width, _) = options.rotation.size(chip8.resolution());
+    let scale = (options.rotation.size(chip8::window_size(options.scale)).0 as usize / width).max(1);
+    match chip8::save_screenshot(&options.screenshot_dir, &options.rom_path, &framebuffer, width, scale, &options.theme) {
+        Ok(path) => println!("Screenshot saved to {}", path.display()),
+        Err(error) => eprintln!("Cannot save the screenshot : {error}"),
     }
+}
 
-    //CXNN: Sets VX to the result of a bitwise and operation on a random number (Typically: 0 to 255) and NN
-    fn op_0xcxnn(&mut self, x: usize, nn: u8) -> ProgramCounterInstruction {
-        let random_u8: u8 = rand::random();
-        self.v[x] = random_u8 & nn;
-        NEXT
-    }
+// GIF recording of a window frontend, from --record or F9 until F9 is pressed again or the emulator exits. The file is
+// finished when the recording is dropped, on errors too
+#[cfg(all(
+    feature = "record",
+    any(feature = "terminal", feature = "sdl2", feature = "minifb", feature = "pixels")
+))]
+struct Recording {
+    // Recorder and path of the file, None while not recording
+    recorder: Option<(chip8::GifRecorder<std::io::BufWriter<std::fs::File>>, PathBuf)>,
+}
 
-    //DXYN: Draws a sprite at coordinate (VX, VY) that has a width of 8 pixels and a height of N pixels.
-    // Each row of 8 pixels is read as bit-coded starting from memory location I; I value does not change after
-    // the execution of this instruction. As described above, VF is set to 1 if any screen pixels are flipped
-    // from set to unset when the sprite is drawn, and to 0 if that does not happen
-    fn op_0xdxyn(&self, x: usize, y: usize, n: u8) -> ProgramCounterInstruction { //TODO : Test
-        self.draw(self.v[x], self.v[y], n);
-        NEXT
+#[cfg(all(
+    feature = "record",
+    any(feature = "terminal", feature = "sdl2", feature = "minifb", feature = "pixels")
+))]
+impl Recording {
+    // Starts recording to the --record file if there is one
+    fn new(options: &Options) -> Recording {
+        let mut recording = Recording { recorder: None };
+        if let Some(path) = &options.record_path {
+            recording.start(path.clone(), options);
+        }
+        recording
     }
 
-    //EX9E: Skips the next instruction if the key stored in VX is pressed. (Usually the next instruction is a jump to skip a code block)
-    fn op_0xex9e(&self, x: usize) -> ProgramCounterInstruction { //TODO : Test
-        if self.key_pressed() == self.v[x] {
-            SKIP
+    // Stops the recording, or starts a new one in the screenshot directory named after the ROM and the time
+    fn toggle(&mut self, options: &Options) {
+        if self.recorder.is_some() {
+            self.stop();
         } else {
-            NEXT
+            let name = chip8::capture_file_name(&options.rom_path, std::time::SystemTime::now(), "gif");
+            if let Err(error) = std::fs::create_dir_all(&options.screenshot_dir) {
+                eprintln!("Cannot start the recording : {error}");
+                return;
+            }
+            self.start(options.screenshot_dir.join(name), options);
         }
     }
 
-    //EXA1: Skips the next instruction if the key stored in VX is not pressed. (Usually the next instruction is a jump to skip a code block)
-    fn op_0xexa1(&self, x: usize) -> ProgramCounterInstruction {
-        if self.key_pressed() != self.v[x] { //TODO : Test
-            SKIP
-        } else {
-            NEXT
+    fn start(&mut self, path: PathBuf, options: &Options) {
+        match chip8::GifRecorder::create(&path, options.scale, options.rotation, &options.theme) {
+            Ok(recorder) => {
+                println!("Recording to {}", path.display());
+                self.recorder = Some((recorder, path));
+            }
+            Err(error) => eprintln!("Cannot start the recording : {error}"),
         }
     }
 
-    fn set_keys(&self) {
-        todo!()
-    }
-    fn clear_screen(&self) {
-        todo!()
-    }
-    fn draw(&self, vx: u8, vy: u8, n: u8) {
-        todo!()
+    // Adds the screen for the 60 Hz frames run since the last call, in emulated time like the audio recording. A
+    // failure stops the recording
+    fn push(&mut self, chip8: &Chip8, frames: u32) {
+        if let Some((recorder, _)) = &mut self.recorder {
+            if let Err(error) = recorder.push_frames(&chip8.framebuffer(), chip8.width(), frames) {
+                eprintln!("Cannot record the screen : {error}");
+                self.recorder = None;
+            }
+        }
     }
-    fn key_pressed(&self) -> u8 {
-        todo!()
+
+    fn stop(&mut self) {
+        if let Some((mut recorder, path)) = self.recorder.take() {
+            match recorder.finish() {
+                Ok(()) => println!("Recording saved to {}", path.display()),
+                Err(error) => eprintln!("Cannot save the recording : {error}"),
+            }
+        }
     }
 }
 
-enum ProgramCounterInstruction {
-    NEXT,
-    SKIP,
-    GOTO(u16)
+#[cfg(all(
+    feature = "record",
+    any(feature = "terminal", feature = "sdl2", feature = "minifb", feature = "pixels")
+))]
+impl Drop for Recording {
+    fn drop(&mut self) {
+        self.stop();
+    }
 }
 
-fn setup_graphics() {
-    todo!()
+// WAV recording of the beep of a window frontend, from the start with --record-audio until the emulator exits. The
+// file is finished when the recording is dropped, on errors too
+#[cfg(all(
+    feature = "record-audio",
+    any(feature = "terminal", feature = "sdl2", feature = "minifb", feature = "pixels")
+))]
+struct AudioRecording {
+    // Recorder and path of the file, None without --record-audio or after a failure
+    recorder: Option<(chip8::WavRecorder<std::io::BufWriter<std::fs::File>>, PathBuf)>,
 }
 
-fn setup_input() {
-    todo!()
+#[cfg(all(
+    feature = "record-audio",
+    any(feature = "terminal", feature = "sdl2", feature = "minifb", feature = "pixels")
+))]
+impl AudioRecording {
+    // The sound of each frame run is noted by the tick hook of the machine
+    fn new(chip8: &mut Chip8, options: &Options) -> AudioRecording {
+        let Some(path) = &options.record_audio_path else {
+            return AudioRecording { recorder: None };
+        };
+        match chip8::WavRecorder::create(path, options.beep_wave, options.beep_frequency) {
+            Ok(recorder) => {
+                chip8.set_tick_hook(recorder.tick_hook());
+                println!("Recording the sound to {}", path.display());
+                AudioRecording { recorder: Some((recorder, path.clone())) }
+            }
+            Err(error) => {
+                eprintln!("Cannot start the audio recording : {error}");
+                AudioRecording { recorder: None }
+            }
+        }
+    }
+
+    // Adds the samples of the 60 Hz frames run since the last call. A failure stops the recording
+    fn write(&mut self) {
+        if let Some((recorder, _)) = &mut self.recorder {
+            if let Err(error) = recorder.write_frames() {
+                eprintln!("Cannot record the sound : {error}");
+                self.recorder = None;
+            }
+        }
+    }
 }
 
-fn draw_graphics() {
-    todo!()
+#[cfg(all(
+    feature = "record-audio",
+    any(feature = "terminal", feature = "sdl2", feature = "minifb", feature = "pixels")
+))]
+impl Drop for AudioRecording {
+    fn drop(&mut self) {
+        if let Some((mut recorder, path)) = self.recorder.take() {
+            match recorder.finish() {
+                Ok(()) => println!("Audio recording saved to {}", path.display()),
+                Err(error) => eprintln!("Cannot save the audio recording : {error}"),
+            }
+        }
+    }
 }
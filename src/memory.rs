@@ -0,0 +1,255 @@
+// Memory map
+// 0x000-0x1FF - Chip 8 interpreter (contains font set in emu)
+// 0x050-0x0A0 - Used for the built in 4x5 pixel font set (0-F)
+// 0x0A0-0x140 - Used for the built in 8x10 pixel SCHIP big font set (0-F)
+// 0x200-0xFFF - Program ROM and work RAM
+
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./memory_tests.rs"]
+mod memory_tests;
+
+use std::ops::{Deref, DerefMut, Range};
+use crate::{Chip8, Chip8Error};
+
+pub(crate) const MEMORY_SIZE: usize = 4096;
+// End of the interpreter area, programs have no reason to write below it
+pub(crate) const INTERPRETER_END: usize = 0x200;
+
+// The 4x5 pixel font set (0-F), loaded at 0x050 so that FX29 can point I at a digit
+pub(crate) const FONTSET_START: u16 = 0x50;
+pub(crate) const FONTSET: [u8; 80] = [
+    0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
+    0x20, 0x60, 0x20, 0x20, 0x70, // 1
+    0xF0, 0x10, 0xF0, 0x80, 0xF0, // 2
+    0xF0, 0x10, 0xF0, 0x10, 0xF0, // 3
+    0x90, 0x90, 0xF0, 0x10, 0x10, // 4
+    0xF0, 0x80, 0xF0, 0x10, 0xF0, // 5
+    0xF0, 0x80, 0xF0, 0x90, 0xF0, // 6
+    0xF0, 0x10, 0x20, 0x40, 0x40, // 7
+    0xF0, 0x90, 0xF0, 0x90, 0xF0, // 8
+    0xF0, 0x90, 0xF0, 0x10, 0xF0, // 9
+    0xF0, 0x90, 0xF0, 0x90, 0x90, // A
+    0xE0, 0x90, 0xE0, 0x90, 0xE0, // B
+    0xF0, 0x80, 0x80, 0x80, 0xF0, // C
+    0xE0, 0x90, 0x90, 0x90, 0xE0, // D
+    0xF0, 0x80, 0xF0, 0x80, 0xF0, // E
+    0xF0, 0x80, 0xF0, 0x80, 0x80, // F
+];
+
+// The 8x10 pixel SCHIP big font set, loaded right after the small font so that FX30 can point I at a digit.
+// The original SCHIP only has the digits 0-9, A-F are the glyphs used by modern interpreters
+pub(crate) const BIG_FONTSET_START: u16 = 0xA0;
+pub(crate) const BIG_FONTSET: [u8; 160] = [
+    0xFF, 0xFF, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, // 0
+    0x18, 0x78, 0x78, 0x18, 0x18, 0x18, 0x18, 0x18, 0xFF, 0xFF, // 1
+    0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, // 2
+    0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 3
+    0xC3, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, 0x03, 0x03, 0x03, 0x03, // 4
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 5
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, // 6
+    0xFF, 0xFF, 0x03, 0x03, 0x06, 0x0C, 0x18, 0x18, 0x18, 0x18, // 7
+    0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, // 8
+    0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 9
+    0x7E, 0xFF, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xC3, // A
+    0xFC, 0xFC, 0xC3, 0xC3, 0xFC, 0xFC, 0xC3, 0xC3, 0xFC, 0xFC, // B
+    0x3C, 0xFF, 0xC3, 0xC0, 0xC0, 0xC0, 0xC0, 0xC3, 0xFF, 0x3C, // C
+    0xFC, 0xFE, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFE, 0xFC, // D
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, // E
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xC0, 0xC0, // F
+];
+
+// Alternative 4x5 pixel font sets of other CHIP-8 computers, some test programs expect them
+const DREAM6800_FONTSET: [u8; 80] = [
+    0xE0, 0xA0, 0xA0, 0xA0, 0xE0, // 0
+    0x40, 0x40, 0x40, 0x40, 0x40, // 1
+    0xE0, 0x20, 0xE0, 0x80, 0xE0, // 2
+    0xE0, 0x20, 0xE0, 0x20, 0xE0, // 3
+    0x80, 0xA0, 0xA0, 0xE0, 0x20, // 4
+    0xE0, 0x80, 0xE0, 0x20, 0xE0, // 5
+    0xE0, 0x80, 0xE0, 0xA0, 0xE0, // 6
+    0xE0, 0x20, 0x20, 0x20, 0x20, // 7
+    0xE0, 0xA0, 0xE0, 0xA0, 0xE0, // 8
+    0xE0, 0xA0, 0xE0, 0x20, 0xE0, // 9
+    0xE0, 0xA0, 0xE0, 0xA0, 0xA0, // A
+    0xC0, 0xA0, 0xE0, 0xA0, 0xC0, // B
+    0xE0, 0x80, 0x80, 0x80, 0xE0, // C
+    0xC0, 0xA0, 0xA0, 0xA0, 0xC0, // D
+    0xE0, 0x80, 0xE0, 0x80, 0xE0, // E
+    0xE0, 0x80, 0xC0, 0x80, 0x80, // F
+];
+const ETI660_FONTSET: [u8; 80] = [
+    0xE0, 0xA0, 0xA0, 0xA0, 0xE0, // 0
+    0x20, 0x20, 0x20, 0x20, 0x20, // 1
+    0xE0, 0x20, 0xE0, 0x80, 0xE0, // 2
+    0xE0, 0x20, 0xE0, 0x20, 0xE0, // 3
+    0xA0, 0xA0, 0xE0, 0x20, 0x20, // 4
+    0xE0, 0x80, 0xE0, 0x20, 0xE0, // 5
+    0xE0, 0x80, 0xE0, 0xA0, 0xE0, // 6
+    0xE0, 0x20, 0x20, 0x20, 0x20, // 7
+    0xE0, 0xA0, 0xE0, 0xA0, 0xE0, // 8
+    0xE0, 0xA0, 0xE0, 0x20, 0xE0, // 9
+    0xE0, 0xA0, 0xE0, 0xA0, 0xA0, // A
+    0x80, 0x80, 0xE0, 0xA0, 0xE0, // B
+    0xE0, 0x80, 0x80, 0x80, 0xE0, // C
+    0x20, 0x20, 0xE0, 0xA0, 0xE0, // D
+    0xE0, 0x80, 0xE0, 0x80, 0xE0, // E
+    0xE0, 0x80, 0xC0, 0x80, 0x80, // F
+];
+
+// What to do when a program writes to the interpreter area (0x000-0x1FF), usually a bug corrupting the fonts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProtectedWritePolicy {
+    // Write like the original interpreters, some self-modifying COSMAC VIP programs use the low memory
+    Allow,
+    // Write and report it on stderr
+    Log,
+    // Trap with a Chip8Error::ProtectedWrite
+    Deny,
+}
+
+// Why a memory access failed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum MemoryFault {
+    OutOfBounds,
+    Protected,
+}
+
+// The 4K memory. Writes to the interpreter area go through the protected write policy, the memory can still be
+// indexed directly like a slice for loading ROMs and fonts
+#[derive(Clone, PartialEq)]
+pub(crate) struct Memory {
+    bytes: [u8; MEMORY_SIZE],
+    pub(crate) protected_write_policy: ProtectedWritePolicy,
+}
+
+// The interpreter area is only used by the font sets
+impl Default for Memory {
+    fn default() -> Memory {
+        let mut bytes = [0; MEMORY_SIZE];
+        let font_start = usize::from(FONTSET_START);
+        bytes[font_start..font_start + FONTSET.len()].copy_from_slice(&FONTSET);
+        let big_font_start = usize::from(BIG_FONTSET_START);
+        bytes[big_font_start..big_font_start + BIG_FONTSET.len()].copy_from_slice(&BIG_FONTSET);
+        Memory { bytes, protected_write_policy: ProtectedWritePolicy::Allow }
+    }
+}
+
+impl Memory {
+    pub(crate) fn read(&self, addr: usize) -> Result<u8, MemoryFault> {
+        self.bytes.get(addr).copied().ok_or(MemoryFault::OutOfBounds)
+    }
+
+    pub(crate) fn write(&mut self, addr: usize, value: u8) -> Result<(), MemoryFault> {
+        if addr < INTERPRETER_END {
+            match self.protected_write_policy {
+                ProtectedWritePolicy::Allow => {}
+                ProtectedWritePolicy::Log => eprintln!("Write to the interpreter area : 0x{addr:04X}"),
+                ProtectedWritePolicy::Deny => return Err(MemoryFault::Protected),
+            }
+        }
+        let cell = self.bytes.get_mut(addr).ok_or(MemoryFault::OutOfBounds)?;
+        *cell = value;
+        Ok(())
+    }
+}
+
+impl Deref for Memory {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl DerefMut for Memory {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes
+    }
+}
+
+impl Chip8 {
+    // Replaces the 4x5 font set used by FX29, it must not overlap the program area
+    pub fn load_font(&mut self, font: &[u8; 80]) -> Result<(), Chip8Error> {
+        self.write_font(FONTSET_START, font)
+    }
+
+    // Replaces the 8x10 big font set used by FX30, it must not overlap the program area
+    pub fn load_big_font(&mut self, font: &[u8; 160]) -> Result<(), Chip8Error> {
+        self.write_font(BIG_FONTSET_START, font)
+    }
+
+    fn write_font(&mut self, start: u16, font: &[u8]) -> Result<(), Chip8Error> {
+        let start = usize::from(start);
+        if start + font.len() > usize::from(self.start_address) {
+            return Err(Chip8Error::FontOverlapsProgram { start_address: self.start_address });
+        }
+        self.memory[start..start + font.len()].copy_from_slice(font);
+        Ok(())
+    }
+
+    // Hex dump of a memory range, 16 bytes per line prefixed with the address of the first one.
+    // The range is cut at the end of the memory
+    pub fn dump_memory(&self, range: Range<usize>) -> String {
+        let range = range.start.min(MEMORY_SIZE)..range.end.min(MEMORY_SIZE);
+        let mut dump = String::new();
+        for (line, bytes) in self.memory[range.clone()].chunks(16).enumerate() {
+            let bytes: Vec<String> = bytes.iter().map(|byte| format!("{byte:02X}")).collect();
+            dump += &format!("0x{:04X}: {}\n", range.start + line * 16, bytes.join(" "));
+        }
+        dump
+    }
+
+    pub(crate) fn read_byte(&self, addr: usize) -> Result<u8, Chip8Error> {
+        self.memory.read(addr).map_err(|fault| self.memory_error(addr, fault))
+    }
+
+    // Opcodes are stored big-endian
+    pub(crate) fn read_word(&self, addr: usize) -> Result<u16, Chip8Error> {
+        Ok(u16::from(self.read_byte(addr)?) << 8 | u16::from(self.read_byte(addr + 1)?))
+    }
+
+    pub(crate) fn write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Chip8Error> {
+        self.memory.write(addr, byte).map_err(|fault| self.memory_error(addr, fault))
+    }
+
+    // Addresses past the end of the 16-bit address space are reported as 0xFFFF
+    fn memory_error(&self, addr: usize, fault: MemoryFault) -> Chip8Error {
+        let addr = u16::try_from(addr).unwrap_or(u16::MAX);
+        match fault {
+            MemoryFault::OutOfBounds => Chip8Error::MemoryOutOfBounds { addr, pc: self.pc },
+            MemoryFault::Protected => Chip8Error::ProtectedWrite { addr, pc: self.pc },
+        }
+    }
+}
+
+// Built-in 4x5 font sets
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FontStyle {
+    // COSMAC VIP font
+    Chip8,
+    // DREAM 6800 font
+    Dream6800,
+    // ETI-660 font
+    Eti660,
+}
+
+impl FontStyle {
+    // Font of a --font option
+    pub fn from_name(name: &str) -> Option<FontStyle> {
+        match name {
+            "chip8" => Some(FontStyle::Chip8),
+            "dream6800" => Some(FontStyle::Dream6800),
+            "eti660" => Some(FontStyle::Eti660),
+            _ => None,
+        }
+    }
+
+    pub fn glyphs(self) -> &'static [u8; 80] {
+        match self {
+            FontStyle::Chip8 => &FONTSET,
+            FontStyle::Dream6800 => &DREAM6800_FONTSET,
+            FontStyle::Eti660 => &ETI660_FONTSET,
+        }
+    }
+}
@@ -0,0 +1,420 @@
+#[cfg(test)]
+mod memory_tests {
+    use crate::{Chip8, Chip8Config, Chip8Error, FontStyle, ProtectedWritePolicy};
+    use crate::memory::{BIG_FONTSET, FONTSET};
+    use crate::ProgramCounterInstruction::NEXT;
+
+    #[test]
+    fn op_0x5xy2_stores_vx_to_vy_in_memory_at_i() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.v[1..5].copy_from_slice(&[0x01, 0x02, 0x03, 0x04]);
+
+        let result = chip8.op_0x5xy2(2, 4);
+
+        assert!(matches!(result, Ok(NEXT)));
+        assert_eq!(chip8.memory[0x300..0x304], [0x02, 0x03, 0x04, 0x00]);
+        assert_eq!(chip8.index(), 0x300);
+    }
+
+    #[test]
+    fn op_0x5xy2_stores_vx_to_vy_in_reverse_order_when_x_is_greater_than_y() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.v[1..5].copy_from_slice(&[0x01, 0x02, 0x03, 0x04]);
+
+        chip8.op_0x5xy2(4, 2).unwrap();
+
+        assert_eq!(chip8.memory[0x300..0x304], [0x04, 0x03, 0x02, 0x00]);
+        assert_eq!(chip8.index(), 0x300);
+    }
+
+    #[test]
+    fn op_0x5xy2_stores_a_single_register_when_x_equals_y() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.v[3] = 0xAB;
+
+        chip8.op_0x5xy2(3, 3).unwrap();
+
+        assert_eq!(chip8.memory[0x300..0x302], [0xAB, 0x00]);
+        assert_eq!(chip8.index(), 0x300);
+    }
+
+    #[test]
+    fn op_0x5xy3_fills_vx_to_vy_from_memory_at_i() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.memory[0x300..0x304].copy_from_slice(&[0x01, 0x02, 0x03, 0x04]);
+
+        let result = chip8.op_0x5xy3(2, 4);
+
+        assert!(matches!(result, Ok(NEXT)));
+        assert_eq!(chip8.v[1..6], [0x00, 0x01, 0x02, 0x03, 0x00]);
+        assert_eq!(chip8.index(), 0x300);
+    }
+
+    #[test]
+    fn op_0x5xy3_fills_vx_to_vy_in_reverse_order_when_x_is_greater_than_y() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.memory[0x300..0x304].copy_from_slice(&[0x01, 0x02, 0x03, 0x04]);
+
+        chip8.op_0x5xy3(4, 2).unwrap();
+
+        assert_eq!(chip8.v[1..6], [0x00, 0x03, 0x02, 0x01, 0x00]);
+        assert_eq!(chip8.index(), 0x300);
+    }
+
+    #[test]
+    fn op_0x5xy3_fills_a_single_register_when_x_equals_y() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.memory[0x300..0x302].copy_from_slice(&[0xAB, 0xCD]);
+
+        chip8.op_0x5xy3(3, 3).unwrap();
+
+        assert_eq!(chip8.v[2..5], [0x00, 0xAB, 0x00]);
+        assert_eq!(chip8.index(), 0x300);
+    }
+
+    #[test]
+    fn op_0x5xy2_is_not_decoded_as_a_skip() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.memory[0x200..0x202].copy_from_slice(&[0x51, 0x12]);
+
+        chip8.emulate_cycle().unwrap();
+
+        assert_eq!(chip8.pc(), 0x202);
+    }
+
+    #[test]
+    fn default_loads_font_set_at_0x50() {
+        let chip8 = Chip8::default();
+
+        assert_eq!(chip8.memory[0x50..0x55], [0xF0, 0x90, 0x90, 0x90, 0xF0]);
+        assert_eq!(chip8.memory[0x9B..0xA0], [0xF0, 0x80, 0xF0, 0x80, 0x80]);
+        assert_eq!(chip8.memory[0x50..0xA0], FONTSET);
+    }
+
+    #[test]
+    fn op_0xfx29_sets_i_to_the_sprite_address_of_the_digit_in_vx() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+
+        for digit in 0x0..=0xF {
+            chip8.v[x] = digit;
+
+            let result = chip8.op_0xfx29(x);
+
+            assert!(matches!(result, NEXT));
+            assert_eq!(chip8.index(), 0x50 + u16::from(digit) * 5);
+        }
+    }
+
+    #[test]
+    fn op_0xfx29_points_i_at_the_glyph_of_a_custom_font() {
+        let mut chip8 = Chip8::default();
+        chip8.load_font(FontStyle::Eti660.glyphs()).unwrap();
+        chip8.v[1] = 0x1;
+
+        chip8.op_0xfx29(1);
+
+        let glyph = usize::from(chip8.i);
+        assert_eq!(chip8.memory[glyph..glyph + 5], [0x20, 0x20, 0x20, 0x20, 0x20]);
+    }
+
+    #[test]
+    fn op_0xfx30_points_i_at_the_glyph_of_a_custom_big_font() {
+        let mut chip8 = Chip8::default();
+        let mut font = [0; 160];
+        font[20..30].copy_from_slice(&[0x3C; 10]);
+        chip8.load_big_font(&font).unwrap();
+        chip8.v[1] = 0x2;
+
+        chip8.op_0xfx30(1);
+
+        let glyph = usize::from(chip8.i);
+        assert_eq!(chip8.memory[glyph..glyph + 10], [0x3C; 10]);
+    }
+
+    #[test]
+    fn font_style_from_name_reads_the_names_of_the_font_option() {
+        assert_eq!(FontStyle::from_name("chip8"), Some(FontStyle::Chip8));
+        assert_eq!(FontStyle::from_name("dream6800"), Some(FontStyle::Dream6800));
+        assert_eq!(FontStyle::from_name("eti660"), Some(FontStyle::Eti660));
+        assert_eq!(FontStyle::from_name("vip"), None);
+    }
+
+    #[test]
+    fn load_font_rejects_a_font_overlapping_the_program() {
+        let mut chip8 = Chip8::default();
+        chip8.set_start_address(0x80).unwrap();
+
+        let result = chip8.load_font(FontStyle::Dream6800.glyphs());
+
+        assert!(matches!(result, Err(Chip8Error::FontOverlapsProgram { start_address: 0x80 })));
+        assert_eq!(chip8.memory[0x50..0xA0], FONTSET);
+    }
+
+    #[test]
+    fn default_loads_big_font_set_after_the_font_set() {
+        let chip8 = Chip8::default();
+
+        assert_eq!(chip8.memory[0xA0..0xAA], [0xFF, 0xFF, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF]);
+        assert_eq!(chip8.memory[0xA0..0x140], BIG_FONTSET);
+        assert_eq!(chip8.memory[0x50..0xA0], FONTSET);
+    }
+
+    #[test]
+    fn op_0xfx30_sets_i_to_the_big_sprite_address_of_the_digit_in_vx() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+
+        for digit in 0x0..=0x9 {
+            chip8.v[x] = digit;
+
+            let result = chip8.op_0xfx30(x);
+
+            assert!(matches!(result, NEXT));
+            assert_eq!(chip8.index(), 0xA0 + u16::from(digit) * 10);
+        }
+    }
+
+    #[test]
+    fn op_0xfx30_uses_the_low_nibble_of_vx_above_9() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        chip8.v[x] = 0x1A;
+
+        chip8.op_0xfx30(x);
+
+        assert_eq!(chip8.index(), 0xA0 + 0xA * 10);
+        assert_eq!(chip8.memory[usize::from(chip8.index())..usize::from(chip8.index()) + 2], [0x7E, 0xFF]);
+    }
+
+    #[test]
+    fn op_0xfx33_stores_the_decimal_digits_of_vx_at_i() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.v[4] = 254;
+
+        let result = chip8.op_0xfx33(4);
+
+        assert!(matches!(result, Ok(NEXT)));
+        assert_eq!(chip8.memory[0x300..0x303], [2, 5, 4]);
+        assert_eq!(chip8.index(), 0x300);
+    }
+
+    #[test]
+    fn op_0xfx33_stores_leading_zeros() {
+        let mut chip8 = Chip8::with_program(&[0x6007, 0xA300, 0xF033]);
+
+        chip8.step_n(3).unwrap();
+
+        assert_eq!(chip8.memory[0x300..0x303], [0, 0, 7]);
+    }
+
+    #[test]
+    fn op_0xfx33_returns_an_error_below_0x200_with_the_deny_policy() {
+        let mut chip8 = Chip8Config::new().protected_write_policy(ProtectedWritePolicy::Deny).build().unwrap();
+        chip8.i = 0x50;
+
+        let result = chip8.op_0xfx33(0);
+
+        assert!(matches!(result, Err(Chip8Error::ProtectedWrite { addr: 0x50, pc: 0x200 })));
+        assert_eq!(chip8.memory[0x50..0x53], FONTSET[..3]);
+    }
+
+    #[test]
+    fn op_0xfx55_stores_v0_in_memory_at_i_and_increments_i() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.v[0] = 0xAB;
+        chip8.v[1] = 0xCD;
+
+        let result = chip8.op_0xfx55(0);
+
+        assert!(matches!(result, Ok(NEXT)));
+        assert_eq!(chip8.memory[0x300], 0xAB);
+        assert_eq!(chip8.memory[0x301], 0x00);
+        assert_eq!(chip8.index(), 0x301);
+    }
+
+    #[test]
+    fn op_0xfx55_stores_v0_to_vf_in_memory_at_i() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        for register in 0..16 {
+            chip8.v[register] = register as u8 + 1;
+        }
+
+        chip8.op_0xfx55(0xF).unwrap();
+
+        for register in 0..16 {
+            assert_eq!(chip8.memory[0x300 + register], register as u8 + 1);
+        }
+        assert_eq!(chip8.index(), 0x310);
+    }
+
+    #[test]
+    fn op_0xfx55_does_not_change_i_with_load_store_quirk() {
+        let mut chip8 = Chip8Config::new().load_store_quirk(true).build().unwrap();
+        chip8.i = 0x300;
+        chip8.v[0] = 0xAB;
+        chip8.v[1] = 0xCD;
+
+        chip8.op_0xfx55(1).unwrap();
+
+        assert_eq!(chip8.memory[0x300..0x302], [0xAB, 0xCD]);
+        assert_eq!(chip8.index(), 0x300);
+    }
+
+    #[test]
+    fn op_0xfx55_increments_i_past_0xfff_without_index_mask() {
+        let mut chip8 = Chip8 { i: 0xFFC, ..Chip8::default() };
+
+        chip8.op_0xfx55(3).unwrap();
+
+        assert_eq!(chip8.index(), 0x1000);
+    }
+
+    #[test]
+    fn op_0xfx55_wraps_i_to_12_bits_with_index_mask() {
+        let mut chip8 = Chip8 { i: 0xFFC, ..Chip8Config::new().index_mask_12bit(true).build().unwrap() };
+
+        chip8.op_0xfx55(3).unwrap();
+
+        assert_eq!(chip8.index(), 0x000);
+    }
+
+    #[test]
+    fn op_0xfx55_returns_an_error_past_the_end_of_memory() {
+        let mut chip8 = Chip8 { i: 0xFFE, ..Chip8::default() };
+        chip8.v[0..4].copy_from_slice(&[0x01, 0x02, 0x03, 0x04]);
+
+        let result = chip8.op_0xfx55(3);
+
+        assert!(matches!(result, Err(Chip8Error::MemoryOutOfBounds { addr: 0x1000, pc: 0x200 })));
+        assert_eq!(chip8.index(), 0xFFE);
+    }
+
+    #[test]
+    fn op_0xfx55_returns_an_error_below_0x200_with_the_deny_policy() {
+        let mut chip8 = Chip8Config::new().protected_write_policy(ProtectedWritePolicy::Deny).build().unwrap();
+        chip8.i = 0x50;
+        chip8.v[0] = 0xAA;
+
+        let result = chip8.op_0xfx55(0);
+
+        assert!(matches!(result, Err(Chip8Error::ProtectedWrite { addr: 0x50, pc: 0x200 })));
+        assert_eq!(chip8.memory[0x50], FONTSET[0]);
+    }
+
+    #[test]
+    fn op_0xfx55_writes_below_0x200_with_the_log_policy() {
+        let mut chip8 = Chip8Config::new().protected_write_policy(ProtectedWritePolicy::Log).build().unwrap();
+        chip8.i = 0x1F0;
+        chip8.v[0] = 0xAA;
+
+        let result = chip8.op_0xfx55(0);
+
+        assert!(matches!(result, Ok(NEXT)));
+        assert_eq!(chip8.memory[0x1F0], 0xAA);
+    }
+
+    #[test]
+    fn op_0xfx55_writes_at_0x200_with_the_deny_policy() {
+        let mut chip8 = Chip8Config::new().protected_write_policy(ProtectedWritePolicy::Deny).build().unwrap();
+        chip8.i = 0x200;
+        chip8.v[0] = 0xAA;
+
+        let result = chip8.op_0xfx55(0);
+
+        assert!(matches!(result, Ok(NEXT)));
+        assert_eq!(chip8.memory[0x200], 0xAA);
+    }
+
+    #[test]
+    fn fonts_are_intact_after_a_program_storing_over_them_with_the_deny_policy() {
+        let mut chip8 = Chip8Config::new().protected_write_policy(ProtectedWritePolicy::Deny).build().unwrap();
+        // Points I at the font with a small address and stores all the registers there
+        chip8.load_rom(&[0x60, 0xFF, 0xA0, 0x50, 0xFF, 0x55]).unwrap();
+
+        let results: Vec<_> = (0..3).map(|_| chip8.emulate_cycle()).collect();
+
+        assert!(matches!(results[2], Err(Chip8Error::ProtectedWrite { addr: 0x50, pc: 0x204 })));
+        assert_eq!(chip8.memory[0x50..0x50 + FONTSET.len()], FONTSET);
+        assert_eq!(chip8.memory[0xA0..0xA0 + BIG_FONTSET.len()], BIG_FONTSET);
+    }
+
+    #[test]
+    fn op_0xfx65_fills_v0_from_memory_at_i_and_increments_i() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        chip8.memory[0x300] = 0xAB;
+        chip8.memory[0x301] = 0xCD;
+
+        let result = chip8.op_0xfx65(0);
+
+        assert!(matches!(result, Ok(NEXT)));
+        assert_eq!(chip8.register(0), 0xAB);
+        assert_eq!(chip8.register(1), 0x00);
+        assert_eq!(chip8.index(), 0x301);
+    }
+
+    #[test]
+    fn op_0xfx65_fills_v0_to_vf_from_memory_at_i() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        for offset in 0..16 {
+            chip8.memory[0x300 + offset] = offset as u8 + 1;
+        }
+
+        chip8.op_0xfx65(0xF).unwrap();
+
+        for register in 0..16 {
+            assert_eq!(chip8.register(register), register as u8 + 1);
+        }
+        assert_eq!(chip8.index(), 0x310);
+    }
+
+    #[test]
+    fn op_0xfx65_does_not_change_i_with_load_store_quirk() {
+        let mut chip8 = Chip8Config::new().load_store_quirk(true).build().unwrap();
+        chip8.i = 0x300;
+        chip8.memory[0x300] = 0xAB;
+        chip8.memory[0x301] = 0xCD;
+
+        chip8.op_0xfx65(1).unwrap();
+
+        assert_eq!(chip8.v[0..2], [0xAB, 0xCD]);
+        assert_eq!(chip8.index(), 0x300);
+    }
+
+    #[test]
+    fn op_0xfx65_wraps_i_to_12_bits_with_index_mask() {
+        let mut chip8 = Chip8 { i: 0xFFE, ..Chip8Config::new().index_mask_12bit(true).build().unwrap() };
+
+        chip8.op_0xfx65(1).unwrap();
+
+        assert_eq!(chip8.index(), 0x000);
+    }
+
+    #[test]
+    fn op_0xfx65_returns_an_error_past_the_end_of_memory() {
+        let mut chip8 = Chip8 { i: 0xFFE, ..Chip8::default() };
+
+        let result = chip8.op_0xfx65(3);
+
+        assert!(matches!(result, Err(Chip8Error::MemoryOutOfBounds { addr: 0x1000, pc: 0x200 })));
+        assert_eq!(chip8.index(), 0xFFE);
+    }
+
+    #[test]
+    fn dump_memory_prints_16_bytes_per_line() {
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(&[0x60, 0x05, 0xA2, 0x10]).unwrap();
+
+        let dump = chip8.dump_memory(0x200..0x214);
+
+        assert_eq!(dump, "0x0200: 60 05 A2 10 00 00 00 00 00 00 00 00 00 00 00 00\n0x0210: 00 00 00 00\n");
+    }
+
+    #[test]
+    fn dump_memory_stops_at_the_end_of_the_memory() {
+        let chip8 = Chip8::default();
+
+        assert_eq!(chip8.dump_memory(0xFFE..0x2000), "0x0FFE: 00 00\n");
+        assert_eq!(chip8.dump_memory(0x2000..0x3000), "");
+    }
+}
@@ -0,0 +1,292 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./minifb_frontend_tests.rs"]
+mod minifb_frontend_tests;
+
+// minifb frontend, enabled with the minifb feature: a window showing the screen and the keyboard as the keypad, with
+// no native library needed
+
+use std::iter;
+use std::mem;
+use minifb::{Key as WindowKey, KeyRepeat, ScaleMode, Window, WindowOptions};
+use crate::{
+    control, crt_image, letterbox, overlay_scale, rotate, shade, window_size, Control, ControlKey, Crt, Display,
+    Input, Keymap, Phosphor, PhysicalKey, Rgb, Rotation, TextBitmap, Theme, WindowFrontend,
+};
+
+// Key of minifb for each key of the emulator controls
+fn control_window_key(key: ControlKey) -> WindowKey {
+    match key {
+        ControlKey::Escape => WindowKey::Escape,
+        ControlKey::Backspace => WindowKey::Backspace,
+        ControlKey::Letter(letter) => window_key(letter),
+    }
+}
+
+// Key of minifb for each keyboard key of the keymap
+fn window_key(physical_key: PhysicalKey) -> WindowKey {
+    match physical_key {
+        PhysicalKey::Key0 => WindowKey::Key0,
+        PhysicalKey::Key1 => WindowKey::Key1,
+        PhysicalKey::Key2 => WindowKey::Key2,
+        PhysicalKey::Key3 => WindowKey::Key3,
+        PhysicalKey::Key4 => WindowKey::Key4,
+        PhysicalKey::Key5 => WindowKey::Key5,
+        PhysicalKey::Key6 => WindowKey::Key6,
+        PhysicalKey::Key7 => WindowKey::Key7,
+        PhysicalKey::Key8 => WindowKey::Key8,
+        PhysicalKey::Key9 => WindowKey::Key9,
+        PhysicalKey::A => WindowKey::A,
+        PhysicalKey::B => WindowKey::B,
+        PhysicalKey::C => WindowKey::C,
+        PhysicalKey::D => WindowKey::D,
+        PhysicalKey::E => WindowKey::E,
+        PhysicalKey::F => WindowKey::F,
+        PhysicalKey::G => WindowKey::G,
+        PhysicalKey::H => WindowKey::H,
+        PhysicalKey::I => WindowKey::I,
+        PhysicalKey::J => WindowKey::J,
+        PhysicalKey::K => WindowKey::K,
+        PhysicalKey::L => WindowKey::L,
+        PhysicalKey::M => WindowKey::M,
+        PhysicalKey::N => WindowKey::N,
+        PhysicalKey::O => WindowKey::O,
+        PhysicalKey::P => WindowKey::P,
+        PhysicalKey::Q => WindowKey::Q,
+        PhysicalKey::R => WindowKey::R,
+        PhysicalKey::S => WindowKey::S,
+        PhysicalKey::T => WindowKey::T,
+        PhysicalKey::U => WindowKey::U,
+        PhysicalKey::V => WindowKey::V,
+        PhysicalKey::W => WindowKey::W,
+        PhysicalKey::X => WindowKey::X,
+        PhysicalKey::Y => WindowKey::Y,
+        PhysicalKey::Z => WindowKey::Z,
+        PhysicalKey::Up => WindowKey::Up,
+        PhysicalKey::Down => WindowKey::Down,
+        PhysicalKey::Left => WindowKey::Left,
+        PhysicalKey::Right => WindowKey::Right,
+        PhysicalKey::Space => WindowKey::Space,
+    }
+}
+
+// Converts the colors of the pixels to ARGB, each pixel repeated in a square of scale by scale
+pub(crate) fn to_argb(colors: &[Rgb], width: usize, scale: usize) -> Vec<u32> {
+    let mut buffer = Vec::with_capacity(colors.len() * scale * scale);
+    for row in colors.chunks(width) {
+        let line: Vec<u32> = row.iter()
+            .flat_map(|&[red, green, blue]| iter::repeat_n(u32::from_be_bytes([0xFF, red, green, blue]), scale))
+            .collect();
+        for _ in 0..scale {
+            buffer.extend_from_slice(&line);
+        }
+    }
+    buffer
+}
+
+pub struct MinifbFrontend {
+    window: Window,
+    // Last drawn screen, rotated, and its size
+    rotation: Rotation,
+    framebuffer: Vec<u8>,
+    width: usize,
+    height: usize,
+    theme: Theme,
+    // Screen shown in the window, following the drawn one with the fading of the pixels turned off
+    phosphor: Phosphor,
+    // Window contents: the scaled screen within borders of the background color, rebuilt when the screen changes or
+    // the window is resized
+    buffer: Vec<u32>,
+    buffer_width: usize,
+    buffer_height: usize,
+    // Debug overlay over the top left corner of the screen, toggled by F3
+    overlay_shown: bool,
+    overlay: Option<TextBitmap>,
+    // CRT effect of the scaled screen, and the one F6 switches to
+    crt: Crt,
+    crt_toggled: Crt,
+    // The window has the keyboard focus
+    focused: bool,
+    keymap: Keymap,
+}
+
+impl MinifbFrontend {
+    // Opens a window of the low resolution screen with each pixel a square of scale by scale, turned by the rotation
+    pub fn new(title: &str, scale: u32, rotation: Rotation) -> Result<MinifbFrontend, minifb::Error> {
+        let (window_width, window_height) = rotation.size(window_size(scale));
+        let (width, height) = rotation.size((64, 32));
+        let (buffer_width, buffer_height) = (window_width as usize, window_height as usize);
+        let options = WindowOptions { resize: true, scale_mode: ScaleMode::UpperLeft, ..WindowOptions::default() };
+        let mut window = Window::new(title, buffer_width, buffer_height, options)?;
+        // The emulation loop paces the updates, minifb does not limit them
+        window.set_target_fps(0);
+        Ok(MinifbFrontend {
+            window,
+            rotation,
+            framebuffer: vec![0; width * height],
+            width,
+            height,
+            theme: Theme::default(),
+            phosphor: Phosphor::new(0),
+            buffer: vec![Theme::default().argb(0); buffer_width * buffer_height],
+            buffer_width,
+            buffer_height,
+            overlay_shown: false,
+            overlay: None,
+            crt: Crt::OFF,
+            crt_toggled: Crt::SCANLINES,
+            focused: true,
+            keymap: Keymap::default(),
+        })
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.refresh();
+    }
+
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+    }
+
+    // Frames a pixel stays visible after turning off, 0 shows the screen as it is
+    pub fn set_phosphor(&mut self, decay_frames: u32) {
+        self.phosphor = Phosphor::new(decay_frames);
+        self.refresh();
+    }
+
+    // F6 switches between this effect and none, or the scanlines when it is none
+    pub fn set_crt(&mut self, crt: Crt) {
+        self.crt = crt;
+        self.crt_toggled = if crt.is_off() { Crt::SCANLINES } else { Crt::OFF };
+        self.compose();
+    }
+
+    // Moves the shown screen one frame towards the drawn one, then draws it
+    fn refresh(&mut self) {
+        self.phosphor.step(&self.framebuffer);
+        self.compose();
+    }
+
+    // Draws the shown screen at the largest whole scale fitting in the window, centered
+    fn compose(&mut self) {
+        let (window_width, window_height) = self.window.get_size();
+        let viewport = letterbox(self.width, self.height, window_width as u32, window_height as u32);
+        let (colors, scale) = (self.phosphor.colors(&self.theme), viewport.scale as usize);
+        let screen = if self.crt.is_off() {
+            to_argb(&colors, self.width, scale)
+        } else {
+            to_argb(&crt_image(&colors, self.width, scale, &self.crt), viewport.width as usize, 1)
+        };
+        let (x, y) = (viewport.x as usize, viewport.y as usize);
+        self.buffer = vec![self.theme.argb(0); window_width * window_height];
+        // The screen is cut when the window is smaller than it
+        let visible_width = (viewport.width as usize).min(window_width - x);
+        for (row, line) in screen.chunks(viewport.width as usize).enumerate().take(window_height - y) {
+            let start = (y + row) * window_width + x;
+            self.buffer[start..start + visible_width].copy_from_slice(&line[..visible_width]);
+        }
+        if let Some(overlay) = self.overlay.as_ref().filter(|_| self.overlay_shown) {
+            overlay.draw_over(&mut self.buffer, window_width, (x, y), overlay_scale(&viewport), |argb, lit| {
+                let [_, red, green, blue] = argb.to_be_bytes();
+                let [red, green, blue] = shade([red, green, blue], lit);
+                u32::from_be_bytes([0xFF, red, green, blue])
+            });
+        }
+        self.buffer_width = window_width;
+        self.buffer_height = window_height;
+    }
+}
+
+impl Display for MinifbFrontend {
+    fn draw(&mut self, framebuffer: &[u8], width: usize, height: usize) {
+        self.framebuffer = rotate(framebuffer, width, self.rotation);
+        (self.width, self.height) = self.rotation.size((width, height));
+        // With the phosphor the screen is shown by the next frame
+        if !self.phosphor.enabled() {
+            self.refresh();
+        }
+    }
+
+    fn clear(&mut self) {
+        self.framebuffer.fill(0);
+        if !self.phosphor.enabled() {
+            self.refresh();
+        }
+    }
+}
+
+impl Input for MinifbFrontend {
+    // The key states are the ones of the last update of the window
+    fn poll(&mut self) -> [bool; 16] {
+        self.keymap.poll(|physical_key| self.window.is_key_down(window_key(physical_key)))
+    }
+}
+
+impl WindowFrontend for MinifbFrontend {
+    // Shows the last drawn screen and handles the pending window events, returns false once the window is closed.
+    // Called once per 60 Hz frame, which is also when the fading pixels dim
+    fn handle_events(&mut self) -> bool {
+        if self.phosphor.enabled() {
+            self.refresh();
+        } else if self.window.get_size() != (self.buffer_width, self.buffer_height) {
+            self.compose();
+        }
+        // A failed update only loses this frame, the next one shows the buffer again
+        let _ = self.window.update_with_buffer(&self.buffer, self.buffer_width, self.buffer_height);
+        self.focused = self.window.is_active();
+        if self.window.is_key_pressed(WindowKey::F3, KeyRepeat::No) {
+            self.overlay_shown = !self.overlay_shown;
+            self.overlay = None;
+            self.compose();
+        }
+        if self.window.is_key_pressed(WindowKey::F6, KeyRepeat::No) {
+            mem::swap(&mut self.crt, &mut self.crt_toggled);
+            self.compose();
+        }
+        self.window.is_open()
+    }
+
+    // P, Backspace, R, Esc and Q pressed since the last update of the window, the letters when the keymap leaves them
+    fn take_controls(&mut self) -> Vec<Control> {
+        ControlKey::ALL.into_iter()
+            .filter(|&key| self.window.is_key_pressed(control_window_key(key), KeyRepeat::No))
+            .filter_map(|key| control(key, &self.keymap))
+            .collect()
+    }
+
+    // Whether Tab is held, to fast-forward the emulation
+    fn turbo_held(&self) -> bool {
+        self.window.is_key_down(WindowKey::Tab)
+    }
+
+    // Whether the window has the keyboard focus, as of the last update of the window
+    fn has_focus(&self) -> bool {
+        self.focused
+    }
+
+    fn set_title(&mut self, title: &str) {
+        self.window.set_title(title);
+    }
+
+    // Whether the debug overlay is shown, its lines are then given once per frame to set_overlay
+    fn overlay_shown(&self) -> bool {
+        self.overlay_shown
+    }
+
+    // Shown by the next update of the window
+    fn set_overlay(&mut self, lines: &[String]) {
+        self.overlay = Some(TextBitmap::new(lines));
+        self.compose();
+    }
+
+    // Whether F12 was pressed since the last update of the window
+    fn screenshot_requested(&mut self) -> bool {
+        self.window.is_key_pressed(WindowKey::F12, KeyRepeat::No)
+    }
+
+    // Whether F9 was pressed since the last update of the window, to start or stop a recording
+    fn record_requested(&mut self) -> bool {
+        self.window.is_key_pressed(WindowKey::F9, KeyRepeat::No)
+    }
+}
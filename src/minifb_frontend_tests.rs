@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod minifb_frontend_tests {
+    use crate::minifb_frontend::to_argb;
+    use crate::{Phosphor, Theme};
+
+    #[test]
+    fn to_argb_maps_set_pixels_to_the_foreground_at_every_scale() {
+        let (width, height) = (64, 32);
+        let mut framebuffer = vec![0; width * height];
+        // Corners and a pixel in the middle
+        for (x, y) in [(0, 0), (63, 0), (0, 31), (63, 31), (20, 10)] {
+            framebuffer[y * width + x] = 1;
+        }
+        let mut phosphor = Phosphor::new(0);
+        phosphor.step(&framebuffer);
+        let colors = phosphor.colors(&Theme::default());
+        for scale in 1..=10 {
+            let buffer = to_argb(&colors, width, scale);
+            assert_eq!(buffer.len(), width * height * scale * scale);
+            for (index, &color) in buffer.iter().enumerate() {
+                let (x, y) = (index % (width * scale) / scale, index / (width * scale) / scale);
+                let expected = if framebuffer[y * width + x] == 1 { 0xFFFF_FFFF } else { 0xFF00_0000 };
+                assert_eq!(color, expected, "pixel ({}, {}) at scale {}", x, y, scale);
+            }
+        }
+    }
+
+    #[test]
+    fn to_argb_gives_the_theme_colors() {
+        let theme = Theme::preset("amber").unwrap();
+        assert_eq!(to_argb(&[theme.color(0), theme.color(1)], 2, 1), [0xFF1A_1000, 0xFFFF_B000]);
+    }
+}
@@ -0,0 +1,209 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./overlay_tests.rs"]
+mod overlay_tests;
+
+// Debug overlay shown by the frontends over the game: the registers, the instruction at the PC and the measured speed,
+// written with a tiny bitmap font so that every frontend can draw it the same way
+
+use crate::{decode, Chip8, Rgb, Throughput, Viewport};
+
+// Font pixels of a character, and of a character with the space after it and the line below it
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+const CELL_WIDTH: usize = GLYPH_WIDTH + 1;
+const CELL_HEIGHT: usize = GLYPH_HEIGHT + 1;
+
+// State shown by the overlay, read once per frame
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DebugInfo {
+    pub pc: u16,
+    pub index: u16,
+    // Opcode at the PC, the next one to run
+    pub opcode: u16,
+    pub registers: [u8; 16],
+    pub delay_timer: u8,
+    pub sound_timer: u8,
+    pub stack_depth: usize,
+    // Zero until measured
+    pub instructions_per_second: f64,
+    // Frames run without being rendered because the host was late, since the start
+    pub skipped_frames: u64,
+    // Emulated frames per host frame, above 1 while fast-forwarding
+    pub speed: u32,
+}
+
+impl DebugInfo {
+    pub fn new(chip8: &Chip8, throughput: Throughput, skipped_frames: u64) -> DebugInfo {
+        let pc = usize::from(chip8.pc());
+        let memory = chip8.memory();
+        // A PC at the last byte of the memory reads a zero low byte, like past the end
+        let opcode = u16::from_be_bytes([
+            memory.get(pc).copied().unwrap_or(0),
+            memory.get(pc + 1).copied().unwrap_or(0),
+        ]);
+        DebugInfo {
+            pc: chip8.pc(),
+            index: chip8.index(),
+            opcode,
+            registers: std::array::from_fn(|x| chip8.register(x)),
+            delay_timer: chip8.delay_timer(),
+            sound_timer: chip8.sound_timer(),
+            stack_depth: chip8.stack().len(),
+            instructions_per_second: throughput.instructions_per_second,
+            skipped_frames,
+            speed: chip8.speed(),
+        }
+    }
+}
+
+// Instruction of an opcode as the disassembly of the debugger shows it, ???? when it does not decode
+pub fn mnemonic(opcode: u16) -> String {
+    match decode(opcode) {
+        Ok(instruction) => format!("{instruction:?}"),
+        Err(_) => String::from("????"),
+    }
+}
+
+// Lines of the overlay, the registers and the PC in hexadecimal like the debugger
+pub fn overlay_lines(info: &DebugInfo) -> Vec<String> {
+    let mut lines = vec![
+        format!("PC {:04X}  I {:04X}", info.pc, info.index),
+        format!("{:04X} {}", info.opcode, mnemonic(info.opcode)),
+    ];
+    for (row, registers) in info.registers.chunks(4).enumerate() {
+        let values: Vec<String> = registers.iter().map(|value| format!("{value:02X}")).collect();
+        lines.push(format!("V{:X}-{:X} {}", row * 4, row * 4 + 3, values.join(" ")));
+    }
+    lines.push(format!("DT {}  ST {}  SP {}", info.delay_timer, info.sound_timer, info.stack_depth));
+    lines.push(format!("IPS {:.0}  SKIP {}", info.instructions_per_second, info.skipped_frames));
+    if info.speed > 1 {
+        lines.push(format!("TURBO {}x", info.speed));
+    }
+    lines
+}
+
+// Rows of a character in the font, the 3 low bits of each from left to right. Lowercase letters are drawn as
+// uppercase ones and the characters missing from the font as a question mark
+fn glyph(character: char) -> [u8; GLYPH_HEIGHT] {
+    match character.to_ascii_uppercase() {
+        ' ' => [0b000, 0b000, 0b000, 0b000, 0b000],
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        ',' => [0b000, 0b000, 0b000, 0b010, 0b100],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '(' => [0b001, 0b010, 0b010, 0b010, 0b001],
+        ')' => [0b100, 0b010, 0b010, 0b010, 0b100],
+        '{' => [0b011, 0b010, 0b110, 0b010, 0b011],
+        '}' => [0b110, 0b010, 0b011, 0b010, 0b110],
+        _ => [0b111, 0b001, 0b010, 0b000, 0b010],
+    }
+}
+
+// Lines written with the font in a box with a margin of one font pixel, each pixel true where the text is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextBitmap {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<bool>,
+}
+
+impl TextBitmap {
+    pub fn new(lines: &[String]) -> TextBitmap {
+        let columns = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        let width = columns * CELL_WIDTH + 1;
+        let height = lines.len() * CELL_HEIGHT + 1;
+        let mut pixels = vec![false; width * height];
+        for (row, line) in lines.iter().enumerate() {
+            for (column, character) in line.chars().enumerate() {
+                for (y, bits) in glyph(character).into_iter().enumerate() {
+                    for x in 0..GLYPH_WIDTH {
+                        let lit = bits & (0b100 >> x) != 0;
+                        pixels[(1 + row * CELL_HEIGHT + y) * width + 1 + column * CELL_WIDTH + x] = lit;
+                    }
+                }
+            }
+        }
+        TextBitmap { width, height, pixels }
+    }
+
+    // Positions of the pixels of the text
+    pub fn lit(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.pixels.iter()
+            .enumerate()
+            .filter(|(_, &lit)| lit)
+            .map(|(index, _)| (index % self.width, index / self.width))
+    }
+
+    // Draws the box over a buffer of pixels of any format at (x, y), each font pixel a square of scale by scale. The
+    // pixels of the box are replaced by blend of the pixel and whether it is part of the text. The box is cut at the
+    // edges of the buffer
+    pub fn draw_over<P: Copy>(
+        &self,
+        buffer: &mut [P],
+        buffer_width: usize,
+        (x, y): (usize, usize),
+        scale: usize,
+        blend: impl Fn(P, bool) -> P,
+    ) {
+        let buffer_height = buffer.len() / buffer_width.max(1);
+        for row in y..(y + self.height * scale).min(buffer_height) {
+            for column in x..(x + self.width * scale).min(buffer_width) {
+                let lit = self.pixels[(row - y) / scale * self.width + (column - x) / scale];
+                let pixel = &mut buffer[row * buffer_width + column];
+                *pixel = blend(*pixel, lit);
+            }
+        }
+    }
+}
+
+// Color of a pixel of the box: white for the text, the game darkened to a quarter behind it
+pub fn shade([red, green, blue]: Rgb, lit: bool) -> Rgb {
+    if lit {
+        [0xFF, 0xFF, 0xFF]
+    } else {
+        [red / 4, green / 4, blue / 4]
+    }
+}
+
+// Size of a font pixel over the screen of the viewport, a quarter of a pixel of the low resolution screen so that the
+// text is as large in both resolutions
+pub fn overlay_scale(viewport: &Viewport) -> usize {
+    (viewport.width as usize / 256).max(1)
+}
@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod overlay_tests {
+    use crate::{letterbox, mnemonic, overlay_lines, overlay_scale, shade, Chip8, DebugInfo, TextBitmap, Throughput};
+
+    fn running() -> DebugInfo {
+        let mut registers = [0; 16];
+        registers[0x0] = 0x05;
+        registers[0x7] = 0xA2;
+        registers[0xF] = 0x01;
+        DebugInfo {
+            pc: 0x0202,
+            index: 0x02F0,
+            opcode: 0xD015,
+            registers,
+            delay_timer: 60,
+            sound_timer: 0,
+            stack_depth: 1,
+            instructions_per_second: 699.6,
+            skipped_frames: 3,
+            speed: 1,
+        }
+    }
+
+    #[test]
+    fn overlay_lines_show_the_registers_timers_and_rates() {
+        assert_eq!(overlay_lines(&running()), [
+            "PC 0202  I 02F0",
+            "D015 Draw { x: 0, y: 1, n: 5 }",
+            "V0-3 05 00 00 00",
+            "V4-7 00 00 00 A2",
+            "V8-B 00 00 00 00",
+            "VC-F 00 00 00 01",
+            "DT 60  ST 0  SP 1",
+            "IPS 700  SKIP 3",
+        ]);
+    }
+
+    #[test]
+    fn overlay_lines_mark_an_unknown_opcode_and_an_unmeasured_rate() {
+        let info = DebugInfo { opcode: 0xE000, instructions_per_second: 0.0, ..running() };
+        let lines = overlay_lines(&info);
+        assert_eq!(lines[1], "E000 ????");
+        // Not measured during the first second
+        assert_eq!(lines[7], "IPS 0  SKIP 3");
+    }
+
+    #[test]
+    fn overlay_lines_add_the_speed_while_fast_forwarding() {
+        let lines = overlay_lines(&DebugInfo { speed: 8, ..running() });
+        assert_eq!(lines.len(), 9);
+        assert_eq!(lines[8], "TURBO 8x");
+    }
+
+    #[test]
+    fn mnemonic_is_the_decoded_instruction() {
+        assert_eq!(mnemonic(0x00E0), "ClearScreen");
+        assert_eq!(mnemonic(0x6A0F), "LoadImm { x: 10, nn: 15 }");
+    }
+
+    #[test]
+    fn debug_info_reads_the_machine_before_the_next_instruction() {
+        // 6005: V0 = 5, then A300: I = 0x300
+        let mut chip8 = Chip8::with_program(&[0x6005, 0xA300, 0x2208]);
+        chip8.emulate_cycle().unwrap();
+        chip8.emulate_cycle().unwrap();
+        let throughput = Throughput { frames_per_second: 60.0, instructions_per_second: 660.0 };
+        let info = DebugInfo::new(&chip8, throughput, 2);
+        assert_eq!(info.pc, 0x204);
+        assert_eq!(info.index, 0x300);
+        assert_eq!(info.opcode, 0x2208);
+        assert_eq!(info.registers[0], 5);
+        assert_eq!(info.stack_depth, 0);
+        assert_eq!(info.instructions_per_second, 660.0);
+        assert_eq!(info.skipped_frames, 2);
+        assert_eq!(info.speed, 1);
+        chip8.emulate_cycle().unwrap();
+        assert_eq!(DebugInfo::new(&chip8, throughput, 0).stack_depth, 1);
+    }
+
+    #[test]
+    fn text_bitmap_draws_the_lines_in_the_font_within_a_margin() {
+        let bitmap = TextBitmap::new(&[String::from("1"), String::from("-:")]);
+        // Two characters of 4 font pixels, two lines of 6, and the margin
+        assert_eq!((bitmap.width, bitmap.height), (9, 13));
+        let rows: Vec<String> = bitmap.pixels.chunks(bitmap.width)
+            .map(|row| row.iter().map(|&lit| if lit { '#' } else { '.' }).collect())
+            .collect();
+        assert_eq!(rows, [
+            ".........",
+            "..#......",
+            ".##......",
+            "..#......",
+            "..#......",
+            ".###.....",
+            ".........",
+            ".........",
+            "......#..",
+            ".###.....",
+            "......#..",
+            ".........",
+            ".........",
+        ]);
+        assert_eq!(bitmap.lit().next(), Some((2, 1)));
+    }
+
+    #[test]
+    fn text_bitmap_draws_lowercase_as_uppercase() {
+        assert_eq!(TextBitmap::new(&[String::from("ld")]), TextBitmap::new(&[String::from("LD")]));
+    }
+
+    #[test]
+    fn draw_over_darkens_the_box_and_draws_the_text_in_white() {
+        let bitmap = TextBitmap::new(&[String::from("1")]);
+        let mut buffer = vec![[0x80, 0x40, 0x20]; 12 * 10];
+        bitmap.draw_over(&mut buffer, 12, (2, 1), 2, shade);
+        // The margin is darkened, the text is white, around the box is left as it was
+        assert_eq!(buffer[0], [0x80, 0x40, 0x20]);
+        assert_eq!(buffer[12 + 2], [0x20, 0x10, 0x08]);
+        assert_eq!(buffer[3 * 12 + 2 + 4], [0xFF, 0xFF, 0xFF]);
+        assert_eq!(buffer[3 * 12 + 2 + 2], [0x20, 0x10, 0x08]);
+        // Cut at the bottom of the buffer, 14 rows high from the second one
+        assert_eq!(buffer[9 * 12 + 2], [0x20, 0x10, 0x08]);
+    }
+
+    #[test]
+    fn overlay_scale_follows_the_window_not_the_resolution() {
+        assert_eq!(overlay_scale(&letterbox(64, 32, 640, 320)), 2);
+        // As large in high resolution
+        assert_eq!(overlay_scale(&letterbox(128, 64, 640, 320)), 2);
+        assert_eq!(overlay_scale(&letterbox(64, 32, 128, 64)), 1);
+    }
+}
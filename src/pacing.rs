@@ -0,0 +1,211 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./pacing_tests.rs"]
+mod pacing_tests;
+
+// Frame pacing of the emulation loops: 60 frames per second by the host clock, sleeping between them instead of
+// spinning, with the frames per second and instructions per second actually run
+
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::{Chip8, Chip8Error, FrameInfo, FRAME_DURATION};
+
+// Frames run without rendering them when the loop is late, on a host too slow to render every frame. After a longer
+// pause of the host, a debugger break or a dragged window, the rest is dropped so that the game resumes at its speed
+// instead of running fast to catch up
+pub const DEFAULT_MAX_SKIP: u32 = 4;
+
+// Speed factor of the emulation while Tab is held
+pub const DEFAULT_TURBO: u32 = 8;
+
+// The OS sleep can wake up a millisecond or two late, the end of the wait yields to the other threads until the frame
+// is due instead
+const SPIN_MARGIN: Duration = Duration::from_millis(2);
+
+// The frames and instructions per second are counted over this time
+const THROUGHPUT_PERIOD: Duration = Duration::from_secs(1);
+
+// Time source of the pacer, the system clock outside the tests
+pub trait Clock {
+    // Time since an arbitrary start
+    fn now(&self) -> Duration;
+
+    fn sleep(&mut self, duration: Duration);
+
+    // Waits a very short time while spinning before the frame
+    fn spin(&mut self) {
+        thread::yield_now();
+    }
+}
+
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> SystemClock {
+        SystemClock { start: Instant::now() }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+// Frames to run at once by a loop late by some frames, only the last one is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameSkip {
+    pub frames: u32,
+    // Run but not rendered
+    pub skipped: u32,
+    // The loop is too late to catch up, the next frames start again from now
+    pub resync: bool,
+}
+
+// Frames to run when the frame due is behind_by whole frames late: the late ones are run without being rendered, up to
+// max_skip of them
+pub fn frame_skip(behind_by: u32, max_skip: u32) -> FrameSkip {
+    let skipped = behind_by.min(max_skip);
+    FrameSkip { frames: skipped + 1, skipped, resync: behind_by > max_skip }
+}
+
+// Frames and instructions run per second, for the title bar or the stats
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Throughput {
+    pub frames_per_second: f64,
+    pub instructions_per_second: f64,
+}
+
+pub struct FramePacer<C: Clock = SystemClock> {
+    clock: C,
+    // Time the next frame is due
+    next_frame: Duration,
+    // Frames and instructions run since the start of the count
+    count_start: Duration,
+    counted_frames: u32,
+    counted_instructions: usize,
+    throughput: Throughput,
+    max_skip: u32,
+    // Frames run without being rendered since the start
+    skipped_frames: u64,
+}
+
+impl Default for FramePacer {
+    fn default() -> FramePacer {
+        FramePacer::with_clock(SystemClock::default())
+    }
+}
+
+impl FramePacer {
+    pub fn new() -> FramePacer {
+        FramePacer::default()
+    }
+}
+
+impl<C: Clock> FramePacer<C> {
+    // The first frame is due at once
+    pub fn with_clock(clock: C) -> FramePacer<C> {
+        let now = clock.now();
+        FramePacer {
+            clock,
+            next_frame: now,
+            count_start: now,
+            counted_frames: 0,
+            counted_instructions: 0,
+            throughput: Throughput::default(),
+            max_skip: DEFAULT_MAX_SKIP,
+            skipped_frames: 0,
+        }
+    }
+
+    // Frames run at most without rendering them when late, DEFAULT_MAX_SKIP by default
+    pub fn max_skip(mut self, frames: u32) -> FramePacer<C> {
+        self.max_skip = frames;
+        self
+    }
+
+    // Frames due since the last call, see frame_skip. After a resync the frames start again from now
+    pub fn frames_due(&mut self) -> u32 {
+        let now = self.clock.now();
+        if now < self.next_frame {
+            return 0;
+        }
+        let late = (now - self.next_frame).as_nanos() / FRAME_DURATION.as_nanos();
+        let skip = frame_skip(late.min(u128::from(u32::MAX)) as u32, self.max_skip);
+        if skip.resync {
+            self.next_frame = now + FRAME_DURATION;
+        } else {
+            self.next_frame += FRAME_DURATION * skip.frames;
+        }
+        skip.frames
+    }
+
+    // Runs the frames due, like Chip8::run_for with the time of these frames, and counts them in the throughput. The
+    // frame info is the one of all of them, rendered once
+    pub fn run(&mut self, chip8: &mut Chip8) -> Result<FrameInfo, Chip8Error> {
+        let frames = self.frames_due();
+        let frame = chip8.run_for(FRAME_DURATION * frames)?;
+        self.count(frames, frame.instructions);
+        self.skipped_frames += u64::from(frames.saturating_sub(1));
+        Ok(frame)
+    }
+
+    // Frames run without being rendered because the loop was late, since the start
+    pub fn skipped_frames(&self) -> u64 {
+        self.skipped_frames
+    }
+
+    // Lets the frames due pass without running them, while the emulation is paused. The throughput is counted again
+    // from now, so that the pause does not lower it
+    pub fn skip(&mut self) -> FrameInfo {
+        self.frames_due();
+        self.count_start = self.clock.now();
+        self.counted_frames = 0;
+        self.counted_instructions = 0;
+        FrameInfo { drew: false, sound_active: false, halted: false, instructions: 0, frames: 0 }
+    }
+
+    // Sleeps until the next frame is due, the last SPIN_MARGIN spinning for precision
+    pub fn wait(&mut self) {
+        loop {
+            let now = self.clock.now();
+            if now >= self.next_frame {
+                return;
+            }
+            let remaining = self.next_frame - now;
+            if remaining > SPIN_MARGIN {
+                self.clock.sleep(remaining - SPIN_MARGIN);
+            } else {
+                self.clock.spin();
+            }
+        }
+    }
+
+    // Adds frames and instructions run to the throughput, which is measured again once per THROUGHPUT_PERIOD
+    pub fn count(&mut self, frames: u32, instructions: usize) {
+        self.counted_frames += frames;
+        self.counted_instructions += instructions;
+        let elapsed = self.clock.now() - self.count_start;
+        if elapsed >= THROUGHPUT_PERIOD {
+            let seconds = elapsed.as_secs_f64();
+            self.throughput = Throughput {
+                frames_per_second: f64::from(self.counted_frames) / seconds,
+                instructions_per_second: self.counted_instructions as f64 / seconds,
+            };
+            self.count_start += elapsed;
+            self.counted_frames = 0;
+            self.counted_instructions = 0;
+        }
+    }
+
+    // Measured over the last THROUGHPUT_PERIOD, zero during the first one
+    pub fn throughput(&self) -> Throughput {
+        self.throughput
+    }
+}
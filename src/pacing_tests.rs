@@ -0,0 +1,218 @@
+#[cfg(test)]
+mod pacing_tests {
+    use std::time::Duration;
+    use crate::{frame_skip, Chip8Config, Clock, FrameSkip, FramePacer, DEFAULT_MAX_SKIP, DEFAULT_TURBO, FRAME_DURATION};
+
+    // Clock only moving when told to, by sleeping or spinning
+    #[derive(Default)]
+    struct MockClock {
+        now: Duration,
+        // Added to each sleep, like a late wake up of the OS
+        oversleep: Duration,
+        sleeps: Vec<Duration>,
+        spins: u32,
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> Duration {
+            self.now
+        }
+
+        fn sleep(&mut self, duration: Duration) {
+            self.sleeps.push(duration);
+            self.now += duration + self.oversleep;
+        }
+
+        fn spin(&mut self) {
+            self.spins += 1;
+            self.now += Duration::from_micros(100);
+        }
+    }
+
+    fn pacer() -> FramePacer<MockClock> {
+        FramePacer::with_clock(MockClock::default())
+    }
+
+    #[test]
+    fn frames_due_counts_the_frames_passed_keeping_the_rest() {
+        let mut pacer = pacer();
+        assert_eq!(pacer.frames_due(), 1);
+        assert_eq!(pacer.frames_due(), 0);
+        pacer.clock.now += FRAME_DURATION;
+        assert_eq!(pacer.frames_due(), 1);
+        // The half frame left is not lost
+        pacer.clock.now += FRAME_DURATION * 5 / 2;
+        assert_eq!(pacer.frames_due(), 2);
+        pacer.clock.now += FRAME_DURATION / 2;
+        assert_eq!(pacer.frames_due(), 1);
+    }
+
+    #[test]
+    fn frames_due_caps_the_catch_up() {
+        let mut pacer = pacer();
+        pacer.frames_due();
+        // A hiccup of the host
+        pacer.clock.now += Duration::from_secs(2);
+        assert_eq!(pacer.frames_due(), DEFAULT_MAX_SKIP + 1);
+        // The next frame is one frame later, not the ones behind
+        assert_eq!(pacer.frames_due(), 0);
+        pacer.clock.now += FRAME_DURATION;
+        assert_eq!(pacer.frames_due(), 1);
+        // Late by less than the cap, every frame is run
+        pacer.clock.now += FRAME_DURATION * DEFAULT_MAX_SKIP;
+        assert_eq!(pacer.frames_due(), DEFAULT_MAX_SKIP);
+    }
+
+    #[test]
+    fn wait_sleeps_then_spins_until_the_next_frame() {
+        let mut pacer = pacer();
+        pacer.frames_due();
+        pacer.clock.now += Duration::from_millis(4);
+        pacer.wait();
+        // The sleep stops short of the frame, the rest is spun
+        assert_eq!(pacer.clock.sleeps, [FRAME_DURATION - Duration::from_millis(6)]);
+        assert!(pacer.clock.spins > 0);
+        assert!(pacer.clock.now >= FRAME_DURATION);
+        assert!(pacer.clock.now < FRAME_DURATION + Duration::from_micros(100));
+        assert_eq!(pacer.frames_due(), 1);
+    }
+
+    #[test]
+    fn wait_does_not_spin_after_a_late_wake_up() {
+        let mut pacer = pacer();
+        pacer.clock.oversleep = Duration::from_millis(3);
+        pacer.frames_due();
+        pacer.wait();
+        assert_eq!(pacer.clock.spins, 0);
+        assert_eq!(pacer.frames_due(), 1);
+        // Nothing to wait for once the frame is due
+        pacer.clock.now += FRAME_DURATION * 2;
+        pacer.wait();
+        assert_eq!(pacer.clock.sleeps.len(), 1);
+    }
+
+    #[test]
+    fn throughput_is_measured_once_a_second() {
+        let mut pacer = pacer();
+        assert_eq!(pacer.throughput().frames_per_second, 0.0);
+        for _ in 0..60 {
+            let frames = pacer.frames_due();
+            pacer.count(frames, frames as usize * 11);
+            pacer.clock.now += FRAME_DURATION;
+        }
+        // Measured once a second passed
+        assert_eq!(pacer.throughput().frames_per_second, 0.0);
+        pacer.clock.now = Duration::from_secs(1);
+        pacer.count(0, 0);
+        assert_eq!(pacer.throughput().frames_per_second, 60.0);
+        assert_eq!(pacer.throughput().instructions_per_second, 660.0);
+        // Then counted again from zero
+        pacer.clock.now += Duration::from_secs(2);
+        pacer.count(30, 0);
+        assert_eq!(pacer.throughput().frames_per_second, 15.0);
+    }
+
+    #[test]
+    fn run_runs_the_frames_due() {
+        let mut chip8 = Chip8Config::new().instructions_per_frame(11).build().unwrap();
+        chip8.load_rom(&[0x12, 0x00]).unwrap(); // Loops forever
+        let mut pacer = pacer();
+        let frame = pacer.run(&mut chip8).unwrap();
+        assert_eq!((frame.instructions, frame.frames), (11, 1));
+        assert_eq!(pacer.run(&mut chip8).unwrap().frames, 0);
+        pacer.clock.now += FRAME_DURATION * 3;
+        let frame = pacer.run(&mut chip8).unwrap();
+        assert_eq!((frame.instructions, frame.frames), (33, 3));
+    }
+
+    #[test]
+    fn run_runs_the_frames_of_the_speed() {
+        let mut chip8 = Chip8Config::new().instructions_per_frame(11).build().unwrap();
+        chip8.load_rom(&[0x12, 0x00]).unwrap(); // Loops forever
+        let mut pacer = pacer();
+        pacer.frames_due();
+        for speed in [DEFAULT_TURBO, 3] {
+            chip8.set_speed(speed);
+            // 2 seconds of the host tick the timers 2 * 60 * speed times, the frames of the host are unchanged
+            let mut ticks = 0;
+            for _ in 0..120 {
+                pacer.clock.now += FRAME_DURATION;
+                ticks += pacer.run(&mut chip8).unwrap().frames;
+            }
+            assert_eq!(ticks, 2 * 60 * speed);
+        }
+        assert_eq!(pacer.skipped_frames(), 0);
+    }
+
+    #[test]
+    fn skip_runs_nothing_and_resumes_without_catching_up() {
+        let mut chip8 = Chip8Config::new().instructions_per_frame(11).build().unwrap();
+        chip8.load_rom(&[0x12, 0x00]).unwrap(); // Loops forever
+        let mut pacer = pacer();
+        for _ in 0..60 {
+            pacer.run(&mut chip8).unwrap();
+            pacer.clock.now += FRAME_DURATION;
+        }
+        pacer.clock.now = Duration::from_secs(1);
+        pacer.count(0, 0);
+        let throughput = pacer.throughput();
+        assert_eq!(throughput.instructions_per_second, 660.0);
+        // A long pause runs nothing and keeps the measured throughput
+        pacer.clock.now += Duration::from_secs(10);
+        assert_eq!(pacer.skip().frames, 0);
+        assert_eq!(pacer.throughput(), throughput);
+        // Then the frames start again from the end of the pause, without catching up, and are counted from there
+        let resumed = pacer.clock.now;
+        for _ in 0..60 {
+            pacer.clock.now += FRAME_DURATION;
+            assert_eq!(pacer.run(&mut chip8).unwrap().instructions, 11);
+        }
+        pacer.clock.now = resumed + Duration::from_secs(1);
+        pacer.count(0, 0);
+        assert_eq!(pacer.throughput(), throughput);
+    }
+
+    #[test]
+    fn frame_skip_renders_the_last_late_frame_and_drops_the_rest() {
+        // On time
+        assert_eq!(frame_skip(0, 4), FrameSkip { frames: 1, skipped: 0, resync: false });
+        // Late frames are run, only the last one is rendered
+        assert_eq!(frame_skip(1, 4), FrameSkip { frames: 2, skipped: 1, resync: false });
+        assert_eq!(frame_skip(4, 4), FrameSkip { frames: 5, skipped: 4, resync: false });
+        // Too late, the rest is dropped
+        assert_eq!(frame_skip(5, 4), FrameSkip { frames: 5, skipped: 4, resync: true });
+        assert_eq!(frame_skip(u32::MAX, 4), FrameSkip { frames: 5, skipped: 4, resync: true });
+        // Without skipping, any late frame resyncs
+        assert_eq!(frame_skip(0, 0), FrameSkip { frames: 1, skipped: 0, resync: false });
+        assert_eq!(frame_skip(1, 0), FrameSkip { frames: 1, skipped: 0, resync: true });
+    }
+
+    #[test]
+    fn max_skip_caps_the_frames_due() {
+        let mut pacer = pacer().max_skip(1);
+        pacer.frames_due();
+        pacer.clock.now += FRAME_DURATION * 3;
+        assert_eq!(pacer.frames_due(), 2);
+        assert_eq!(pacer.frames_due(), 0);
+    }
+
+    #[test]
+    fn skipped_frames_counts_the_frames_run_without_rendering() {
+        let mut chip8 = Chip8Config::new().instructions_per_frame(11).build().unwrap();
+        chip8.load_rom(&[0x12, 0x00]).unwrap(); // Loops forever
+        let mut pacer = pacer();
+        pacer.run(&mut chip8).unwrap();
+        assert_eq!(pacer.skipped_frames(), 0);
+        pacer.clock.now += FRAME_DURATION * 3;
+        assert_eq!(pacer.run(&mut chip8).unwrap().instructions, 33);
+        assert_eq!(pacer.skipped_frames(), 2);
+        // The frames dropped by a resync are not run, so not skipped either
+        pacer.clock.now += Duration::from_secs(1);
+        pacer.run(&mut chip8).unwrap();
+        assert_eq!(pacer.skipped_frames(), 2 + u64::from(DEFAULT_MAX_SKIP));
+        // Nor the ones passing during a pause
+        pacer.clock.now += FRAME_DURATION * 3;
+        pacer.skip();
+        assert_eq!(pacer.skipped_frames(), 2 + u64::from(DEFAULT_MAX_SKIP));
+    }
+}
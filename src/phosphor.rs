@@ -0,0 +1,88 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./phosphor_tests.rs"]
+mod phosphor_tests;
+
+// Phosphor persistence of the window frontends: a pixel turning off fades out over a few frames instead of
+// disappearing at once, which hides most of the flicker of sprites erased and drawn again with XOR
+
+use crate::{Rgb, Theme};
+
+// Brightness of a fading pixel one frame before it is off, from 0 to 1
+const CUTOFF: f32 = 1.0 / 32.0;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Phosphor {
+    // Frames a pixel stays visible after turning off, 0 when the mode is off
+    decay_frames: u32,
+    // Value each pixel was last lit with, 0 once it faded out
+    values: Vec<u8>,
+    // Frames since each pixel turned off, 0 while it is lit
+    ages: Vec<u32>,
+}
+
+impl Phosphor {
+    // A pixel turning off stays visible for decay_frames frames, its brightness divided by the same factor each one.
+    // 0 switches the persistence off, the screen is then shown as it is
+    pub fn new(decay_frames: u32) -> Phosphor {
+        Phosphor { decay_frames, values: Vec::new(), ages: Vec::new() }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.decay_frames > 0
+    }
+
+    // Takes the screen at the end of the next 60 Hz frame. A change of resolution starts again from that screen
+    pub fn step(&mut self, framebuffer: &[u8]) {
+        if framebuffer.len() != self.values.len() {
+            self.values = vec![0; framebuffer.len()];
+            self.ages = vec![0; framebuffer.len()];
+        }
+        for ((&pixel, value), age) in framebuffer.iter().zip(&mut self.values).zip(&mut self.ages) {
+            if pixel != 0 {
+                *value = pixel;
+                *age = 0;
+            } else if *value != 0 {
+                *age += 1;
+                if *age > self.decay_frames {
+                    *value = 0;
+                    *age = 0;
+                }
+            }
+        }
+    }
+
+    // Whether some pixels are still fading, their color changes with each step even if the screen does not
+    pub fn fading(&self) -> bool {
+        self.ages.iter().any(|&age| age > 0)
+    }
+
+    // Brightness of a pixel from 0 to 1. It goes down exponentially from 1 when lit to CUTOFF one frame after the
+    // last visible one
+    pub fn intensity(&self, index: usize) -> f32 {
+        if self.values.get(index).copied().unwrap_or(0) == 0 {
+            return 0.0;
+        }
+        CUTOFF.powf(self.ages[index] as f32 / (self.decay_frames + 1) as f32)
+    }
+
+    // Color of each pixel, between the background and the color it was lit with
+    pub fn colors(&self, theme: &Theme) -> Vec<Rgb> {
+        let background = theme.color(0);
+        (0..self.values.len())
+            .map(|index| {
+                let (color, intensity) = (theme.color(self.values[index]), self.intensity(index));
+                let mut blended = background;
+                for (channel, &lit) in blended.iter_mut().zip(&color) {
+                    *channel = (f32::from(*channel) + (f32::from(lit) - f32::from(*channel)) * intensity).round() as u8;
+                }
+                blended
+            })
+            .collect()
+    }
+
+    // Opaque RGBA bytes of the colors, 4 per pixel
+    pub fn to_rgba(&self, theme: &Theme) -> Vec<u8> {
+        self.colors(theme).into_iter().flat_map(|[red, green, blue]| [red, green, blue, 0xFF]).collect()
+    }
+}
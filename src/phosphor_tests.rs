@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod phosphor_tests {
+    use crate::phosphor::{Phosphor, CUTOFF};
+    use crate::Theme;
+
+    // Intensities of the single pixel of the screen after each step
+    fn fade(decay_frames: u32, screens: &[u8]) -> Vec<f32> {
+        let mut phosphor = Phosphor::new(decay_frames);
+        screens.iter()
+            .map(|&pixel| {
+                phosphor.step(&[pixel]);
+                phosphor.intensity(0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pixel_lit_for_one_frame_fades_over_the_next_ones() {
+        let intensities = fade(3, &[1, 0, 0, 0, 0, 0]);
+
+        assert_eq!(intensities[0], 1.0);
+        // Divided by the same factor each frame, down to the cutoff one frame after the last visible one
+        let factor = CUTOFF.powf(0.25);
+        for frame in 1..=3 {
+            assert!((intensities[frame] - factor.powi(frame as i32)).abs() < 1e-6, "{intensities:?}");
+            assert!(intensities[frame] > 0.0 && intensities[frame] < intensities[frame - 1]);
+        }
+        assert_eq!(intensities[4..], [0.0, 0.0]);
+    }
+
+    #[test]
+    fn pixel_lit_again_while_fading_is_at_full_brightness() {
+        let intensities = fade(4, &[1, 0, 0, 1, 0]);
+        assert_eq!(intensities[3], 1.0);
+        assert!(intensities[4] < 1.0);
+    }
+
+    #[test]
+    fn no_decay_shows_the_screen_as_it_is() {
+        let phosphor = Phosphor::new(0);
+        assert!(!phosphor.enabled());
+        assert_eq!(fade(0, &[1, 0, 2, 0]), [1.0, 0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn fading_is_set_while_a_pixel_turned_off_is_still_visible() {
+        let mut phosphor = Phosphor::new(2);
+        phosphor.step(&[1, 0]);
+        assert!(!phosphor.fading());
+        phosphor.step(&[0, 0]);
+        assert!(phosphor.fading());
+        phosphor.step(&[0, 0]);
+        phosphor.step(&[0, 0]);
+        assert!(!phosphor.fading());
+    }
+
+    #[test]
+    fn colors_blend_from_the_lit_color_to_the_background() {
+        let theme = Theme::new([0x00, 0x00, 0x00], [0xFF, 0x80, 0x00]);
+        let mut phosphor = Phosphor::new(1);
+        phosphor.step(&[1, 2, 0]);
+        assert_eq!(phosphor.colors(&theme), [theme.color(1), theme.color(2), theme.color(0)]);
+
+        // A fading pixel keeps the color of its plane, at the square root of the cutoff after one frame
+        phosphor.step(&[0, 0, 0]);
+        let colors = phosphor.colors(&theme);
+        let intensity = CUTOFF.sqrt();
+        assert_eq!(colors[0], [(255.0 * intensity).round() as u8, (128.0 * intensity).round() as u8, 0]);
+        assert_eq!(colors[2], theme.color(0));
+
+        phosphor.step(&[0, 0, 0]);
+        assert_eq!(phosphor.colors(&theme), [theme.color(0); 3]);
+    }
+
+    #[test]
+    fn to_rgba_gives_the_theme_colors_without_decay() {
+        let theme = Theme::default();
+        let mut phosphor = Phosphor::new(0);
+        phosphor.step(&[0, 1]);
+        assert_eq!(phosphor.to_rgba(&theme), [theme.rgba(0), theme.rgba(1)].concat());
+    }
+
+    #[test]
+    fn resolution_change_starts_the_fading_again() {
+        let mut phosphor = Phosphor::new(5);
+        phosphor.step(&[1, 1]);
+        phosphor.step(&[0, 0, 0, 0]);
+        assert!(!phosphor.fading());
+        assert_eq!(phosphor.intensity(0), 0.0);
+    }
+}
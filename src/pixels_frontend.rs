@@ -0,0 +1,463 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./pixels_frontend_tests.rs"]
+mod pixels_frontend_tests;
+
+// pixels frontend, enabled with the pixels feature: a winit window where the GPU scales the screen buffer up with
+// nearest-neighbor filtering, and the keyboard as the keypad
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::mem;
+use std::time::Instant;
+use pixels::{wgpu, Pixels, SurfaceTexture};
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event::{ElementState, Event, KeyboardInput, ModifiersState, VirtualKeyCode, WindowEvent};
+use winit::event_loop::EventLoop;
+use winit::platform::run_return::EventLoopExtRunReturn;
+use winit::window::{Fullscreen, Window, WindowBuilder};
+use crate::{
+    control, crt_image, letterbox, overlay_scale, rotate, shade, window_size, Control, ControlKey, Crt, Display,
+    Input, Keymap, Phosphor, PhysicalKey, Rgb, Rotation, TextBitmap, Theme, WindowFrontend, FRAME_DURATION,
+};
+
+// Keyboard key of the keymap at the position of a scancode. They are physical positions, so the keypad stays the
+// same on AZERTY or Dvorak layouts. Linux and Windows both report the PC set 1 scancodes for the digits, the letters
+// and the space bar, the arrows are the Linux codes or the extended Windows ones
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn physical_key(scancode: u32) -> Option<PhysicalKey> {
+    const DIGITS: [PhysicalKey; 10] = [
+        PhysicalKey::Key1, PhysicalKey::Key2, PhysicalKey::Key3, PhysicalKey::Key4, PhysicalKey::Key5,
+        PhysicalKey::Key6, PhysicalKey::Key7, PhysicalKey::Key8, PhysicalKey::Key9, PhysicalKey::Key0,
+    ];
+    const TOP_ROW: [PhysicalKey; 10] = [
+        PhysicalKey::Q, PhysicalKey::W, PhysicalKey::E, PhysicalKey::R, PhysicalKey::T,
+        PhysicalKey::Y, PhysicalKey::U, PhysicalKey::I, PhysicalKey::O, PhysicalKey::P,
+    ];
+    const MIDDLE_ROW: [PhysicalKey; 9] = [
+        PhysicalKey::A, PhysicalKey::S, PhysicalKey::D, PhysicalKey::F, PhysicalKey::G,
+        PhysicalKey::H, PhysicalKey::J, PhysicalKey::K, PhysicalKey::L,
+    ];
+    const BOTTOM_ROW: [PhysicalKey; 7] = [
+        PhysicalKey::Z, PhysicalKey::X, PhysicalKey::C, PhysicalKey::V, PhysicalKey::B, PhysicalKey::N, PhysicalKey::M,
+    ];
+    let index = |first: u32| (scancode - first) as usize;
+    match scancode {
+        0x02..=0x0B => Some(DIGITS[index(0x02)]),
+        0x10..=0x19 => Some(TOP_ROW[index(0x10)]),
+        0x1E..=0x26 => Some(MIDDLE_ROW[index(0x1E)]),
+        0x2C..=0x32 => Some(BOTTOM_ROW[index(0x2C)]),
+        0x39 => Some(PhysicalKey::Space),
+        0x67 | 0xE048 => Some(PhysicalKey::Up),
+        0x69 | 0xE04B => Some(PhysicalKey::Left),
+        0x6A | 0xE04D => Some(PhysicalKey::Right),
+        0x6C | 0xE050 => Some(PhysicalKey::Down),
+        _ => None,
+    }
+}
+
+// macOS reports its own virtual key codes, which follow the ANSI keyboard in no order
+#[cfg(target_os = "macos")]
+pub(crate) fn physical_key(scancode: u32) -> Option<PhysicalKey> {
+    const CODES: [(u32, PhysicalKey); 41] = [
+        (0x1D, PhysicalKey::Key0), (0x12, PhysicalKey::Key1), (0x13, PhysicalKey::Key2), (0x14, PhysicalKey::Key3),
+        (0x15, PhysicalKey::Key4), (0x17, PhysicalKey::Key5), (0x16, PhysicalKey::Key6), (0x1A, PhysicalKey::Key7),
+        (0x1C, PhysicalKey::Key8), (0x19, PhysicalKey::Key9),
+        (0x00, PhysicalKey::A), (0x0B, PhysicalKey::B), (0x08, PhysicalKey::C), (0x02, PhysicalKey::D),
+        (0x0E, PhysicalKey::E), (0x03, PhysicalKey::F), (0x05, PhysicalKey::G), (0x04, PhysicalKey::H),
+        (0x22, PhysicalKey::I), (0x26, PhysicalKey::J), (0x28, PhysicalKey::K), (0x25, PhysicalKey::L),
+        (0x2E, PhysicalKey::M), (0x2D, PhysicalKey::N), (0x1F, PhysicalKey::O), (0x23, PhysicalKey::P),
+        (0x0C, PhysicalKey::Q), (0x0F, PhysicalKey::R), (0x01, PhysicalKey::S), (0x11, PhysicalKey::T),
+        (0x20, PhysicalKey::U), (0x09, PhysicalKey::V), (0x0D, PhysicalKey::W), (0x07, PhysicalKey::X),
+        (0x10, PhysicalKey::Y), (0x06, PhysicalKey::Z),
+        (0x7E, PhysicalKey::Up), (0x7D, PhysicalKey::Down), (0x7B, PhysicalKey::Left), (0x7C, PhysicalKey::Right),
+        (0x31, PhysicalKey::Space),
+    ];
+    CODES.iter().find(|&&(code, _)| code == scancode).map(|&(_, physical_key)| physical_key)
+}
+
+// Whether a key event is of a key of the emulator controls. The letters are read by their position like the keypad
+fn is_control_key(key: ControlKey, scancode: u32, virtual_keycode: Option<VirtualKeyCode>) -> bool {
+    match key {
+        ControlKey::Escape => virtual_keycode == Some(VirtualKeyCode::Escape),
+        ControlKey::Backspace => virtual_keycode == Some(VirtualKeyCode::Back),
+        ControlKey::Letter(letter) => physical_key(scancode) == Some(letter),
+    }
+}
+
+// Copies the framebuffer to the RGBA bytes of the surface buffer, of the same size
+pub(crate) fn to_rgba(framebuffer: &[u8], frame: &mut [u8], theme: &Theme) {
+    for (&pixel, rgba) in framebuffer.iter().zip(frame.chunks_exact_mut(4)) {
+        rgba.copy_from_slice(&theme.rgba(pixel));
+    }
+}
+
+// Key acting once per press: winit repeats the presses while a key is held
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct Hotkey {
+    // Pressed since the last call of take, and held
+    pressed: bool,
+    down: bool,
+}
+
+impl Hotkey {
+    pub(crate) fn update(&mut self, down: bool) {
+        self.pressed |= down && !self.down;
+        self.down = down;
+    }
+
+    pub(crate) fn take(&mut self) -> bool {
+        mem::take(&mut self.pressed)
+    }
+}
+
+// Color of the borders around the screen
+fn to_wgpu_color(theme: Theme) -> wgpu::Color {
+    let [red, green, blue] = theme.color(0).map(|channel| f64::from(channel) / 255.0);
+    wgpu::Color { r: red, g: green, b: blue, a: 1.0 }
+}
+
+pub struct PixelsFrontend {
+    // The surface is created from the window, so it is dropped first
+    pixels: Pixels,
+    window: Window,
+    event_loop: EventLoop<()>,
+    // Keyboard keys held, pressing the keypad keys of the keymap
+    held: HashSet<PhysicalKey>,
+    // Tab held, fast-forwarding the emulation
+    turbo: bool,
+    keymap: Keymap,
+    theme: Theme,
+    // Screen shown with the fading of the pixels turned off, when the persistence is on: the last drawn screen, moved
+    // towards once per 60 Hz frame
+    phosphor: Phosphor,
+    // Last drawn screen, rotated
+    rotation: Rotation,
+    framebuffer: Vec<u8>,
+    last_step: Instant,
+    // F12 for screenshot_requested, F9 for record_requested, F11 and Alt+Enter for the fullscreen
+    screenshot: Hotkey,
+    record: Hotkey,
+    fullscreen: Hotkey,
+    modifiers: ModifiersState,
+    // Keys of the emulator controls, in the order of ControlKey::ALL
+    control_keys: [Hotkey; 5],
+    // Position and size of the window before it went fullscreen, restored when leaving it
+    windowed: Option<(Option<PhysicalPosition<i32>>, PhysicalSize<u32>)>,
+    // Debug overlay over the top left corner of the screen, toggled by F3
+    overlay_shown: bool,
+    overlay_toggle: Hotkey,
+    overlay: Option<TextBitmap>,
+    // CRT effect of the scaled screen, and the one F6 switches to
+    crt: Crt,
+    crt_toggled: Crt,
+    crt_toggle: Hotkey,
+    // The window has the keyboard focus
+    focused: bool,
+    // Size of the rotated screen, 64x32 or 128x64 in high resolution mode, and of the surface buffer
+    width: u32,
+    height: u32,
+    buffer_size: (u32, u32),
+}
+
+impl PixelsFrontend {
+    // Opens a window of the low resolution screen with each pixel a square of scale by scale, turned by the rotation
+    pub fn new(title: &str, scale: u32, rotation: Rotation) -> Result<PixelsFrontend, Box<dyn Error>> {
+        let event_loop = EventLoop::new();
+        let (window_width, window_height) = rotation.size(window_size(scale));
+        let (min_width, min_height) = rotation.size((128, 64));
+        let window = WindowBuilder::new()
+            .with_title(title)
+            .with_inner_size(PhysicalSize::new(window_width, window_height))
+            // Large enough for the high resolution screen at a scale of 1
+            .with_min_inner_size(PhysicalSize::new(min_width, min_height))
+            .build(&event_loop)?;
+        let size = window.inner_size();
+        let (width, height) = rotation.size((64, 32));
+        let mut pixels = Pixels::new(width, height, SurfaceTexture::new(size.width, size.height, &window))?;
+        pixels.clear_color(to_wgpu_color(Theme::default()));
+        Ok(PixelsFrontend {
+            pixels,
+            window,
+            event_loop,
+            held: HashSet::new(),
+            turbo: false,
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            phosphor: Phosphor::new(0),
+            rotation,
+            framebuffer: vec![0; 64 * 32],
+            last_step: Instant::now(),
+            screenshot: Hotkey::default(),
+            record: Hotkey::default(),
+            fullscreen: Hotkey::default(),
+            modifiers: ModifiersState::empty(),
+            control_keys: [Hotkey::default(); 5],
+            windowed: None,
+            overlay_shown: false,
+            overlay_toggle: Hotkey::default(),
+            overlay: None,
+            crt: Crt::OFF,
+            crt_toggled: Crt::SCANLINES,
+            crt_toggle: Hotkey::default(),
+            focused: true,
+            width,
+            height,
+            buffer_size: (width, height),
+        })
+    }
+
+    // Applies to the next drawn screen, the borders around the screen take the background color at once
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.pixels.clear_color(to_wgpu_color(theme));
+        self.window.request_redraw();
+    }
+
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+    }
+
+    // Frames a pixel stays visible after turning off, 0 shows the screen as it is
+    pub fn set_phosphor(&mut self, decay_frames: u32) {
+        self.phosphor = Phosphor::new(decay_frames);
+    }
+
+    // F6 switches between this effect and none, or the scanlines when it is none
+    pub fn set_crt(&mut self, crt: Crt) {
+        self.crt = crt;
+        self.crt_toggled = if crt.is_off() { Crt::SCANLINES } else { Crt::OFF };
+        self.show(&self.screen_rgba());
+    }
+
+    // Borderless fullscreen on the monitor of the window, which gets back its position and size when leaving it. The
+    // surface follows with the resize events
+    fn toggle_fullscreen(&mut self) {
+        if self.window.fullscreen().is_some() {
+            self.window.set_fullscreen(None);
+            if let Some((position, size)) = self.windowed.take() {
+                self.window.set_inner_size(size);
+                if let Some(position) = position {
+                    self.window.set_outer_position(position);
+                }
+            }
+        } else {
+            self.windowed = Some((self.window.outer_position().ok(), self.window.inner_size()));
+            self.window.set_fullscreen(Some(Fullscreen::Borderless(None)));
+        }
+        self.window.request_redraw();
+    }
+
+    // Screen shown, with the fading of the pixels turned off when the persistence is on
+    fn screen_rgba(&self) -> Vec<u8> {
+        if self.phosphor.enabled() {
+            self.phosphor.to_rgba(&self.theme)
+        } else {
+            let mut rgba = vec![0; self.framebuffer.len() * 4];
+            to_rgba(&self.framebuffer, &mut rgba, &self.theme);
+            rgba
+        }
+    }
+
+    // Copies the RGBA bytes of the screen to the surface buffer. The buffer has the resolution of the screen and the
+    // GPU scales it to the window, but while the overlay or the CRT effect is shown it is the screen already scaled to
+    // the window, so that they can be finer than a pixel of the screen. A buffer failing to resize loses the frame
+    fn show(&mut self, rgba: &[u8]) {
+        let (width, height) = (self.width, self.height);
+        let overlay = self.overlay.as_ref().filter(|_| self.overlay_shown);
+        let size = self.window.inner_size();
+        let viewport = letterbox(width as usize, height as usize, size.width, size.height);
+        let scaled = overlay.is_some() || !self.crt.is_off();
+        let scale = if scaled { viewport.scale } else { 1 };
+        let buffer_size = (width * scale, height * scale);
+        if buffer_size != self.buffer_size {
+            if self.pixels.resize_buffer(buffer_size.0, buffer_size.1).is_err() {
+                return;
+            }
+            self.buffer_size = buffer_size;
+        }
+        let frame = self.pixels.frame_mut();
+        if scaled {
+            let (pixels, _) = rgba.as_chunks::<4>();
+            let colors: Vec<Rgb> = pixels.iter().map(|&[red, green, blue, _]| [red, green, blue]).collect();
+            let mut image = crt_image(&colors, width as usize, scale as usize, &self.crt);
+            if let Some(overlay) = overlay {
+                overlay.draw_over(&mut image, buffer_size.0 as usize, (0, 0), overlay_scale(&viewport), shade);
+            }
+            for (&[red, green, blue], pixel) in image.iter().zip(frame.as_chunks_mut::<4>().0) {
+                *pixel = [red, green, blue, 0xFF];
+            }
+        } else {
+            frame.copy_from_slice(rgba);
+        }
+        self.window.request_redraw();
+    }
+}
+
+impl Display for PixelsFrontend {
+    // The surface buffer follows the resolution of the screen, the GPU scales it to the window. With the phosphor the
+    // screen is shown by the next frame
+    fn draw(&mut self, framebuffer: &[u8], width: usize, height: usize) {
+        self.framebuffer = rotate(framebuffer, width, self.rotation);
+        let (width, height) = self.rotation.size((width, height));
+        self.width = width as u32;
+        self.height = height as u32;
+        if !self.phosphor.enabled() {
+            self.show(&self.screen_rgba());
+        }
+    }
+
+    fn clear(&mut self) {
+        self.framebuffer.fill(0);
+        if !self.phosphor.enabled() {
+            self.show(&self.screen_rgba());
+        }
+    }
+}
+
+impl Input for PixelsFrontend {
+    fn poll(&mut self) -> [bool; 16] {
+        self.keymap.poll(|physical_key| self.held.contains(&physical_key))
+    }
+}
+
+impl WindowFrontend for PixelsFrontend {
+    // Handles the pending window events and redraws the window if the screen changed, returns false once the window
+    // is closed
+    fn handle_events(&mut self) -> bool {
+        if self.phosphor.enabled() && self.last_step.elapsed() >= FRAME_DURATION {
+            self.last_step = Instant::now();
+            self.phosphor.step(&self.framebuffer);
+            self.show(&self.phosphor.to_rgba(&self.theme));
+        }
+        let PixelsFrontend {
+            pixels,
+            event_loop,
+            held,
+            turbo,
+            screenshot,
+            record,
+            fullscreen,
+            modifiers,
+            control_keys,
+            overlay_toggle,
+            crt_toggle,
+            focused,
+            ..
+        } = self;
+        let (mut open, mut resized) = (true, false);
+        event_loop.run_return(|event, _, control_flow| {
+            control_flow.set_poll();
+            match event {
+                Event::WindowEvent { event, .. } => match event {
+                    WindowEvent::CloseRequested => open = false,
+                    // The surface scales the buffer by the largest whole factor fitting in the window and centers it,
+                    // which keeps the aspect ratio with borders of the background color
+                    WindowEvent::Resized(size) => {
+                        let _ = pixels.resize_surface(size.width, size.height);
+                        resized = true;
+                    }
+                    WindowEvent::KeyboardInput { input: KeyboardInput { scancode, state, virtual_keycode, .. }, .. } => {
+                        let pressed = state == ElementState::Pressed;
+                        if let Some(physical_key) = physical_key(scancode) {
+                            if pressed {
+                                held.insert(physical_key);
+                            } else {
+                                held.remove(&physical_key);
+                            }
+                        }
+                        for (key, hotkey) in ControlKey::ALL.into_iter().zip(control_keys.iter_mut()) {
+                            if is_control_key(key, scancode, virtual_keycode) {
+                                hotkey.update(pressed);
+                            }
+                        }
+                        match virtual_keycode {
+                            Some(VirtualKeyCode::F12) => screenshot.update(pressed),
+                            Some(VirtualKeyCode::F9) => record.update(pressed),
+                            Some(VirtualKeyCode::F11) => fullscreen.update(pressed),
+                            Some(VirtualKeyCode::F3) => overlay_toggle.update(pressed),
+                            Some(VirtualKeyCode::F6) => crt_toggle.update(pressed),
+                            Some(VirtualKeyCode::Tab) => *turbo = pressed,
+                            Some(VirtualKeyCode::Return) => fullscreen.update(pressed && modifiers.alt()),
+                            _ => {}
+                        }
+                    }
+                    WindowEvent::ModifiersChanged(state) => *modifiers = state,
+                    // The release of the keys held when the focus is lost is never received
+                    WindowEvent::Focused(false) => {
+                        held.clear();
+                        *turbo = false;
+                        *focused = false;
+                    }
+                    WindowEvent::Focused(true) => *focused = true,
+                    _ => {}
+                },
+                Event::RedrawRequested(_) => {
+                    // A failed rendering only loses this frame, the next one redraws everything
+                    let _ = pixels.render();
+                }
+                // Gives the control back to the emulation loop once the events and redraws are handled
+                Event::RedrawEventsCleared => control_flow.set_exit(),
+                _ => {}
+            }
+        });
+        if self.fullscreen.take() {
+            self.toggle_fullscreen();
+        }
+        if self.overlay_toggle.take() {
+            self.overlay_shown = !self.overlay_shown;
+            self.overlay = None;
+            self.show(&self.screen_rgba());
+        }
+        if self.crt_toggle.take() {
+            mem::swap(&mut self.crt, &mut self.crt_toggled);
+            self.show(&self.screen_rgba());
+        } else if resized {
+            // The screen scaled on the CPU follows the size of the window
+            self.show(&self.screen_rgba());
+        }
+        open
+    }
+
+    // P, Backspace, R, Esc and Q pressed since the last call, the letters when the keymap leaves them
+    fn take_controls(&mut self) -> Vec<Control> {
+        ControlKey::ALL.into_iter()
+            .zip(self.control_keys.iter_mut())
+            .filter_map(|(key, hotkey)| hotkey.take().then_some(key))
+            .filter_map(|key| control(key, &self.keymap))
+            .collect()
+    }
+
+    // Whether Tab is held, to fast-forward the emulation
+    fn turbo_held(&self) -> bool {
+        self.turbo
+    }
+
+    // Whether the window has the keyboard focus, as of the last handle_events
+    fn has_focus(&self) -> bool {
+        self.focused
+    }
+
+    fn set_title(&mut self, title: &str) {
+        self.window.set_title(title);
+    }
+
+    // Whether the debug overlay is shown, its lines are then given once per frame to set_overlay
+    fn overlay_shown(&self) -> bool {
+        self.overlay_shown
+    }
+
+    fn set_overlay(&mut self, lines: &[String]) {
+        self.overlay = Some(TextBitmap::new(lines));
+        self.show(&self.screen_rgba());
+    }
+
+    fn screenshot_requested(&mut self) -> bool {
+        self.screenshot.take()
+    }
+
+    // Whether F9 was pressed since the last call, to start or stop a recording
+    fn record_requested(&mut self) -> bool {
+        self.record.take()
+    }
+}
@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod pixels_frontend_tests {
+    use crate::pixels_frontend::{physical_key, to_rgba, Hotkey};
+    use crate::{PhysicalKey, Theme};
+
+    #[test]
+    fn to_rgba_gives_the_theme_color_of_each_pixel() {
+        let framebuffer = [0, 1, 2, 3, 1, 0];
+        let mut frame = vec![0; framebuffer.len() * 4];
+        let theme = Theme::preset("green").unwrap();
+        to_rgba(&framebuffer, &mut frame, &theme);
+        for (&pixel, rgba) in framebuffer.iter().zip(frame.chunks_exact(4)) {
+            assert_eq!(rgba, theme.rgba(pixel));
+        }
+    }
+
+    #[test]
+    fn hotkey_is_taken_once_per_press_ignoring_the_repeats() {
+        let mut hotkey = Hotkey::default();
+        assert!(!hotkey.take());
+        // Held down, winit repeats the press
+        hotkey.update(true);
+        hotkey.update(true);
+        assert!(hotkey.take());
+        hotkey.update(true);
+        assert!(!hotkey.take());
+        // Released and pressed again before the next take
+        hotkey.update(false);
+        hotkey.update(true);
+        hotkey.update(false);
+        assert!(hotkey.take());
+        assert!(!hotkey.take());
+    }
+
+    #[test]
+    fn physical_key_reaches_every_key_of_the_keymap() {
+        let keys: Vec<PhysicalKey> = (0..0x10000).filter_map(physical_key).collect();
+        for key in PhysicalKey::ALL {
+            assert!(keys.contains(&key), "{key:?}");
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn physical_key_follows_the_positions_of_a_qwerty_keyboard() {
+        // 1, 0, Q, P, L and M of a QWERTY keyboard
+        assert_eq!(physical_key(0x02), Some(PhysicalKey::Key1));
+        assert_eq!(physical_key(0x0B), Some(PhysicalKey::Key0));
+        assert_eq!(physical_key(0x10), Some(PhysicalKey::Q));
+        assert_eq!(physical_key(0x19), Some(PhysicalKey::P));
+        assert_eq!(physical_key(0x26), Some(PhysicalKey::L));
+        assert_eq!(physical_key(0x32), Some(PhysicalKey::M));
+        assert_eq!(physical_key(0x39), Some(PhysicalKey::Space));
+        // The arrows of Linux and Windows
+        assert_eq!(physical_key(0x67), Some(PhysicalKey::Up));
+        assert_eq!(physical_key(0xE050), Some(PhysicalKey::Down));
+        // Enter and the left Shift
+        assert_eq!(physical_key(0x1C), None);
+        assert_eq!(physical_key(0x2A), None);
+    }
+}
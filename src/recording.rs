@@ -0,0 +1,178 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./recording_tests.rs"]
+mod recording_tests;
+
+// Animated GIF recording, enabled with the record feature. The screen is taken once per 60 Hz frame and written to
+// the file as soon as it changes, so that only the last frame is kept in memory however long the recording is
+
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+use gif::{Encoder, Frame, Repeat};
+use crate::{rotate, window_size, Rotation, Theme, FRAME_DURATION};
+
+// Browsers show the frames of less than 2 centiseconds for 10, so shorter frames are left out and their time given to
+// the next one
+const MIN_DELAY: u64 = 2;
+
+// Converts the 60 Hz frames to GIF delays in centiseconds. The delays are rounded down from the total time so far,
+// so the rounding errors do not add up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Timeline {
+    // Frames since the start of the recording, and time written in the file for them
+    frames: u64,
+    centiseconds: u64,
+}
+
+impl Timeline {
+    // Delay of a screen shown for the frames, None when it is too short to be seen and is left out
+    fn delay(&mut self, frames: u64) -> Option<u16> {
+        self.frames += frames;
+        let delay = self.frames * 100 / 60 - self.centiseconds;
+        if delay < MIN_DELAY {
+            return None;
+        }
+        self.centiseconds += delay;
+        Some(delay.min(u64::from(u16::MAX)) as u16)
+    }
+
+    // Delay of the last screen, shown for at least the minimum delay
+    fn last_delay(&mut self, frames: u64) -> u16 {
+        self.delay(frames).unwrap_or(MIN_DELAY as u16)
+    }
+}
+
+// Screen waiting to be written until a different one comes, with the frames it was shown for
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PendingFrame {
+    framebuffer: Vec<u8>,
+    width: usize,
+    frames: u64,
+}
+
+pub struct GifRecorder<W: Write> {
+    // None once the recording is finished
+    encoder: Option<Encoder<W>>,
+    // Size of the image: the window of the scale, which also fits the high resolution screen, turned by the rotation
+    // like the screens
+    image_width: usize,
+    image_height: usize,
+    rotation: Rotation,
+    pending: Option<PendingFrame>,
+    timeline: Timeline,
+    // Time of the next frame not pushed yet, for push_for
+    pending_time: Duration,
+}
+
+impl GifRecorder<BufWriter<File>> {
+    pub fn create(
+        path: &Path,
+        scale: u32,
+        rotation: Rotation,
+        theme: &Theme,
+    ) -> io::Result<GifRecorder<BufWriter<File>>> {
+        GifRecorder::new(BufWriter::new(File::create(path)?), scale, rotation, theme)
+    }
+}
+
+impl<W: Write> GifRecorder<W> {
+    // The colors of the image are the ones of the theme, the GIF loops forever
+    pub fn new(writer: W, scale: u32, rotation: Rotation, theme: &Theme) -> io::Result<GifRecorder<W>> {
+        let (image_width, image_height) = rotation.size(window_size(scale));
+        let palette: Vec<u8> = theme.colors.concat();
+        let mut encoder = Encoder::new(writer, image_width as u16, image_height as u16, &palette)
+            .map_err(io::Error::other)?;
+        encoder.set_repeat(Repeat::Infinite).map_err(io::Error::other)?;
+        Ok(GifRecorder {
+            encoder: Some(encoder),
+            image_width: image_width as usize,
+            image_height: image_height as usize,
+            rotation,
+            pending: None,
+            timeline: Timeline::default(),
+            pending_time: Duration::ZERO,
+        })
+    }
+
+    // Adds the screen of one 60 Hz frame. It is only written once a different screen comes, with the time of all the
+    // frames it was shown for
+    pub fn push_frame(&mut self, framebuffer: &[u8], width: usize) -> io::Result<()> {
+        let framebuffer = rotate(framebuffer, width, self.rotation);
+        let (width, _) = self.rotation.size((width, framebuffer.len() / width.max(1)));
+        if let Some(pending) = self.pending.as_mut().filter(|pending| pending.framebuffer == framebuffer) {
+            pending.frames += 1;
+            return Ok(());
+        }
+        if let Some(pending) = self.pending.take() {
+            if let Some(delay) = self.timeline.delay(pending.frames) {
+                self.write_frame(&pending, delay)?;
+            }
+        }
+        self.pending = Some(PendingFrame { framebuffer, width, frames: 1 });
+        Ok(())
+    }
+
+    // Adds the screen for frames run at once, like the ones of a FrameInfo
+    pub fn push_frames(&mut self, framebuffer: &[u8], width: usize, frames: u32) -> io::Result<()> {
+        for _ in 0..frames {
+            self.push_frame(framebuffer, width)?;
+        }
+        Ok(())
+    }
+
+    // Adds the screen for each 60 Hz frame in the time elapsed on the host, the remaining time is kept for the next
+    // call like Chip8::run_for
+    pub fn push_for(&mut self, framebuffer: &[u8], width: usize, duration: Duration) -> io::Result<()> {
+        self.pending_time += duration;
+        while self.pending_time >= FRAME_DURATION {
+            self.pending_time -= FRAME_DURATION;
+            self.push_frame(framebuffer, width)?;
+        }
+        Ok(())
+    }
+
+    // Writes the last screen and the end of the file. Nothing is recorded afterwards
+    pub fn finish(&mut self) -> io::Result<()> {
+        if let Some(pending) = self.pending.take() {
+            let delay = self.timeline.last_delay(pending.frames);
+            self.write_frame(&pending, delay)?;
+        }
+        if let Some(encoder) = self.encoder.take() {
+            encoder.into_inner()?.flush()?;
+        }
+        Ok(())
+    }
+
+    // Each pixel is a square filling the width of the image, the palette index is the pixel value
+    fn write_frame(&mut self, pending: &PendingFrame, delay: u16) -> io::Result<()> {
+        let Some(encoder) = self.encoder.as_mut() else {
+            return Ok(());
+        };
+        let scale = (self.image_width / pending.width.max(1)).max(1);
+        let mut buffer = vec![0; self.image_width * self.image_height];
+        for (y, line) in buffer.chunks_mut(self.image_width).enumerate() {
+            let row = y / scale * pending.width;
+            for (x, index) in line.iter_mut().enumerate() {
+                *index = pending.framebuffer.get(row + x / scale).map_or(0, |&pixel| pixel % 4);
+            }
+        }
+        let frame = Frame {
+            width: self.image_width as u16,
+            height: self.image_height as u16,
+            delay,
+            buffer: Cow::Owned(buffer),
+            ..Frame::default()
+        };
+        encoder.write_frame(&frame).map_err(io::Error::other)
+    }
+}
+
+// The recording is also finished when the recorder is dropped, on the errors stopping the emulator for example
+impl<W: Write> Drop for GifRecorder<W> {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
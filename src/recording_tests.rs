@@ -0,0 +1,163 @@
+#[cfg(test)]
+mod recording_tests {
+    use std::time::Duration;
+    use crate::recording::Timeline;
+    use crate::{window_size, GifRecorder, Rotation, Theme, FRAME_DURATION};
+
+    // Delay and palette indexes of a decoded frame
+    type DecodedFrame = (u16, Vec<u8>);
+
+    // Frames of a GIF file, with the size of its image
+    fn decode(file: &[u8]) -> ((u16, u16), Vec<DecodedFrame>) {
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = options.read_info(file).unwrap();
+        let size = (decoder.width(), decoder.height());
+        let mut frames = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            frames.push((frame.delay, frame.buffer.to_vec()));
+        }
+        (size, frames)
+    }
+
+    fn record(scale: u32, push: impl FnOnce(&mut GifRecorder<&mut Vec<u8>>)) -> Vec<u8> {
+        record_rotated(scale, Rotation::None, push)
+    }
+
+    fn record_rotated(scale: u32, rotation: Rotation, push: impl FnOnce(&mut GifRecorder<&mut Vec<u8>>)) -> Vec<u8> {
+        let mut file = Vec::new();
+        let mut recorder = GifRecorder::new(&mut file, scale, rotation, &Theme::default()).unwrap();
+        push(&mut recorder);
+        recorder.finish().unwrap();
+        drop(recorder);
+        file
+    }
+
+    #[test]
+    fn timeline_delay_keeps_the_time_of_the_frames_in_centiseconds() {
+        let mut timeline = Timeline::default();
+        // 6 frames are 10 centiseconds
+        assert_eq!(timeline.delay(6), Some(10));
+        // A single frame is too short, its time goes to the next screen
+        assert_eq!(timeline.delay(1), None);
+        assert_eq!(timeline.delay(1), Some(3));
+        // The rounding errors do not add up: 60 frames make one second
+        let mut timeline = Timeline::default();
+        let total: u16 = (0..30).filter_map(|_| timeline.delay(2)).sum();
+        assert_eq!(total, 100);
+        // The last screen is always shown
+        assert_eq!(Timeline::default().last_delay(1), 2);
+    }
+
+    #[test]
+    fn push_frame_merges_identical_frames() {
+        let (first, second) = (vec![0, 1, 1, 0], vec![1, 0, 0, 1]);
+        let file = record(1, |recorder| {
+            for _ in 0..3 {
+                recorder.push_frame(&first, 2).unwrap();
+            }
+            for _ in 0..12 {
+                recorder.push_frame(&second, 2).unwrap();
+            }
+            recorder.push_frame(&first, 2).unwrap();
+        });
+        let (_, frames) = decode(&file);
+        let delays: Vec<u16> = frames.iter().map(|(delay, _)| *delay).collect();
+        // 3 frames are 5 centiseconds, 12 more reach 25, the last one is shown for the minimum delay
+        assert_eq!(delays, [5, 20, 2]);
+    }
+
+    #[test]
+    fn push_frame_leaves_out_short_frames() {
+        let file = record(1, |recorder| {
+            recorder.push_frame(&[0, 0], 2).unwrap();
+            recorder.push_frame(&[1, 1], 2).unwrap();
+            recorder.push_frame(&[2, 2], 2).unwrap();
+        });
+        let (_, frames) = decode(&file);
+        // The first screen was shown for 1 frame only, the second one gets its time
+        let delays: Vec<u16> = frames.iter().map(|(delay, _)| *delay).collect();
+        assert_eq!(delays, [3, 2]);
+        assert!(frames[0].1.iter().all(|&index| index == 1));
+    }
+
+    #[test]
+    fn push_frames_adds_the_screen_for_the_frames_run() {
+        let file = record(1, |recorder| {
+            recorder.push_frames(&[1], 1, 3).unwrap();
+            recorder.push_frames(&[1], 1, 0).unwrap();
+            recorder.push_frames(&[0], 1, 3).unwrap();
+        });
+        let (_, frames) = decode(&file);
+        assert_eq!(frames.iter().map(|frame| frame.0).collect::<Vec<_>>(), [5, 5]);
+    }
+
+    #[test]
+    fn push_for_keeps_the_part_of_a_frame_left() {
+        let file = record(1, |recorder| {
+            recorder.push_for(&[1], 1, FRAME_DURATION * 5 / 2).unwrap();
+            // Half a frame was left, with this one they make a third frame
+            recorder.push_for(&[1], 1, FRAME_DURATION / 2 + Duration::from_micros(1)).unwrap();
+        });
+        let (_, frames) = decode(&file);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].0, 5);
+    }
+
+    #[test]
+    fn gif_shows_both_resolutions_at_the_size_of_the_window() {
+        let (image_width, image_height) = window_size(2);
+        let mut low = vec![0; 64 * 32];
+        low[0] = 1;
+        low[64 * 32 - 1] = 3;
+        let mut high = vec![0; 128 * 64];
+        high[1] = 2;
+        let file = record(2, |recorder| {
+            recorder.push_frame(&low, 64).unwrap();
+            recorder.push_frame(&low, 64).unwrap();
+            recorder.push_frame(&high, 128).unwrap();
+            recorder.push_frame(&high, 128).unwrap();
+        });
+        let (size, frames) = decode(&file);
+        assert_eq!(size, (image_width as u16, image_height as u16));
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].0, 3);
+        // The low resolution pixels are squares of 2 by 2, the high resolution ones fill the same image
+        let (width, low_frame, high_frame) = (image_width as usize, &frames[0].1, &frames[1].1);
+        assert_eq!(&low_frame[..3], [1, 1, 0]);
+        assert_eq!(&low_frame[width..width + 3], [1, 1, 0]);
+        assert_eq!(low_frame[low_frame.len() - 1], 3);
+        assert_eq!(&high_frame[..3], [0, 2, 0]);
+        assert_eq!(high_frame[width + 1], 0);
+    }
+
+    #[test]
+    fn gif_is_turned_by_the_rotation() {
+        let mut low = vec![0; 64 * 32];
+        low[0] = 1;
+        let file = record_rotated(2, Rotation::Quarter, |recorder| {
+            recorder.push_frame(&low, 64).unwrap();
+        });
+        let (size, frames) = decode(&file);
+        assert_eq!(size, (64, 128));
+        // The top left pixel is at the top right, a square of 2 by 2
+        let frame = &frames[0].1;
+        assert_eq!(&frame[60..64], [0, 0, 1, 1]);
+        assert_eq!(&frame[124..128], [0, 0, 1, 1]);
+        assert_eq!(frame.iter().filter(|&&index| index == 1).count(), 4);
+    }
+
+    #[test]
+    fn finish_stops_the_recording_once() {
+        let mut file = Vec::new();
+        let mut recorder = GifRecorder::new(&mut file, 1, Rotation::None, &Theme::default()).unwrap();
+        recorder.push_frame(&[1], 1).unwrap();
+        recorder.finish().unwrap();
+        // Nothing is recorded once finished
+        recorder.push_frame(&[0], 1).unwrap();
+        recorder.finish().unwrap();
+        drop(recorder);
+        let (_, frames) = decode(&file);
+        assert_eq!(frames.len(), 1);
+    }
+}
@@ -0,0 +1,69 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./rotation_tests.rs"]
+mod rotation_tests;
+
+// Rotation of the screen shown by the frontends, for portrait programs and screens mounted sideways. The emulation
+// keeps its screen, only the image shown, the screenshots and the recordings turn
+
+// Clockwise rotation of the image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rotation {
+    #[default]
+    None,
+    Quarter,
+    Half,
+    ThreeQuarters,
+}
+
+impl Rotation {
+    // Rotation of 0, 90, 180 or 270 degrees
+    pub fn from_degrees(degrees: u32) -> Option<Rotation> {
+        match degrees {
+            0 => Some(Rotation::None),
+            90 => Some(Rotation::Quarter),
+            180 => Some(Rotation::Half),
+            270 => Some(Rotation::ThreeQuarters),
+            _ => None,
+        }
+    }
+
+    // Whether the width and the height swap
+    pub fn is_sideways(&self) -> bool {
+        matches!(self, Rotation::Quarter | Rotation::ThreeQuarters)
+    }
+
+    // Size of the rotated image of a width by height one, like a window size
+    pub fn size<T>(&self, (width, height): (T, T)) -> (T, T) {
+        if self.is_sideways() { (height, width) } else { (width, height) }
+    }
+}
+
+// Index in a width by height image of the pixel shown at (x, y) once the image is rotated
+pub fn rotated_index(x: usize, y: usize, width: usize, height: usize, rotation: Rotation) -> usize {
+    let (source_x, source_y) = match rotation {
+        Rotation::None => (x, y),
+        Rotation::Quarter => (y, height - 1 - x),
+        Rotation::Half => (width - 1 - x, height - 1 - y),
+        Rotation::ThreeQuarters => (width - 1 - y, x),
+    };
+    source_y * width + source_x
+}
+
+// Pixels of a width wide image rotated, row by row, with the width of the rotated image being rotation.size of the
+// width and height
+pub fn rotate<P: Copy>(pixels: &[P], width: usize, rotation: Rotation) -> Vec<P> {
+    if rotation == Rotation::None {
+        return pixels.to_vec();
+    }
+    let width = width.max(1);
+    let height = pixels.len() / width;
+    let (rotated_width, rotated_height) = rotation.size((width, height));
+    let mut rotated = Vec::with_capacity(rotated_width * rotated_height);
+    for y in 0..rotated_height {
+        for x in 0..rotated_width {
+            rotated.push(pixels[rotated_index(x, y, width, height, rotation)]);
+        }
+    }
+    rotated
+}
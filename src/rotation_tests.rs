@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod rotation_tests {
+    use crate::{rotate, rotated_index, window_size, Rotation};
+
+    // 3x2 image with a different value in every pixel:
+    // 1 2 3
+    // 4 5 6
+    const PATTERN: [u8; 6] = [1, 2, 3, 4, 5, 6];
+
+    #[test]
+    fn rotate_none_keeps_the_image() {
+        assert_eq!(rotate(&PATTERN, 3, Rotation::None), PATTERN);
+    }
+
+    #[test]
+    fn rotate_quarter_turns_the_image_clockwise() {
+        // 4 1
+        // 5 2
+        // 6 3
+        assert_eq!(rotate(&PATTERN, 3, Rotation::Quarter), [4, 1, 5, 2, 6, 3]);
+    }
+
+    #[test]
+    fn rotate_half_turns_the_image_upside_down() {
+        assert_eq!(rotate(&PATTERN, 3, Rotation::Half), [6, 5, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn rotate_three_quarters_turns_the_image_counterclockwise() {
+        // 3 6
+        // 2 5
+        // 1 4
+        assert_eq!(rotate(&PATTERN, 3, Rotation::ThreeQuarters), [3, 6, 2, 5, 1, 4]);
+    }
+
+    #[test]
+    fn rotations_of_a_rotated_image_add_up() {
+        let twice = rotate(&rotate(&PATTERN, 3, Rotation::Quarter), 2, Rotation::Quarter);
+        assert_eq!(twice, rotate(&PATTERN, 3, Rotation::Half));
+        let back = rotate(&rotate(&PATTERN, 3, Rotation::Quarter), 2, Rotation::ThreeQuarters);
+        assert_eq!(back, PATTERN);
+    }
+
+    #[test]
+    fn rotated_index_is_the_source_of_the_top_left_corner() {
+        // The top left corner of the rotated image
+        assert_eq!(rotated_index(0, 0, 3, 2, Rotation::None), 0);
+        assert_eq!(rotated_index(0, 0, 3, 2, Rotation::Quarter), 3);
+        assert_eq!(rotated_index(0, 0, 3, 2, Rotation::Half), 5);
+        assert_eq!(rotated_index(0, 0, 3, 2, Rotation::ThreeQuarters), 2);
+    }
+
+    #[test]
+    fn rotation_from_degrees_reads_the_quarter_turns_only() {
+        assert_eq!(Rotation::from_degrees(0), Some(Rotation::None));
+        assert_eq!(Rotation::from_degrees(90), Some(Rotation::Quarter));
+        assert_eq!(Rotation::from_degrees(180), Some(Rotation::Half));
+        assert_eq!(Rotation::from_degrees(270), Some(Rotation::ThreeQuarters));
+        assert_eq!(Rotation::from_degrees(45), None);
+        assert_eq!(Rotation::from_degrees(360), None);
+    }
+
+    #[test]
+    fn rotation_size_swaps_the_window_sides_for_the_quarter_turns() {
+        assert_eq!(Rotation::None.size(window_size(10)), (640, 320));
+        assert_eq!(Rotation::Quarter.size(window_size(10)), (320, 640));
+        assert_eq!(Rotation::Half.size(window_size(10)), (640, 320));
+        assert_eq!(Rotation::ThreeQuarters.size(window_size(10)), (320, 640));
+    }
+}
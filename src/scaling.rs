@@ -0,0 +1,45 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./scaling_tests.rs"]
+mod scaling_tests;
+
+// Scaling of the screen in the windows of the frontends: whole scale factors only, so that every CHIP-8 pixel is the
+// same square of window pixels, with black borders for the rest of the window
+
+use crate::display::{SCREEN_HEIGHT, SCREEN_WIDTH};
+
+// Window pixels per CHIP-8 pixel of the low resolution screen when no scale is given
+pub const DEFAULT_SCALE: u32 = 10;
+
+// Area of the window showing the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    // Window pixels per screen pixel
+    pub scale: u32,
+}
+
+// Size of a window showing the low resolution screen at the scale. The high resolution screen fits in the same window
+// at half the scale, so the window keeps its size when a program switches modes
+pub fn window_size(scale: u32) -> (u32, u32) {
+    let scale = scale.max(1);
+    (SCREEN_WIDTH as u32 * scale, SCREEN_HEIGHT as u32 * scale)
+}
+
+// Largest whole scale of the screen fitting in the window, centered. The scale is at least 1, the screen is cut when
+// the window is smaller than it
+pub fn letterbox(screen_width: usize, screen_height: usize, window_width: u32, window_height: u32) -> Viewport {
+    let (screen_width, screen_height) = (screen_width.max(1) as u32, screen_height.max(1) as u32);
+    let scale = (window_width / screen_width).min(window_height / screen_height).max(1);
+    let (width, height) = (screen_width * scale, screen_height * scale);
+    Viewport {
+        x: window_width.saturating_sub(width) / 2,
+        y: window_height.saturating_sub(height) / 2,
+        width,
+        height,
+        scale,
+    }
+}
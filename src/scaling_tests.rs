@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod scaling_tests {
+    use crate::scaling::{letterbox, window_size, Viewport};
+
+    #[test]
+    fn window_size_is_the_low_resolution_screen_at_the_scale() {
+        assert_eq!(window_size(8), (512, 256));
+        assert_eq!(window_size(16), (1024, 512));
+        // A scale of 0 is taken as 1
+        assert_eq!(window_size(0), (64, 32));
+    }
+
+    #[test]
+    fn letterbox_fills_a_window_of_a_whole_scale() {
+        let viewport = letterbox(64, 32, 640, 320);
+        assert_eq!(viewport, Viewport { x: 0, y: 0, width: 640, height: 320, scale: 10 });
+    }
+
+    #[test]
+    fn letterbox_snaps_down_to_a_whole_scale() {
+        // 700 / 64 is 10.9, the screen stays at 10 with the rest split on both sides
+        let viewport = letterbox(64, 32, 700, 330);
+        assert_eq!(viewport, Viewport { x: 30, y: 5, width: 640, height: 320, scale: 10 });
+    }
+
+    #[test]
+    fn letterbox_centers_the_screen_in_wide_and_tall_windows() {
+        // Limited by the height
+        assert_eq!(letterbox(64, 32, 1920, 320), Viewport { x: 640, y: 0, width: 640, height: 320, scale: 10 });
+        // Limited by the width
+        assert_eq!(letterbox(64, 32, 640, 1000), Viewport { x: 0, y: 340, width: 640, height: 320, scale: 10 });
+    }
+
+    #[test]
+    fn letterbox_fits_the_high_resolution_screen_in_the_same_window() {
+        // The high resolution screen fills the window of the low resolution one at half the scale
+        let (width, height) = window_size(8);
+        assert_eq!(letterbox(128, 64, width, height), Viewport { x: 0, y: 0, width: 512, height: 256, scale: 4 });
+        // With an odd scale it is one smaller with borders
+        let (width, height) = window_size(5);
+        assert_eq!(letterbox(128, 64, width, height), Viewport { x: 32, y: 16, width: 256, height: 128, scale: 2 });
+    }
+
+    #[test]
+    fn letterbox_fits_the_screen_on_common_displays() {
+        // 1080p: limited by the width, 1920 / 64 is exactly 30
+        assert_eq!(letterbox(64, 32, 1920, 1080), Viewport { x: 0, y: 60, width: 1920, height: 960, scale: 30 });
+        assert_eq!(letterbox(128, 64, 1920, 1080), Viewport { x: 0, y: 60, width: 1920, height: 960, scale: 15 });
+        // 1440p, 2560 / 64 is 40
+        assert_eq!(letterbox(64, 32, 2560, 1440), Viewport { x: 0, y: 80, width: 2560, height: 1280, scale: 40 });
+        // 1366x768 laptops: 21.3 snaps down to 21 and 10.7 to 10, the high resolution screen keeps wider borders
+        assert_eq!(letterbox(64, 32, 1366, 768), Viewport { x: 11, y: 48, width: 1344, height: 672, scale: 21 });
+        assert_eq!(letterbox(128, 64, 1366, 768), Viewport { x: 43, y: 64, width: 1280, height: 640, scale: 10 });
+        // 4:3 and portrait displays
+        assert_eq!(letterbox(64, 32, 1024, 768), Viewport { x: 0, y: 128, width: 1024, height: 512, scale: 16 });
+        assert_eq!(letterbox(64, 32, 1080, 1920), Viewport { x: 28, y: 704, width: 1024, height: 512, scale: 16 });
+    }
+
+    #[test]
+    fn letterbox_keeps_a_scale_of_1_in_a_window_smaller_than_the_screen() {
+        assert_eq!(letterbox(128, 64, 100, 50), Viewport { x: 0, y: 0, width: 128, height: 64, scale: 1 });
+    }
+}
@@ -0,0 +1,59 @@
+mod schip_check {
+    use crate::{Chip8Config, Variant};
+
+    // Small SCHIP program exercising 00FF, FX30, DXY0 and 00CN
+    const PROGRAM: [u8; 58] = [
+        0x00, 0xFF, // 0x200: 00FF - switch to high resolution
+        0x60, 0x05, // 0x202: V0 = 5
+        0xF0, 0x30, // 0x204: I = big font digit V0
+        0x61, 0x02, // 0x206: V1 = 2
+        0x62, 0x02, // 0x208: V2 = 2
+        0xD1, 0x2A, // 0x20A: draw the 8x10 big digit at (V1, V2)
+        0xA2, 0x1A, // 0x20C: I = 0x21A (box sprite)
+        0x61, 0x14, // 0x20E: V1 = 20
+        0xD1, 0x20, // 0x210: draw the 16x16 sprite at (V1, V2)
+        0x00, 0xC4, // 0x212: scroll down 4 pixels
+        0x00, 0xFD, // 0x214: exit
+        0x12, 0x16, // 0x216: unreachable loop
+        0x00, 0x00, // 0x218: padding
+        // 0x21A: 16x16 hollow box
+        0xFF, 0xFF,
+        0x80, 0x01, 0x80, 0x01, 0x80, 0x01, 0x80, 0x01, 0x80, 0x01, 0x80, 0x01, 0x80, 0x01,
+        0x80, 0x01, 0x80, 0x01, 0x80, 0x01, 0x80, 0x01, 0x80, 0x01, 0x80, 0x01, 0x80, 0x01,
+        0xFF, 0xFF,
+    ];
+    const MAX_CYCLES: usize = 100;
+    const GOLDEN: &str = include_str!("./schip_check_golden.txt");
+
+    fn diff(expected: &[&str], actual: &[&str]) -> String {
+        let mut out = String::new();
+        for row in 0..expected.len().max(actual.len()) {
+            let expected_row = expected.get(row).copied().unwrap_or("");
+            let actual_row = actual.get(row).copied().unwrap_or("");
+            if expected_row != actual_row {
+                out += &format!("row {row:2} expected {expected_row}\n");
+                out += &format!("row {row:2} actual   {actual_row}\n");
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn schip_program_matches_golden_framebuffer() {
+        let mut chip8 = Chip8Config::new().variant(Variant::SuperChip).build().unwrap();
+        chip8.load_rom(&PROGRAM).unwrap();
+
+        let mut cycles = 0;
+        while !chip8.emulate_cycle().unwrap().halted {
+            cycles += 1;
+            assert!(cycles < MAX_CYCLES, "program did not exit within {MAX_CYCLES} cycles");
+        }
+
+        // The golden file has the # and . of the dump, which also shows the whole screen on a failure
+        let screen = chip8.framebuffer_ascii_with('#', '.');
+        let expected: Vec<&str> = GOLDEN.lines().collect();
+        let actual: Vec<&str> = screen.lines().collect();
+        let diff = diff(&expected, &actual);
+        assert!(diff.is_empty(), "framebuffer differs from golden:\n{diff}\nscreen:\n{screen}");
+    }
+}
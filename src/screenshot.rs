@@ -0,0 +1,55 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./screenshot_tests.rs"]
+mod screenshot_tests;
+
+// PNG screenshots, enabled with the screenshot feature: the screen in the colors of the theme, each pixel a square of
+// scale by scale, saved under the name of the ROM and the time
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use crate::{capture_file_name, Theme};
+
+// RGBA bytes of the framebuffer with each pixel repeated in a square of scale by scale
+pub fn to_scaled_rgba(framebuffer: &[u8], width: usize, scale: usize, theme: &Theme) -> Vec<u8> {
+    let scale = scale.max(1);
+    let mut rgba = Vec::with_capacity(framebuffer.len() * scale * scale * 4);
+    for row in framebuffer.chunks(width.max(1)) {
+        let line: Vec<u8> = row.iter().flat_map(|&pixel| theme.rgba(pixel).repeat(scale)).collect();
+        for _ in 0..scale {
+            rgba.extend_from_slice(&line);
+        }
+    }
+    rgba
+}
+
+// PNG file of the framebuffer, each pixel a square of scale by scale
+pub fn encode_png(framebuffer: &[u8], width: usize, scale: usize, theme: &Theme) -> io::Result<Vec<u8>> {
+    let scale = scale.max(1);
+    let height = framebuffer.len() / width.max(1);
+    let mut file = Vec::new();
+    let mut encoder = png::Encoder::new(&mut file, (width * scale) as u32, (height * scale) as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()
+        .and_then(|mut writer| writer.write_image_data(&to_scaled_rgba(framebuffer, width, scale, theme)))
+        .map_err(io::Error::other)?;
+    Ok(file)
+}
+
+// Writes the screenshot in the directory, created if missing, and returns the path of the file
+pub fn save_screenshot(
+    directory: &Path,
+    rom_path: &Path,
+    framebuffer: &[u8],
+    width: usize,
+    scale: usize,
+    theme: &Theme,
+) -> io::Result<PathBuf> {
+    fs::create_dir_all(directory)?;
+    let path = directory.join(capture_file_name(rom_path, SystemTime::now(), "png"));
+    fs::write(&path, encode_png(framebuffer, width, scale, theme)?)?;
+    Ok(path)
+}
@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod screenshot_tests {
+    use std::path::Path;
+    use crate::screenshot::{encode_png, save_screenshot, to_scaled_rgba};
+    use crate::Theme;
+
+    // Size and RGBA bytes of a PNG file
+    fn decode_png(file: &[u8]) -> (u32, u32, Vec<u8>) {
+        let mut reader = png::Decoder::new(file).read_info().unwrap();
+        let mut rgba = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut rgba).unwrap();
+        assert_eq!(info.color_type, png::ColorType::Rgba);
+        rgba.truncate(info.buffer_size());
+        (info.width, info.height, rgba)
+    }
+
+    #[test]
+    fn to_scaled_rgba_draws_each_pixel_as_a_square() {
+        let theme = Theme::default();
+        let rgba = to_scaled_rgba(&[1, 0], 2, 2, &theme);
+        let (lit, off) = (theme.rgba(1), theme.rgba(0));
+        let line = [lit, lit, off, off].concat();
+        assert_eq!(rgba, [line.clone(), line].concat());
+    }
+
+    #[test]
+    fn encode_png_decodes_back_to_the_screen() {
+        let (width, height) = (64, 32);
+        let mut framebuffer = vec![0; width * height];
+        framebuffer[0] = 1;
+        framebuffer[width + 5] = 2;
+        framebuffer[width * height - 1] = 3;
+        let theme = Theme::preset("amber").unwrap();
+
+        let (image_width, image_height, rgba) = decode_png(&encode_png(&framebuffer, width, 4, &theme).unwrap());
+
+        assert_eq!((image_width, image_height), (256, 128));
+        let pixel = |x: usize, y: usize| rgba[(y * 256 + x) * 4..(y * 256 + x) * 4 + 4].to_vec();
+        assert_eq!(pixel(0, 0), theme.rgba(1));
+        assert_eq!(pixel(3, 3), theme.rgba(1));
+        assert_eq!(pixel(4, 0), theme.rgba(0));
+        assert_eq!(pixel(21, 6), theme.rgba(2));
+        assert_eq!(pixel(255, 127), theme.rgba(3));
+        assert_eq!(pixel(100, 50), theme.rgba(0));
+    }
+
+    #[test]
+    fn save_screenshot_writes_a_png_named_after_the_rom() {
+        let directory = std::env::temp_dir().join(format!("chip8-screenshots-{}", std::process::id()));
+        let path = save_screenshot(&directory, Path::new("pong.rom"), &[1; 64 * 32], 64, 1, &Theme::default()).unwrap();
+
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        assert!(name.starts_with("pong-") && name.ends_with(".png"), "{name}");
+        let (width, height, _) = decode_png(&std::fs::read(&path).unwrap());
+        assert_eq!((width, height), (64, 32));
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,431 @@
+// SDL2 frontend, enabled with the sdl2 feature: a window showing the screen, the keyboard as the keypad and a
+// tone or the XO-CHIP audio pattern for the buzzer, faded in and out
+
+use std::collections::BTreeMap;
+use std::mem;
+use std::time::Instant;
+use sdl2::audio::{AudioCallback, AudioDevice, AudioSpecDesired};
+use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::{Mod, Scancode};
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, Canvas};
+use sdl2::video::{FullscreenType, Window, WindowPos};
+use sdl2::{EventPump, Sdl};
+use crate::{
+    control, crt_image, letter_free, letterbox, overlay_scale, rotate, window_size, BeepGate, BeepSource, Buzzer,
+    Control, ControlKey, Crt, Display, Input, Keymap, Phosphor, PhysicalKey, Rgb, Rotation, SoundHook, TextBitmap,
+    Theme, Viewport, Waveform, WindowFrontend, FRAME_DURATION,
+};
+
+// Scancode of each key of the emulator controls
+fn control_scancode(key: ControlKey) -> Scancode {
+    match key {
+        ControlKey::Escape => Scancode::Escape,
+        ControlKey::Backspace => Scancode::Backspace,
+        ControlKey::Letter(letter) => scancode(letter),
+    }
+}
+
+// Scancode of each keyboard key of the keymap, by its place on the keyboard whatever the layout
+fn scancode(physical_key: PhysicalKey) -> Scancode {
+    match physical_key {
+        PhysicalKey::Key0 => Scancode::Num0,
+        PhysicalKey::Key1 => Scancode::Num1,
+        PhysicalKey::Key2 => Scancode::Num2,
+        PhysicalKey::Key3 => Scancode::Num3,
+        PhysicalKey::Key4 => Scancode::Num4,
+        PhysicalKey::Key5 => Scancode::Num5,
+        PhysicalKey::Key6 => Scancode::Num6,
+        PhysicalKey::Key7 => Scancode::Num7,
+        PhysicalKey::Key8 => Scancode::Num8,
+        PhysicalKey::Key9 => Scancode::Num9,
+        PhysicalKey::A => Scancode::A,
+        PhysicalKey::B => Scancode::B,
+        PhysicalKey::C => Scancode::C,
+        PhysicalKey::D => Scancode::D,
+        PhysicalKey::E => Scancode::E,
+        PhysicalKey::F => Scancode::F,
+        PhysicalKey::G => Scancode::G,
+        PhysicalKey::H => Scancode::H,
+        PhysicalKey::I => Scancode::I,
+        PhysicalKey::J => Scancode::J,
+        PhysicalKey::K => Scancode::K,
+        PhysicalKey::L => Scancode::L,
+        PhysicalKey::M => Scancode::M,
+        PhysicalKey::N => Scancode::N,
+        PhysicalKey::O => Scancode::O,
+        PhysicalKey::P => Scancode::P,
+        PhysicalKey::Q => Scancode::Q,
+        PhysicalKey::R => Scancode::R,
+        PhysicalKey::S => Scancode::S,
+        PhysicalKey::T => Scancode::T,
+        PhysicalKey::U => Scancode::U,
+        PhysicalKey::V => Scancode::V,
+        PhysicalKey::W => Scancode::W,
+        PhysicalKey::X => Scancode::X,
+        PhysicalKey::Y => Scancode::Y,
+        PhysicalKey::Z => Scancode::Z,
+        PhysicalKey::Up => Scancode::Up,
+        PhysicalKey::Down => Scancode::Down,
+        PhysicalKey::Left => Scancode::Left,
+        PhysicalKey::Right => Scancode::Right,
+        PhysicalKey::Space => Scancode::Space,
+    }
+}
+
+const SAMPLE_RATE: i32 = 44_100;
+
+impl AudioCallback for BeepSource {
+    type Channel = f32;
+
+    fn callback(&mut self, samples: &mut [f32]) {
+        self.fill(samples);
+    }
+}
+
+pub struct SdlFrontend {
+    // The subsystems are closed when the context is dropped, so it is kept as long as the frontend
+    _sdl: Sdl,
+    canvas: Canvas<Window>,
+    event_pump: EventPump,
+    // The device plays until it is dropped, the beep sounds while its gate is open
+    audio: AudioDevice<BeepSource>,
+    beep: BeepGate,
+    // Last drawn screen, rotated, and its size, drawn again when the window is resized
+    rotation: Rotation,
+    framebuffer: Vec<u8>,
+    width: usize,
+    height: usize,
+    theme: Theme,
+    // Screen shown in the window, following the drawn one with the fading of the pixels turned off. With the
+    // persistence on it moves once per 60 Hz frame
+    phosphor: Phosphor,
+    last_step: Instant,
+    // F12 was pressed since the last call of screenshot_requested
+    screenshot: bool,
+    // Same for F9 and record_requested
+    record: bool,
+    // Same for M and mute_requested
+    mute: bool,
+    // Emulator controls pressed since the last call of take_controls
+    controls: Vec<Control>,
+    // Position and size of the window before it went fullscreen, restored when leaving it
+    windowed: Option<((i32, i32), (u32, u32))>,
+    // Debug overlay over the top left corner of the screen, toggled by F3
+    overlay_shown: bool,
+    overlay: Option<TextBitmap>,
+    // CRT effect of the scaled screen, and the one F6 switches to
+    crt: Crt,
+    crt_toggled: Crt,
+    // The window has the keyboard focus
+    focused: bool,
+    keymap: Keymap,
+}
+
+impl SdlFrontend {
+    // Opens a window of the low resolution screen with each pixel a square of scale by scale, turned by the rotation
+    pub fn new(title: &str, scale: u32, rotation: Rotation) -> Result<SdlFrontend, String> {
+        let sdl = sdl2::init()?;
+        let (window_width, window_height) = rotation.size(window_size(scale));
+        let window = sdl.video()?
+            .window(title, window_width, window_height)
+            .position_centered()
+            .resizable()
+            .build()
+            .map_err(|error| error.to_string())?;
+        let canvas = window.into_canvas().build().map_err(|error| error.to_string())?;
+        let desired = AudioSpecDesired { freq: Some(SAMPLE_RATE), channels: Some(1), samples: None };
+        let beep = BeepGate::default();
+        let audio = sdl.audio()?.open_playback(None, &desired, |spec| BeepSource::new(beep.clone(), spec.freq as u32))?;
+        audio.resume();
+        let event_pump = sdl.event_pump()?;
+        let (width, height) = rotation.size((64, 32));
+        Ok(SdlFrontend {
+            _sdl: sdl,
+            canvas,
+            event_pump,
+            audio,
+            beep,
+            rotation,
+            framebuffer: Vec::new(),
+            width,
+            height,
+            theme: Theme::default(),
+            phosphor: Phosphor::new(0),
+            last_step: Instant::now(),
+            screenshot: false,
+            record: false,
+            mute: false,
+            controls: Vec::new(),
+            windowed: None,
+            overlay_shown: false,
+            overlay: None,
+            crt: Crt::OFF,
+            crt_toggled: Crt::SCANLINES,
+            focused: true,
+            keymap: Keymap::default(),
+        })
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.refresh();
+    }
+
+    // Frames a pixel stays visible after turning off, 0 shows the screen as it is
+    pub fn set_phosphor(&mut self, decay_frames: u32) {
+        self.phosphor = Phosphor::new(decay_frames);
+        self.refresh();
+    }
+
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+    }
+
+    // F6 switches between this effect and none, or the scanlines when it is none
+    pub fn set_crt(&mut self, crt: Crt) {
+        self.crt = crt;
+        self.crt_toggled = if crt.is_off() { Crt::SCANLINES } else { Crt::OFF };
+        self.redraw();
+    }
+
+    // Wave of the beep, for the programs without an XO-CHIP audio pattern
+    pub fn set_tone(&mut self, waveform: Waveform, frequency: f32) {
+        self.audio.lock().set_tone(waveform, frequency);
+    }
+
+    // Volume of the beep in percent, clamped to 100
+    pub fn set_volume(&mut self, volume: u8) {
+        self.beep.set_volume(volume);
+    }
+
+    pub fn muted(&self) -> bool {
+        self.beep.muted()
+    }
+
+    // Borderless fullscreen at the size of the desktop, the window gets back its position and size when leaving it.
+    // A failed switch leaves the window as it was
+    fn toggle_fullscreen(&mut self) {
+        let window = self.canvas.window_mut();
+        if window.fullscreen_state() == FullscreenType::Off {
+            self.windowed = Some((window.position(), window.size()));
+            let _ = window.set_fullscreen(FullscreenType::Desktop);
+        } else if window.set_fullscreen(FullscreenType::Off).is_ok() {
+            if let Some(((x, y), (width, height))) = self.windowed.take() {
+                let _ = window.set_size(width, height);
+                window.set_position(WindowPos::Positioned(x), WindowPos::Positioned(y));
+            }
+        }
+    }
+
+    // Moves the shown screen one frame towards the drawn one, then draws it
+    fn refresh(&mut self) {
+        self.phosphor.step(&self.framebuffer);
+        self.redraw();
+    }
+
+    // Each pixel of the shown screen is drawn as a square of the largest whole size fitting in the window, with
+    // borders of the background color around the screen
+    fn redraw(&mut self) {
+        let (window_width, window_height) = self.canvas.output_size().unwrap_or(window_size(1));
+        let viewport = letterbox(self.width, self.height, window_width, window_height);
+        let background = self.theme.color(0);
+        let colors = self.phosphor.colors(&self.theme);
+        let color = |[red, green, blue]: Rgb| Color::RGB(red, green, blue);
+        self.canvas.set_draw_color(color(background));
+        self.canvas.clear();
+        if self.crt.is_off() {
+            // The squares are filled together by color, there are more than 4 colors while pixels fade
+            let mut squares: BTreeMap<Rgb, Vec<Rect>> = BTreeMap::new();
+            for (index, rgb) in colors.into_iter().enumerate() {
+                if rgb != background {
+                    let x = viewport.x + (index % self.width) as u32 * viewport.scale;
+                    let y = viewport.y + (index / self.width) as u32 * viewport.scale;
+                    squares.entry(rgb).or_default().push(Rect::new(x as i32, y as i32, viewport.scale, viewport.scale));
+                }
+            }
+            for (rgb, rects) in squares {
+                self.canvas.set_draw_color(color(rgb));
+                // A failed drawing only loses this frame, the next one redraws everything
+                let _ = self.canvas.fill_rects(&rects);
+            }
+        } else {
+            self.draw_crt(&colors, &viewport);
+        }
+        if let Some(overlay) = self.overlay.as_ref().filter(|_| self.overlay_shown) {
+            // The box darkens the screen to a quarter below the white text
+            let scale = overlay_scale(&viewport) as u32;
+            let square = |(x, y): (usize, usize)| {
+                Rect::new((viewport.x + x as u32 * scale) as i32, (viewport.y + y as u32 * scale) as i32, scale, scale)
+            };
+            self.canvas.set_blend_mode(BlendMode::Blend);
+            self.canvas.set_draw_color(Color::RGBA(0, 0, 0, 192));
+            let _ = self.canvas.fill_rect(Rect::new(
+                viewport.x as i32,
+                viewport.y as i32,
+                overlay.width as u32 * scale,
+                overlay.height as u32 * scale,
+            ));
+            self.canvas.set_blend_mode(BlendMode::None);
+            self.canvas.set_draw_color(Color::RGB(0xFF, 0xFF, 0xFF));
+            let _ = self.canvas.fill_rects(&overlay.lit().map(square).collect::<Vec<Rect>>());
+        }
+        self.canvas.present();
+    }
+
+    // The screen with the CRT effect is scaled on the CPU, then copied to the window as a texture
+    fn draw_crt(&mut self, colors: &[Rgb], viewport: &Viewport) {
+        let image = crt_image(colors, self.width, viewport.scale as usize, &self.crt);
+        let creator = self.canvas.texture_creator();
+        let Ok(mut texture) = creator.create_texture_static(PixelFormatEnum::RGB24, viewport.width, viewport.height) else {
+            return;
+        };
+        if texture.update(None, image.as_flattened(), viewport.width as usize * 3).is_ok() {
+            let destination = Rect::new(viewport.x as i32, viewport.y as i32, viewport.width, viewport.height);
+            let _ = self.canvas.copy(&texture, None, destination);
+        }
+    }
+}
+
+impl Display for SdlFrontend {
+    fn draw(&mut self, framebuffer: &[u8], width: usize, height: usize) {
+        self.framebuffer = rotate(framebuffer, width, self.rotation);
+        (self.width, self.height) = self.rotation.size((width, height));
+        // With the phosphor the screen is shown by the next frame
+        if !self.phosphor.enabled() {
+            self.refresh();
+        }
+    }
+
+    fn clear(&mut self) {
+        self.framebuffer.fill(0);
+        if !self.phosphor.enabled() {
+            self.refresh();
+        }
+    }
+}
+
+impl Input for SdlFrontend {
+    fn poll(&mut self) -> [bool; 16] {
+        let keyboard = self.event_pump.keyboard_state();
+        self.keymap.poll(|physical_key| keyboard.is_scancode_pressed(scancode(physical_key)))
+    }
+}
+
+impl WindowFrontend for SdlFrontend {
+    // Handles the pending window events, returns false once the window is closed. F11 and Alt+Enter switch between
+    // the window and fullscreen
+    fn handle_events(&mut self) -> bool {
+        let (mut resized, mut toggle_fullscreen) = (false, false);
+        for event in self.event_pump.poll_iter() {
+            match event {
+                Event::Quit { .. } => return false,
+                Event::Window { win_event: WindowEvent::SizeChanged(..), .. } => resized = true,
+                Event::Window { win_event: WindowEvent::FocusGained, .. } => self.focused = true,
+                Event::Window { win_event: WindowEvent::FocusLost, .. } => self.focused = false,
+                Event::KeyDown { scancode: Some(Scancode::F11), repeat: false, .. } => toggle_fullscreen = true,
+                Event::KeyDown { scancode: Some(Scancode::Return), keymod, repeat: false, .. }
+                    if keymod.intersects(Mod::LALTMOD | Mod::RALTMOD) => toggle_fullscreen = true,
+                Event::KeyDown { scancode: Some(Scancode::F12), repeat: false, .. } => self.screenshot = true,
+                Event::KeyDown { scancode: Some(Scancode::F9), repeat: false, .. } => self.record = true,
+                Event::KeyDown { scancode: Some(Scancode::M), repeat: false, .. }
+                    if letter_free(PhysicalKey::M, &self.keymap) => self.mute = true,
+                Event::KeyDown { scancode: Some(Scancode::F6), repeat: false, .. } => {
+                    mem::swap(&mut self.crt, &mut self.crt_toggled);
+                    resized = true;
+                }
+                Event::KeyDown { scancode: Some(Scancode::F3), repeat: false, .. } => {
+                    self.overlay_shown = !self.overlay_shown;
+                    self.overlay = None;
+                    resized = true;
+                }
+                Event::KeyDown { scancode: Some(pressed), repeat: false, .. } => {
+                    let key = ControlKey::ALL.into_iter().find(|&key| control_scancode(key) == pressed);
+                    self.controls.extend(key.and_then(|key| control(key, &self.keymap)));
+                }
+                _ => {}
+            }
+        }
+        if toggle_fullscreen {
+            self.toggle_fullscreen();
+            resized = true;
+        }
+        if self.phosphor.enabled() && self.last_step.elapsed() >= FRAME_DURATION {
+            self.last_step = Instant::now();
+            self.refresh();
+        } else if resized {
+            self.redraw();
+        }
+        true
+    }
+
+    // P, Backspace, R, Esc and Q pressed since the last call, the letters when the keymap leaves them
+    fn take_controls(&mut self) -> Vec<Control> {
+        mem::take(&mut self.controls)
+    }
+
+    // Whether Tab is held, to fast-forward the emulation
+    fn turbo_held(&self) -> bool {
+        self.event_pump.keyboard_state().is_scancode_pressed(Scancode::Tab)
+    }
+
+    // Whether the window has the keyboard focus, as of the last handle_events
+    fn has_focus(&self) -> bool {
+        self.focused
+    }
+
+    fn set_title(&mut self, title: &str) {
+        // Titles with a NUL character are left out
+        let _ = self.canvas.window_mut().set_title(title);
+    }
+
+    // Whether the debug overlay is shown, its lines are then given once per frame to set_overlay
+    fn overlay_shown(&self) -> bool {
+        self.overlay_shown
+    }
+
+    fn set_overlay(&mut self, lines: &[String]) {
+        self.overlay = Some(TextBitmap::new(lines));
+        self.redraw();
+    }
+
+    fn screenshot_requested(&mut self) -> bool {
+        mem::take(&mut self.screenshot)
+    }
+
+    // Whether F9 was pressed since the last call, to start or stop a recording
+    fn record_requested(&mut self) -> bool {
+        mem::take(&mut self.record)
+    }
+
+    // Whether M was pressed since the last call, to mute or unmute the beep
+    fn mute_requested(&mut self) -> bool {
+        mem::take(&mut self.mute)
+    }
+
+    // Only silences the output, the sound timer and the gate go on
+    fn set_muted(&mut self, muted: bool) {
+        self.beep.set_muted(muted);
+    }
+
+    // Opens and closes the gate of the audio thread from tick_timers, at the emulated frame the sound starts or stops
+    fn sound_hook(&self) -> Option<SoundHook> {
+        Some(self.beep.sound_hook())
+    }
+
+    fn set_sound_held(&mut self, held: bool) {
+        self.beep.set_held(held);
+    }
+
+    // Audio pattern of the program, given by Chip8::loaded_audio_pattern once per frame, played instead of the tone
+    fn set_audio_pattern(&mut self, pattern: Option<(&[u8; 16], f64)>) {
+        self.beep.set_pattern(pattern);
+    }
+}
+
+impl Buzzer for SdlFrontend {
+    fn set_active(&mut self, on: bool) {
+        self.beep.set_active(on);
+    }
+}
@@ -0,0 +1,120 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./settings_tests.rs"]
+mod settings_tests;
+
+// Settings changed while playing and kept for the next runs, in a text file of "name = value" lines under the
+// configuration directory of the user, like ~/.config/chip8/settings.cfg. A [keymap] section after them gives the
+// keyboard keys of the keypad, one "KEY = NAME" assignment per line
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use crate::MAX_VOLUME;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settings {
+    // Volume of the beep in percent, up to MAX_VOLUME
+    pub volume: u8,
+    pub muted: bool,
+    // Assignments of the [keymap] section, one per line as written, parsed by parse_keymap when the frontend starts
+    // so that a mistake is reported instead of ignored
+    pub keymap: String,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings { volume: MAX_VOLUME, muted: false, keymap: String::new() }
+    }
+}
+
+impl Settings {
+    // Reads the lines of a settings file. The unknown names and the values that do not parse keep their default, so
+    // that a file from another version still loads
+    pub fn parse(text: &str) -> Settings {
+        let mut settings = Settings::default();
+        let mut section = "";
+        for line in text.lines().map(str::trim) {
+            if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+                section = name.trim();
+                continue;
+            }
+            if section == "keymap" {
+                if !line.is_empty() {
+                    settings.keymap.push_str(line);
+                    settings.keymap.push('\n');
+                }
+                continue;
+            }
+            // The lines of unknown sections are left out
+            let Some((name, value)) = line.split_once('=').filter(|_| section.is_empty()) else {
+                continue;
+            };
+            match name.trim() {
+                "volume" => {
+                    if let Ok(volume) = value.trim().parse::<u8>() {
+                        settings.volume = volume.min(MAX_VOLUME);
+                    }
+                }
+                "muted" => {
+                    if let Ok(muted) = value.trim().parse() {
+                        settings.muted = muted;
+                    }
+                }
+                _ => {}
+            }
+        }
+        settings
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!("volume = {}\nmuted = {}\n", self.volume, self.muted);
+        if !self.keymap.is_empty() {
+            text.push_str("\n[keymap]\n");
+            text.push_str(&self.keymap);
+        }
+        text
+    }
+
+    // The settings of the file, the default ones when it does not exist
+    pub fn load_from(path: &Path) -> io::Result<Settings> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(Settings::parse(&text)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Settings::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    // Writes the file, creating its directory
+    pub fn save_to(&self, path: &Path) -> io::Result<()> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        fs::write(path, self.to_text())
+    }
+
+    // The settings of the user, the default ones when they cannot be read
+    pub fn load() -> Settings {
+        settings_path().and_then(|path| Settings::load_from(&path).ok()).unwrap_or_default()
+    }
+
+    // A failure only loses the change, it is reported
+    pub fn save(&self) {
+        let Some(path) = settings_path() else {
+            return;
+        };
+        if let Err(error) = self.save_to(&path) {
+            eprintln!("Cannot save the settings to {} : {error}", path.display());
+        }
+    }
+}
+
+// chip8/settings.cfg in $XDG_CONFIG_HOME, or in ~/.config without it. None without a home directory
+pub fn settings_path() -> Option<PathBuf> {
+    let directory = env::var_os("XDG_CONFIG_HOME")
+        .filter(|directory| !directory.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(directory.join("chip8").join("settings.cfg"))
+}
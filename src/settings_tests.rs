@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod settings_tests {
+    use std::env;
+    use std::fs;
+    use crate::Settings;
+
+    #[test]
+    fn parse_reads_the_volume_and_the_mute() {
+        let settings = Settings { volume: 40, muted: true, ..Settings::default() };
+        assert_eq!(Settings::parse("volume = 40\nmuted = true\n"), settings);
+        assert_eq!(Settings::parse("muted=true"), Settings { volume: 100, muted: true, ..Settings::default() });
+        assert_eq!(Settings::parse(""), Settings::default());
+    }
+
+    #[test]
+    fn parse_keeps_the_default_of_what_is_wrong() {
+        // Clamped to 100
+        assert_eq!(Settings::parse("volume = 250").volume, 100);
+        assert_eq!(Settings::parse("volume = -3\nmuted = yes\ncolor = red\nnoise"), Settings::default());
+    }
+
+    #[test]
+    fn to_text_is_read_back_by_parse() {
+        let settings = Settings { volume: 65, muted: true, ..Settings::default() };
+        assert_eq!(settings.to_text(), "volume = 65\nmuted = true\n");
+        assert_eq!(Settings::parse(&settings.to_text()), settings);
+        let settings = Settings { keymap: String::from("5 = Up\n8 = Down\n"), ..settings };
+        assert_eq!(settings.to_text(), "volume = 65\nmuted = true\n\n[keymap]\n5 = Up\n8 = Down\n");
+        assert_eq!(Settings::parse(&settings.to_text()), settings);
+    }
+
+    #[test]
+    fn parse_keeps_the_lines_of_the_keymap_section() {
+        let text = "volume = 40\n[keymap]\n 5 = Up \n\n8=Down, A=X\nmuted = true\n[colors]\nfg = red\n";
+        let settings = Settings::parse(text);
+        assert_eq!(settings.volume, 40);
+        // The lines of the section are kept as they are for parse_keymap, which reports the wrong ones
+        assert_eq!(settings.keymap, "5 = Up\n8=Down, A=X\nmuted = true\n");
+        assert!(!settings.muted);
+    }
+
+    #[test]
+    fn save_to_is_read_back_by_load_from() {
+        let directory = env::temp_dir().join(format!("chip8-settings-{}", std::process::id()));
+        let path = directory.join("chip8").join("settings.cfg");
+        assert_eq!(Settings::load_from(&path).unwrap(), Settings::default());
+        let settings = Settings { volume: 30, muted: false, keymap: String::from("5 = Up\n") };
+        settings.save_to(&path).unwrap();
+        assert_eq!(Settings::load_from(&path).unwrap(), settings);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
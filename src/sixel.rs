@@ -0,0 +1,88 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./sixel_tests.rs"]
+mod sixel_tests;
+
+// SIXEL encoding of the screen, a bitmap format shown by terminals like xterm, foot or wezterm without a window
+// system
+
+use std::fmt::Write;
+use crate::Theme;
+
+// Colors of the image: the off and lit pixels
+const COLORS: usize = 2;
+
+// Rows of pixels in a sixel band
+const BAND_HEIGHT: usize = 6;
+
+// Runs of a sixel longer than this are run-length encoded
+const MAX_LITERAL_RUN: usize = 3;
+
+// Encodes the framebuffer as a SIXEL image, each pixel a square of scale by scale. The image has the background and
+// foreground colors of the theme, every pixel lit on any plane has the foreground one
+pub fn to_sixel(framebuffer: &[u8], width: usize, scale: usize, theme: &Theme) -> String {
+    let scale = scale.max(1);
+    let height = framebuffer.len() / width.max(1);
+    let (image_width, image_height) = (width * scale, height * scale);
+    let lit = |x: usize, y: usize| framebuffer[y / scale * width + x / scale] != 0;
+
+    // Introducer, then the aspect ratio of the pixels and the image size
+    let mut sixel = format!("\x1BPq\"1;1;{image_width};{image_height}");
+    // SIXEL colors are percents of red, green and blue
+    for color in 0..COLORS {
+        let [red, green, blue] = theme.colors[color].map(|channel| (u16::from(channel) * 100 + 127) / 255);
+        let _ = write!(sixel, "#{color};2;{red};{green};{blue}");
+    }
+    for band in (0..image_height).step_by(BAND_HEIGHT) {
+        if band > 0 {
+            sixel.push('-');
+        }
+        let rows = band..(band + BAND_HEIGHT).min(image_height);
+        let mut first_color = true;
+        for color in 0..COLORS {
+            // Bits of the rows of the band having the color, for each column
+            let sixels: Vec<u8> = (0..image_width)
+                .map(|x| rows.clone().enumerate()
+                    .filter(|&(_, y)| usize::from(lit(x, y)) == color)
+                    .fold(0, |bits, (row, _)| bits | 1 << row))
+                .collect();
+            if sixels.iter().all(|&bits| bits == 0) {
+                continue;
+            }
+            // Each color of a band is drawn over the same columns, going back to the start of the band
+            if !first_color {
+                sixel.push('$');
+            }
+            first_color = false;
+            let _ = write!(sixel, "#{color}");
+            push_runs(&mut sixel, &sixels);
+        }
+    }
+    sixel.push_str("\x1B\\");
+    sixel
+}
+
+// Appends the sixel characters of a band row, repeating the runs with !<count><character>
+fn push_runs(sixel: &mut String, sixels: &[u8]) {
+    let mut start = 0;
+    while start < sixels.len() {
+        let run = sixels[start..].iter().take_while(|&&bits| bits == sixels[start]).count();
+        let character = char::from(0x3F + sixels[start]);
+        if run > MAX_LITERAL_RUN {
+            let _ = write!(sixel, "!{run}{character}");
+        } else {
+            sixel.extend(std::iter::repeat_n(character, run));
+        }
+        start += run;
+    }
+}
+
+// Whether the answer to the primary device attributes query (ESC [ c) lists the SIXEL graphics attribute, 4. The
+// answer looks like ESC [ ? 62 ; 4 ; 22 c
+pub fn advertises_sixel(attributes: &str) -> bool {
+    attributes.trim_start_matches("\x1B[?")
+        .trim_end_matches('c')
+        .split(';')
+        .skip(1)
+        .any(|attribute| attribute == "4")
+}
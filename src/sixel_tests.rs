@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod sixel_tests {
+    use crate::sixel::{advertises_sixel, to_sixel};
+    use crate::Theme;
+
+    // Image with the black and white colors of the default theme
+    fn encode(framebuffer: &[u8], width: usize, scale: usize) -> String {
+        to_sixel(framebuffer, width, scale, &Theme::default())
+    }
+
+    // Introducer, size and palette of an image
+    fn header(width: usize, height: usize) -> String {
+        format!("\x1BPq\"1;1;{width};{height}#0;2;0;0;0#1;2;100;100;100")
+    }
+
+    #[test]
+    fn to_sixel_draws_a_single_pixel_in_its_color() {
+        assert_eq!(encode(&[1], 1, 1), format!("{}#1@\x1B\\", header(1, 1)));
+        assert_eq!(encode(&[0], 1, 1), format!("{}#0@\x1B\\", header(1, 1)));
+    }
+
+    #[test]
+    fn to_sixel_draws_each_color_in_turn() {
+        // The off pixels are drawn first, then the band starts again for the lit ones
+        assert_eq!(encode(&[1, 0], 2, 1), format!("{}#0?@$#1@?\x1B\\", header(2, 1)));
+        // Any lit plane has the second color
+        assert_eq!(encode(&[0, 2, 3], 3, 1), format!("{}#0@??$#1?@@\x1B\\", header(3, 1)));
+    }
+
+    #[test]
+    fn to_sixel_draws_each_pixel_as_a_square() {
+        assert_eq!(encode(&[1], 1, 2), format!("{}#1BB\x1B\\", header(2, 2)));
+        // 2x2 checkerboard at scale 3: the left columns of the band are lit on the top rows, the right ones below
+        let checkerboard = encode(&[1, 0, 0, 1], 2, 3);
+        assert_eq!(checkerboard, format!("{}#0wwwFFF$#1FFFwww\x1B\\", header(6, 6)));
+    }
+
+    #[test]
+    fn to_sixel_compresses_the_runs_longer_than_3() {
+        assert_eq!(encode(&[0; 8], 8, 1), format!("{}#0!8@\x1B\\", header(8, 1)));
+        // Runs of up to 3 stay literal
+        let framebuffer = [1, 1, 1, 0, 0, 0, 0];
+        assert_eq!(encode(&framebuffer, 7, 1), format!("{}#0???!4@$#1@@@!4?\x1B\\", header(7, 1)));
+    }
+
+    #[test]
+    fn to_sixel_splits_the_image_into_bands_of_six_rows() {
+        // 7 rows take 2 bands, the second one only has its first row
+        let column = [0, 0, 0, 0, 0, 0, 1];
+        assert_eq!(encode(&column, 1, 1), format!("{}#0~-#1@\x1B\\", header(1, 7)));
+    }
+
+    #[test]
+    fn to_sixel_sets_the_theme_colors_in_percents() {
+        // 0xFF, 0xB0 and 0x00 are 100, 69 and 0 percents
+        let amber = Theme::preset("amber").unwrap();
+        let sixel = to_sixel(&[1], 1, 1, &amber);
+        assert!(sixel.starts_with("\x1BPq\"1;1;1;1#0;2;10;6;0#1;2;100;69;0"), "{sixel:?}");
+    }
+
+    #[test]
+    fn advertises_sixel_reads_attribute_4_of_the_device_attributes() {
+        assert!(advertises_sixel("\x1B[?62;4;6;22c"));
+        assert!(advertises_sixel("\x1B[?64;1;2;4c"));
+        assert!(!advertises_sixel("\x1B[?62;1;6;22c"));
+        assert!(!advertises_sixel("\x1B[?1;2c"));
+        // The first number is the device class, not an attribute
+        assert!(!advertises_sixel("\x1B[?4;6c"));
+        assert!(!advertises_sixel(""));
+    }
+}
@@ -0,0 +1,386 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./terminal_tests.rs"]
+mod terminal_tests;
+
+// Terminal frontend, enabled with the terminal feature: the screen drawn with half block characters or as a SIXEL
+// image in the alternate screen and the keyboard as the keypad, for runs over SSH
+
+use std::collections::HashMap;
+use std::io;
+use std::io::{Read, Stdout, Write};
+use std::mem;
+use std::panic;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
+    PushKeyboardEnhancementFlags,
+};
+use crossterm::style::{Color, Colors, Print, ResetColor, SetColors};
+use crossterm::terminal::{Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen, SetTitle};
+use crossterm::{event, execute, queue, terminal};
+use crate::{
+    advertises_sixel, control, rotate, to_sixel, Control, ControlKey, Display, Input, Keymap, PhysicalKey,
+    Rotation, Theme, WindowFrontend,
+};
+
+// Most terminals only report key presses, repeated while the key is held. A key counts as held for this long after
+// its last press, which covers the delay before the first repeat
+const KEY_HOLD: Duration = Duration::from_millis(250);
+
+// Terminals answer the device attributes query right away, this only bounds the wait for one that does not
+const ATTRIBUTES_TIMEOUT: Duration = Duration::from_millis(500);
+
+// Columns kept for the debug overlay panel, enough for its longest lines
+const PANEL_WIDTH: u16 = 40;
+
+// Pixel values of the upper and lower half of a character cell
+pub(crate) type Cell = (u8, u8);
+
+// Pairs the rows of the framebuffer into rows of cells, the lower half of the last one is off for an odd height
+pub(crate) fn to_cells(framebuffer: &[u8], width: usize) -> Vec<Vec<Cell>> {
+    framebuffer.chunks(width * 2)
+        .map(|rows| {
+            let (top, bottom) = rows.split_at(width.min(rows.len()));
+            (0..top.len()).map(|x| (top[x], bottom.get(x).copied().unwrap_or(0))).collect()
+        })
+        .collect()
+}
+
+// Escape sequences drawing the cells that differ from the ones on the terminal, or all of them when the terminal
+// contents are unknown or of another size. The cursor is only moved to the start of each run of changed cells and the
+// colors only set when they change. Nothing is written when no cell changed
+pub(crate) fn cell_diff(previous: Option<&[Vec<Cell>]>, cells: &[Vec<Cell>], theme: &Theme) -> io::Result<Vec<u8>> {
+    let previous = previous.filter(|previous| {
+        previous.len() == cells.len() && previous.iter().zip(cells).all(|(old, new)| old.len() == new.len())
+    });
+    let color = |pixel: u8| {
+        let [r, g, b] = theme.color(pixel);
+        Color::Rgb { r, g, b }
+    };
+    let mut output = Vec::new();
+    // Where the terminal cursor is after the last cell printed, and the colors it was printed with
+    let mut cursor = None;
+    let mut colors = None;
+    for (y, row) in cells.iter().enumerate() {
+        for (x, &(top, bottom)) in row.iter().enumerate() {
+            if previous.is_some_and(|previous| previous[y][x] == (top, bottom)) {
+                continue;
+            }
+            if cursor != Some((x, y)) {
+                queue!(output, MoveTo(x as u16, y as u16))?;
+            }
+            if colors != Some((top, bottom)) {
+                queue!(output, SetColors(Colors::new(color(top), color(bottom))))?;
+            }
+            queue!(output, Print('▀'))?;
+            cursor = Some((x + 1, y));
+            colors = Some((top, bottom));
+        }
+    }
+    if colors.is_some() {
+        queue!(output, ResetColor)?;
+    }
+    Ok(output)
+}
+
+// First column of the debug overlay panel: next to the screen of half blocks, which is as wide as the CHIP-8 screen,
+// or at the right edge of the terminal for a SIXEL image, whose width in columns is unknown
+pub(crate) fn panel_column(screen_width: Option<usize>, terminal_columns: u16) -> u16 {
+    match screen_width {
+        Some(width) => width as u16 + 2,
+        None => terminal_columns.saturating_sub(PANEL_WIDTH),
+    }
+}
+
+// Key of the emulator controls of a key event, the letters whatever their case
+pub(crate) fn control_key(code: KeyCode) -> Option<ControlKey> {
+    match code {
+        KeyCode::Esc => Some(ControlKey::Escape),
+        KeyCode::Backspace => Some(ControlKey::Backspace),
+        code => {
+            let letter = ControlKey::Letter(physical_key(code)?);
+            ControlKey::ALL.contains(&letter).then_some(letter)
+        }
+    }
+}
+
+// Keyboard key of the keymap of a key event, the letters whatever their case
+pub(crate) fn physical_key(code: KeyCode) -> Option<PhysicalKey> {
+    match code {
+        KeyCode::Char(character) => PhysicalKey::from_char(character),
+        KeyCode::Up => Some(PhysicalKey::Up),
+        KeyCode::Down => Some(PhysicalKey::Down),
+        KeyCode::Left => Some(PhysicalKey::Left),
+        KeyCode::Right => Some(PhysicalKey::Right),
+        _ => None,
+    }
+}
+
+// Asks the terminal, in raw mode, whether it shows SIXEL images. The answer is read by a thread so that a terminal
+// not answering only costs the timeout
+fn query_sixel_support() -> bool {
+    let mut stdout = io::stdout();
+    if write!(stdout, "\x1B[c").and_then(|_| stdout.flush()).is_err() {
+        return false;
+    }
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut answer = String::new();
+        for byte in io::stdin().lock().bytes() {
+            let Ok(byte) = byte else { break };
+            answer.push(char::from(byte));
+            if byte == b'c' {
+                break;
+            }
+        }
+        let _ = sender.send(answer);
+    });
+    receiver.recv_timeout(ATTRIBUTES_TIMEOUT).is_ok_and(|answer| advertises_sixel(&answer))
+}
+
+// Leaves the raw mode and the alternate screen, errors are ignored as nothing else can be done on the way out
+fn restore_terminal(keyboard_enhanced: bool) {
+    let mut stdout = io::stdout();
+    if keyboard_enhanced {
+        let _ = execute!(stdout, PopKeyboardEnhancementFlags);
+    }
+    let _ = execute!(stdout, ResetColor, Show, LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+}
+
+// Owns the terminal while it is set up for the emulator, it is given back when the frontend is dropped, also on a
+// panic
+pub struct TerminalFrontend {
+    stdout: Stdout,
+    // Time of the last press of each keyboard key, until its release when the terminal reports releases
+    pressed: HashMap<PhysicalKey, Instant>,
+    // Time of the last press of Tab, fast-forwarding the emulation while held
+    turbo_pressed: Option<Instant>,
+    keymap: Keymap,
+    // Emulator controls pressed since the last call of take_controls
+    controls: Vec<Control>,
+    // Set when the terminal reports the key releases
+    keyboard_enhanced: bool,
+    // Size of a pixel in the SIXEL image, when the screen is drawn as one
+    sixel_scale: Option<usize>,
+    theme: Theme,
+    rotation: Rotation,
+    // Width of the last drawn screen, rotated
+    width: usize,
+    // Cells shown on the terminal, the next screen only redraws the ones that changed. None when unknown, after a
+    // resize, a theme change or a failed write, so that the next screen is drawn whole
+    cells: Option<Vec<Vec<Cell>>>,
+    // Debug overlay printed as a side panel, toggled by F3, and the column and lines it takes
+    overlay_shown: bool,
+    panel: Option<(u16, usize)>,
+}
+
+impl TerminalFrontend {
+    // Draws the screen with half block characters
+    pub fn new() -> io::Result<TerminalFrontend> {
+        TerminalFrontend::open(None)
+    }
+
+    // Draws the screen as a SIXEL image with each pixel a square of scale by scale, or with half block characters
+    // when the terminal does not advertise SIXEL support
+    pub fn with_sixel(scale: usize) -> io::Result<TerminalFrontend> {
+        TerminalFrontend::open(Some(scale))
+    }
+
+    fn open(sixel_scale: Option<usize>) -> io::Result<TerminalFrontend> {
+        terminal::enable_raw_mode()?;
+        let sixel_scale = sixel_scale.filter(|_| query_sixel_support());
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen, Hide, Clear(ClearType::All))?;
+        let keyboard_enhanced = terminal::supports_keyboard_enhancement().unwrap_or(false);
+        if keyboard_enhanced {
+            execute!(stdout, PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES))?;
+        }
+
+        // The panic message is printed before the frontend is dropped, the terminal is restored first so that it
+        // is not lost in the alternate screen
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            restore_terminal(keyboard_enhanced);
+            previous_hook(info);
+        }));
+
+        Ok(TerminalFrontend {
+            stdout,
+            pressed: HashMap::new(),
+            turbo_pressed: None,
+            keymap: Keymap::default(),
+            controls: Vec::new(),
+            keyboard_enhanced,
+            sixel_scale,
+            theme: Theme::default(),
+            rotation: Rotation::None,
+            width: 64,
+            cells: None,
+            overlay_shown: false,
+            panel: None,
+        })
+    }
+
+    // Applies to the next drawn screen. The colors are sent as 24-bit RGB, which most terminals show
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.cells = None;
+    }
+
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+    }
+
+    // Applies to the next drawn screen
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.rotation = rotation;
+        self.cells = None;
+    }
+
+    // Whether the screen is drawn as a SIXEL image, false after falling back to half block characters
+    pub fn uses_sixel(&self) -> bool {
+        self.sixel_scale.is_some()
+    }
+
+    fn clear_panel(&mut self) {
+        if let Some((column, rows)) = self.panel.take() {
+            for row in 0..rows {
+                let _ = queue!(self.stdout, MoveTo(column, row as u16), Clear(ClearType::UntilNewLine));
+            }
+            let _ = self.stdout.flush();
+        }
+    }
+}
+
+impl Drop for TerminalFrontend {
+    fn drop(&mut self) {
+        restore_terminal(self.keyboard_enhanced);
+    }
+}
+
+impl Display for TerminalFrontend {
+    // Each cell shows two pixels: the upper half block in the foreground color and the rest in the background color.
+    // Only the cells that changed since the last screen are drawn, which keeps the output small over slow links. A
+    // SIXEL image is drawn over the previous one from the top left corner
+    fn draw(&mut self, framebuffer: &[u8], width: usize, height: usize) {
+        let framebuffer = &rotate(framebuffer, width, self.rotation);
+        let (width, _) = self.rotation.size((width, height));
+        // A wider screen of half blocks is drawn over the panel, which moves to its right
+        if width > self.width && self.sixel_scale.is_none() {
+            self.panel = None;
+        }
+        self.width = width;
+        let mut frame = || -> io::Result<()> {
+            if let Some(scale) = self.sixel_scale {
+                queue!(self.stdout, MoveTo(0, 0), Print(to_sixel(framebuffer, width, scale, &self.theme)))?;
+                return self.stdout.flush();
+            }
+            let cells = to_cells(framebuffer, width);
+            let diff = cell_diff(self.cells.take().as_deref(), &cells, &self.theme)?;
+            if !diff.is_empty() {
+                self.stdout.write_all(&diff)?;
+                self.stdout.flush()?;
+            }
+            self.cells = Some(cells);
+            Ok(())
+        };
+        // A failed write only loses this frame, the next one redraws everything
+        let _ = frame();
+    }
+
+    fn clear(&mut self) {
+        self.cells = None;
+        let _ = execute!(self.stdout, ResetColor, Clear(ClearType::All));
+    }
+}
+
+impl Input for TerminalFrontend {
+    fn poll(&mut self) -> [bool; 16] {
+        let keyboard_enhanced = self.keyboard_enhanced;
+        self.keymap.poll(|physical_key| {
+            self.pressed.get(&physical_key).is_some_and(|time| keyboard_enhanced || time.elapsed() < KEY_HOLD)
+        })
+    }
+}
+
+impl WindowFrontend for TerminalFrontend {
+    // Reads the pending key events without waiting, returns false once Ctrl+C is pressed
+    fn handle_events(&mut self) -> bool {
+        while event::poll(Duration::ZERO).unwrap_or(false) {
+            let event = event::read();
+            // Terminals may reflow or clear their contents when resized
+            if let Ok(Event::Resize(..)) = event {
+                self.cells = None;
+            }
+            let Ok(Event::Key(KeyEvent { code, modifiers, kind, .. })) = event else {
+                continue;
+            };
+            let pressed_control = control_key(code).and_then(|key| control(key, &self.keymap));
+            match code {
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return false,
+                _ if pressed_control.is_some() => {
+                    if kind == KeyEventKind::Press {
+                        self.controls.extend(pressed_control);
+                    }
+                }
+                KeyCode::Tab => self.turbo_pressed = (kind != KeyEventKind::Release).then(Instant::now),
+                KeyCode::F(3) if kind == KeyEventKind::Press => {
+                    self.overlay_shown = !self.overlay_shown;
+                    self.clear_panel();
+                }
+                code => {
+                    if let Some(physical_key) = physical_key(code) {
+                        if kind == KeyEventKind::Release {
+                            self.pressed.remove(&physical_key);
+                        } else {
+                            self.pressed.insert(physical_key, Instant::now());
+                        }
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    // P, Backspace, R, Esc and Q pressed since the last call, the letters when the keymap leaves them
+    fn take_controls(&mut self) -> Vec<Control> {
+        mem::take(&mut self.controls)
+    }
+
+    // Whether Tab is held, like the keys of the keypad
+    fn turbo_held(&self) -> bool {
+        self.turbo_pressed.is_some_and(|time| self.keyboard_enhanced || time.elapsed() < KEY_HOLD)
+    }
+
+    // Title of the terminal window or tab, for the terminals showing one. A failed write leaves the title as it was
+    fn set_title(&mut self, title: &str) {
+        let _ = execute!(self.stdout, SetTitle(title));
+    }
+
+    // Whether the debug overlay is shown, its lines are then given once per frame to set_overlay
+    fn overlay_shown(&self) -> bool {
+        self.overlay_shown
+    }
+
+    // Prints the lines in the panel, in the colors of the terminal. A failed write only loses this frame
+    fn set_overlay(&mut self, lines: &[String]) {
+        let columns = terminal::size().map_or(0, |(columns, _)| columns);
+        let column = panel_column(self.sixel_scale.is_none().then_some(self.width), columns);
+        if self.panel.is_some_and(|(previous, _)| previous != column) {
+            self.clear_panel();
+        }
+        let mut panel = || -> io::Result<()> {
+            for (row, line) in lines.iter().enumerate() {
+                queue!(self.stdout, MoveTo(column, row as u16), Clear(ClearType::UntilNewLine), Print(line))?;
+            }
+            self.stdout.flush()
+        };
+        let _ = panel();
+        self.panel = Some((column, lines.len()));
+    }
+}
@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod terminal_tests {
+    use crossterm::event::KeyCode;
+    use crate::terminal::{cell_diff, control_key, panel_column, physical_key, to_cells, Cell};
+    use crate::{ControlKey, PhysicalKey, Theme};
+
+    // Escape sequences as text, with the escape character shown as ^
+    fn diff(previous: Option<&[Vec<Cell>]>, cells: &[Vec<Cell>]) -> String {
+        let output = cell_diff(previous, cells, &Theme::default()).unwrap();
+        String::from_utf8(output).unwrap().replace('\x1B', "^")
+    }
+
+    const WHITE_ON_BLACK: &str = "^[38;2;255;255;255;48;2;0;0;0m";
+    const BLACK_ON_WHITE: &str = "^[38;2;0;0;0;48;2;255;255;255m";
+    const WHITE: &str = "^[38;2;255;255;255;48;2;255;255;255m";
+
+    #[test]
+    fn to_cells_pairs_the_pixel_rows_into_half_block_cells() {
+        // 3x4 screen: the 2 cell rows pair the pixel rows 0 and 1, then 2 and 3
+        let framebuffer = [
+            1, 0, 1,
+            1, 1, 0,
+            0, 2, 0,
+            0, 0, 3,
+        ];
+        let cells = to_cells(&framebuffer, 3);
+        assert_eq!(cells, vec![
+            vec![(1, 1), (0, 1), (1, 0)],
+            vec![(0, 0), (2, 0), (0, 3)],
+        ]);
+    }
+
+    #[test]
+    fn to_cells_pairs_the_last_row_of_an_odd_height_with_the_background() {
+        let cells = to_cells(&[1, 0, 0, 1, 1, 1], 2);
+        assert_eq!(cells, vec![vec![(1, 0), (0, 1)], vec![(1, 0), (1, 0)]]);
+    }
+
+    #[test]
+    fn to_cells_of_the_screen_are_16_rows_of_64() {
+        let cells = to_cells(&[0; 64 * 32], 64);
+        assert_eq!(cells.len(), 16);
+        assert!(cells.iter().all(|row| row.len() == 64));
+    }
+
+    #[test]
+    fn physical_key_reads_the_characters_in_any_case_and_the_arrows() {
+        assert_eq!(physical_key(KeyCode::Char('q')), Some(PhysicalKey::Q));
+        assert_eq!(physical_key(KeyCode::Char('Q')), Some(PhysicalKey::Q));
+        assert_eq!(physical_key(KeyCode::Char('4')), Some(PhysicalKey::Key4));
+        assert_eq!(physical_key(KeyCode::Char(' ')), Some(PhysicalKey::Space));
+        assert_eq!(physical_key(KeyCode::Left), Some(PhysicalKey::Left));
+        assert_eq!(physical_key(KeyCode::Char(';')), None);
+        assert_eq!(physical_key(KeyCode::Enter), None);
+    }
+
+    #[test]
+    fn control_key_reads_esc_backspace_and_the_control_letters() {
+        assert_eq!(control_key(KeyCode::Esc), Some(ControlKey::Escape));
+        assert_eq!(control_key(KeyCode::Backspace), Some(ControlKey::Backspace));
+        assert_eq!(control_key(KeyCode::Char('P')), Some(ControlKey::Letter(PhysicalKey::P)));
+        assert_eq!(control_key(KeyCode::Char('q')), Some(ControlKey::Letter(PhysicalKey::Q)));
+        assert_eq!(control_key(KeyCode::Char('w')), None);
+        assert_eq!(control_key(KeyCode::Up), None);
+    }
+
+    #[test]
+    fn panel_column_is_next_to_the_half_blocks_or_at_the_right_edge() {
+        // Next to the half blocks, with a blank column between
+        assert_eq!(panel_column(Some(64), 120), 66);
+        assert_eq!(panel_column(Some(128), 120), 130);
+        // At the right edge next to a SIXEL image
+        assert_eq!(panel_column(None, 120), 80);
+        assert_eq!(panel_column(None, 20), 0);
+    }
+
+    #[test]
+    fn cell_diff_is_empty_without_change() {
+        let cells = vec![vec![(1, 0), (0, 1)], vec![(0, 0), (1, 1)]];
+        assert_eq!(diff(Some(&cells), &cells), "");
+    }
+
+    #[test]
+    fn cell_diff_draws_everything_when_unknown() {
+        let cells = vec![vec![(1, 0), (1, 0)], vec![(0, 1), (1, 1)]];
+        let everything = format!("^[1;1H{WHITE_ON_BLACK}▀▀^[2;1H{BLACK_ON_WHITE}▀{WHITE}▀^[0m");
+        assert_eq!(diff(None, &cells), everything);
+        // Of another size, after a switch of resolution
+        assert_eq!(diff(Some(&[vec![(1, 0), (1, 0)]]), &cells), everything);
+        assert_eq!(diff(Some(&[vec![(1, 0)], vec![(0, 1)]]), &cells), everything);
+    }
+
+    #[test]
+    fn cell_diff_draws_every_row_when_everything_changed() {
+        let previous = vec![vec![(0, 0); 2]; 2];
+        let cells = vec![vec![(1, 0); 2]; 2];
+        assert_eq!(diff(Some(&previous), &cells), format!("^[1;1H{WHITE_ON_BLACK}▀▀^[2;1H▀▀^[0m"));
+    }
+
+    #[test]
+    fn cell_diff_draws_the_changed_cells() {
+        let previous = vec![vec![(0, 0); 5]; 2];
+        let mut cells = previous.clone();
+        cells[0][1] = (1, 0);
+        cells[0][2] = (1, 0);
+        cells[1][4] = (0, 1);
+        // One move per run of changed cells
+        assert_eq!(diff(Some(&previous), &cells), format!("^[1;2H{WHITE_ON_BLACK}▀▀^[2;5H{BLACK_ON_WHITE}▀^[0m"));
+    }
+}
@@ -0,0 +1,164 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./theme_tests.rs"]
+mod theme_tests;
+
+// Colors of the screen shown by the frontends, picked from named presets or given as #RRGGBB strings. A theme has a
+// color per XO-CHIP pixel value, the presets blend the two planes between a background and a foreground and the
+// palettes give the four colors
+
+use std::error::Error;
+use std::fmt;
+
+// Red, green and blue
+pub type Rgb = [u8; 3];
+
+// Names of the presets with their background and foreground, the first one is the default
+pub const PRESETS: [(&str, Rgb, Rgb); 5] = [
+    ("classic", [0x00, 0x00, 0x00], [0xFF, 0xFF, 0xFF]),
+    ("green", [0x0A, 0x1A, 0x0A], [0x33, 0xFF, 0x66]),
+    ("amber", [0x1A, 0x10, 0x00], [0xFF, 0xB0, 0x00]),
+    ("paperwhite", [0xF2, 0xEF, 0xE6], [0x22, 0x22, 0x22]),
+    ("inverted", [0xFF, 0xFF, 0xFF], [0x00, 0x00, 0x00]),
+];
+
+// Names of the palettes of Octo with their colors for the background, the first plane, the second one and both
+// planes. XO-CHIP games are often drawn for one of them, octo is the default of Octo
+pub const PALETTES: [(&str, [Rgb; 4]); 6] = [
+    ("octo", [[0x99, 0x66, 0x00], [0xFF, 0xCC, 0x00], [0xFF, 0x66, 0x00], [0x66, 0x22, 0x00]]),
+    ("lcd", [[0xF9, 0xFF, 0xB3], [0x3D, 0x80, 0x26], [0xAB, 0xCC, 0x47], [0x00, 0x13, 0x1A]]),
+    ("hotdog", [[0x00, 0x00, 0x00], [0xFF, 0x00, 0x00], [0xFF, 0xFF, 0x00], [0xFF, 0xFF, 0xFF]]),
+    ("gray", [[0xAA, 0xAA, 0xAA], [0x00, 0x00, 0x00], [0xFF, 0xFF, 0xFF], [0x66, 0x66, 0x66]]),
+    ("cga0", [[0x00, 0x00, 0x00], [0x00, 0xFF, 0x00], [0xFF, 0x00, 0x00], [0xFF, 0xFF, 0x00]]),
+    ("cga1", [[0x00, 0x00, 0x00], [0xFF, 0x00, 0xFF], [0x00, 0xFF, 0xFF], [0xFF, 0xFF, 0xFF]]),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThemeError {
+    UnknownTheme(String),
+    InvalidColor(String),
+    InvalidPalette(String),
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ThemeError::UnknownTheme(name) => {
+                let names: Vec<&str> = PRESETS.iter().map(|&(name, _, _)| name).collect();
+                write!(f, "Unknown theme : {name}, the themes are {}", names.join(", "))
+            }
+            ThemeError::InvalidColor(color) => {
+                write!(f, "Invalid color : \"{color}\", colors are written #RRGGBB with hexadecimal digits")
+            }
+            ThemeError::InvalidPalette(palette) => {
+                let names: Vec<&str> = PALETTES.iter().map(|&(name, _)| name).collect();
+                let names = names.join(", ");
+                write!(f, "Invalid palette : \"{palette}\", the palettes are {names} or 4 colors separated by commas")
+            }
+        }
+    }
+}
+
+impl Error for ThemeError {}
+
+// Parses a #RRGGBB color, the # can be left out
+pub fn parse_color(color: &str) -> Result<Rgb, ThemeError> {
+    let digits = color.strip_prefix('#').unwrap_or(color);
+    if digits.len() != 6 || !digits.chars().all(|digit| digit.is_ascii_hexdigit()) {
+        return Err(ThemeError::InvalidColor(color.to_string()));
+    }
+    let channel = |index: usize| u8::from_str_radix(&digits[index * 2..index * 2 + 2], 16);
+    match (channel(0), channel(1), channel(2)) {
+        (Ok(red), Ok(green), Ok(blue)) => Ok([red, green, blue]),
+        _ => Err(ThemeError::InvalidColor(color.to_string())),
+    }
+}
+
+// Theme of a palette named in PALETTES, or of 4 #RRGGBB colors separated by commas for the background, the first plane,
+// the second one and both planes
+pub fn parse_palette(palette: &str) -> Result<Theme, ThemeError> {
+    if let Some(&(_, colors)) = PALETTES.iter().find(|&&(name, _)| name.eq_ignore_ascii_case(palette)) {
+        return Ok(Theme::palette(colors));
+    }
+    let colors: Vec<&str> = palette.split(',').map(str::trim).collect();
+    let Ok(colors) = <[&str; 4]>::try_from(colors) else {
+        return Err(ThemeError::InvalidPalette(palette.to_string()));
+    };
+    let mut palette = [[0; 3]; 4];
+    for (color, text) in palette.iter_mut().zip(colors) {
+        *color = parse_color(text)?;
+    }
+    Ok(Theme::palette(palette))
+}
+
+// Color of each pixel value: the background, the first XO-CHIP plane, the second one and both planes. The programs
+// not using the second plane only show the first two
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub colors: [Rgb; 4],
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        let (_, background, foreground) = PRESETS[0];
+        Theme::new(background, foreground)
+    }
+}
+
+impl Theme {
+    // The second plane is two thirds of the way from the background to the foreground and both planes one third, so
+    // that the four values stay apart on monochrome themes
+    pub fn new(background: Rgb, foreground: Rgb) -> Theme {
+        let blend = |thirds: u16| {
+            let mut color = [0; 3];
+            for (channel, (&back, &front)) in color.iter_mut().zip(background.iter().zip(&foreground)) {
+                *channel = ((u16::from(back) * (3 - thirds) + u16::from(front) * thirds) / 3) as u8;
+            }
+            color
+        };
+        Theme { colors: [background, foreground, blend(2), blend(1)] }
+    }
+
+    // The 4 colors of the pixel values, for the XO-CHIP games drawn with a palette in mind
+    pub fn palette(colors: [Rgb; 4]) -> Theme {
+        Theme { colors }
+    }
+
+    pub fn preset(name: &str) -> Result<Theme, ThemeError> {
+        PRESETS.iter()
+            .find(|&&(preset, _, _)| preset.eq_ignore_ascii_case(name))
+            .map(|&(_, background, foreground)| Theme::new(background, foreground))
+            .ok_or_else(|| ThemeError::UnknownTheme(name.to_string()))
+    }
+
+    // Replaces the foreground, the colors of the planes follow it
+    pub fn foreground(self, foreground: Rgb) -> Theme {
+        Theme::new(self.colors[0], foreground)
+    }
+
+    // Replaces the background, the colors of the planes follow it
+    pub fn background(self, background: Rgb) -> Theme {
+        Theme::new(background, self.colors[1])
+    }
+
+    pub fn color(&self, pixel: u8) -> Rgb {
+        self.colors[usize::from(pixel) % self.colors.len()]
+    }
+
+    // Opaque RGBA bytes of a pixel value
+    pub fn rgba(&self, pixel: u8) -> [u8; 4] {
+        let [red, green, blue] = self.color(pixel);
+        [red, green, blue, 0xFF]
+    }
+
+    // Opaque 0xAARRGGBB color of a pixel value
+    pub fn argb(&self, pixel: u8) -> u32 {
+        let [red, green, blue] = self.color(pixel);
+        u32::from_be_bytes([0xFF, red, green, blue])
+    }
+
+    // Copies the framebuffer to RGBA bytes, 4 per pixel
+    pub fn to_rgba(&self, framebuffer: &[u8]) -> Vec<u8> {
+        framebuffer.iter().flat_map(|&pixel| self.rgba(pixel)).collect()
+    }
+}
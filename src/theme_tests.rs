@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod theme_tests {
+    use crate::theme::{parse_color, parse_palette, Theme, ThemeError, PALETTES, PRESETS};
+    use crate::Chip8;
+
+    #[test]
+    fn parse_color_reads_hexadecimal_rgb() {
+        assert_eq!(parse_color("#FFB000"), Ok([0xFF, 0xB0, 0x00]));
+        assert_eq!(parse_color("#33ff66"), Ok([0x33, 0xFF, 0x66]));
+        // The # is optional
+        assert_eq!(parse_color("0A1A0A"), Ok([0x0A, 0x1A, 0x0A]));
+    }
+
+    #[test]
+    fn parse_color_rejects_bad_strings() {
+        for color in ["", "#", "#FFF", "#FFB0000", "#GGGGGG", "##FFB00", "#FF B00", "#ÉÉÉ"] {
+            assert_eq!(parse_color(color), Err(ThemeError::InvalidColor(color.to_string())), "{color}");
+        }
+        let message = parse_color("#12345").unwrap_err().to_string();
+        assert_eq!(message, "Invalid color : \"#12345\", colors are written #RRGGBB with hexadecimal digits");
+    }
+
+    #[test]
+    fn preset_gives_the_colors_of_each_theme() {
+        for (name, background, foreground) in PRESETS {
+            let theme = Theme::preset(name).unwrap();
+            assert_eq!(theme.colors[0], background, "{name}");
+            assert_eq!(theme.colors[1], foreground, "{name}");
+        }
+        // The names are not case sensitive
+        assert_eq!(Theme::preset("Amber"), Theme::preset("amber"));
+        assert_eq!(Theme::preset("classic").unwrap(), Theme::default());
+    }
+
+    #[test]
+    fn preset_rejects_an_unknown_theme() {
+        let error = Theme::preset("blue").unwrap_err();
+        assert_eq!(error, ThemeError::UnknownTheme(String::from("blue")));
+        assert_eq!(error.to_string(), "Unknown theme : blue, the themes are classic, green, amber, paperwhite, inverted");
+    }
+
+    #[test]
+    fn plane_colors_blend_between_background_and_foreground() {
+        let theme = Theme::default();
+        assert_eq!(theme.colors, [[0x00; 3], [0xFF; 3], [0xAA; 3], [0x55; 3]]);
+        let inverted = Theme::preset("inverted").unwrap();
+        assert_eq!(inverted.colors, [[0xFF; 3], [0x00; 3], [0x55; 3], [0xAA; 3]]);
+    }
+
+    #[test]
+    fn background_and_foreground_replace_the_colors_of_a_theme() {
+        let theme = Theme::preset("amber").unwrap().background([0x00, 0x00, 0x30]);
+        assert_eq!(theme.colors[0], [0x00, 0x00, 0x30]);
+        assert_eq!(theme.colors[1], [0xFF, 0xB0, 0x00]);
+        let theme = theme.foreground([0x30, 0x00, 0x00]);
+        assert_eq!(theme.colors, [[0x00, 0x00, 0x30], [0x30, 0x00, 0x00], [0x20, 0x00, 0x10], [0x10, 0x00, 0x20]]);
+    }
+
+    #[test]
+    fn rgba_and_argb_give_the_color_of_a_pixel() {
+        let theme = Theme::preset("amber").unwrap();
+        assert_eq!(theme.rgba(1), [0xFF, 0xB0, 0x00, 0xFF]);
+        assert_eq!(theme.argb(1), 0xFFFF_B000);
+        assert_eq!(theme.argb(0), 0xFF1A_1000);
+        // Values past the 4 colors wrap around
+        assert_eq!(theme.color(5), theme.color(1));
+        let rgba = theme.to_rgba(&[0, 1, 2, 3]);
+        assert_eq!(rgba, [theme.rgba(0), theme.rgba(1), theme.rgba(2), theme.rgba(3)].concat());
+    }
+
+    #[test]
+    fn parse_palette_reads_four_colors_or_a_name() {
+        let theme = parse_palette("#000000,#FFFFFF, #FF0000 ,555555").unwrap();
+        assert_eq!(theme.colors, [[0x00; 3], [0xFF; 3], [0xFF, 0x00, 0x00], [0x55; 3]]);
+        for (name, colors) in PALETTES {
+            assert_eq!(parse_palette(name), Ok(Theme::palette(colors)), "{name}");
+        }
+        // The names are not case sensitive
+        assert_eq!(parse_palette("Octo"), parse_palette("#996600,#FFCC00,#FF6600,#662200"));
+    }
+
+    #[test]
+    fn parse_palette_rejects_bad_strings() {
+        for palette in ["", "blue", "#000000,#FFFFFF", "#000000,#FFFFFF,#FF0000,#555555,#AAAAAA"] {
+            assert_eq!(parse_palette(palette), Err(ThemeError::InvalidPalette(palette.to_string())), "{palette}");
+        }
+        let error = parse_palette("#000000,#FFFFFF,red,#555555").unwrap_err();
+        assert_eq!(error, ThemeError::InvalidColor(String::from("red")));
+        let message = parse_palette("blue").unwrap_err().to_string();
+        assert_eq!(
+            message,
+            "Invalid palette : \"blue\", the palettes are octo, lcd, hotdog, gray, cga0, cga1 or 4 colors separated by commas",
+        );
+    }
+
+    #[test]
+    fn palette_gives_a_color_to_each_pixel_value() {
+        let theme = parse_palette("hotdog").unwrap();
+        // No plane, the first one, the second one and both
+        assert_eq!(theme.rgba(0), [0x00, 0x00, 0x00, 0xFF]);
+        assert_eq!(theme.rgba(1), [0xFF, 0x00, 0x00, 0xFF]);
+        assert_eq!(theme.rgba(2), [0xFF, 0xFF, 0x00, 0xFF]);
+        assert_eq!(theme.rgba(3), [0xFF, 0xFF, 0xFF, 0xFF]);
+        let rgba = theme.to_rgba(&[3, 2, 1, 0]);
+        assert_eq!(rgba, [theme.rgba(3), theme.rgba(2), theme.rgba(1), theme.rgba(0)].concat());
+    }
+
+    #[test]
+    fn palette_shows_a_single_plane_screen_in_two_colors() {
+        // A CHIP-8 or SUPER-CHIP program only draws the first plane, its pixels are 0 or 1
+        // I = the font sprite of 0 in V0, then drawn at (V0, V0)
+        let mut chip8 = Chip8::with_program(&[0xF029, 0xD005]);
+        chip8.emulate_cycle().unwrap();
+        chip8.emulate_cycle().unwrap();
+        let theme = parse_palette("octo").unwrap();
+        let rgba = theme.to_rgba(&chip8.framebuffer());
+        let colors: Vec<&[u8]> = rgba.chunks(4).collect();
+        assert!(colors.iter().all(|&color| color == theme.rgba(0) || color == theme.rgba(1)));
+        assert_eq!(colors[0], theme.rgba(1));
+        assert_eq!(colors[4], theme.rgba(0));
+    }
+}
@@ -0,0 +1,113 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./timers_tests.rs"]
+mod timers_tests;
+
+use std::time::Duration;
+use crate::{Chip8, State};
+
+// The timers count down at 60 Hz, once per frame
+pub const FRAME_DURATION: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+// Interrupts and hardware registers.
+// The Chip 8 has none, but there are two timer registers that count at 60 Hz. When set above zero they will count down to zero.
+#[derive(Clone, PartialEq)]
+pub(crate) struct Timers {
+    pub(crate) delay_timer: u8,
+    // The system’s buzzer sounds whenever the sound timer reaches zero.
+    pub(crate) sound_timer: u8,
+    // XO-CHIP 1-bit audio pattern (128 samples) played while the sound timer is above zero, at a rate set by the pitch
+    pub(crate) pattern_buffer: [u8; 16],
+    pub(crate) pitch: u8,
+    // F002 loaded a pattern, the program plays it instead of the beep of the frontend
+    pub(crate) pattern_loaded: bool,
+    // Last state given to the buzzer, so that it is only told about changes
+    pub(crate) buzzing: bool,
+}
+
+impl Default for Timers {
+    fn default() -> Timers {
+        Timers {
+            delay_timer: 0,
+            sound_timer: 0,
+            pattern_buffer: [0; 16],
+            pitch: 64,
+            pattern_loaded: false,
+            buzzing: false,
+        }
+    }
+}
+
+impl Timers {
+    pub(crate) fn tick(&mut self) {
+        if self.delay_timer > 0 {
+            self.delay_timer -= 1;
+        }
+        if self.sound_timer > 0 {
+            self.sound_timer -= 1;
+        }
+    }
+
+    // The buzzer sounds for as long as the sound timer is above zero
+    pub(crate) fn sound_active(&self) -> bool {
+        self.sound_timer > 0
+    }
+
+    pub(crate) fn update_buzzer(&mut self, buzzer: &mut dyn Buzzer) {
+        let active = self.sound_active();
+        if active != self.buzzing {
+            self.buzzing = active;
+            buzzer.set_active(active);
+        }
+    }
+
+    // Audio pattern to play and its sample rate in Hz, or None when the sound timer is zero (silence)
+    pub(crate) fn audio_pattern(&self) -> Option<(&[u8; 16], f64)> {
+        if self.sound_timer == 0 {
+            return None;
+        }
+        Some((&self.pattern_buffer, self.pattern_rate()))
+    }
+
+    // Samples of the pattern played per second, 4000 at the default pitch of 64 and twice as many 48 higher
+    pub(crate) fn pattern_rate(&self) -> f64 {
+        4000.0 * 2f64.powf((f64::from(self.pitch) - 64.0) / 48.0)
+    }
+}
+
+impl Chip8 {
+    // Counts the timers down, has to be called at 60 Hz whatever the clock speed is. This is also the vertical blank
+    // awaited by DXYN with the display wait quirk
+    pub fn tick_timers(&mut self) {
+        let sound_active = self.timers.sound_active();
+        self.timers.tick();
+        self.call_sound_hook(sound_active);
+        self.call_tick_hook();
+        if self.state == State::WaitingForVblank {
+            self.state = State::Running;
+        }
+    }
+}
+
+// Audio backend of a frontend, switched on when the sound timer becomes nonzero and off when it reaches zero
+pub trait Buzzer {
+    fn set_active(&mut self, on: bool);
+}
+
+// Silent buzzer, for headless runs
+pub struct NullBuzzer;
+
+impl Buzzer for NullBuzzer {
+    fn set_active(&mut self, _on: bool) {}
+}
+
+// Buzzer ringing the terminal bell when switched on
+pub struct StdoutBuzzer;
+
+impl Buzzer for StdoutBuzzer {
+    fn set_active(&mut self, on: bool) {
+        if on {
+            print!("\x07");
+        }
+    }
+}
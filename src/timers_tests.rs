@@ -0,0 +1,296 @@
+#[cfg(test)]
+mod timers_tests {
+    use crate::{Chip8, Chip8Config};
+    use crate::timers::{Buzzer, Timers};
+    use crate::ProgramCounterInstruction::NEXT;
+
+    #[test]
+    fn op_0xf002_loads_the_audio_pattern_from_memory_at_i() {
+        let mut chip8 = Chip8 { i: 0x300, ..Chip8::default() };
+        for offset in 0..17 {
+            chip8.memory[0x300 + offset] = offset as u8 + 1;
+        }
+        chip8.memory[0x200..0x202].copy_from_slice(&[0xF0, 0x02]);
+
+        chip8.emulate_cycle().unwrap();
+
+        assert_eq!(chip8.timers.pattern_buffer, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]);
+        assert!(chip8.timers.pattern_loaded);
+        assert_eq!(chip8.index(), 0x300);
+        assert_eq!(chip8.pc(), 0x202);
+    }
+
+    #[test]
+    fn op_0xfx07_sets_vx_to_delay_timer() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        chip8.timers.delay_timer = 0x3C;
+
+        let result = chip8.op_0xfx07(x);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0x3C);
+    }
+
+    #[test]
+    fn op_0xfx07_sets_vx_to_zero_when_delay_timer_is_zero() {
+        let mut chip8 = Chip8::default();
+        let x = 1;
+        chip8.v[x] = 0xFF;
+
+        let result = chip8.op_0xfx07(x);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.register(x), 0x00);
+    }
+
+    #[test]
+    fn op_0xfx15_sets_delay_timer_to_vx() {
+        let mut chip8 = Chip8::default();
+        let x = 2;
+        chip8.v[x] = 0x3C;
+
+        let result = chip8.op_0xfx15(x);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.delay_timer(), 0x3C);
+    }
+
+    #[test]
+    fn op_0xfx15_delay_timer_counts_down_on_each_tick() {
+        let mut chip8 = Chip8::default();
+        chip8.memory[0x200] = 0xF2; // FX15 with x = 2
+        chip8.memory[0x201] = 0x15;
+        chip8.memory[0x202] = 0x12; // 1NNN jumping to itself
+        chip8.memory[0x203] = 0x02;
+        chip8.v[2] = 5;
+
+        chip8.emulate_cycle().unwrap();
+        assert_eq!(chip8.delay_timer(), 5);
+        chip8.emulate_cycle().unwrap();
+        chip8.tick_timers();
+        chip8.emulate_cycle().unwrap();
+        chip8.tick_timers();
+        chip8.tick_timers();
+
+        assert_eq!(chip8.delay_timer(), 2);
+    }
+
+    #[test]
+    fn op_0xfx18_sets_sound_timer_to_vx() {
+        let mut chip8 = Chip8::default();
+        let x = 2;
+        chip8.v[x] = 0x3C;
+
+        let result = chip8.op_0xfx18(x);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.sound_timer(), 0x3C);
+    }
+
+    #[test]
+    fn op_0xfx18_sound_timer_counts_down_on_each_tick() {
+        let mut chip8 = Chip8::default();
+        chip8.memory[0x200] = 0xF2; // FX18 with x = 2
+        chip8.memory[0x201] = 0x18;
+        chip8.memory[0x202] = 0x12; // 1NNN jumping to itself
+        chip8.memory[0x203] = 0x02;
+        chip8.v[2] = 5;
+
+        chip8.emulate_cycle().unwrap();
+        assert_eq!(chip8.sound_timer(), 5);
+        chip8.emulate_cycle().unwrap();
+        chip8.tick_timers();
+        chip8.emulate_cycle().unwrap();
+        chip8.tick_timers();
+        chip8.tick_timers();
+
+        assert_eq!(chip8.sound_timer(), 2);
+    }
+
+    #[test]
+    fn op_0xfx3a_sets_the_pitch_to_vx() {
+        let mut chip8 = Chip8::default();
+        chip8.v[2] = 112;
+
+        let result = chip8.op_0xfx3a(2);
+
+        assert!(matches!(result, NEXT));
+        assert_eq!(chip8.timers.pitch, 112);
+    }
+
+    #[test]
+    fn audio_pattern_is_silent_when_the_sound_timer_is_zero() {
+        let chip8 = Chip8 { timers: Timers { pattern_buffer: [0xFF; 16], ..Timers::default() }, ..Chip8::default() };
+
+        assert_eq!(chip8.audio_pattern(), None);
+    }
+
+    #[test]
+    fn audio_pattern_reports_the_pattern_and_sample_rate_while_the_sound_timer_runs() {
+        let mut chip8 = Chip8 { timers: Timers { pattern_buffer: [0xF0; 16], sound_timer: 2, ..Timers::default() }, ..Chip8::default() };
+
+        assert_eq!(chip8.audio_pattern(), Some((&[0xF0; 16], 4000.0)));
+        chip8.timers.pitch = 112;
+        assert_eq!(chip8.audio_pattern(), Some((&[0xF0; 16], 8000.0)));
+    }
+
+    #[test]
+    fn tick_counts_both_timers_down_to_zero() {
+        let mut timers = Timers { delay_timer: 2, sound_timer: 1, ..Timers::default() };
+
+        timers.tick();
+        assert_eq!((timers.delay_timer, timers.sound_timer), (1, 0));
+
+        timers.tick();
+        assert_eq!((timers.delay_timer, timers.sound_timer), (0, 0));
+
+        timers.tick();
+        assert_eq!((timers.delay_timer, timers.sound_timer), (0, 0));
+    }
+
+    #[derive(Default)]
+    struct RecordingBuzzer {
+        transitions: Vec<bool>,
+    }
+
+    impl Buzzer for RecordingBuzzer {
+        fn set_active(&mut self, on: bool) {
+            self.transitions.push(on);
+        }
+    }
+
+    #[test]
+    fn update_buzzer_switches_on_while_the_sound_timer_runs_and_off_when_it_reaches_zero() {
+        let mut timers = Timers { sound_timer: 3, ..Timers::default() };
+        let mut buzzer = RecordingBuzzer::default();
+
+        timers.update_buzzer(&mut buzzer);
+        assert_eq!(buzzer.transitions, [true]);
+
+        for _ in 0..2 {
+            timers.tick();
+            timers.update_buzzer(&mut buzzer);
+        }
+        assert_eq!(buzzer.transitions, [true]);
+
+        timers.tick();
+        timers.update_buzzer(&mut buzzer);
+        assert_eq!(buzzer.transitions, [true, false]);
+
+        timers.tick();
+        timers.update_buzzer(&mut buzzer);
+        assert_eq!(buzzer.transitions, [true, false]);
+    }
+
+    #[test]
+    fn update_buzzer_does_nothing_while_the_sound_timer_is_zero() {
+        let mut chip8 = Chip8::default();
+        let mut buzzer = RecordingBuzzer::default();
+
+        chip8.update_buzzer(&mut buzzer);
+
+        assert!(buzzer.transitions.is_empty());
+    }
+
+    #[test]
+    fn op_0xfx18_switches_the_buzzer_on_until_the_sound_timer_runs_out() {
+        let mut chip8 = Chip8Config::new().instructions_per_frame(1).build().unwrap();
+        chip8.load_rom(&[
+            0x60, 0x02, // V0 = 2, the timer ticks once right after FX18
+            0xF0, 0x18, // sound timer = V0
+            0x12, 0x04, // loop forever
+        ]).unwrap();
+        let mut buzzer = RecordingBuzzer::default();
+
+        let mut history = Vec::new();
+        for _ in 0..5 {
+            chip8.run_frame().unwrap();
+            chip8.update_buzzer(&mut buzzer);
+            history.push(buzzer.transitions.clone());
+        }
+
+        assert_eq!(history, [vec![], vec![true], vec![true, false], vec![true, false], vec![true, false]]);
+    }
+
+    #[test]
+    fn emulate_cycle_leaves_the_timers_untouched() {
+        let mut chip8 = Chip8 { timers: Timers { delay_timer: 10, sound_timer: 10, ..Timers::default() }, ..Chip8::default() };
+        chip8.load_rom(&[0x12, 0x00]).unwrap(); // loop forever
+
+        for _ in 0..100 {
+            chip8.emulate_cycle().unwrap();
+        }
+
+        assert_eq!((chip8.delay_timer(), chip8.sound_timer()), (10, 10));
+    }
+
+    #[test]
+    fn tick_timers_counts_down_once_per_tick_until_zero() {
+        let mut chip8 = Chip8 { timers: Timers { delay_timer: 3, sound_timer: 2, ..Timers::default() }, ..Chip8::default() };
+
+        let mut history = Vec::new();
+        for _ in 0..4 {
+            chip8.tick_timers();
+            history.push((chip8.delay_timer(), chip8.sound_timer()));
+        }
+
+        assert_eq!(history, [(2, 1), (1, 0), (0, 0), (0, 0)]);
+    }
+
+    #[test]
+    fn sound_is_active_for_as_many_ticks_as_the_sound_timer() {
+        let mut chip8 = Chip8 { timers: Timers { sound_timer: 3, ..Timers::default() }, ..Chip8::default() };
+
+        let mut history = Vec::new();
+        for _ in 0..4 {
+            history.push(chip8.sound_active());
+            chip8.tick_timers();
+        }
+
+        assert_eq!(history, [true, true, true, false]);
+    }
+
+    #[test]
+    fn sound_stops_on_a_tick_boundary() {
+        let mut chip8 = Chip8::default();
+        chip8.load_rom(&[0x60, 0x02, 0xF0, 0x18, 0x12, 0x04]).unwrap(); // sound timer = 2, loop forever
+        chip8.emulate_cycle().unwrap();
+        chip8.emulate_cycle().unwrap();
+        assert!(chip8.sound_active());
+
+        for _ in 0..50 {
+            chip8.emulate_cycle().unwrap();
+        }
+        assert!(chip8.sound_active());
+        chip8.tick_timers();
+        assert!(chip8.sound_active());
+        chip8.tick_timers();
+
+        assert!(!chip8.sound_active());
+    }
+
+    #[test]
+    fn run_frame_runs_the_instructions_then_ticks_the_timers_once() {
+        let chip8 = Chip8Config::new().instructions_per_frame(10).build().unwrap();
+        let mut chip8 = Chip8 { timers: Timers { delay_timer: 10, ..Timers::default() }, ..chip8 };
+        chip8.load_rom(&[0x70, 0x01, 0x12, 0x00]).unwrap(); // V0 += 1, loop forever
+
+        chip8.run_frame().unwrap();
+
+        assert_eq!(chip8.register(0), 5);
+        assert_eq!(chip8.delay_timer(), 9);
+    }
+
+    #[test]
+    fn loaded_audio_pattern_is_reported_once_f002_ran_whatever_the_sound_timer() {
+        // The plain beep until a pattern is loaded, even after FX3A
+        let mut chip8 = Chip8::with_program(&[0x6070, 0xF03A, 0xF002]);
+        chip8.emulate_cycle().unwrap();
+        chip8.emulate_cycle().unwrap();
+        assert_eq!(chip8.loaded_audio_pattern(), None);
+        chip8.emulate_cycle().unwrap();
+        assert_eq!(chip8.sound_timer(), 0);
+        assert_eq!(chip8.loaded_audio_pattern().map(|(_, sample_rate)| sample_rate), Some(8000.0));
+    }
+}
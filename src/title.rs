@@ -0,0 +1,54 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./title_tests.rs"]
+mod title_tests;
+
+// Titles of the windows: the ROM, its variant and the measured speed, like "chip8 — PONG [CHIP-8] 60fps 700ips"
+
+use std::path::Path;
+use crate::{Throughput, Variant};
+
+// What the title of a window shows
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TitleInfo {
+    pub rom_name: String,
+    // Left out when the quirks are not the ones of a variant
+    pub variant: Option<Variant>,
+    // Left out until measured
+    pub throughput: Throughput,
+    pub paused: bool,
+    pub rewinding: bool,
+    // The beep is muted, the sound timer still runs
+    pub muted: bool,
+    // Speed factor while Tab fast-forwards
+    pub turbo: Option<u32>,
+}
+
+// Name of the ROM shown to the user: its file name without the extension
+pub fn rom_name(rom_path: &Path) -> String {
+    rom_path.file_stem().map_or(String::from("chip8"), |stem| stem.to_string_lossy().into_owned())
+}
+
+pub fn window_title(info: &TitleInfo) -> String {
+    let mut title = format!("chip8 — {}", info.rom_name);
+    if let Some(variant) = info.variant {
+        title += &format!(" [{variant}]");
+    }
+    let Throughput { frames_per_second, instructions_per_second } = info.throughput;
+    if frames_per_second > 0.0 {
+        title += &format!(" {frames_per_second:.0}fps {instructions_per_second:.0}ips");
+    }
+    if info.paused {
+        title += " (PAUSED)";
+    }
+    if info.rewinding {
+        title += " (REWIND)";
+    }
+    if info.muted {
+        title += " (MUTED)";
+    }
+    if let Some(speed) = info.turbo {
+        title += &format!(" (TURBO {speed}x)");
+    }
+    title
+}
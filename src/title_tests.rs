@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod title_tests {
+    use std::path::Path;
+    use crate::{rom_name, window_title, Throughput, TitleInfo, Variant};
+
+    fn pong() -> TitleInfo {
+        TitleInfo { rom_name: String::from("PONG"), ..TitleInfo::default() }
+    }
+
+    #[test]
+    fn rom_name_is_the_file_name_without_its_extension() {
+        assert_eq!(rom_name(Path::new("roms/PONG")), "PONG");
+        assert_eq!(rom_name(Path::new("/home/me/Space Invaders.ch8")), "Space Invaders");
+        assert_eq!(rom_name(Path::new("")), "chip8");
+    }
+
+    #[test]
+    fn window_title_shows_the_rom_variant_and_rates() {
+        let info = TitleInfo {
+            variant: Some(Variant::Chip8),
+            throughput: Throughput { frames_per_second: 59.97, instructions_per_second: 699.6 },
+            ..pong()
+        };
+        assert_eq!(window_title(&info), "chip8 — PONG [CHIP-8] 60fps 700ips");
+    }
+
+    #[test]
+    fn window_title_leaves_out_what_is_unknown() {
+        // The rates during the first second, the default quirks
+        assert_eq!(window_title(&pong()), "chip8 — PONG");
+        let info = TitleInfo { variant: Some(Variant::XoChip), ..pong() };
+        assert_eq!(window_title(&info), "chip8 — PONG [XO-CHIP]");
+    }
+
+    #[test]
+    fn window_title_ends_with_the_states_of_the_emulator() {
+        let info = TitleInfo { variant: Some(Variant::SuperChip), paused: true, ..pong() };
+        assert_eq!(window_title(&info), "chip8 — PONG [SCHIP] (PAUSED)");
+        let throughput = Throughput { frames_per_second: 60.0, instructions_per_second: 1800.0 };
+        let info = TitleInfo { throughput, rewinding: true, ..pong() };
+        assert_eq!(window_title(&info), "chip8 — PONG 60fps 1800ips (REWIND)");
+        let info = TitleInfo { paused: true, muted: true, ..pong() };
+        assert_eq!(window_title(&info), "chip8 — PONG (PAUSED) (MUTED)");
+        let info = TitleInfo { turbo: Some(8), ..pong() };
+        assert_eq!(window_title(&info), "chip8 — PONG (TURBO 8x)");
+    }
+}
@@ -0,0 +1,87 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./tone_tests.rs"]
+mod tone_tests;
+
+// Waves of the beep, the one of the audio backends when the program does not play an XO-CHIP audio pattern
+
+use std::f64::consts::TAU;
+
+// Shape of a period of the wave
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Waveform {
+    #[default]
+    Square,
+    Sine,
+    Triangle,
+}
+
+impl Waveform {
+    pub const NAMES: [&'static str; 3] = ["square", "sine", "triangle"];
+
+    pub fn from_name(name: &str) -> Option<Waveform> {
+        match name {
+            "square" => Some(Waveform::Square),
+            "sine" => Some(Waveform::Sine),
+            "triangle" => Some(Waveform::Triangle),
+            _ => None,
+        }
+    }
+
+    // Level of the wave at a phase from 0 to 1 of its period, from -1 to 1. All the waves start their period rising
+    // from 0 or at their top
+    pub fn sample(self, phase: f64) -> f32 {
+        let level = match self {
+            Waveform::Square => if phase < 0.5 { 1.0 } else { -1.0 },
+            Waveform::Sine => (TAU * phase).sin(),
+            Waveform::Triangle if phase < 0.25 => 4.0 * phase,
+            Waveform::Triangle if phase < 0.75 => 2.0 - 4.0 * phase,
+            Waveform::Triangle => 4.0 * phase - 4.0,
+        };
+        level as f32
+    }
+}
+
+// Samples of a wave at a frequency, endless, from -1 to 1. The phase is computed from the number of samples rather
+// than added up, so the wave keeps its frequency when the period is not a whole number of samples and a whole period
+// lasts exactly as many samples as it should when it is one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tone {
+    waveform: Waveform,
+    frequency: f32,
+    sample_rate: u32,
+    // Samples produced
+    index: u64,
+}
+
+impl Tone {
+    pub fn new(waveform: Waveform, frequency: f32, sample_rate: u32) -> Tone {
+        Tone { waveform, frequency: frequency.max(0.0), sample_rate: sample_rate.max(1), index: 0 }
+    }
+
+    pub fn waveform(&self) -> Waveform {
+        self.waveform
+    }
+
+    pub fn frequency(&self) -> f32 {
+        self.frequency
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn next_sample(&mut self) -> f32 {
+        let phase = (self.index as f64 * f64::from(self.frequency) / f64::from(self.sample_rate)).fract();
+        self.index += 1;
+        self.waveform.sample(phase)
+    }
+}
+
+impl Iterator for Tone {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        Some(self.next_sample())
+    }
+}
@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod tone_tests {
+    use crate::{Tone, Waveform};
+
+    const WAVEFORMS: [Waveform; 3] = [Waveform::Square, Waveform::Sine, Waveform::Triangle];
+
+    // Samples where the wave goes from below zero to zero or above, the start of each period
+    fn period_starts(samples: &[f32]) -> Vec<usize> {
+        (1..samples.len()).filter(|&index| samples[index - 1] < 0.0 && samples[index] >= 0.0).collect()
+    }
+
+    #[test]
+    fn waveform_from_name_reads_the_names_of_the_beep_wave_option() {
+        assert_eq!(Waveform::from_name("square"), Some(Waveform::Square));
+        assert_eq!(Waveform::from_name("sine"), Some(Waveform::Sine));
+        assert_eq!(Waveform::from_name("triangle"), Some(Waveform::Triangle));
+        assert_eq!(Waveform::from_name("saw"), None);
+        for name in Waveform::NAMES {
+            assert!(Waveform::from_name(name).is_some());
+        }
+    }
+
+    #[test]
+    fn waveform_sample_gives_the_level_at_a_phase() {
+        assert_eq!(Waveform::Square.sample(0.0), 1.0);
+        assert_eq!(Waveform::Square.sample(0.5), -1.0);
+        assert_eq!(Waveform::Sine.sample(0.0), 0.0);
+        assert_eq!(Waveform::Sine.sample(0.25), 1.0);
+        assert_eq!(Waveform::Triangle.sample(0.0), 0.0);
+        assert_eq!(Waveform::Triangle.sample(0.25), 1.0);
+        assert_eq!(Waveform::Triangle.sample(0.5), 0.0);
+        assert_eq!(Waveform::Triangle.sample(0.75), -1.0);
+        assert_eq!(Waveform::Triangle.sample(0.875), -0.5);
+    }
+
+    #[test]
+    fn tone_repeats_whole_periods_of_a_frequency_dividing_the_sample_rate() {
+        // 480 Hz at 48 kHz: 100 samples per period
+        for waveform in WAVEFORMS {
+            let samples: Vec<f32> = Tone::new(waveform, 480.0, 48_000).take(1000).collect();
+            assert_eq!(period_starts(&samples), [100, 200, 300, 400, 500, 600, 700, 800, 900], "{waveform:?}");
+            for (index, &sample) in samples.iter().enumerate().skip(100) {
+                assert!((sample - samples[index % 100]).abs() < 1e-5, "{waveform:?} sample {index}");
+            }
+        }
+    }
+
+    #[test]
+    fn tone_goes_from_minus_one_to_one() {
+        for waveform in WAVEFORMS {
+            let samples: Vec<f32> = Tone::new(waveform, 480.0, 48_000).take(100).collect();
+            let top = samples.iter().copied().fold(f32::MIN, f32::max);
+            let bottom = samples.iter().copied().fold(f32::MAX, f32::min);
+            assert!((top - 1.0).abs() < 1e-6, "{waveform:?} top {top}");
+            assert!((bottom + 1.0).abs() < 1e-6, "{waveform:?} bottom {bottom}");
+        }
+        // Half the period at each level
+        let square: Vec<f32> = Tone::new(Waveform::Square, 480.0, 48_000).take(100).collect();
+        assert!(square[..50].iter().all(|&sample| sample == 1.0));
+        assert!(square[50..].iter().all(|&sample| sample == -1.0));
+    }
+
+    #[test]
+    fn tone_keeps_the_average_period_of_any_frequency() {
+        // Periods of 109.09 samples at 48 kHz and 200.45 at 44.1 kHz, on average
+        for (frequency, sample_rate) in [(440.0, 48_000), (220.0, 44_100)] {
+            for waveform in WAVEFORMS {
+                let tone = Tone::new(waveform, frequency, sample_rate);
+                let samples: Vec<f32> = tone.take(sample_rate as usize).collect();
+                let starts = period_starts(&samples);
+                // One second holds the frequency in periods, the first starts at sample 0
+                assert_eq!(starts.len() + 1, frequency as usize, "{waveform:?} at {sample_rate}");
+                let average = (starts[starts.len() - 1] - starts[0]) as f32 / (starts.len() - 1) as f32;
+                assert!((average - sample_rate as f32 / frequency).abs() < 0.01, "{waveform:?} {average}");
+            }
+        }
+    }
+
+    #[test]
+    fn tone_keeps_its_settings_and_is_silent_without_a_frequency() {
+        let tone = Tone::new(Waveform::Sine, 220.0, 44_100);
+        assert_eq!((tone.waveform(), tone.frequency(), tone.sample_rate()), (Waveform::Sine, 220.0, 44_100));
+        // Silent without a frequency
+        assert!(Tone::new(Waveform::Sine, -10.0, 44_100).take(100).all(|sample| sample == 0.0));
+    }
+}
@@ -0,0 +1,90 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+#[path = "./wasm_tests.rs"]
+mod wasm_tests;
+
+// WebAssembly binding, enabled with the wasm feature: the page runs a frame per animation frame, draws the RGBA
+// framebuffer into a canvas and forwards the keyboard. See www/ for an example page
+
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+use crate::{parse_color, parse_palette, Chip8, Chip8Config, Key, State, Theme};
+
+#[wasm_bindgen]
+pub struct WasmChip8 {
+    chip8: Chip8,
+    theme: Theme,
+}
+
+#[wasm_bindgen]
+impl WasmChip8 {
+    // The page has no OS entropy to give, it passes the seed of the random numbers, from crypto.getRandomValues for
+    // example
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: u32) -> Result<WasmChip8, JsError> {
+        let chip8 = Chip8Config::new().seed(u64::from(seed)).build()?;
+        Ok(WasmChip8 { chip8, theme: Theme::default() })
+    }
+
+    pub fn load_rom(&mut self, rom: &Uint8Array) -> Result<(), JsError> {
+        self.chip8.load_rom(&rom.to_vec())?;
+        Ok(())
+    }
+
+    // Runs the instructions of one 60 Hz frame, returns whether the screen changed
+    pub fn run_frame(&mut self) -> Result<bool, JsError> {
+        Ok(self.chip8.run_frame()?.drew)
+    }
+
+    // Visible part of the screen, 4 bytes per pixel row by row, ready for an ImageData of width by height
+    pub fn framebuffer_rgba(&self) -> Vec<u8> {
+        self.chip8.pixels().flat_map(|pixel| self.theme.rgba(pixel)).collect()
+    }
+
+    // One of classic, green, amber, paperwhite or inverted
+    pub fn set_theme(&mut self, name: &str) -> Result<(), JsError> {
+        self.theme = Theme::preset(name)?;
+        Ok(())
+    }
+
+    // One of the palettes of Octo, or 4 #RRGGBB colors separated by commas, for XO-CHIP games
+    pub fn set_palette(&mut self, palette: &str) -> Result<(), JsError> {
+        self.theme = parse_palette(palette)?;
+        Ok(())
+    }
+
+    // #RRGGBB colors replacing the ones of the theme
+    pub fn set_colors(&mut self, foreground: &str, background: &str) -> Result<(), JsError> {
+        self.theme = self.theme.foreground(parse_color(foreground)?).background(parse_color(background)?);
+        Ok(())
+    }
+
+    pub fn width(&self) -> usize {
+        self.chip8.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.chip8.height()
+    }
+
+    // Key from 0x0 to 0xF
+    pub fn key_down(&mut self, key: u8) -> Result<(), JsError> {
+        self.chip8.press(Key::try_from(key)?);
+        Ok(())
+    }
+
+    pub fn key_up(&mut self, key: u8) -> Result<(), JsError> {
+        self.chip8.release(Key::try_from(key)?);
+        Ok(())
+    }
+
+    // Whether the page plays the beep
+    pub fn sound_active(&self) -> bool {
+        self.chip8.sound_active()
+    }
+
+    // Whether the program exited, the page stops running frames
+    pub fn halted(&self) -> bool {
+        self.chip8.state() == &State::Halted
+    }
+}
@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod wasm_tests {
+    use crate::wasm::WasmChip8;
+    use crate::{Key, Theme};
+
+    // Draws the 0 digit at (0, 0), starts the sound, then loops
+    const PROGRAM: [u8; 10] = [
+        0xF0, 0x29, // I = font digit V0
+        0xD0, 0x05, // draws it at (V0, V0)
+        0x61, 0x10, // V1 = 16
+        0xF1, 0x18, // sound timer = V1
+        0x12, 0x08, // loops
+    ];
+
+    fn wasm_chip8() -> WasmChip8 {
+        let mut wasm_chip8 = WasmChip8::new(42).unwrap();
+        wasm_chip8.chip8.load_rom(&PROGRAM).unwrap();
+        wasm_chip8
+    }
+
+    #[test]
+    fn run_frame_returns_whether_the_screen_changed() {
+        let mut wasm_chip8 = wasm_chip8();
+
+        assert!(wasm_chip8.run_frame().unwrap());
+        assert!(wasm_chip8.sound_active());
+        assert!(!wasm_chip8.halted());
+        // Nothing is drawn by the loop
+        assert!(!wasm_chip8.run_frame().unwrap());
+    }
+
+    #[test]
+    fn framebuffer_rgba_gives_four_bytes_per_pixel() {
+        let mut wasm_chip8 = wasm_chip8();
+        wasm_chip8.run_frame().unwrap();
+
+        let rgba = wasm_chip8.framebuffer_rgba();
+
+        assert_eq!(rgba.len(), wasm_chip8.width() * wasm_chip8.height() * 4);
+        // The top row of the 0 digit is 0xF0
+        assert_eq!(rgba[0..16], [0xFF; 4].repeat(4));
+        assert_eq!(rgba[16..20], [0x00, 0x00, 0x00, 0xFF]);
+    }
+
+    #[test]
+    fn framebuffer_rgba_follows_the_theme_colors_and_palette() {
+        let mut wasm_chip8 = wasm_chip8();
+        wasm_chip8.run_frame().unwrap();
+
+        wasm_chip8.set_theme("amber").unwrap();
+        let amber = Theme::preset("amber").unwrap();
+        assert_eq!(wasm_chip8.framebuffer_rgba()[0..8], [amber.rgba(1), amber.rgba(1)].concat());
+
+        wasm_chip8.set_colors("#102030", "#000000").unwrap();
+        assert_eq!(wasm_chip8.framebuffer_rgba()[0..4], [0x10, 0x20, 0x30, 0xFF]);
+        assert_eq!(wasm_chip8.framebuffer_rgba()[16..20], [0x00, 0x00, 0x00, 0xFF]);
+
+        wasm_chip8.set_palette("#102030,#405060,#708090,#A0B0C0").unwrap();
+        assert_eq!(wasm_chip8.framebuffer_rgba()[0..4], [0x40, 0x50, 0x60, 0xFF]);
+        assert_eq!(wasm_chip8.framebuffer_rgba()[16..20], [0x10, 0x20, 0x30, 0xFF]);
+    }
+
+    #[test]
+    fn key_down_and_key_up_press_and_release_the_keypad_keys() {
+        let mut wasm_chip8 = wasm_chip8();
+
+        wasm_chip8.key_down(0xA).unwrap();
+        assert!(wasm_chip8.chip8.is_pressed(Key::KA));
+
+        wasm_chip8.key_up(0xA).unwrap();
+        assert!(!wasm_chip8.chip8.is_pressed(Key::KA));
+    }
+}
@@ -0,0 +1,56 @@
+// Interactive frontends run by the emulation loop of the binary: the terminal and the sdl2, minifb and pixels windows.
+// Besides showing the screen and reading the keypad, they handle their events and read the keys controlling the
+// emulator. The loop is the same for all of them, the ones without sound or without some keys keep the defaults
+
+use crate::{Control, Display, Input, SoundHook};
+
+pub trait WindowFrontend: Display + Input {
+    // Handles the pending events, returns false once the window is closed
+    fn handle_events(&mut self) -> bool;
+
+    // P, Backspace, R, Esc and Q pressed since the last call, the letters when the keymap leaves them
+    fn take_controls(&mut self) -> Vec<Control>;
+
+    // Whether Tab is held, to fast-forward the emulation
+    fn turbo_held(&self) -> bool;
+
+    // Whether the window has the keyboard focus, as of the last handle_events. Always for the frontends not told
+    fn has_focus(&self) -> bool {
+        true
+    }
+
+    fn set_title(&mut self, title: &str);
+
+    // Whether the debug overlay is shown, its lines are then given once per frame to set_overlay
+    fn overlay_shown(&self) -> bool;
+
+    fn set_overlay(&mut self, lines: &[String]);
+
+    // Whether F12 was pressed since the last call, to save a screenshot
+    fn screenshot_requested(&mut self) -> bool {
+        false
+    }
+
+    // Whether F9 was pressed since the last call, to start or stop a recording
+    fn record_requested(&mut self) -> bool {
+        false
+    }
+
+    // Whether M was pressed since the last call, to mute or unmute the beep
+    fn mute_requested(&mut self) -> bool {
+        false
+    }
+
+    fn set_muted(&mut self, _muted: bool) {}
+
+    // Hook for Chip8::set_sound_hook switching the beep as the timers tick, None for the frontends without sound
+    fn sound_hook(&self) -> Option<SoundHook> {
+        None
+    }
+
+    // Keeps the beep silent while the emulation is paused or fast-forwarded, the sound hook still follows the timer
+    fn set_sound_held(&mut self, _held: bool) {}
+
+    // Audio pattern of the program, given once per frame like Chip8::loaded_audio_pattern
+    fn set_audio_pattern(&mut self, _pattern: Option<(&[u8; 16], f64)>) {}
+}
@@ -0,0 +1,108 @@
+use chip8::{Chip8, Chip8Config, FixedKeypad};
+
+#[test]
+fn runs_a_program_until_it_exits() {
+    let mut chip8 = Chip8Config::new().build().unwrap();
+    chip8.load_rom(&[0x60, 0x01, 0x00, 0xFD]).unwrap(); // V0 = 1, exit
+
+    assert!(!chip8.emulate_cycle().unwrap().halted);
+    assert!(chip8.emulate_cycle().unwrap().halted);
+    assert!(chip8.emulate_cycle().unwrap().halted);
+}
+
+fn keys(pressed: &[usize]) -> [bool; 16] {
+    let mut keys = [false; 16];
+    for &key in pressed {
+        keys[key] = true;
+    }
+    keys
+}
+
+// Polls the input before each cycle like the emulation loop does, returns the cycle count once the program exits
+fn run(chip8: &mut Chip8, input: &mut FixedKeypad, max_cycles: usize) -> Option<usize> {
+    for cycle in 1..=max_cycles {
+        chip8.set_keys(input);
+        if chip8.emulate_cycle().unwrap().halted {
+            return Some(cycle);
+        }
+    }
+    None
+}
+
+#[test]
+fn fx0a_waits_for_a_key_then_ex9e_skips_while_it_is_held() {
+    let mut chip8 = Chip8Config::new().build().unwrap();
+    chip8.load_rom(&[
+        0xF1, 0x0A, // wait for a key, stored in V1
+        0xE1, 0x9E, // skip the next instruction if the key in V1 is pressed
+        0x12, 0x04, // loop forever
+        0x00, 0xFD, // exit
+    ]).unwrap();
+    let mut input = FixedKeypad::new(vec![keys(&[]), keys(&[]), keys(&[0x7]), keys(&[0x7])]);
+
+    // FX0A, wait, key 7 pressed, EX9E skips, exit
+    assert_eq!(run(&mut chip8, &mut input, 10), Some(5));
+}
+
+#[test]
+fn ex9e_does_not_skip_once_the_key_is_released() {
+    let mut chip8 = Chip8Config::new().build().unwrap();
+    chip8.load_rom(&[0xF1, 0x0A, 0xE1, 0x9E, 0x12, 0x04, 0x00, 0xFD]).unwrap();
+    let mut input = FixedKeypad::new(vec![keys(&[]), keys(&[0x7])]);
+
+    assert_eq!(run(&mut chip8, &mut input, 10), None);
+}
+
+#[test]
+fn exa1_skips_only_when_the_key_is_not_pressed() {
+    let program = [
+        0x61, 0x05, // V1 = 5
+        0xE1, 0xA1, // skip the next instruction if key 5 is not pressed
+        0x00, 0xFD, // exit
+        0x12, 0x06, // loop forever
+    ];
+
+    let mut chip8 = Chip8Config::new().build().unwrap();
+    chip8.load_rom(&program).unwrap();
+    let mut input = FixedKeypad::new(vec![keys(&[0x5]); 3]);
+    assert_eq!(run(&mut chip8, &mut input, 10), Some(3));
+
+    let mut chip8 = Chip8Config::new().build().unwrap();
+    chip8.load_rom(&program).unwrap();
+    let mut input = FixedKeypad::new(vec![keys(&[0x4, 0x6]); 3]);
+    assert_eq!(run(&mut chip8, &mut input, 10), None);
+}
+
+#[test]
+fn accessors_expose_the_state_of_the_machine() {
+    let mut chip8 = Chip8Config::new().build().unwrap();
+    chip8.load_rom(&[
+        0x60, 0x05, // V0 = 5
+        0xF0, 0x15, // delay timer = V0
+        0xF0, 0x18, // sound timer = V0
+        0xF0, 0x29, // I = font digit V0
+        0xD1, 0x15, // draw the digit at (V1, V1)
+        0x00, 0xFF, // switch to high resolution
+    ]).unwrap();
+
+    for _ in 0..5 {
+        chip8.emulate_cycle().unwrap();
+    }
+
+    assert_eq!(chip8.register(0), 5);
+    assert_eq!(chip8.pc(), 0x20A);
+    assert_eq!(chip8.index(), 0x50 + 5 * 5);
+    assert_eq!(chip8.delay_timer(), 5);
+    assert_eq!(chip8.sound_timer(), 5);
+    assert!(chip8.sound_active());
+    assert_eq!((chip8.width(), chip8.height()), (64, 32));
+    assert_eq!(chip8.framebuffer().len(), 64 * 32);
+    assert_eq!(chip8.framebuffer()[..4], [1, 1, 1, 1]); // top of the 5 digit
+    assert_eq!(chip8.framebuffer()[64..66], [1, 0]);
+
+    chip8.emulate_cycle().unwrap();
+
+    assert_eq!((chip8.width(), chip8.height()), (128, 64));
+    assert_eq!(chip8.framebuffer().len(), 128 * 64);
+    assert!(chip8.framebuffer().iter().all(|&pixel| pixel == 0));
+}
@@ -0,0 +1,94 @@
+// Runs a ROM picked in the page, built with: wasm-pack build --target web --out-dir www/pkg --out-name chip8 -- --features wasm
+import init, { WasmChip8 } from "./pkg/chip8.js";
+
+// Keys of the left side of a QWERTY keyboard, in the layout of the COSMAC VIP keypad
+const KEYMAP = {
+    Digit1: 0x1, Digit2: 0x2, Digit3: 0x3, Digit4: 0xC,
+    KeyQ: 0x4, KeyW: 0x5, KeyE: 0x6, KeyR: 0xD,
+    KeyA: 0x7, KeyS: 0x8, KeyD: 0x9, KeyF: 0xE,
+    KeyZ: 0xA, KeyX: 0x0, KeyC: 0xB, KeyV: 0xF,
+};
+
+const canvas = document.getElementById("screen");
+const context = canvas.getContext("2d");
+let chip8 = null;
+// Set while a frame is scheduled
+let running = false;
+let audio = null;
+let oscillator = null;
+
+// Square wave at 440 Hz while the sound timer runs. Browsers only start audio after a user gesture
+function setBeep(on) {
+    if (on && !oscillator) {
+        audio = audio || new AudioContext();
+        oscillator = audio.createOscillator();
+        oscillator.type = "square";
+        oscillator.frequency.value = 440;
+        oscillator.connect(audio.destination);
+        oscillator.start();
+    } else if (!on && oscillator) {
+        oscillator.stop();
+        oscillator = null;
+    }
+}
+
+function draw() {
+    if (canvas.width !== chip8.width() || canvas.height !== chip8.height()) {
+        canvas.width = chip8.width();
+        canvas.height = chip8.height();
+    }
+    const pixels = new Uint8ClampedArray(chip8.framebuffer_rgba());
+    context.putImageData(new ImageData(pixels, canvas.width, canvas.height), 0, 0);
+}
+
+// One emulated frame per animation frame, about 60 per second
+function frame() {
+    if (!chip8 || chip8.halted()) {
+        running = false;
+        setBeep(false);
+        return;
+    }
+    try {
+        if (chip8.run_frame()) {
+            draw();
+        }
+    } catch (error) {
+        console.error(error);
+        chip8 = null;
+        running = false;
+        setBeep(false);
+        return;
+    }
+    setBeep(chip8.sound_active());
+    requestAnimationFrame(frame);
+}
+
+document.addEventListener("keydown", (event) => {
+    if (chip8 && event.code in KEYMAP) {
+        chip8.key_down(KEYMAP[event.code]);
+        event.preventDefault();
+    }
+});
+
+document.addEventListener("keyup", (event) => {
+    if (chip8 && event.code in KEYMAP) {
+        chip8.key_up(KEYMAP[event.code]);
+        event.preventDefault();
+    }
+});
+
+await init();
+
+document.getElementById("rom").addEventListener("change", async (event) => {
+    const file = event.target.files[0];
+    if (!file) {
+        return;
+    }
+    const seed = crypto.getRandomValues(new Uint32Array(1))[0];
+    chip8 = new WasmChip8(seed);
+    chip8.load_rom(new Uint8Array(await file.arrayBuffer()));
+    if (!running) {
+        running = true;
+        requestAnimationFrame(frame);
+    }
+});